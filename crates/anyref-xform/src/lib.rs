@@ -62,6 +62,8 @@ struct Transform<'a> {
     clone_ref: FunctionId,
     heap_alloc: FunctionId,
     heap_dealloc: FunctionId,
+    heap_live_count: Option<ExportId>,
+    heap_capacity: Option<ExportId>,
     stack_pointer: GlobalId,
 }
 
@@ -77,6 +79,8 @@ enum Intrinsic {
     TableSetNull,
     DropRef,
     CloneRef,
+    HeapLiveCount(FunctionId),
+    HeapCapacity(FunctionId),
 }
 
 impl Context {
@@ -172,6 +176,8 @@ impl Context {
 
         let mut heap_alloc = None;
         let mut heap_dealloc = None;
+        let mut heap_live_count = None;
+        let mut heap_capacity = None;
 
         // Find exports of some intrinsics which we only need for a runtime
         // implementation.
@@ -183,6 +189,8 @@ impl Context {
             match export.name.as_str() {
                 "__wbindgen_anyref_table_alloc" => heap_alloc = Some(f),
                 "__wbindgen_anyref_table_dealloc" => heap_dealloc = Some(f),
+                "__wbindgen_anyref_heap_live_count_impl" => heap_live_count = Some(export.id()),
+                "__wbindgen_anyref_heap_capacity_impl" => heap_capacity = Some(export.id()),
                 _ => {}
             }
         }
@@ -224,6 +232,8 @@ impl Context {
             clone_ref,
             heap_alloc,
             heap_dealloc,
+            heap_live_count,
+            heap_capacity,
             stack_pointer,
         }
         .run(module)
@@ -254,6 +264,25 @@ impl Transform<'_> {
         // functions and make sure everything is still hooked up right.
         self.rewrite_calls(module);
 
+        // Calls to the heap statistics intrinsics now go straight to their
+        // implementations, so neither the imports nor the exports are needed
+        // by the JS glue anymore.
+        for import in module.imports.iter_mut() {
+            let f = match import.kind {
+                walrus::ImportKind::Function(f) => f,
+                _ => continue,
+            };
+            match self.intrinsic_map.get(&f) {
+                Some(Intrinsic::HeapLiveCount(_)) | Some(Intrinsic::HeapCapacity(_)) => {
+                    import.name = format!("{}_unused", import.name);
+                }
+                _ => {}
+            }
+        }
+        for export in self.heap_live_count.iter().chain(&self.heap_capacity) {
+            module.exports.delete(*export);
+        }
+
         // Inject initialization routine to set up default slots in the table
         // (things like null/undefined/true/false)
         self.inject_initialization(module);
@@ -287,6 +316,29 @@ impl Transform<'_> {
                     "__wbindgen_object_clone_ref" => {
                         self.intrinsic_map.insert(f, Intrinsic::CloneRef);
                     }
+                    // The number of live objects and the capacity of the heap
+                    // are only known to the slab in wasm, so instead of going
+                    // through JS call its implementations directly. These
+                    // imports are only renamed by `run` once calls to them are
+                    // rewritten, as JS still has to implement them otherwise.
+                    "__wbindgen_anyref_heap_live_count" => {
+                        let export = match self.heap_live_count {
+                            Some(id) => module.exports.get(id),
+                            None => continue,
+                        };
+                        let intrinsic = Intrinsic::HeapLiveCount(export_func(export));
+                        self.intrinsic_map.insert(f, intrinsic);
+                        continue;
+                    }
+                    "__wbindgen_heap_capacity" => {
+                        let export = match self.heap_capacity {
+                            Some(id) => module.exports.get(id),
+                            None => continue,
+                        };
+                        let intrinsic = Intrinsic::HeapCapacity(export_func(export));
+                        self.intrinsic_map.insert(f, intrinsic);
+                        continue;
+                    }
                     _ => continue,
                 }
             } else {
@@ -665,6 +717,7 @@ impl Transform<'_> {
                     }
                     Intrinsic::DropRef => e.func = self.xform.heap_dealloc,
                     Intrinsic::CloneRef => e.func = self.xform.clone_ref,
+                    Intrinsic::HeapLiveCount(f) | Intrinsic::HeapCapacity(f) => e.func = *f,
                 }
             }
         }
@@ -697,3 +750,10 @@ impl Transform<'_> {
         module.start = Some(new_start);
     }
 }
+
+fn export_func(export: &walrus::Export) -> FunctionId {
+    match export.item {
+        walrus::ExportItem::Function(f) => f,
+        _ => unreachable!(),
+    }
+}
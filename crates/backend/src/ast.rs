@@ -23,6 +23,9 @@ pub struct Program {
     /// objects" in the sense that they represent a JS object with a particular
     /// shape in JIT parlance.
     pub dictionaries: Vec<Dictionary>,
+    /// enums over imported types, generated for WebIDL unions of interfaces
+    /// so that functions taking a union can be passed any of its members
+    pub unions: Vec<Union>,
     /// custom typescript sections to be included in the definition file
     pub typescript_custom_sections: Vec<String>,
     /// Inline JS snippets
@@ -40,17 +43,41 @@ pub struct Export {
     pub function: Function,
     /// The class name in JS this is attached to
     pub js_class: Option<String>,
+    /// The path of the JS namespace a free function is exported in, empty for
+    /// the top level
+    pub js_namespace: Vec<String>,
     /// The kind (static, named, regular)
     pub method_kind: MethodKind,
     /// The type of `self` (either `self`, `&self`, or `&mut self`)
     pub method_self: Option<MethodSelf>,
     /// The struct name, in Rust, this is attached to
     pub rust_class: Option<Ident>,
+    /// The trait, if any, whose impl for `rust_class` this method is part of
+    pub rust_trait: Option<syn::Path>,
     /// The name of the rust function/method on the rust side.
     pub rust_name: Ident,
     /// Whether or not this function should be flagged as the wasm start
+    /// function, and if so how.
+    pub start: Option<Start>,
+    /// Whether a returned `&[T]` is handed to JS as a view directly into wasm
+    /// memory rather than copied.
+    pub unsafe_view: bool,
+    /// Whether this method takes `self` by value and returns `Self`, in which
+    /// case the result is written back in place and JS returns `this`.
+    pub chain: bool,
+}
+
+/// Options of a `#[wasm_bindgen(start)]` function.
+#[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
+#[derive(Clone)]
+pub struct Start {
+    /// Start functions with a higher priority run first, and those with the
+    /// same priority run in declaration order.
+    pub priority: i32,
+    /// Whether this is an `async fn`, whose future is awaited by the JS
+    /// initialization function rather than run as part of the wasm start
     /// function.
-    pub start: bool,
+    pub asyncness: bool,
 }
 
 /// The 3 types variations of `self`.
@@ -125,6 +152,10 @@ pub struct ImportFunction {
     pub kind: ImportFunctionKind,
     pub shim: Ident,
     pub doc_comment: Option<String>,
+    pub vendor_prefixes: Vec<Ident>,
+    /// The path, relative to the crate root, of the file whose URL this import
+    /// returns, as given to `link_to!`
+    pub link_to: Option<(String, Span)>,
 }
 
 #[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
@@ -171,6 +202,8 @@ pub struct ImportStatic {
     pub shim: Ident,
     pub rust_name: Ident,
     pub js_name: String,
+    pub setter: Option<Ident>,
+    pub thread_local: bool,
 }
 
 #[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
@@ -185,6 +218,9 @@ pub struct ImportType {
     pub is_type_of: Option<syn::Expr>,
     pub extends: Vec<syn::Path>,
     pub vendor_prefixes: Vec<Ident>,
+    /// Whether the type is imported from the global scope, rather than from a
+    /// module or a namespace.
+    pub global: bool,
 }
 
 #[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
@@ -212,6 +248,21 @@ pub struct Function {
     pub ret: Option<syn::Type>,
     pub rust_attrs: Vec<syn::Attribute>,
     pub rust_vis: syn::Visibility,
+    pub wrapped_arguments: Vec<(usize, Wrapper)>,
+    pub wrapped_ret: Option<Wrapper>,
+    pub typescript_types: Vec<(String, String)>,
+    pub arg_defaults: Vec<(String, String)>,
+}
+
+/// A wrapper type which an argument or return value is converted through
+/// rather than its own type.
+#[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
+#[derive(Copy, Clone)]
+pub enum Wrapper {
+    /// `wasm_bindgen::Serde`, used by the `serde` attribute
+    Serde,
+    /// `wasm_bindgen::PlainObject`, used by the `plain_object` attribute
+    PlainObject,
 }
 
 #[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
@@ -219,14 +270,22 @@ pub struct Function {
 pub struct Struct {
     pub rust_name: Ident,
     pub js_name: String,
+    /// The path of the JS namespace the class is exported in, empty for the
+    /// top level.
+    pub js_namespace: Vec<String>,
     pub fields: Vec<StructField>,
     pub comments: Vec<String>,
+    /// Whether the JS class is generated without a `free` method.
+    pub no_free: bool,
+    /// The JS method called by `free` before the Rust value is dropped.
+    pub on_free: Option<String>,
 }
 
 #[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
 #[derive(Clone)]
 pub struct StructField {
     pub name: syn::Member,
+    pub js_name: String,
     pub struct_name: Ident,
     pub readonly: bool,
     pub ty: syn::Type,
@@ -307,6 +366,20 @@ pub struct DictionaryField {
     pub doc_comment: Option<String>,
 }
 
+#[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
+#[derive(Clone)]
+pub struct Union {
+    /// The Rust enum's visibility
+    pub vis: syn::Visibility,
+    /// The Rust enum's identifier
+    pub name: Ident,
+    /// The Rust identifiers for the variants
+    pub variants: Vec<Ident>,
+    /// The imported types wrapped by each variant, by reference
+    pub variant_tys: Vec<syn::Type>,
+    pub doc_comment: Option<String>,
+}
+
 impl Export {
     /// Mangles a rust -> javascript export, so that the created Ident will be unique over function
     /// name and class name, if the function belongs to a javascript class.
@@ -346,6 +419,44 @@ impl ImportKind {
 }
 
 impl Function {
+    /// Returns the wrapper the `i`th argument is converted through, if any.
+    pub fn argument_wrapper(&self, i: usize) -> Option<Wrapper> {
+        self.wrapped_arguments
+            .iter()
+            .find(|(j, _)| *j == i)
+            .map(|(_, wrapper)| *wrapper)
+    }
+
+    /// Returns the `#[cfg]` attributes of the function, which everything
+    /// generated for it must be gated on as well.
+    pub fn cfg_attrs(&self) -> Vec<&syn::Attribute> {
+        self.rust_attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("cfg"))
+            .collect()
+    }
+
+    /// Returns the condition the function is compiled under, like
+    /// `feature = "advanced"`, combining several `#[cfg]` attributes with
+    /// `all(...)`, or `None` if it's always compiled.
+    pub fn cfg_condition(&self) -> Option<String> {
+        let predicates = self
+            .cfg_attrs()
+            .into_iter()
+            .map(|attr| match attr.parse_meta() {
+                Ok(syn::Meta::List(list)) if list.nested.len() == 1 => {
+                    cfg_predicate(&list.nested[0])
+                }
+                _ => attr.tts.to_string(),
+            })
+            .collect::<Vec<_>>();
+        match predicates.len() {
+            0 => None,
+            1 => predicates.into_iter().next(),
+            _ => Some(format!("all({})", predicates.join(", "))),
+        }
+    }
+
     /// If the rust object has a `fn xxx(&self) -> MyType` method, get the name for a getter in
     /// javascript (in this case `xxx`, so you can write `val = obj.xxx`)
     pub fn infer_getter_property(&self) -> &str {
@@ -374,3 +485,21 @@ impl Function {
         Ok(name[4..].to_string())
     }
 }
+
+/// Formats a `#[cfg]` predicate the way it's usually written in Rust, like
+/// `all(unix, feature = "advanced")`.
+fn cfg_predicate(predicate: &syn::NestedMeta) -> String {
+    use quote::ToTokens;
+
+    match predicate {
+        syn::NestedMeta::Meta(syn::Meta::Word(ident)) => ident.to_string(),
+        syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => {
+            format!("{} = {}", nv.ident, nv.lit.clone().into_token_stream())
+        }
+        syn::NestedMeta::Meta(syn::Meta::List(list)) => {
+            let nested = list.nested.iter().map(cfg_predicate).collect::<Vec<_>>();
+            format!("{}({})", list.ident, nested.join(", "))
+        }
+        syn::NestedMeta::Literal(lit) => lit.clone().into_token_stream().to_string(),
+    }
+}
@@ -70,6 +70,9 @@ impl TryToTokens for ast::Program {
         for d in self.dictionaries.iter() {
             d.to_tokens(tokens);
         }
+        for u in self.unions.iter() {
+            u.to_tokens(tokens);
+        }
 
         Diagnostic::from_vec(errors)?;
 
@@ -88,9 +91,10 @@ impl TryToTokens for ast::Program {
         // See comments in `crates/cli-support/src/lib.rs` about what this
         // `schema_version` is.
         let prefix_json = format!(
-            r#"{{"schema_version":"{}","version":"{}"}}"#,
+            r#"{{"schema_version":"{}","version":"{}","schema_hash":"{}"}}"#,
             shared::SCHEMA_VERSION,
-            shared::version()
+            shared::version(),
+            shared::schema_hash(),
         );
         let encoded = encode::encode(self)?;
         let mut bytes = Vec::new();
@@ -107,14 +111,15 @@ impl TryToTokens for ast::Program {
         // We already consumed the contents of included files when generating
         // the custom section, but we want to make sure that updates to the
         // generated files will cause this macro to rerun incrementally. To do
-        // that we use `include_str!` to force rustc to think it has a
-        // dependency on these files. That way when the file changes Cargo will
+        // that we use `include_bytes!` to force rustc to think it has a
+        // dependency on these files, which aren't necessarily UTF-8 when
+        // linked with `link_to!`. That way when the file changes Cargo will
         // automatically rerun rustc which will rerun this macro. Other than
-        // this we don't actually need the results of the `include_str!`, so
+        // this we don't actually need the results of the `include_bytes!`, so
         // it's just shoved into an anonymous static.
         let file_dependencies = encoded.included_files.iter().map(|file| {
             let file = file.to_str().unwrap();
-            quote! { include_str!(#file) }
+            quote! { include_bytes!(#file) }
         });
 
         (quote! {
@@ -124,7 +129,7 @@ impl TryToTokens for ast::Program {
             #[doc(hidden)]
             #[allow(clippy::all)]
             pub static #generated_static_name: [u8; #generated_static_length] = {
-                static _INCLUDED_FILES: &[&str] = &[#(#file_dependencies),*];
+                static _INCLUDED_FILES: &[&[u8]] = &[#(#file_dependencies),*];
 
                 *#generated_static_value
             };
@@ -146,6 +151,7 @@ impl ToTokens for ast::Struct {
         let free_fn = Ident::new(&shared::free_function(&name_str), Span::call_site());
         (quote! {
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             impl wasm_bindgen::describe::WasmDescribe for #name {
                 fn describe() {
                     use wasm_bindgen::__wbindgen_if_not_std;
@@ -163,6 +169,7 @@ impl ToTokens for ast::Struct {
             }
 
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             impl wasm_bindgen::convert::IntoWasmAbi for #name {
                 type Abi = u32;
 
@@ -174,6 +181,7 @@ impl ToTokens for ast::Struct {
             }
 
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             impl wasm_bindgen::convert::FromWasmAbi for #name {
                 type Abi = u32;
 
@@ -190,6 +198,7 @@ impl ToTokens for ast::Struct {
             }
 
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             impl wasm_bindgen::__rt::core::convert::From<#name> for
                 wasm_bindgen::JsValue
             {
@@ -218,11 +227,13 @@ impl ToTokens for ast::Struct {
             #[no_mangle]
             #[doc(hidden)]
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             pub unsafe extern "C" fn #free_fn(ptr: u32) {
                 <#name as wasm_bindgen::convert::FromWasmAbi>::from_abi(ptr);
             }
 
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             impl wasm_bindgen::convert::RefFromWasmAbi for #name {
                 type Abi = u32;
                 type Anchor = wasm_bindgen::__rt::Ref<'static, #name>;
@@ -235,6 +246,7 @@ impl ToTokens for ast::Struct {
             }
 
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             impl wasm_bindgen::convert::RefMutFromWasmAbi for #name {
                 type Abi = u32;
                 type Anchor = wasm_bindgen::__rt::RefMut<'static, #name>;
@@ -246,11 +258,13 @@ impl ToTokens for ast::Struct {
                 }
             }
 
+            #[allow(deprecated)]
             impl wasm_bindgen::convert::OptionIntoWasmAbi for #name {
                 #[inline]
                 fn none() -> Self::Abi { 0 }
             }
 
+            #[allow(deprecated)]
             impl wasm_bindgen::convert::OptionFromWasmAbi for #name {
                 #[inline]
                 fn is_none(abi: &Self::Abi) -> bool { *abi == 0 }
@@ -273,24 +287,26 @@ impl ToTokens for ast::StructField {
         let getter = &self.getter;
         let setter = &self.setter;
 
-        let assert_copy = quote! { assert_copy::<#ty>() };
-        let assert_copy = respan(assert_copy, ty);
+        // Fields are either `Copy` or another exported struct which is cloned,
+        // see `wasm_bindgen::__rt::FieldValue` for how one is picked.
+        let field_value =
+            quote! { (&FieldValue::<#ty>(&(*js).borrow().#name)).field_value().get() };
+        let field_value = respan(field_value, ty);
         (quote! {
             #[doc(hidden)]
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             #[cfg_attr(all(target_arch = "wasm32", not(target_os = "emscripten")), no_mangle)]
             pub unsafe extern "C" fn #getter(js: u32)
                 -> <#ty as wasm_bindgen::convert::IntoWasmAbi>::Abi
             {
                 use wasm_bindgen::__rt::{WasmRefCell, assert_not_null};
+                use wasm_bindgen::__rt::{FieldValue, ClassFieldValue, OptionClassFieldValue, CopyFieldValue};
                 use wasm_bindgen::convert::IntoWasmAbi;
 
-                fn assert_copy<T: Copy>(){}
-                #assert_copy;
-
                 let js = js as *mut WasmRefCell<#struct_name>;
                 assert_not_null(js);
-                let val = (*js).borrow().#name;
+                let val = #field_value;
                 <#ty as IntoWasmAbi>::into_abi(val)
             }
         })
@@ -298,6 +314,7 @@ impl ToTokens for ast::StructField {
 
         Descriptor(
             &getter,
+            &[],
             quote! {
                 <#ty as WasmDescribe>::describe();
             },
@@ -313,6 +330,7 @@ impl ToTokens for ast::StructField {
             #[doc(hidden)]
             #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             pub unsafe extern "C" fn #setter(
                 js: u32,
                 val: <#ty as wasm_bindgen::convert::FromWasmAbi>::Abi,
@@ -347,7 +365,21 @@ impl TryToTokens for ast::Export {
         };
 
         let name = &self.rust_name;
-        let receiver = match self.method_self {
+        let me = match self.method_self {
+            // A chained method moves `self` out of its slot and writes the
+            // returned value back into it, so JS keeps using the same
+            // pointer and wrapper object.
+            Some(ast::MethodSelf::ByValue) if self.chain => {
+                let class = self.rust_class.as_ref().unwrap();
+                arg_conversions.push(quote! {
+                    let mut me = unsafe {
+                        <#class as wasm_bindgen::convert::RefMutFromWasmAbi>
+                            ::ref_mut_from_abi(me)
+                    };
+                    let me: *mut #class = &mut *me;
+                });
+                Some(quote! { wasm_bindgen::__rt::core::ptr::read(me) })
+            }
             Some(ast::MethodSelf::ByValue) => {
                 let class = self.rust_class.as_ref().unwrap();
                 arg_conversions.push(quote! {
@@ -355,7 +387,7 @@ impl TryToTokens for ast::Export {
                         <#class as wasm_bindgen::convert::FromWasmAbi>::from_abi(me)
                     };
                 });
-                quote! { me.#name }
+                Some(quote! { me })
             }
             Some(ast::MethodSelf::RefMutable) => {
                 let class = self.rust_class.as_ref().unwrap();
@@ -366,7 +398,7 @@ impl TryToTokens for ast::Export {
                     };
                     let me = &mut *me;
                 });
-                quote! { me.#name }
+                Some(quote! { me })
             }
             Some(ast::MethodSelf::RefShared) => {
                 let class = self.rust_class.as_ref().unwrap();
@@ -377,18 +409,52 @@ impl TryToTokens for ast::Export {
                     };
                     let me = &*me;
                 });
-                quote! { me.#name }
+                Some(quote! { me })
             }
-            None => match &self.rust_class {
+            None => None,
+        };
+        let receiver = match (&self.rust_trait, me) {
+            // Methods of trait impls are called through the trait, which isn't
+            // necessarily in scope here, with `self` as the first argument.
+            (Some(trait_), me) => {
+                let class = self.rust_class.as_ref().unwrap();
+                converted_arguments.extend(me);
+                quote! { <#class as #trait_>::#name }
+            }
+            (None, Some(me)) => quote! { #me.#name },
+            (None, None) => match &self.rust_class {
                 Some(class) => quote! { #class::#name },
                 None => quote! { #name },
             },
         };
 
         for (i, syn::ArgCaptured { ty, .. }) in self.function.arguments.iter().enumerate() {
+            let wrapper = self.function.argument_wrapper(i);
             let i = i + offset;
             let ident = Ident::new(&format!("arg{}", i), Span::call_site());
             match *ty {
+                // Arguments going through a wrapper are always converted into
+                // an owned value, which is then borrowed if need be.
+                _ if wrapper.is_some() => {
+                    let (ty, borrow) = match ty {
+                        syn::Type::Reference(syn::TypeReference {
+                            mutability: None,
+                            elem,
+                            ..
+                        }) => (&**elem, quote! { let #ident = &#ident; }),
+                        _ => (ty, quote! {}),
+                    };
+                    args.push(quote! {
+                        #ident: <#wrapper<#ty> as wasm_bindgen::convert::FromWasmAbi>::Abi
+                    });
+                    arg_conversions.push(quote! {
+                        let #ident = unsafe {
+                            <#wrapper<#ty> as wasm_bindgen::convert::FromWasmAbi>
+                                ::from_abi(#ident)
+                        }.0;
+                        #borrow
+                    });
+                }
                 syn::Type::Reference(syn::TypeReference {
                     mutability: Some(_),
                     ref elem,
@@ -435,10 +501,71 @@ impl TryToTokens for ast::Export {
             elems: Default::default(),
             paren_token: Default::default(),
         });
-        let syn_ret = self.function.ret.as_ref().unwrap_or(&syn_unit);
-        if let syn::Type::Reference(_) = syn_ret {
-            bail_span!(syn_ret, "cannot return a borrowed ref with #[wasm_bindgen]",)
+        let mut syn_ret = self.function.ret.as_ref().unwrap_or(&syn_unit);
+        let view_ret;
+        let mut call = quote! { #receiver(#(#converted_arguments),*) };
+        if self.chain {
+            call = quote! {
+                unsafe { wasm_bindgen::__rt::core::ptr::write(me, #call) }
+            };
+            syn_ret = &syn_unit;
         }
+        let syn_ret = match syn_ret {
+            // With `unsafe_view` the returned slice is passed to JS without
+            // copying it, so its lifetime is extended to `'static` here and
+            // it's up to the caller to not use the view after the data it
+            // points to is gone.
+            syn::Type::Reference(syn::TypeReference {
+                mutability: None,
+                elem,
+                ..
+            }) if self.unsafe_view => {
+                let elem = match &**elem {
+                    syn::Type::Slice(slice) => &slice.elem,
+                    _ => bail_span!(
+                        syn_ret,
+                        "the `unsafe_view` attribute requires the function to return `&[T]`",
+                    ),
+                };
+                view_ret = syn::parse_quote! { &'static [#elem] };
+                call = quote! {
+                    unsafe { &*(#call as *const [#elem]) }
+                };
+                &view_ret
+            }
+            syn::Type::Reference(_) => {
+                bail_span!(syn_ret, "cannot return a borrowed ref with #[wasm_bindgen]",)
+            }
+            _ if self.unsafe_view => bail_span!(
+                syn_ret,
+                "the `unsafe_view` attribute requires the function to return `&[T]`",
+            ),
+            _ => syn_ret,
+        };
+        // The future of an `async` start function is handed to JS as a
+        // promise, which the JS initialization function then awaits.
+        let promise_ret: syn::Type;
+        let syn_ret = match &self.start {
+            Some(ast::Start {
+                asyncness: true, ..
+            }) => {
+                promise_ret = syn::parse_quote! { wasm_bindgen::JsValue };
+                call = quote! {
+                    wasm_bindgen_futures::futures_0_3::__wbindgen_start_to_promise(#call)
+                };
+                &promise_ret
+            }
+            _ => syn_ret,
+        };
+        let wrapped_ret;
+        let syn_ret = match &self.function.wrapped_ret {
+            Some(wrapper) => {
+                wrapped_ret = syn::parse_quote! { #wrapper<#syn_ret> };
+                call = quote! { #wrapper(#call) };
+                &wrapped_ret
+            }
+            None => syn_ret,
+        };
         let ret_ty = quote! {
             -> <#syn_ret as wasm_bindgen::convert::ReturnWasmAbi>::Abi
         };
@@ -450,10 +577,13 @@ impl TryToTokens for ast::Export {
             <#syn_ret as WasmDescribe>::describe();
         };
         let nargs = self.function.arguments.len() as u32;
-        let argtys = self.function.arguments.iter().map(|arg| &arg.ty);
+        let argtys = describe_argument_types(&self.function);
         let attrs = &self.function.rust_attrs;
 
-        let start_check = if self.start {
+        let start_check = if let Some(ast::Start {
+            asyncness: false, ..
+        }) = self.start
+        {
             quote! {
                 const _ASSERT: fn() = || #ret_ty { loop {} };
             }
@@ -467,14 +597,17 @@ impl TryToTokens for ast::Export {
             #[allow(non_snake_case)]
             #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             pub extern "C" fn #generated_name(#(#args),*) #ret_ty {
                 #start_check
+                // See definition of `init_panic_hook` for what this is doing
+                wasm_bindgen::__rt::init_panic_hook();
                 // Scope all local variables to be destroyed after we call the
                 // function to ensure that `#convert_ret`, if it panics, doesn't
                 // leak anything.
                 let #ret = {
                     #(#arg_conversions)*
-                    #receiver(#(#converted_arguments),*)
+                    #call
                 };
                 #convert_ret
             }
@@ -497,9 +630,14 @@ impl TryToTokens for ast::Export {
         // In any case, there's complications in `wasm-bindgen` to handle
         // this, but the tl;dr; is that this is stripped from the final wasm
         // binary along with anything it references.
+        //
+        // Exports which are conditionally compiled have their descriptor gated
+        // on the same condition, so that the CLI knows they're missing.
+        let cfgs = self.function.cfg_attrs();
         let export = Ident::new(&export_name, Span::call_site());
         Descriptor(
             &export,
+            &cfgs,
             quote! {
                 inform(FUNCTION);
                 inform(0);
@@ -510,6 +648,36 @@ impl TryToTokens for ast::Export {
         )
         .to_tokens(into);
 
+        // The module path of free functions isn't known to us, but it's part
+        // of the mangled symbol of functions defined next to them. So we also
+        // generate such a function, kept alive by another descriptor, which
+        // the CLI finds by name and demangles for `--demangle-exports`. It
+        // describes the export's name so that it can't be merged with the
+        // one of another export. The CLI always strips both functions, so they
+        // never make it to the final wasm file.
+        if self.js_class.is_none() {
+            let marker = Ident::new(
+                &format!("__wbindgen_module_path_{}", export_name),
+                Span::call_site(),
+            );
+            let name_chars = export_name.chars().map(|c| c as u32);
+            let attrs = &cfgs;
+            (quote! {
+                #(#attrs)*
+                #[inline(never)]
+                #[allow(non_snake_case)]
+                #[doc(hidden)]
+                #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+                #[allow(clippy::all)]
+                fn #marker() {
+                    use wasm_bindgen::describe::*;
+                    #(inform(#name_chars);)*
+                }
+            })
+            .to_tokens(into);
+            Descriptor(&marker, &cfgs, quote! { #marker(); }).to_tokens(into);
+        }
+
         Ok(())
     }
 }
@@ -549,15 +717,56 @@ impl ToTokens for ast::ImportType {
             }
         };
 
-        let is_type_of = self.is_type_of.as_ref().map(|is_type_of| {
+        // Types with a custom `is_type_of` check (like `JsString`) typically
+        // can't be checked with `instanceof`, and types imported from a module
+        // or a namespace aren't the global class of the same name, so they're
+        // described as plain `JsValue`s. All others carry their names along so
+        // the generated JS can check values in debug mode.
+        let describe = if self.is_type_of.is_some() || !self.global {
+            quote! { JsValue::describe(); }
+        } else {
+            let js_name = &self.js_name;
+            let js_name_len = js_name.len() as u32;
+            let js_name_chars = js_name.chars().map(|c| c as u32);
+            let rust_name_str = rust_name.to_string();
+            let rust_name_len = rust_name_str.len() as u32;
+            let rust_name_chars = rust_name_str.chars().map(|c| c as u32);
             quote! {
+                use wasm_bindgen::describe::*;
+                inform(NAMED_ANYREF);
+                inform(#js_name_len);
+                #(inform(#js_name_chars);)*
+                inform(#rust_name_len);
+                #(inform(#rust_name_chars);)*
+            }
+        };
+
+        // Without a custom `is_type_of` check values which fail `instanceof`
+        // are still accepted if they look like an instance from another realm
+        // (like an iframe or a worker): the prototype chain has a constructor
+        // with the same name, and the value is also of all the declared
+        // superclasses.
+        let is_type_of = match &self.is_type_of {
+            Some(is_type_of) => quote! {
                 #[inline]
                 fn is_type_of(val: &JsValue) -> bool {
                     let is_type_of: fn(&JsValue) -> bool = #is_type_of;
                     is_type_of(val)
                 }
+            },
+            None => {
+                let js_name = &self.js_name;
+                let extends = &self.extends;
+                quote! {
+                    fn is_type_of(val: &JsValue) -> bool {
+                        Self::instanceof(val) || (
+                            wasm_bindgen::__rt::has_constructor_named(val, #js_name)
+                            #(&& <#extends as JsCast>::is_type_of(val))*
+                        )
+                    }
+                }
             }
-        });
+        };
 
         (quote! {
             #[allow(bad_style)]
@@ -581,7 +790,7 @@ impl ToTokens for ast::ImportType {
 
                 impl WasmDescribe for #rust_name {
                     fn describe() {
-                        JsValue::describe();
+                        #describe
                     }
                 }
 
@@ -735,6 +944,43 @@ impl ToTokens for ast::ImportType {
                 }
             })
             .to_tokens(tokens);
+
+            // `From<JsValue>` is already implemented above, which means that a
+            // `TryFrom<JsValue>` would conflict with the blanket impl in
+            // `core`. Downcasting from `JsValue` is handled by `JsCast`.
+            let is_js_value = superclass
+                .segments
+                .last()
+                .map(|s| s.value().ident == "JsValue")
+                .unwrap_or(false);
+            if is_js_value {
+                continue;
+            }
+
+            (quote! {
+                #[allow(clippy::all)]
+                impl wasm_bindgen::__rt::core::convert::TryFrom<#superclass> for #rust_name {
+                    type Error = #superclass;
+
+                    #[inline]
+                    fn try_from(obj: #superclass) -> Result<#rust_name, #superclass> {
+                        use wasm_bindgen::JsCast;
+                        obj.dyn_into::<#rust_name>()
+                    }
+                }
+
+                #[allow(clippy::all)]
+                impl<'a> wasm_bindgen::__rt::core::convert::TryFrom<&'a #superclass> for &'a #rust_name {
+                    type Error = &'a #superclass;
+
+                    #[inline]
+                    fn try_from(obj: &'a #superclass) -> Result<&'a #rust_name, &'a #superclass> {
+                        use wasm_bindgen::JsCast;
+                        obj.dyn_ref::<#rust_name>().ok_or(obj)
+                    }
+                }
+            })
+            .to_tokens(tokens);
         }
     }
 }
@@ -890,17 +1136,24 @@ impl TryToTokens for ast::ImportFunction {
             };
 
             abi_argument_names.push(name.clone());
-            abi_arguments.push(quote! {
-                #name: <#ty as wasm_bindgen::convert::IntoWasmAbi>::Abi
-            });
-            let var = if i == 0 && is_method {
+            let mut var = if i == 0 && is_method {
                 quote! { self }
             } else {
                 arguments.push(quote! { #name: #ty });
                 quote! { #name }
             };
+            let abi_ty = match self.function.argument_wrapper(i) {
+                Some(wrapper) => {
+                    var = quote! { #wrapper(#var) };
+                    quote! { #wrapper<#ty> }
+                }
+                None => quote! { #ty },
+            };
+            abi_arguments.push(quote! {
+                #name: <#abi_ty as wasm_bindgen::convert::IntoWasmAbi>::Abi
+            });
             arg_conversions.push(quote! {
-                let #name = <#ty as wasm_bindgen::convert::IntoWasmAbi>
+                let #name = <#abi_ty as wasm_bindgen::convert::IntoWasmAbi>
                     ::into_abi(#var);
             });
         }
@@ -913,6 +1166,16 @@ impl TryToTokens for ast::ImportFunction {
                     "cannot return references in #[wasm_bindgen] imports yet"
                 );
             }
+            Some(ref ty) if self.function.wrapped_ret.is_some() => {
+                let wrapper = &self.function.wrapped_ret;
+                abi_ret = quote! {
+                    <#wrapper<#ty> as wasm_bindgen::convert::FromWasmAbi>::Abi
+                };
+                convert_ret = quote! {
+                    <#wrapper<#ty> as wasm_bindgen::convert::FromWasmAbi>
+                        ::from_abi(#ret_ident).0
+                };
+            }
             Some(ref ty) => {
                 abi_ret = quote! {
                     <#ty as wasm_bindgen::convert::FromWasmAbi>::Abi
@@ -1032,15 +1295,20 @@ impl<'a> ToTokens for DescribeImport<'a> {
             ast::ImportKind::Type(_) => return,
             ast::ImportKind::Enum(_) => return,
         };
-        let argtys = f.function.arguments.iter().map(|arg| &arg.ty);
+        let argtys = describe_argument_types(&f.function);
         let nargs = f.function.arguments.len() as u32;
         let inform_ret = match &f.js_ret {
+            Some(ref t) if f.function.wrapped_ret.is_some() => {
+                let wrapper = &f.function.wrapped_ret;
+                quote! { <#wrapper<#t> as WasmDescribe>::describe(); }
+            }
             Some(ref t) => quote! { <#t as WasmDescribe>::describe(); },
             None => quote! { <() as WasmDescribe>::describe(); },
         };
 
         Descriptor(
             &f.shim,
+            &[],
             quote! {
                 inform(FUNCTION);
                 inform(0);
@@ -1067,6 +1335,7 @@ impl ToTokens for ast::Enum {
         });
         (quote! {
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             impl wasm_bindgen::convert::IntoWasmAbi for #enum_name {
                 type Abi = u32;
 
@@ -1077,6 +1346,7 @@ impl ToTokens for ast::Enum {
             }
 
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             impl wasm_bindgen::convert::FromWasmAbi for #enum_name {
                 type Abi = u32;
 
@@ -1089,18 +1359,21 @@ impl ToTokens for ast::Enum {
             }
 
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             impl wasm_bindgen::convert::OptionFromWasmAbi for #enum_name {
                 #[inline]
                 fn is_none(val: &u32) -> bool { *val == #hole }
             }
 
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             impl wasm_bindgen::convert::OptionIntoWasmAbi for #enum_name {
                 #[inline]
                 fn none() -> Self::Abi { #hole }
             }
 
             #[allow(clippy::all)]
+            #[allow(deprecated)]
             impl wasm_bindgen::describe::WasmDescribe for #enum_name {
                 fn describe() {
                     use wasm_bindgen::describe::*;
@@ -1113,16 +1386,71 @@ impl ToTokens for ast::Enum {
     }
 }
 
+impl ToTokens for ast::Wrapper {
+    fn to_tokens(&self, into: &mut TokenStream) {
+        (match self {
+            ast::Wrapper::Serde => quote! { wasm_bindgen::Serde },
+            ast::Wrapper::PlainObject => quote! { wasm_bindgen::PlainObject },
+        })
+        .to_tokens(into);
+    }
+}
+
 impl ToTokens for ast::ImportStatic {
     fn to_tokens(&self, into: &mut TokenStream) {
         let name = &self.rust_name;
         let ty = &self.ty;
         let shim_name = &self.shim;
         let vis = &self.vis;
+        let (static_ty, value) = if let Some(setter) = &self.setter {
+            (
+                quote! { wasm_bindgen::JsMutStatic<#ty> },
+                quote! {
+                    fn set(val: #ty) {
+                        #[link(wasm_import_module = "__wbindgen_placeholder__")]
+                        #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+                        extern "C" {
+                            fn #setter(val: <#ty as wasm_bindgen::convert::IntoWasmAbi>::Abi);
+                        }
+                        #[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+                        unsafe fn #setter(_: <#ty as wasm_bindgen::convert::IntoWasmAbi>::Abi) {
+                            panic!("cannot access imported statics on non-wasm targets")
+                        }
+
+                        unsafe {
+                            #setter(<#ty as wasm_bindgen::convert::IntoWasmAbi>::into_abi(val))
+                        }
+                    }
+                    wasm_bindgen::JsMutStatic {
+                        __get: init,
+                        __set: set,
+                    }
+                },
+            )
+        } else if self.thread_local {
+            (
+                quote! { wasm_bindgen::JsThreadLocal<#ty> },
+                quote! {
+                    wasm_bindgen::JsThreadLocal {
+                        __init: init,
+                    }
+                },
+            )
+        } else {
+            (
+                quote! { wasm_bindgen::JsStatic<#ty> },
+                quote! {
+                    thread_local!(static _VAL: #ty = init(););
+                    wasm_bindgen::JsStatic {
+                        __inner: &_VAL,
+                    }
+                },
+            )
+        };
         (quote! {
             #[allow(bad_style)]
             #[allow(clippy::all)]
-            #vis static #name: wasm_bindgen::JsStatic<#ty> = {
+            #vis static #name: #static_ty = {
                 fn init() -> #ty {
                     #[link(wasm_import_module = "__wbindgen_placeholder__")]
                     #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
@@ -1138,10 +1466,7 @@ impl ToTokens for ast::ImportStatic {
                         <#ty as wasm_bindgen::convert::FromWasmAbi>::from_abi(#shim_name())
                     }
                 }
-                thread_local!(static _VAL: #ty = init(););
-                wasm_bindgen::JsStatic {
-                    __inner: &_VAL,
-                }
+                #value
             };
         })
         .to_tokens(into);
@@ -1231,9 +1556,8 @@ impl ToTokens for ast::Dictionary {
             quote! {
                 #[doc = #doc_comment]
                 pub fn new(#(#required_names: #required_types),*) -> #name {
-                    let mut _ret = #name { obj: ::js_sys::Object::new() };
-                    #(_ret.#required_names2(#required_names3);)*
-                    return _ret
+                    #name { obj: ::js_sys::Object::new() }
+                        #(.#required_names2(#required_names3))*
                 }
             }
         } else {
@@ -1368,7 +1692,7 @@ impl ToTokens for ast::DictionaryField {
         (quote! {
             #[allow(clippy::all)]
             #[doc = #doc_comment]
-            pub fn #rust_name(&mut self, val: #ty) -> &mut Self {
+            pub fn #rust_name(self, val: #ty) -> Self {
                 use wasm_bindgen::JsValue;
                 let r = ::js_sys::Reflect::set(
                     self.obj.as_ref(),
@@ -1385,7 +1709,101 @@ impl ToTokens for ast::DictionaryField {
 
 /// Emits the necessary glue tokens for "descriptor", generating an appropriate
 /// symbol name as well as attributes around the descriptor function itself.
-struct Descriptor<'a, T>(&'a Ident, T);
+impl ToTokens for ast::Union {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let vis = &self.vis;
+        let name = &self.name;
+        let variants = &self.variants;
+        let tys = &self.variant_tys;
+
+        // A vector of UnionName::VariantName tokens for this union
+        let variant_paths: Vec<TokenStream> =
+            self.variants.iter().map(|v| quote!(#name::#v)).collect();
+        let variant_paths_ref = &variant_paths;
+
+        let from_impls = variant_paths.iter().zip(tys).map(|(path, ty)| {
+            quote! {
+                impl<'a> From<&'a #ty> for #name<'a> {
+                    #[inline]
+                    fn from(val: &'a #ty) -> #name<'a> {
+                        #path(val)
+                    }
+                }
+            }
+        });
+        let doc_comment = match &self.doc_comment {
+            None => "",
+            Some(doc_string) => doc_string,
+        };
+
+        let const_name = Ident::new(&format!("_CONST_{}", name), Span::call_site());
+        (quote! {
+            #[allow(bad_style)]
+            #[allow(clippy::all)]
+            #[derive(Clone, Copy, Debug)]
+            #[doc = #doc_comment]
+            #vis enum #name<'a> {
+                #(#variants(&'a #tys),)*
+            }
+
+            #[allow(bad_style)]
+            #[allow(clippy::all)]
+            const #const_name: () = {
+                use wasm_bindgen::describe::WasmDescribe;
+                use wasm_bindgen::convert::*;
+                use wasm_bindgen::JsValue;
+
+                #(#from_impls)*
+
+                impl<'a> #name<'a> {
+                    #[inline]
+                    fn as_js_value(self) -> &'a JsValue {
+                        match self {
+                            #(#variant_paths_ref(val) => val.as_ref(),)*
+                        }
+                    }
+                }
+
+                impl<'a> AsRef<JsValue> for #name<'a> {
+                    #[inline]
+                    fn as_ref(&self) -> &JsValue {
+                        self.as_js_value()
+                    }
+                }
+
+                impl<'a> From<#name<'a>> for JsValue {
+                    #[inline]
+                    fn from(val: #name<'a>) -> JsValue {
+                        val.as_js_value().clone()
+                    }
+                }
+
+                // Unions are passed to JS the same way as a `&JsValue`
+                impl<'a> WasmDescribe for #name<'a> {
+                    fn describe() {
+                        <&'a JsValue as WasmDescribe>::describe();
+                    }
+                }
+
+                impl<'a> IntoWasmAbi for #name<'a> {
+                    type Abi = <&'a JsValue as IntoWasmAbi>::Abi;
+                    #[inline]
+                    fn into_abi(self) -> Self::Abi {
+                        self.as_js_value().into_abi()
+                    }
+                }
+
+                impl<'a> OptionIntoWasmAbi for #name<'a> {
+                    #[inline]
+                    fn none() -> Self::Abi { 0 }
+                }
+            };
+        })
+        .to_tokens(tokens);
+    }
+}
+
+struct Descriptor<'a, T>(&'a Ident, &'a [&'a syn::Attribute], T);
 
 impl<'a, T: ToTokens> ToTokens for Descriptor<'a, T> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
@@ -1400,17 +1818,19 @@ impl<'a, T: ToTokens> ToTokens for Descriptor<'a, T> {
         lazy_static::lazy_static! {
             static ref DESCRIPTORS_EMITTED: Mutex<HashSet<String>> = Default::default();
         }
-        if !DESCRIPTORS_EMITTED
-            .lock()
-            .unwrap()
-            .insert(self.0.to_string())
-        {
+        //
+        // Descriptors gated on different `#[cfg]` attributes are all emitted,
+        // since at most one of them is compiled.
+        let attrs = self.1;
+        let key = format!("{}{}", quote!(#(#attrs)*), self.0);
+        if !DESCRIPTORS_EMITTED.lock().unwrap().insert(key) {
             return;
         }
 
         let name = Ident::new(&format!("__wbindgen_describe_{}", self.0), self.0.span());
-        let inner = &self.1;
+        let inner = &self.2;
         (quote! {
+            #(#attrs)*
             #[no_mangle]
             #[allow(non_snake_case)]
             #[doc(hidden)]
@@ -1427,6 +1847,32 @@ impl<'a, T: ToTokens> ToTokens for Descriptor<'a, T> {
     }
 }
 
+/// Returns the types describing the arguments of `function`, where arguments
+/// converted through a wrapper are described as the wrapper, for example
+/// `wasm_bindgen::Serde<T>`. Wrapped arguments are always received as owned
+/// values, so a `&T` argument is described as the wrapper of `T`.
+fn describe_argument_types(function: &ast::Function) -> Vec<TokenStream> {
+    function
+        .arguments
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| {
+            let ty = &arg.ty;
+            match function.argument_wrapper(i) {
+                Some(wrapper) => match ty {
+                    syn::Type::Reference(syn::TypeReference {
+                        mutability: None,
+                        elem,
+                        ..
+                    }) => quote! { #wrapper<#elem> },
+                    _ => quote! { #wrapper<#ty> },
+                },
+                None => quote! { #ty },
+            }
+        })
+        .collect()
+}
+
 /// Converts `span` into a stream of tokens, and attempts to ensure that `input`
 /// has all the appropriate span information so errors in it point to `span`.
 fn respan(input: TokenStream, span: &dyn ToTokens) -> TokenStream {
@@ -94,6 +94,7 @@ impl ImportedTypes for ast::Program {
         self.imports.imported_types(f);
         self.consts.imported_types(f);
         self.dictionaries.imported_types(f);
+        self.unions.imported_types(f);
     }
 }
 
@@ -332,6 +333,18 @@ impl ImportedTypes for ast::DictionaryField {
     }
 }
 
+impl ImportedTypes for ast::Union {
+    fn imported_types<F>(&self, f: &mut F)
+    where
+        F: FnMut(&Ident, ImportedTypeKind),
+    {
+        f(&self.name, ImportedTypeKind::Definition);
+        for ty in self.variant_tys.iter() {
+            ty.imported_types(f);
+        }
+    }
+}
+
 /// Remove any methods, statics, &c, that reference types that are *not*
 /// defined.
 pub trait RemoveUndefinedImports {
@@ -348,6 +361,30 @@ impl RemoveUndefinedImports for ast::Program {
         let mut changed = self.imports.remove_undefined_imports(is_defined);
         changed = self.consts.remove_undefined_imports(is_defined) || changed;
 
+        // Unions remain usable with the members which are defined, so only
+        // remove those variants, and the union once none are left.
+        for union in self.unions.iter_mut() {
+            let before = union.variants.len();
+            let (variants, variant_tys) = union
+                .variants
+                .drain(..)
+                .zip(union.variant_tys.drain(..))
+                .filter(|(_, ty)| {
+                    let mut all_defined = true;
+                    ty.imported_type_references(&mut |id| {
+                        all_defined = all_defined && is_defined(id);
+                    });
+                    all_defined
+                })
+                .unzip();
+            union.variants = variants;
+            union.variant_tys = variant_tys;
+            changed = before != union.variants.len() || changed;
+        }
+        let before = self.unions.len();
+        self.unions.retain(|union| !union.variants.is_empty());
+        changed = before != self.unions.len() || changed;
+
         for dictionary in self.dictionaries.iter_mut() {
             let num_required =
                 |dict: &ast::Dictionary| dict.fields.iter().filter(|f| f.required).count();
@@ -24,6 +24,7 @@ pub fn encode(program: &ast::Program) -> Result<EncodeResult, Diagnostic> {
         .borrow()
         .values()
         .map(|p| &p.path)
+        .chain(i.linked_files.borrow().iter().map(|p| &p.path))
         .cloned()
         .collect();
     Ok(EncodeResult {
@@ -35,6 +36,7 @@ pub fn encode(program: &ast::Program) -> Result<EncodeResult, Diagnostic> {
 struct Interner {
     bump: bumpalo::Bump,
     files: RefCell<HashMap<String, LocalFile>>,
+    linked_files: RefCell<Vec<LinkedPath>>,
     root: PathBuf,
     crate_name: String,
     has_package_json: Cell<bool>,
@@ -46,11 +48,18 @@ struct LocalFile {
     new_identifier: String,
 }
 
+struct LinkedPath {
+    path: PathBuf,
+    definition: Span,
+    relative: String,
+}
+
 impl Interner {
     fn new() -> Interner {
         Interner {
             bump: bumpalo::Bump::new(),
             files: RefCell::new(HashMap::new()),
+            linked_files: RefCell::new(Vec::new()),
             root: env::var_os("CARGO_MANIFEST_DIR").unwrap().into(),
             crate_name: env::var("CARGO_PKG_NAME").unwrap(),
             has_package_json: Cell::new(false),
@@ -101,6 +110,26 @@ impl Interner {
         self.resolve_import_module(id, span)
     }
 
+    /// Records that the file at `path`, relative to the crate root, is linked
+    /// to by a `link_to!` import, so that its contents are embedded in the
+    /// custom section, and returns the path it's identified by there.
+    fn resolve_linked_file(&self, path: &str, span: Span) -> Result<&str, Diagnostic> {
+        let relative = path.trim_start_matches('/');
+        if relative.is_empty() || relative.ends_with('/') {
+            let msg = format!("`{}` is not a path to a file", path);
+            return Err(Diagnostic::span_error(span, msg));
+        }
+        let mut files = self.linked_files.borrow_mut();
+        if !files.iter().any(|f| f.relative == relative) {
+            files.push(LinkedPath {
+                path: self.root.join(relative),
+                definition: span,
+                relative: relative.to_string(),
+            });
+        }
+        Ok(self.intern_str(relative))
+    }
+
     fn unique_crate_identifier(&self) -> String {
         format!("{}-{}", self.crate_name, ShortHash(0))
     }
@@ -158,6 +187,22 @@ fn shared_program<'a>(
                     })
             })
             .collect::<Result<Vec<_>, _>>()?,
+        linked_files: intern
+            .linked_files
+            .borrow()
+            .iter()
+            .map(|file| {
+                fs::read(&file.path)
+                    .map(|contents| LinkedFile {
+                        path: intern.intern_str(&file.relative),
+                        contents: intern.bump.alloc_slice_copy(&contents),
+                    })
+                    .map_err(|e| {
+                        let msg = format!("failed to read file `{}`: {}", file.path.display(), e);
+                        Diagnostic::span_error(file.definition, msg)
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
         inline_js: prog
             .inline_js
             .iter()
@@ -177,17 +222,26 @@ fn shared_export<'a>(
     intern: &'a Interner,
 ) -> Result<Export<'a>, Diagnostic> {
     let consumed = match export.method_self {
-        Some(ast::MethodSelf::ByValue) => true,
+        Some(ast::MethodSelf::ByValue) => !export.chain,
         _ => false,
     };
     let method_kind = from_ast_method_kind(&export.function, intern, &export.method_kind)?;
     Ok(Export {
         class: export.js_class.as_ref().map(|s| &**s),
+        js_namespace: export.js_namespace.iter().map(|s| &**s).collect(),
         comments: export.comments.iter().map(|s| &**s).collect(),
         consumed,
+        chain: export.chain,
         function: shared_function(&export.function, intern),
         method_kind,
-        start: export.start,
+        cfg: export
+            .function
+            .cfg_condition()
+            .map(|cfg| intern.intern_str(&cfg)),
+        start: export.start.as_ref().map(|start| Start {
+            priority: start.priority,
+            asyncness: start.asyncness,
+        }),
     })
 }
 
@@ -207,6 +261,16 @@ fn shared_function<'a>(func: &'a ast::Function, _intern: &'a Interner) -> Functi
     Function {
         arg_names,
         name: &func.name,
+        typescript_types: func
+            .typescript_types
+            .iter()
+            .map(|(name, ty)| TypescriptType { name, ty })
+            .collect(),
+        arg_defaults: func
+            .arg_defaults
+            .iter()
+            .map(|(name, value)| ArgDefault { name, value })
+            .collect(),
     }
 }
 
@@ -275,6 +339,11 @@ fn shared_import_function<'a>(
         structural: i.structural,
         function: shared_function(&i.function, intern),
         variadic: i.variadic,
+        vendor_prefixes: i.vendor_prefixes.iter().map(|x| intern.intern(x)).collect(),
+        link_to: match &i.link_to {
+            Some((path, span)) => Some(intern.resolve_linked_file(path, *span)?),
+            None => None,
+        },
     })
 }
 
@@ -282,6 +351,8 @@ fn shared_import_static<'a>(i: &'a ast::ImportStatic, intern: &'a Interner) -> I
     ImportStatic {
         name: &i.js_name,
         shim: intern.intern(&i.shim),
+        setter: i.setter.as_ref().map(|s| intern.intern(s)),
+        thread_local: i.thread_local,
     }
 }
 
@@ -300,21 +371,21 @@ fn shared_import_enum<'a>(_i: &'a ast::ImportEnum, _intern: &'a Interner) -> Imp
 fn shared_struct<'a>(s: &'a ast::Struct, intern: &'a Interner) -> Struct<'a> {
     Struct {
         name: &s.js_name,
+        js_namespace: s.js_namespace.iter().map(|s| &**s).collect(),
         fields: s
             .fields
             .iter()
             .map(|s| shared_struct_field(s, intern))
             .collect(),
         comments: s.comments.iter().map(|s| &**s).collect(),
+        no_free: s.no_free,
+        on_free: s.on_free.as_ref().map(|s| &**s),
     }
 }
 
-fn shared_struct_field<'a>(s: &'a ast::StructField, intern: &'a Interner) -> StructField<'a> {
+fn shared_struct_field<'a>(s: &'a ast::StructField, _intern: &'a Interner) -> StructField<'a> {
     StructField {
-        name: match &s.name {
-            syn::Member::Named(ident) => intern.intern(ident),
-            syn::Member::Unnamed(index) => intern.intern_str(&index.index.to_string()),
-        },
+        name: &s.js_name,
         readonly: s.readonly,
         comments: s.comments.iter().map(|s| &**s).collect(),
     }
@@ -367,6 +438,12 @@ impl Encode for u32 {
     }
 }
 
+impl Encode for i32 {
+    fn encode(&self, dst: &mut Encoder) {
+        (*self as u32).encode(dst);
+    }
+}
+
 impl Encode for usize {
     fn encode(&self, dst: &mut Encoder) {
         assert!(*self <= u32::max_value() as usize);
@@ -37,12 +37,24 @@ impl<'src> Decode<'src> for u32 {
     }
 }
 
-impl<'src> Decode<'src> for &'src str {
-    fn decode(data: &mut &'src [u8]) -> &'src str {
+impl<'src> Decode<'src> for i32 {
+    fn decode(data: &mut &'src [u8]) -> Self {
+        u32::decode(data) as i32
+    }
+}
+
+impl<'src> Decode<'src> for &'src [u8] {
+    fn decode(data: &mut &'src [u8]) -> &'src [u8] {
         let n = u32::decode(data);
         let (a, b) = data.split_at(n as usize);
         *data = b;
-        let r = str::from_utf8(a).unwrap();
+        a
+    }
+}
+
+impl<'src> Decode<'src> for &'src str {
+    fn decode(data: &mut &'src [u8]) -> &'src str {
+        let r = str::from_utf8(<&'src [u8]>::decode(data)).unwrap();
         log::trace!("decoded string {:?}", r);
         r
     }
@@ -36,6 +36,10 @@ tys! {
     OPTIONAL
     UNIT
     CLAMPED
+    NAMED_ANYREF
+    MAP
+    PLAIN_OBJECT
+    CALLBACK
 }
 
 #[derive(Debug, Clone)]
@@ -60,11 +64,14 @@ pub enum Descriptor {
     Vector(Box<Descriptor>),
     String,
     Anyref,
+    NamedAnyref { js_name: String, rust_name: String },
     Enum { hole: u32 },
     RustStruct(String),
     Char,
     Option(Box<Descriptor>),
     Unit,
+    Map { object: bool },
+    Callback(Box<Callback>),
 }
 
 #[derive(Debug, Clone)]
@@ -74,6 +81,12 @@ pub struct Function {
     pub ret: Descriptor,
 }
 
+#[derive(Debug, Clone)]
+pub struct Callback {
+    pub arguments: Vec<Descriptor>,
+    pub ret: Descriptor,
+}
+
 #[derive(Debug, Clone)]
 pub struct Closure {
     pub shim_idx: u32,
@@ -130,15 +143,20 @@ impl Descriptor {
             STRING => Descriptor::String,
             ANYREF => Descriptor::Anyref,
             ENUM => Descriptor::Enum { hole: get(data) },
-            RUST_STRUCT => {
-                let name = (0..get(data))
-                    .map(|_| char::from_u32(get(data)).unwrap())
-                    .collect();
-                Descriptor::RustStruct(name)
-            }
+            RUST_STRUCT => Descriptor::RustStruct(get_string(data)),
             CHAR => Descriptor::Char,
             UNIT => Descriptor::Unit,
             CLAMPED => Descriptor::_decode(data, true),
+            NAMED_ANYREF => Descriptor::NamedAnyref {
+                js_name: get_string(data),
+                rust_name: get_string(data),
+            },
+            MAP => Descriptor::Map { object: false },
+            PLAIN_OBJECT => match Descriptor::_decode(data, clamped) {
+                Descriptor::Map { .. } => Descriptor::Map { object: true },
+                other => panic!("`PlainObject` can only wrap maps, found {:?}", other),
+            },
+            CALLBACK => Descriptor::Callback(Box::new(Callback::decode(data))),
             other => panic!("unknown descriptor: {}", other),
         }
     }
@@ -157,6 +175,66 @@ impl Descriptor {
         }
     }
 
+    /// Returns the TypeScript type of values described by `self` when they're
+    /// passed to JS, used for the signatures of callbacks.
+    pub fn typescript(&self) -> String {
+        match self {
+            Descriptor::I8
+            | Descriptor::U8
+            | Descriptor::ClampedU8
+            | Descriptor::I16
+            | Descriptor::U16
+            | Descriptor::I32
+            | Descriptor::U32
+            | Descriptor::F32
+            | Descriptor::F64
+            | Descriptor::Enum { .. } => "number".to_string(),
+            Descriptor::I64 | Descriptor::U64 => "BigInt".to_string(),
+            Descriptor::Boolean => "boolean".to_string(),
+            Descriptor::Char | Descriptor::String => "string".to_string(),
+            Descriptor::RustStruct(class) => class.clone(),
+            Descriptor::Ref(d) | Descriptor::RefMut(d) => match self.vector_kind() {
+                Some(kind) => kind.js_ty().to_string(),
+                None => d.typescript(),
+            },
+            Descriptor::Vector(_) | Descriptor::Slice(_) => match self.vector_kind() {
+                Some(kind) => kind.js_ty().to_string(),
+                None => "any".to_string(),
+            },
+            Descriptor::Option(d) => match **d {
+                Descriptor::Callback(_) => format!("({}) | undefined", d.typescript()),
+                _ => format!("{} | undefined", d.typescript()),
+            },
+            Descriptor::Unit => "void".to_string(),
+            Descriptor::Callback(callback) => {
+                let args = callback
+                    .arguments
+                    .iter()
+                    .enumerate()
+                    .map(|(i, arg)| format!("arg{}: {}", i, arg.typescript()))
+                    .collect::<Vec<_>>();
+                format!("({}) => {}", args.join(", "), callback.ret.typescript())
+            }
+            Descriptor::Function(_)
+            | Descriptor::Closure(_)
+            | Descriptor::Anyref
+            | Descriptor::NamedAnyref { .. }
+            | Descriptor::Map { .. } => "any".to_string(),
+        }
+    }
+
+    /// Returns the TypeScript signature of the callback described by `self`,
+    /// if it is one, possibly behind a reference or in an `Option`.
+    pub fn callback_typescript(&self) -> Option<String> {
+        match self {
+            Descriptor::Callback(_) => Some(self.typescript()),
+            Descriptor::Ref(d) => d.callback_typescript(),
+            // The `| undefined` is appended to the type later on
+            Descriptor::Option(d) => d.callback_typescript().map(|ts| format!("({})", ts)),
+            _ => None,
+        }
+    }
+
     pub fn vector_kind(&self) -> Option<VectorKind> {
         let inner = match *self {
             Descriptor::String => return Some(VectorKind::String),
@@ -185,7 +263,7 @@ impl Descriptor {
             Descriptor::U64 => Some(VectorKind::U64),
             Descriptor::F32 => Some(VectorKind::F32),
             Descriptor::F64 => Some(VectorKind::F64),
-            Descriptor::Anyref => Some(VectorKind::Anyref),
+            Descriptor::Anyref | Descriptor::NamedAnyref { .. } => Some(VectorKind::Anyref),
             _ => None,
         }
     }
@@ -197,6 +275,12 @@ fn get(a: &mut &[u32]) -> u32 {
     ret
 }
 
+fn get_string(data: &mut &[u32]) -> String {
+    (0..get(data))
+        .map(|_| char::from_u32(get(data)).unwrap())
+        .collect()
+}
+
 impl Closure {
     fn decode(data: &mut &[u32]) -> Closure {
         let shim_idx = get(data);
@@ -212,6 +296,18 @@ impl Closure {
     }
 }
 
+impl Callback {
+    fn decode(data: &mut &[u32]) -> Callback {
+        let arguments = (0..get(data))
+            .map(|_| Descriptor::_decode(data, false))
+            .collect::<Vec<_>>();
+        Callback {
+            arguments,
+            ret: Descriptor::_decode(data, false),
+        }
+    }
+}
+
 impl Function {
     fn decode(data: &mut &[u32]) -> Function {
         let shim_idx = get(data);
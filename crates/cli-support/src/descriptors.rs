@@ -11,18 +11,23 @@
 //! functions.
 
 use crate::descriptor::{Closure, Descriptor};
-use failure::Error;
+use failure::{bail, Error};
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::mem;
 use walrus::ImportId;
 use walrus::{CustomSection, FunctionId, LocalFunction, Module, TypedCustomSectionId};
 use wasm_bindgen_wasm_interpreter::Interpreter;
 
+const MODULE_PATH_PREFIX: &str = "__wbindgen_module_path_";
+
 #[derive(Default, Debug)]
 pub struct WasmBindgenDescriptorsSection {
     pub descriptors: HashMap<String, Descriptor>,
     pub closure_imports: HashMap<ImportId, Closure>,
+    /// The Rust module path, relative to the crate root, of exported free
+    /// functions keyed by the name of their export.
+    pub module_paths: HashMap<String, Vec<String>>,
 }
 
 pub type WasmBindgenDescriptorsSectionId = TypedCustomSectionId<WasmBindgenDescriptorsSection>;
@@ -30,11 +35,19 @@ pub type WasmBindgenDescriptorsSectionId = TypedCustomSectionId<WasmBindgenDescr
 /// Execute all `__wbindgen_describe_*` functions in a module, inserting a
 /// custom section which represents the executed value of each descriptor.
 ///
-/// Afterwards this will delete all descriptor functions from the module.
-pub fn execute(module: &mut Module) -> Result<WasmBindgenDescriptorsSectionId, Error> {
+/// Afterwards this will delete all descriptor functions from the module, which
+/// includes the functions only generated for `--demangle-exports`, whether or
+/// not `demangle_exports` is set.
+pub fn execute(
+    module: &mut Module,
+    demangle_exports: bool,
+) -> Result<WasmBindgenDescriptorsSectionId, Error> {
     let mut section = WasmBindgenDescriptorsSection::default();
     let mut interpreter = Interpreter::new(module)?;
 
+    if demangle_exports {
+        section.find_module_paths(module)?;
+    }
     section.execute_exports(module, &mut interpreter)?;
     section.execute_closures(module, &mut interpreter)?;
 
@@ -78,6 +91,13 @@ impl WasmBindgenDescriptorsSection {
                 walrus::ExportItem::Function(id) => id,
                 _ => panic!("{} export not a function", export.name),
             };
+            // These only keep the functions found by `find_module_paths`
+            // alive, there's nothing to learn from executing them. Removing
+            // the export lets the GC below strip those functions too.
+            if export.name[prefix.len()..].starts_with(MODULE_PATH_PREFIX) {
+                to_remove.push(export.id());
+                continue;
+            }
             if let Some(d) = interpreter.interpret_descriptor(id, module) {
                 let name = &export.name[prefix.len()..];
                 let descriptor = Descriptor::decode(d);
@@ -92,6 +112,84 @@ impl WasmBindgenDescriptorsSection {
         Ok(())
     }
 
+    /// Finds the functions generated next to exported free functions to learn
+    /// about their module path, see the `ast::Export` codegen in the backend.
+    ///
+    /// This relies on the `name` section and on the symbols in it being
+    /// demangleable, so an error is returned if any free export's module path
+    /// can't be found that way.
+    fn find_module_paths(&mut self, module: &Module) -> Result<(), Error> {
+        let prefix = format!("__wbindgen_describe_{}", MODULE_PATH_PREFIX);
+        let exports = module
+            .exports
+            .iter()
+            .filter(|e| e.name.starts_with(&prefix))
+            .map(|e| e.name[prefix.len()..].to_string())
+            .collect::<HashSet<_>>();
+        if exports.is_empty() {
+            return Ok(());
+        }
+        if module.funcs.iter().all(|f| f.name.is_none()) {
+            bail!(
+                "`--demangle-exports` needs the `name` section of the wasm file \
+                 to find the Rust module of exports, but it was stripped"
+            );
+        }
+
+        let mut mangled = HashMap::new();
+        for func in module.funcs.iter() {
+            let name = match &func.name {
+                Some(name) => name,
+                None => continue,
+            };
+            // Names are already demangled unless `--no-demangle` is passed.
+            let demangled = match rustc_demangle::try_demangle(name) {
+                Ok(sym) => format!("{:#}", sym),
+                Err(_) => name.to_string(),
+            };
+            let mut path = demangled
+                .split("::")
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>();
+            let last = path.pop().unwrap();
+            if !last.starts_with(MODULE_PATH_PREFIX) {
+                continue;
+            }
+            let export = last[MODULE_PATH_PREFIX.len()..].to_string();
+            if path.is_empty() {
+                mangled.insert(export, name.to_string());
+                continue;
+            }
+            // Skip the name of the crate itself.
+            path.remove(0);
+            self.module_paths.insert(export, path);
+        }
+
+        let mut missing = exports
+            .iter()
+            .filter(|e| !self.module_paths.contains_key(*e))
+            .collect::<Vec<_>>();
+        missing.sort();
+        if let Some(export) = missing.first() {
+            match mangled.get(*export) {
+                Some(name) => bail!(
+                    "`--demangle-exports` failed to find the Rust module of `{}` \
+                     because the symbol `{}` in the `name` section of the wasm \
+                     file couldn't be demangled",
+                    export,
+                    name
+                ),
+                None => bail!(
+                    "`--demangle-exports` failed to find the Rust module of `{}` \
+                     because its symbol is missing from the `name` section of \
+                     the wasm file",
+                    export
+                ),
+            }
+        }
+        Ok(())
+    }
+
     fn execute_closures(
         &mut self,
         module: &mut Module,
@@ -110,7 +208,9 @@ impl WasmBindgenDescriptorsSection {
         // specially codegen'd so we know the rough structure of them. For each
         // one we delegate to the interpreter to figure out the actual result.
         let mut element_removal_list = HashSet::new();
-        let mut func_to_descriptor = HashMap::new();
+        // Sorted so that the closure imports below are added in a
+        // deterministic order.
+        let mut func_to_descriptor = BTreeMap::new();
         for (id, local) in module.funcs.iter_local() {
             let entry = local.entry_block();
             let mut find = FindDescribeClosure {
@@ -67,6 +67,63 @@ intrinsics! {
         #[symbol = "__wbindgen_jsval_eq"]
         #[signature = fn(ref_anyref(), ref_anyref()) -> Boolean]
         JsvalEq,
+        #[symbol = "__wbindgen_jsval_loose_eq"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Boolean]
+        JsvalLooseEq,
+        #[symbol = "__wbindgen_object_is"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Boolean]
+        ObjectIs,
+        #[symbol = "__wbindgen_lt"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Boolean]
+        Lt,
+        #[symbol = "__wbindgen_le"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Boolean]
+        Le,
+        #[symbol = "__wbindgen_gt"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Boolean]
+        Gt,
+        #[symbol = "__wbindgen_ge"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Boolean]
+        Ge,
+        #[symbol = "__wbindgen_add"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Anyref]
+        Add,
+        #[symbol = "__wbindgen_sub"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Anyref]
+        Sub,
+        #[symbol = "__wbindgen_mul"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Anyref]
+        Mul,
+        #[symbol = "__wbindgen_div"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Anyref]
+        Div,
+        #[symbol = "__wbindgen_rem"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Anyref]
+        Rem,
+        #[symbol = "__wbindgen_pow"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Anyref]
+        Pow,
+        #[symbol = "__wbindgen_bit_and"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Anyref]
+        BitAnd,
+        #[symbol = "__wbindgen_bit_or"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Anyref]
+        BitOr,
+        #[symbol = "__wbindgen_bit_xor"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Anyref]
+        BitXor,
+        #[symbol = "__wbindgen_shl"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Anyref]
+        Shl,
+        #[symbol = "__wbindgen_shr"]
+        #[signature = fn(ref_anyref(), ref_anyref()) -> Anyref]
+        Shr,
+        #[symbol = "__wbindgen_neg"]
+        #[signature = fn(ref_anyref()) -> Anyref]
+        Neg,
+        #[symbol = "__wbindgen_bit_not"]
+        #[signature = fn(ref_anyref()) -> Anyref]
+        BitNot,
         #[symbol = "__wbindgen_is_function"]
         #[signature = fn(ref_anyref()) -> Boolean]
         IsFunction,
@@ -85,6 +142,15 @@ intrinsics! {
         #[symbol = "__wbindgen_is_string"]
         #[signature = fn(ref_anyref()) -> Boolean]
         IsString,
+        #[symbol = "__wbindgen_is_bigint"]
+        #[signature = fn(ref_anyref()) -> Boolean]
+        IsBigInt,
+        #[symbol = "__wbindgen_typeof"]
+        #[signature = fn(ref_anyref()) -> U32]
+        Typeof,
+        #[symbol = "__wbindgen_has_constructor_named"]
+        #[signature = fn(ref_anyref(), ref_string()) -> Boolean]
+        HasConstructorNamed,
         #[symbol = "__wbindgen_object_clone_ref"]
         #[signature = fn(ref_anyref()) -> Anyref]
         ObjectCloneRef,
@@ -100,6 +166,15 @@ intrinsics! {
         #[symbol = "__wbindgen_number_new"]
         #[signature = fn(F64) -> Anyref]
         NumberNew,
+        #[symbol = "__wbindgen_bigint_from_i64"]
+        #[signature = fn(U32, U32) -> Anyref]
+        BigIntFromI64,
+        #[symbol = "__wbindgen_bigint_from_u64"]
+        #[signature = fn(U32, U32) -> Anyref]
+        BigIntFromU64,
+        #[symbol = "__wbindgen_bigint_from_str"]
+        #[signature = fn(ref_string()) -> Anyref]
+        BigIntFromStr,
         #[symbol = "__wbindgen_string_new"]
         #[signature = fn(ref_string()) -> Anyref]
         StringNew,
@@ -112,6 +187,12 @@ intrinsics! {
         #[symbol = "__wbindgen_number_get"]
         #[signature = fn(ref_anyref(), I32) -> F64]
         NumberGet,
+        #[symbol = "__wbindgen_bigint_get_as_i64"]
+        #[signature = fn(ref_anyref(), I32) -> Boolean]
+        BigIntGetAsI64,
+        #[symbol = "__wbindgen_bigint_get_as_u64"]
+        #[signature = fn(ref_anyref(), I32) -> Boolean]
+        BigIntGetAsU64,
         #[symbol = "__wbindgen_string_get"]
         #[signature = fn(ref_anyref(), I32) -> I32]
         StringGet,
@@ -121,12 +202,21 @@ intrinsics! {
         #[symbol = "__wbindgen_throw"]
         #[signature = fn(ref_string()) -> Unit]
         Throw,
+        #[symbol = "__wbindgen_panic_error"]
+        #[signature = fn(ref_string(), ref_string()) -> Unit]
+        PanicError,
+        #[symbol = "__wbindgen_error_new"]
+        #[signature = fn(ref_string(), ref_string()) -> Anyref]
+        ErrorNew,
         #[symbol = "__wbindgen_rethrow"]
         #[signature = fn(Anyref) -> Unit]
         Rethrow,
         #[symbol = "__wbindgen_memory"]
         #[signature = fn() -> Anyref]
         Memory,
+        #[symbol = "__wbindgen_memory_watch"]
+        #[signature = fn() -> Unit]
+        MemoryWatch,
         #[symbol = "__wbindgen_module"]
         #[signature = fn() -> Anyref]
         Module,
@@ -142,9 +232,42 @@ intrinsics! {
         #[symbol = "__wbindgen_json_serialize"]
         #[signature = fn(ref_anyref()) -> String]
         JsonSerialize,
+        #[symbol = "__wbindgen_object_new"]
+        #[signature = fn() -> Anyref]
+        ObjectNew,
+        #[symbol = "__wbindgen_object_keys"]
+        #[signature = fn(ref_anyref()) -> Anyref]
+        ObjectKeys,
+        #[symbol = "__wbindgen_object_get"]
+        #[signature = fn(ref_anyref(), ref_string()) -> Anyref]
+        ObjectGet,
+        #[symbol = "__wbindgen_object_set"]
+        #[signature = fn(ref_anyref(), ref_string(), Anyref) -> Unit]
+        ObjectSet,
+        #[symbol = "__wbindgen_array_new"]
+        #[signature = fn() -> Anyref]
+        ArrayNew,
+        #[symbol = "__wbindgen_is_array"]
+        #[signature = fn(ref_anyref()) -> Boolean]
+        IsArray,
+        #[symbol = "__wbindgen_array_length"]
+        #[signature = fn(ref_anyref()) -> U32]
+        ArrayLength,
+        #[symbol = "__wbindgen_array_get"]
+        #[signature = fn(ref_anyref(), U32) -> Anyref]
+        ArrayGet,
+        #[symbol = "__wbindgen_array_push"]
+        #[signature = fn(ref_anyref(), Anyref) -> Unit]
+        ArrayPush,
         #[symbol = "__wbindgen_anyref_heap_live_count"]
         #[signature = fn() -> I32]
         AnyrefHeapLiveCount,
+        #[symbol = "__wbindgen_heap_capacity"]
+        #[signature = fn() -> U32]
+        HeapCapacity,
+        #[symbol = "__wbindgen_heap_allocation_sites"]
+        #[signature = fn() -> Anyref]
+        HeapAllocationSites,
         #[symbol = "__wbindgen_init_nyref_table"]
         #[signature = fn() -> Unit]
         InitAnyrefTable,
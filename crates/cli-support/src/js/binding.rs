@@ -8,6 +8,7 @@ use crate::js::incoming;
 use crate::js::outgoing;
 use crate::js::Context;
 use crate::webidl::Binding;
+use crate::JsFlavor;
 use failure::{bail, Error};
 use std::collections::HashSet;
 use wasm_webidl_bindings::ast;
@@ -43,9 +44,24 @@ pub struct Builder<'a, 'b> {
     /// Whether or not this is building a method of a Rust class instance, and
     /// whether or not the method consumes `self` or not.
     method: Option<bool>,
+    /// Whether or not this is a chained method of a Rust class instance which
+    /// returns `this`, and if so the name of that class.
+    chain: Option<String>,
     /// Whether or not we're catching exceptions from the main function
     /// invocation. Currently only used for imports.
     catch: bool,
+    /// A human readable name for the function being bound, used in debug mode
+    /// to report where an unexpected exception came from.
+    debug_name: Option<String>,
+    /// Default values, as JS expressions, for arguments of the function shim
+    /// which are used when the argument is `undefined`.
+    arg_defaults: Vec<(String, String)>,
+    /// Whether this is an export which turns traps of the wasm instance into
+    /// exceptions, and refuses to run once the instance has trapped.
+    poison_on_panic: bool,
+    /// Whether this is an export whose returned promises are wrapped in debug
+    /// mode so rejections caused by a trap name the export.
+    annotate_rejections: bool,
 }
 
 /// Helper struct used in incoming/outgoing to generate JS.
@@ -63,6 +79,18 @@ pub struct TypescriptArg {
     pub optional: bool,
 }
 
+impl TypescriptArg {
+    /// Returns the type of this argument, including `undefined` if it's
+    /// optional.
+    pub fn full_ty(&self) -> String {
+        if self.optional {
+            format!("{} | undefined", self.ty)
+        } else {
+            self.ty.clone()
+        }
+    }
+}
+
 impl<'a, 'b> Builder<'a, 'b> {
     pub fn new(cx: &'a mut Context<'b>) -> Builder<'a, 'b> {
         Builder {
@@ -78,7 +106,12 @@ impl<'a, 'b> Builder<'a, 'b> {
             ts_ret: None,
             constructor: None,
             method: None,
+            chain: None,
             catch: false,
+            debug_name: None,
+            arg_defaults: Vec::new(),
+            poison_on_panic: false,
+            annotate_rejections: false,
         }
     }
 
@@ -86,10 +119,32 @@ impl<'a, 'b> Builder<'a, 'b> {
         self.method = Some(consumed);
     }
 
+    pub fn chain(&mut self, class: &str) {
+        self.chain = Some(class.to_string());
+    }
+
     pub fn constructor(&mut self, class: &str) {
         self.constructor = Some(class.to_string());
     }
 
+    pub fn debug_name(&mut self, name: &str) {
+        self.debug_name = Some(name.to_string());
+    }
+
+    pub fn arg_defaults(&mut self, defaults: &[(String, String)]) {
+        self.arg_defaults = defaults.to_vec();
+    }
+
+    pub fn poison_on_panic(&mut self) {
+        self.cx.expose_poison_on_panic();
+        self.poison_on_panic = true;
+    }
+
+    pub fn annotate_rejections(&mut self) {
+        self.cx.expose_annotate_rejection();
+        self.annotate_rejections = true;
+    }
+
     pub fn catch(&mut self, catch: bool) -> Result<(), Error> {
         if catch {
             self.cx.expose_handle_error()?;
@@ -111,6 +166,25 @@ impl<'a, 'b> Builder<'a, 'b> {
             self.cx.expose_log_error();
         }
 
+        if self.cx.config.performance_marks {
+            if incoming_args && self.debug_name.is_some() {
+                self.cx.expose_mark_first_call();
+                self.args_prelude.push_str("markFirstCall();\n");
+            }
+            if let (true, Some(name)) = (self.cx.config.debug, &self.debug_name) {
+                self.cx.expose_count_call()?;
+                self.args_prelude
+                    .push_str(&format!("countCall({});\n", js_string(name)));
+            }
+        }
+
+        // Calls from wasm into JS are where the memory is most likely to have
+        // grown, so check before JS gets a chance to look at it.
+        if !incoming_args && self.cx.memory_growth_hooks {
+            self.cx.expose_check_memory_grown();
+            self.args_prelude.push_str("checkMemoryGrown();\n");
+        }
+
         // First up we handle all the arguments. Depending on whether incoming
         // or outgoing ar the arguments this is pretty different.
         let mut arg_names = Vec::new();
@@ -140,13 +214,15 @@ impl<'a, 'b> Builder<'a, 'b> {
             match self.method {
                 Some(true) => {
                     drop(webidl_params.next());
-                    self.args_prelude.push_str("const ptr = this.ptr;\n");
-                    self.args_prelude.push_str("this.ptr = 0;\n");
+                    let this_ptr = self.cx.this_ptr();
+                    self.args_prelude
+                        .push_str(&format!("const ptr = {};\n", this_ptr));
+                    self.args_prelude.push_str(&format!("{} = 0;\n", this_ptr));
                     arg_names.push("ptr".to_string());
                 }
                 Some(false) => {
                     drop(webidl_params.next());
-                    arg_names.push("this.ptr".to_string());
+                    arg_names.push(self.cx.this_ptr().to_string());
                 }
                 None => {}
             }
@@ -189,6 +265,15 @@ impl<'a, 'b> Builder<'a, 'b> {
             }
         }
 
+        // Substitute the default values of omitted arguments before any of
+        // them are converted.
+        for (name, value) in self.arg_defaults.iter() {
+            if self.function_args.contains(name) {
+                self.args_prelude
+                    .push_str(&format!("if ({0} === undefined) {0} = {1};\n", name, value));
+            }
+        }
+
         // Save off the results of JS generation for the arguments.
         self.args_prelude.push_str(&js.prelude);
         self.finally.push_str(&js.finally);
@@ -200,6 +285,24 @@ impl<'a, 'b> Builder<'a, 'b> {
             self.ts_args.remove(0);
         }
 
+        // Arguments with a default value can always be omitted.
+        for arg in self.ts_args.iter_mut() {
+            if self.arg_defaults.iter().any(|(name, _)| *name == arg.name) {
+                arg.optional = true;
+            }
+        }
+
+        // Chained methods return nothing from wasm since the result has been
+        // written back in place, so the JS shim hands back the same object.
+        if let Some(class) = &self.chain {
+            self.ts_ret = Some(TypescriptArg {
+                ty: class.clone(),
+                name: "return".to_string(),
+                optional: false,
+            });
+            self.ret_js.push_str("this");
+        }
+
         // Handle the special case where there is no return value. In this case
         // we can skip all the logic below and go straight to the end.
         if incoming_args {
@@ -329,6 +432,22 @@ impl<'a, 'b> Builder<'a, 'b> {
         Ok(self.finalize(&invoc))
     }
 
+    /// Whether the value returned by this export may be a promise which
+    /// `annotateRejection` needs to wrap.
+    ///
+    /// A `JsValue` may be a promise too, like the ones returned by
+    /// `future_to_promise`, so `any` values are passed to `annotateRejection`
+    /// as well, which returns the ones that aren't thenables unchanged.
+    fn returns_promise(&self) -> bool {
+        if !self.annotate_rejections {
+            return false;
+        }
+        match &self.ts_ret {
+            Some(ret) => ret.ty == "any" || ret.ty.starts_with("Promise"),
+            None => false,
+        }
+    }
+
     // This method... is a mess. Refactorings and improvements are more than
     // welcome :)
     fn finalize(&self, invoc: &str) -> String {
@@ -336,13 +455,24 @@ impl<'a, 'b> Builder<'a, 'b> {
         js.push_str("(");
         js.push_str(&self.function_args.join(", "));
         js.push_str(") {\n");
+        // With private fields `__wrap` creates instances through the
+        // constructor, which then only needs to install the pointer.
+        if self.constructor.is_some() && self.cx.config.js_flavor == JsFlavor::Es2022 {
+            js.push_str("if (arguments[0] === wrapToken) {\n");
+            js.push_str("this.#ptr = arguments[1];\n");
+            js.push_str("return;\n");
+            js.push_str("}\n");
+        }
+        if self.poison_on_panic {
+            js.push_str("checkPoisoned();\n");
+        }
         if self.args_prelude.len() > 0 {
             js.push_str(self.args_prelude.trim());
             js.push_str("\n");
         }
 
         let mut call = String::new();
-        if self.ts_ret.is_some() {
+        if self.ts_ret.is_some() && self.chain.is_none() {
             call.push_str("const ret = ");
         }
         call.push_str(invoc);
@@ -358,7 +488,14 @@ impl<'a, 'b> Builder<'a, 'b> {
             // Having a this field isn't supported yet, but shouldn't come up
             assert!(self.ret_finally.len() == 0);
             call.push_str("return ");
-            call.push_str(&self.ret_js);
+            match (&self.debug_name, self.returns_promise()) {
+                (Some(name), true) => call.push_str(&format!(
+                    "annotateRejection({}, {})",
+                    self.ret_js,
+                    js_string(name)
+                )),
+                _ => call.push_str(&self.ret_js),
+            }
             call.push_str(";\n");
         } else if self.ret_finally.len() > 0 {
             call.push_str(self.ret_finally.trim());
@@ -374,7 +511,14 @@ impl<'a, 'b> Builder<'a, 'b> {
         // logs what happened, but keeps the exception being thrown to propagate
         // elsewhere.
         if self.cx.config.debug {
-            call = format!("try {{\n{}}} catch (e) {{\n logError(e)\n}}\n", call);
+            let name = match &self.debug_name {
+                Some(name) => format!(", {}", js_string(name)),
+                None => String::new(),
+            };
+            call = format!(
+                "try {{\n{}}} catch (e) {{\n logError(e{})\n}}\n",
+                call, name
+            );
         }
 
         let finally = self.finally.trim();
@@ -382,12 +526,36 @@ impl<'a, 'b> Builder<'a, 'b> {
             call = format!("try {{\n{}}} finally {{\n{}\n}}\n", call, finally);
         }
 
+        // Once the wasm instance has trapped, for example because of a panic,
+        // it's in an unusable state. Report this as an exception carrying the
+        // panic, if any, which is also thrown from all later calls.
+        if self.poison_on_panic {
+            call = format!(
+                "try {{\n{}}} catch (e) {{\n throw poisonOnPanic(e);\n}}\n",
+                call
+            );
+        }
+
         js.push_str(&call);
         js.push_str("}");
 
         return js;
     }
 
+    /// Overrides the TypeScript types of the arguments with the given names,
+    /// or of the return value if the name is `return`.
+    pub fn typescript_types(&mut self, types: &[(String, String)]) {
+        for (name, ty) in types {
+            if name == "return" {
+                if let Some(ret) = &mut self.ts_ret {
+                    ret.ty = ty.clone();
+                }
+            } else if let Some(arg) = self.ts_args.iter_mut().find(|a| a.name == *name) {
+                arg.ty = ty.clone();
+            }
+        }
+    }
+
     /// Returns the typescript signature of the binding that this has described.
     /// This is used to generate all the TypeScript definitions later on.
     ///
@@ -434,12 +602,22 @@ impl<'a, 'b> Builder<'a, 'b> {
 
     /// Returns a helpful JS doc comment which lists types for all parameters
     /// and the return value.
+    ///
+    /// With `--jsdoc` the comment is complete enough for editors to type
+    /// check plain JS against it, so optional parameters are marked as such
+    /// and functions returning nothing are documented as returning `void`.
     pub fn js_doc_comments(&self) -> String {
+        let jsdoc = self.cx.config.jsdoc;
         let mut ret: String = self
             .ts_args
             .iter()
             .map(|a| {
-                if a.optional {
+                let default = self.arg_defaults.iter().find(|(name, _)| *name == a.name);
+                if let Some((_, value)) = default {
+                    format!("@param {{{}}} [{}={}]\n", a.ty, a.name, value)
+                } else if a.optional && jsdoc {
+                    format!("@param {{{}}} [{}]\n", a.ty, a.name)
+                } else if a.optional {
                     format!("@param {{{} | undefined}} {}\n", a.ty, a.name)
                 } else {
                     format!("@param {{{}}} {}\n", a.ty, a.name)
@@ -447,12 +625,23 @@ impl<'a, 'b> Builder<'a, 'b> {
             })
             .collect();
         if let Some(ts) = &self.ts_ret {
-            ret.push_str(&format!("@returns {{{}}}", ts.ty));
+            if ts.optional {
+                ret.push_str(&format!("@returns {{{} | undefined}}", ts.ty));
+            } else {
+                ret.push_str(&format!("@returns {{{}}}", ts.ty));
+            }
+        } else if jsdoc && self.constructor.is_none() {
+            ret.push_str("@returns {void}");
         }
         ret
     }
 }
 
+/// Returns a JS string literal for `s`.
+fn js_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
 impl JsBuilder {
     pub fn new(args: Vec<String>) -> JsBuilder {
         JsBuilder {
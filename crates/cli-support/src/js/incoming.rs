@@ -8,6 +8,7 @@ use crate::descriptor::VectorKind;
 use crate::js::binding::JsBuilder;
 use crate::js::Context;
 use crate::webidl::NonstandardIncoming;
+use crate::JsFlavor;
 use failure::{bail, Error};
 use wasm_webidl_bindings::ast;
 
@@ -37,6 +38,27 @@ impl<'a, 'b> Incoming<'a, 'b> {
         let single = match incoming {
             NonstandardIncoming::Standard(val) => return self.standard(val),
 
+            // In debug mode make sure the value is actually an instance of the
+            // imported type before handing it to Rust, otherwise this is the
+            // same as a standard `anyref` binding.
+            NonstandardIncoming::CheckedAnyref {
+                val,
+                js_name,
+                rust_name,
+            } => {
+                if self.cx.config.debug {
+                    if let ast::IncomingBindingExpression::As(as_) = val {
+                        let (expr, _) = self.standard_typed(&as_.expr)?;
+                        self.cx.expose_assert_import_type();
+                        self.js.prelude(&format!(
+                            "_assertImportType({}, '{}', '{}');",
+                            expr, js_name, rust_name
+                        ));
+                    }
+                }
+                return self.standard(val);
+            }
+
             // Evaluate the `val` binding, store it into a one-element `BigInt`
             // array (appropriately typed) and then use a 32-bit view into the
             // `BigInt` array to extract the high/low bits and pass them through
@@ -103,6 +125,21 @@ impl<'a, 'b> Incoming<'a, 'b> {
                 ]);
             }
 
+            // Flatten the map into an array of keys and values and then pass
+            // that along like any other list of `anyref` values.
+            NonstandardIncoming::Map { val } => {
+                let (expr, ty) = self.standard_typed(val)?;
+                assert_eq!(ty, ast::WebidlScalarType::Any.into());
+                self.cx.expose_map_to_entries();
+                let func = self.cx.pass_to_wasm_function(VectorKind::Anyref)?;
+                self.js
+                    .typescript_required("Map<string, any> | { [key: string]: any }");
+                return Ok(vec![
+                    format!("{}(mapToEntries({}))", func, expr),
+                    "WASM_VECTOR_LEN".to_string(),
+                ]);
+            }
+
             // There's no `char` in JS, so we take a string instead and just
             // forward along the first code point to Rust.
             NonstandardIncoming::Char { val } => {
@@ -119,10 +156,9 @@ impl<'a, 'b> Incoming<'a, 'b> {
                 let (expr, ty) = self.standard_typed(val)?;
                 assert_eq!(ty, ast::WebidlScalarType::Any.into());
                 self.assert_class(&expr, &class);
-                self.assert_not_moved(&expr);
+                self.assert_not_moved(&expr, &class);
                 let i = self.js.tmp();
-                self.js.prelude(&format!("const ptr{} = {}.ptr;", i, expr));
-                self.js.prelude(&format!("{}.ptr = 0;", expr));
+                self.take_ptr(&format!("const ptr{}", i), &expr, &class);
                 self.js.typescript_required(class);
                 format!("ptr{}", i)
             }
@@ -133,9 +169,9 @@ impl<'a, 'b> Incoming<'a, 'b> {
                 let (expr, ty) = self.standard_typed(val)?;
                 assert_eq!(ty, ast::WebidlScalarType::Any.into());
                 self.assert_class(&expr, &class);
-                self.assert_not_moved(&expr);
+                self.assert_not_moved(&expr, &class);
                 self.js.typescript_required(class);
-                format!("{}.ptr", expr)
+                self.ptr(&expr, &class)
             }
 
             // the "stack-ful" nature means that we're always popping from the
@@ -145,8 +181,8 @@ impl<'a, 'b> Incoming<'a, 'b> {
                 let (expr, ty) = self.standard_typed(val)?;
                 assert_eq!(ty, ast::WebidlScalarType::Any.into());
                 self.cx.expose_borrowed_objects();
-                self.cx.expose_global_stack_pointer();
-                self.js.finally("heap[stack_pointer++] = undefined;");
+                let release = self.cx.release_borrowed_object();
+                self.js.finally(release);
                 self.js.typescript_required("any");
                 format!("addBorrowedObject({})", expr)
             }
@@ -163,7 +199,8 @@ impl<'a, 'b> Incoming<'a, 'b> {
                 self.js
                     .prelude(&format!("const len{} = WASM_VECTOR_LEN;", i));
                 self.finally_free_slice(&expr, i, *kind, true)?;
-                self.js.typescript_required(kind.js_ty());
+                let ty = self.slice_ts_ty(*kind);
+                self.js.typescript_required(&ty);
                 return Ok(vec![format!("ptr{}", i), format!("len{}", i)]);
             }
 
@@ -218,9 +255,8 @@ impl<'a, 'b> Incoming<'a, 'b> {
                 self.js.prelude(&format!("let ptr{} = 0;", i));
                 self.js.prelude(&format!("if (!isLikeNone({0})) {{", expr));
                 self.assert_class(&expr, class);
-                self.assert_not_moved(&expr);
-                self.js.prelude(&format!("ptr{} = {}.ptr;", i, expr));
-                self.js.prelude(&format!("{}.ptr = 0;", expr));
+                self.assert_not_moved(&expr, class);
+                self.take_ptr(&format!("ptr{}", i), &expr, class);
                 self.js.prelude("}");
                 self.js.typescript_optional(class);
                 format!("ptr{}", i)
@@ -305,7 +341,8 @@ impl<'a, 'b> Incoming<'a, 'b> {
                 ));
                 self.js
                     .prelude(&format!("const len{} = WASM_VECTOR_LEN;", i));
-                self.js.typescript_optional(kind.js_ty());
+                let ty = self.slice_ts_ty(*kind);
+                self.js.typescript_optional(&ty);
                 return Ok(vec![format!("ptr{}", i), format!("len{}", i)]);
             }
 
@@ -329,7 +366,8 @@ impl<'a, 'b> Incoming<'a, 'b> {
                 self.js.finally(&format!("if (ptr{} !== 0) {{", i));
                 self.finally_free_slice(&expr, i, *kind, *mutable)?;
                 self.js.finally("}");
-                self.js.typescript_optional(kind.js_ty());
+                let ty = self.slice_ts_ty(*kind);
+                self.js.typescript_optional(&ty);
                 return Ok(vec![format!("ptr{}", i), format!("len{}", i)]);
             }
         };
@@ -410,7 +448,8 @@ impl<'a, 'b> Incoming<'a, 'b> {
                     ast::WebidlScalarType::Float64Array => VectorKind::F64,
                     _ => bail!("unsupported type passed to alloc-copy: {:?}", scalar),
                 };
-                self.js.typescript_required(kind.js_ty());
+                let ty = self.slice_ts_ty(kind);
+                self.js.typescript_required(&ty);
                 let func = self.cx.pass_to_wasm_function(kind)?;
                 return Ok(vec![
                     format!("{}({})", func, expr),
@@ -465,6 +504,16 @@ impl<'a, 'b> Incoming<'a, 'b> {
         }
     }
 
+    /// Returns the TypeScript type of slices of `kind` passed to Rust. Node.js
+    /// `Buffer`s are `Uint8Array`s which can be passed as is, which is spelled
+    /// out for byte slices with the `nodejs` target.
+    fn slice_ts_ty(&self, kind: VectorKind) -> String {
+        match kind {
+            VectorKind::U8 if self.cx.config.mode.nodejs() => "Buffer | Uint8Array".to_string(),
+            _ => kind.js_ty().to_string(),
+        }
+    }
+
     fn assert_class(&mut self, arg: &str, class: &str) {
         self.cx.expose_assert_class();
         self.js
@@ -507,20 +556,45 @@ impl<'a, 'b> Incoming<'a, 'b> {
         self.js.prelude("}");
     }
 
-    fn assert_not_moved(&mut self, arg: &str) {
+    fn assert_not_moved(&mut self, arg: &str, class: &str) {
         if !self.cx.config.debug {
             return;
         }
+        let ptr = self.ptr(arg, class);
         self.js.prelude(&format!(
             "\
-                if ({0}.ptr === 0) {{
+                if ({0} === 0) {{
                     throw new Error('Attempt to use a moved value');
                 }}
             ",
-            arg,
+            ptr,
         ));
     }
 
+    /// Returns an expression for the pointer of `arg`, an instance of the
+    /// exported Rust class `class`.
+    fn ptr(&self, arg: &str, class: &str) -> String {
+        match self.cx.config.js_flavor {
+            JsFlavor::Legacy => format!("{}.ptr", arg),
+            JsFlavor::Es2022 => format!("{}Ptr({})", class, arg),
+        }
+    }
+
+    /// Moves the pointer out of `arg`, an instance of the exported Rust class
+    /// `class`, into `dst` and clears it so the instance can't be used again.
+    fn take_ptr(&mut self, dst: &str, arg: &str, class: &str) {
+        match self.cx.config.js_flavor {
+            JsFlavor::Legacy => {
+                self.js.prelude(&format!("{} = {}.ptr;", dst, arg));
+                self.js.prelude(&format!("{}.ptr = 0;", arg));
+            }
+            JsFlavor::Es2022 => {
+                self.js
+                    .prelude(&format!("{} = {}Ptr({}, true);", dst, class, arg));
+            }
+        }
+    }
+
     fn finally_free_slice(
         &mut self,
         expr: &str,
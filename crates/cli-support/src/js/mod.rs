@@ -3,10 +3,11 @@ use crate::intrinsic::Intrinsic;
 use crate::webidl::{AuxEnum, AuxExport, AuxExportKind, AuxImport, AuxStruct};
 use crate::webidl::{AuxValue, Binding};
 use crate::webidl::{JsImport, JsImportName, NonstandardWebidlSection, WasmBindgenAux};
-use crate::{Bindgen, EncodeInto, OutputMode};
+use crate::{Bindgen, EncodeInto, JsFlavor, OutputMode};
 use failure::{bail, Error, ResultExt};
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
+use std::mem;
 use std::path::{Path, PathBuf};
 use walrus::{ExportId, ImportId, MemoryId, Module};
 use wasm_webidl_bindings::ast;
@@ -14,9 +15,14 @@ use wasm_webidl_bindings::ast;
 mod binding;
 mod incoming;
 mod outgoing;
+mod tokens;
+
+use self::tokens::{is_ident_char, referenced_identifiers, top_level_declarations};
 
 pub struct Context<'a> {
-    globals: String,
+    /// The top-level items of the generated JS, like helpers and exports,
+    /// each along with its doc comment.
+    globals: Vec<String>,
     imports_post: String,
     typescript: String,
     exposed_globals: Option<HashSet<&'static str>>,
@@ -30,6 +36,10 @@ pub struct Context<'a> {
     /// renames for each identifier.
     js_imports: HashMap<String, Vec<(String, Option<String>)>>,
 
+    /// Where each module in `js_imports` comes from: a `module` attribute, a
+    /// `raw_module` attribute, or a JS `snippet`.
+    js_import_kinds: HashMap<String, &'static str>,
+
     /// A map of each wasm import and what JS to hook up to it.
     wasm_import_definitions: HashMap<ImportId, String>,
 
@@ -42,11 +52,46 @@ pub struct Context<'a> {
     defined_identifiers: HashMap<String, usize>,
 
     exported_classes: Option<BTreeMap<String, ExportedClass>>,
+    /// Free functions and classes exported in a JS namespace, keyed by the
+    /// name of the outermost namespace.
+    exported_namespaces: Option<BTreeMap<String, ExportedNamespace>>,
     memory: MemoryId,
 
+    /// The names and kinds of all items exported from the generated JS, in
+    /// the order they were exported.
+    exported_items: Vec<(String, ExportedItem)>,
+
+    /// A map from the debug name of each Rust export to the number of bytes
+    /// of JS generated for it.
+    export_sizes: BTreeMap<String, usize>,
+
     /// A map of the name of npm dependencies we've loaded so far to the path
     /// they're defined in as well as their version specification.
     pub npm_dependencies: HashMap<String, (PathBuf, String)>,
+
+    /// Whether the wasm module reports panics to JS, enabled through the
+    /// `panic-exceptions` feature of the `wasm-bindgen` crate. If so all
+    /// exports turn traps into exceptions and refuse to run once the instance
+    /// has trapped.
+    panic_exceptions: bool,
+
+    /// Whether `wasm_bindgen::memory_grown` is used, in which case the glue
+    /// checks whether the memory has grown whenever wasm calls into JS and
+    /// whenever a view of memory is refreshed, and notifies wasm if so.
+    memory_growth_hooks: bool,
+
+    /// How to refer to each exported free function from within the generated
+    /// JS, which may differ from its exported name.
+    function_references: HashMap<ExportId, String>,
+
+    /// References to the `async` start functions, in the order in which
+    /// they're awaited once the wasm module is instantiated.
+    async_starts: Vec<String>,
+
+    /// With a heap shared between linked modules, the definitions of the
+    /// heap's globals along with the names they export, in order. They're
+    /// emitted in the shared heap module instead of the generated JS.
+    pub heap_globals: Vec<(Vec<&'static str>, String)>,
 }
 
 #[derive(Default)]
@@ -56,10 +101,155 @@ pub struct ExportedClass {
     typescript: String,
     has_constructor: bool,
     wrap_needed: bool,
-    /// Map from field name to type as a string plus whether it has a setter
-    typescript_fields: HashMap<String, (String, bool)>,
+    /// Whether the class is generated without a `free` method, for example
+    /// because its instances are managed by a pool on the Rust side
+    no_free: bool,
+    /// A method called at the start of `free`, before the Rust value is
+    /// dropped
+    on_free: Option<String>,
+    /// The path of the JS namespace the class is exported in, if any
+    js_namespace: Vec<String>,
+    /// Map from field name to the types of its getter and setter
+    typescript_fields: HashMap<String, FieldTypes>,
+    /// Same as `typescript_fields`, but for fields of the class itself
+    typescript_static_fields: HashMap<String, FieldTypes>,
+}
+
+/// The kind of an item exported from the generated JS.
+enum ExportedItem {
+    Function,
+    Class,
+    /// An enum, along with the JS object defining its variants
+    Enum(String),
+    Namespace,
 }
 
+/// Free functions and classes exported in a JS namespace, which is an object
+/// exported from the generated JS, along with the namespaces nested in it.
+#[derive(Default)]
+struct ExportedNamespace {
+    /// The name of each function or class along with how to refer to it from
+    /// within the generated JS.
+    members: Vec<(String, String)>,
+    typescript: String,
+    namespaces: BTreeMap<String, ExportedNamespace>,
+}
+
+/// The TypeScript types of the getter and setter of a field of an exported
+/// class, if it has them.
+#[derive(Default)]
+struct FieldTypes {
+    getter: Option<String>,
+    setter: Option<String>,
+}
+
+/// A JS helper used in debug mode which, given a `WebAssembly.LinkError`, the
+/// list of `[module, name]` pairs that the wasm module imports, and the
+/// imports object it was instantiated with, returns an error describing which
+/// imports are missing and where they likely came from.
+pub const DESCRIBE_LINK_ERROR: &str = "
+function describeLinkError(e, expected, imports) {
+    if (!(e instanceof WebAssembly.LinkError)) {
+        return e;
+    }
+    const missing = expected.filter(([m, n]) => {
+        return imports[m] === undefined || imports[m][n] === undefined;
+    });
+    if (missing.length === 0) {
+        return e;
+    }
+    const lines = missing.map(([m, n]) => {
+        let origin;
+        const wbg = /^__wbg_(.*)_[0-9a-f]{16}$/.exec(n);
+        if (wbg !== null) {
+            origin = 'a `#[wasm_bindgen]` extern block importing `' + wbg[1] + '`';
+        } else if (n.startsWith('__wbindgen_')) {
+            origin = 'a wasm-bindgen intrinsic, are the `wasm-bindgen` crate and CLI the same version?';
+        } else {
+            origin = 'an `extern` block which wasm-bindgen does not provide bindings for';
+        }
+        return '  * `' + m + '.' + n + '`, likely from ' + origin;
+    });
+    return new WebAssembly.LinkError(
+        e.message + '\n\nthe following imports were not provided:\n' + lines.join('\n')
+    );
+}
+";
+
+/// Returns the JS helpers recording the `performance` marks and measures
+/// emitted with `--performance-marks`, which do nothing if the Performance API
+/// isn't available, like in older versions of Node.js. There `fallback`, like
+/// `require('perf_hooks').performance`, is used instead if given.
+///
+/// `markStart(name)` and `markEnd(name)` record `wasm-bindgen:{name}-start`
+/// and `wasm-bindgen:{name}-end` marks, and the latter also records a
+/// `wasm-bindgen:{name}` measure between the two.
+pub fn performance_marks_js(fallback: Option<&str>) -> String {
+    let fallback = match fallback {
+        Some(perf) => format!("try {{ return {}; }} catch (_) {{}}\n", perf),
+        None => String::new(),
+    };
+    format!(
+        "
+function getPerformance() {{
+    if (typeof performance === 'object' && performance !== null) {{
+        return performance;
+    }}
+    {}return null;
+}}
+
+function markStart(name) {{
+    const perf = getPerformance();
+    if (perf !== null) {{
+        perf.mark('wasm-bindgen:' + name + '-start');
+    }}
+}}
+
+function markEnd(name) {{
+    const perf = getPerformance();
+    if (perf !== null) {{
+        perf.mark('wasm-bindgen:' + name + '-end');
+        perf.measure(
+            'wasm-bindgen:' + name,
+            'wasm-bindgen:' + name + '-start',
+            'wasm-bindgen:' + name + '-end'
+        );
+    }}
+}}
+",
+        fallback
+    )
+}
+
+/// The TypeScript declarations of the `startWorker` function generated with
+/// `--worker-entry`.
+const START_WORKER_TS: &str = "
+/**
+* A worker spawned by `startWorker`, which exchanges messages of type `Send`
+* and `Receive` with it.
+*/
+export interface WorkerChannel<Send = any, Receive = any> {
+  readonly worker: Worker;
+  /**
+  * Resolves once the bindings are initialized in the worker.
+  */
+  readonly ready: Promise<void>;
+  onmessage: ((message: Receive) => void) | null;
+  postMessage(message: Send, transfer?: Transferable[]): void;
+  terminate(): void;
+}
+
+/**
+* Spawns a web worker running `url`, by default the generated worker script,
+* and initializes the bindings in it with the same compiled module, and the
+* same memory in threads builds. Messages posted before the worker is ready
+* are delivered once it is.
+*
+* This can only be called once the bindings are initialized.
+*/
+export function startWorker<Send = any, Receive = any>(url?: string | URL): WorkerChannel<Send, Receive>;
+";
+
 const INITIAL_HEAP_VALUES: &[&str] = &["undefined", "null", "true", "false"];
 // Must be kept in sync with `src/lib.rs` of the `wasm-bindgen` crate
 const INITIAL_HEAP_OFFSET: usize = 32;
@@ -79,20 +269,29 @@ impl<'a> Context<'a> {
 
         // And then we're good to go!
         Ok(Context {
-            globals: String::new(),
+            globals: Vec::new(),
             imports_post: String::new(),
             typescript: "/* tslint:disable */\n".to_string(),
             exposed_globals: Some(Default::default()),
             required_internal_exports: Default::default(),
             imported_names: Default::default(),
             js_imports: Default::default(),
+            js_import_kinds: Default::default(),
             defined_identifiers: Default::default(),
             wasm_import_definitions: Default::default(),
             exported_classes: Some(Default::default()),
+            exported_namespaces: Some(Default::default()),
             config,
             module,
             memory,
+            exported_items: Vec::new(),
+            export_sizes: Default::default(),
             npm_dependencies: Default::default(),
+            panic_exceptions: false,
+            memory_growth_hooks: false,
+            function_references: Default::default(),
+            async_starts: Vec::new(),
+            heap_globals: Vec::new(),
         })
     }
 
@@ -100,22 +299,32 @@ impl<'a> Context<'a> {
         self.exposed_globals.as_mut().unwrap().insert(name)
     }
 
+    /// Exports `contents` as `export_name`, returning how to refer to it from
+    /// within the generated JS.
     fn export(
         &mut self,
         export_name: &str,
         contents: &str,
         comments: Option<String>,
-    ) -> Result<(), Error> {
+        item: ExportedItem,
+    ) -> Result<String, Error> {
         let definition_name = generate_identifier(export_name, &mut self.defined_identifiers);
         if contents.starts_with("class") && definition_name != export_name {
             bail!("cannot shadow already defined class `{}`", export_name);
         }
 
         let contents = contents.trim();
+        self.exported_items.push((export_name.to_string(), item));
         if let Some(ref c) = comments {
-            self.globals.push_str(c);
             self.typescript.push_str(c);
         }
+        let reference = match self.config.mode {
+            OutputMode::Node {
+                experimental_modules: false,
+            } => format!("module.exports.{}", export_name),
+            OutputMode::NoModules { .. } => format!("__exports.{}", export_name),
+            _ => definition_name.clone(),
+        };
         let global = match self.config.mode {
             OutputMode::Node {
                 experimental_modules: false,
@@ -157,8 +366,11 @@ impl<'a> Context<'a> {
                 }
             }
         };
-        self.global(&global);
-        Ok(())
+        match comments {
+            Some(c) => self.global(&format!("{}{}", c, global)),
+            None => self.global(&global),
+        }
+        Ok(reference)
     }
 
     fn require_internal_export(&mut self, name: &'static str) -> Result<(), Error> {
@@ -183,6 +395,7 @@ impl<'a> Context<'a> {
         // glue for all classes as well as finish up a few final imports like
         // `__wrap` and such.
         self.write_classes()?;
+        self.write_namespaces()?;
 
         // We're almost done here, so we can delete any internal exports (like
         // `__wbindgen_malloc`) if none of our JS glue actually needed it.
@@ -218,7 +431,189 @@ impl<'a> Context<'a> {
         // we don't ask for items which we can no longer emit.
         drop(self.exposed_globals.take().unwrap());
 
-        self.finalize_js(module_name, needs_manual_start)
+        let (js, ts) = self.finalize_js(module_name, needs_manual_start)?;
+
+        // Unused helpers have been removed from the JS at this point, and they
+        // may have been the only users of internal exports like
+        // `__wbindgen_malloc`. If so delete those exports as well, along with
+        // everything in the wasm module which only they kept alive.
+        if self.unexport_unreferenced_internal_exports(&js) {
+            walrus::passes::gc::run(self.module);
+        }
+
+        Ok((js, ts))
+    }
+
+    /// Returns the names of everything exported by the generated JS for
+    /// users, leaving out the shims imported by the wasm module.
+    pub fn exported_names(&self) -> Vec<String> {
+        self.exported_items
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Generates a small ES module which lazily loads the module named
+    /// `module_name` (the main generated JS file) through a dynamic `import()`
+    /// the first time it's needed, returning the JS and TypeScript for it.
+    ///
+    /// The default export loads and returns the whole module, and each
+    /// exported function is wrapped in a function returning a `Promise` for
+    /// its result. Bundlers can then split the glue and wasm into a separate
+    /// chunk which isn't shipped up front.
+    ///
+    /// Enums are plain objects, so they're defined in the lazy entry point as
+    /// well, but classes and namespaces can't be used before the module is
+    /// loaded and are reported as an error.
+    pub fn generate_lazy_entry(&self, module_name: &str) -> Result<(String, String), Error> {
+        let extension = if self.config.mode.nodejs_experimental_modules() {
+            "mjs"
+        } else {
+            "js"
+        };
+        let mut js = format!(
+            "\
+                let core;
+
+                export default function load() {{
+                    if (core === undefined) {{
+                        core = import('./{}.{}');
+                    }}
+                    return core;
+                }}
+            ",
+            module_name, extension,
+        );
+        let mut ts = format!(
+            "/* tslint:disable */\n\
+             export default function load(): Promise<typeof import('./{}')>;\n",
+            module_name,
+        );
+        for (name, item) in self.exported_items.iter() {
+            match item {
+                ExportedItem::Function => {
+                    js.push_str(&format!(
+                        "\nexport function {0}(...args) {{\n\
+                            return load().then(m => m.{0}(...args));\n\
+                         }}\n",
+                        name,
+                    ));
+                    ts.push_str(&format!(
+                        "export function {0}(...args: Parameters<typeof import('./{1}').{0}>): \
+                         Promise<ReturnType<typeof import('./{1}').{0}>>;\n",
+                        name, module_name,
+                    ));
+                }
+                ExportedItem::Enum(object) => {
+                    js.push_str(&format!("\nexport const {} = {};\n", name, object));
+                    ts.push_str(&format!(
+                        "export declare const {0}: typeof import('./{1}').{0};\n\
+                         export type {0} = import('./{1}').{0};\n",
+                        name, module_name,
+                    ));
+                }
+                ExportedItem::Class => bail!(
+                    "cannot generate a lazy entry point exporting the class `{}`, \
+                     as it can't be used before the module is loaded",
+                    name
+                ),
+                ExportedItem::Namespace => bail!(
+                    "cannot generate a lazy entry point exporting the namespace `{}`, \
+                     as it can't be used before the module is loaded",
+                    name
+                ),
+            }
+        }
+        Ok((js, ts))
+    }
+
+    /// Generates the `{name}_worker.js` script which bootstraps the module
+    /// named `module_name` (the main generated JS file) in a web worker spawned
+    /// by its `startWorker` function.
+    ///
+    /// The worker waits for the compiled module, and the shared memory in
+    /// threads builds, to be posted by `startWorker`, initializes the bindings
+    /// with them and then reports back. Any other message received in the
+    /// meantime is held back and dispatched again once the bindings are
+    /// initialized, so `message` listeners added from a `start` function
+    /// don't miss any.
+    pub fn generate_worker_entry(&self, module_name: &str) -> String {
+        let import = match &self.config.mode {
+            OutputMode::NoModules { global, .. } => format!(
+                "importScripts('{}.js');\nconst init = {};\n",
+                module_name, global
+            ),
+            _ => format!("import init from './{}.js';\n", module_name),
+        };
+        // `init` only takes a memory when the module imports one.
+        let init_args = if self.module.memories.get(self.memory).import.is_some() {
+            "data.module, data.memory"
+        } else {
+            "data.module"
+        };
+        format!(
+            "\
+                {}
+                const pending = [];
+
+                function onMessage(event) {{
+                    const data = event.data;
+                    event.stopImmediatePropagation();
+                    if (data === null || typeof data !== 'object' || data.__wbindgen_worker_init !== true) {{
+                        pending.push(event);
+                        return;
+                    }}
+                    init({})
+                        .then(() => {{
+                            self.removeEventListener('message', onMessage);
+                            self.postMessage({{ __wbindgen_worker_ready: true }});
+                            for (const event of pending) {{
+                                self.dispatchEvent(new MessageEvent('message', {{
+                                    data: event.data,
+                                    ports: event.ports,
+                                }}));
+                            }}
+                        }})
+                        // Rethrow outside of the promise so the error reaches
+                        // the worker's `onerror` on the main thread.
+                        .catch(e => setTimeout(() => {{ throw e; }}));
+                }}
+
+                self.addEventListener('message', onMessage);
+            ",
+            import,
+            init_args
+        )
+    }
+
+    /// Returns a report of how many bytes of JS each export, import,
+    /// intrinsic, and helper contributes to the generated glue.
+    ///
+    /// This must be called after `finalize`.
+    pub fn bindings_profile(&self) -> serde_json::Value {
+        let mut imports = BTreeMap::new();
+        let mut intrinsics = BTreeMap::new();
+        for (id, js) in self.wasm_import_definitions.iter() {
+            let name = self.module.imports.get(*id).name.clone();
+            if name.starts_with("__wbindgen_") {
+                intrinsics.insert(name, js.len());
+            } else {
+                imports.insert(name, js.len());
+            }
+        }
+        let mut helpers = BTreeMap::new();
+        for item in self.globals.iter() {
+            let names = top_level_declarations(item);
+            if names.len() > 0 {
+                *helpers.entry(names.join(", ")).or_insert(0) += item.len();
+            }
+        }
+        serde_json::json!({
+            "exports": self.export_sizes,
+            "imports": imports,
+            "intrinsics": intrinsics,
+            "helpers": helpers,
+        })
     }
 
     /// Performs the task of actually generating the final JS module, be it
@@ -231,8 +626,36 @@ impl<'a> Context<'a> {
     ) -> Result<(String, String), Error> {
         let mut ts = self.typescript.clone();
         let mut js = String::new();
-        if self.config.mode.no_modules() {
-            js.push_str("(function() {\n");
+
+        // The names of the imports we define only need to agree between the
+        // wasm module and the glue, so they can be shortened before either is
+        // written out.
+        if self.config.minify_internals {
+            let ids = sorted_iter(&self.wasm_import_definitions)
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>();
+            for (i, id) in ids.into_iter().enumerate() {
+                self.module.imports.get_mut(id).name = minified_name(i);
+            }
+        }
+
+        match &self.config.mode {
+            // The UMD wrapper only assigns to the global when neither an AMD
+            // loader nor CommonJS are available, and otherwise hands the
+            // bindings to the module system.
+            OutputMode::NoModules { global, umd: true } => {
+                js.push_str("(function(root, factory) {\n");
+                js.push_str("if (typeof define === 'function' && define.amd) {\n");
+                js.push_str("define([], factory);\n");
+                js.push_str("} else if (typeof module === 'object' && module.exports) {\n");
+                js.push_str("module.exports = factory();\n");
+                js.push_str("} else {\n");
+                js.push_str(&assign_global("root", global, "factory()"));
+                js.push_str("}\n");
+                js.push_str("})(typeof self !== 'undefined' ? self : this, function() {\n");
+            }
+            OutputMode::NoModules { .. } => js.push_str("(function() {\n"),
+            _ => {}
         }
 
         // Depending on the output mode, generate necessary glue to actually
@@ -244,14 +667,19 @@ impl<'a> Context<'a> {
             // In `--target no-modules` mode we need to both expose a name on
             // the global object as well as generate our own custom start
             // function.
-            OutputMode::NoModules { global } => {
+            OutputMode::NoModules { global, umd } => {
                 js.push_str("const __exports = {};\n");
                 js.push_str("let wasm;\n");
-                init = self.gen_init(needs_manual_start);
-                footer.push_str(&format!(
-                    "self.{} = Object.assign(init, __exports);\n",
-                    global
-                ));
+                init = self.gen_init(module_name, needs_manual_start)?;
+                let bindings = format!(
+                    "Object.assign(init, {{ {} }}, __exports)",
+                    self.init_exports()
+                );
+                if *umd {
+                    footer.push_str(&format!("return {};\n", bindings));
+                } else {
+                    footer.push_str(&assign_global("self", global, &bindings));
+                }
             }
 
             // With normal CommonJS node we need to defer requiring the wasm
@@ -275,6 +703,11 @@ impl<'a> Context<'a> {
                 if needs_manual_start {
                     footer.push_str("wasm.__wbindgen_start();\n");
                 }
+                // There's no initialization function to await `async` start
+                // functions here, so they're only kicked off.
+                if let Some(starts) = self.async_start_chain() {
+                    footer.push_str(&format!("{};\n", starts));
+                }
             }
 
             // With Bundlers and modern ES6 support in Node we can simply import
@@ -297,6 +730,9 @@ impl<'a> Context<'a> {
                 if needs_manual_start {
                     footer.push_str("\nwasm.__wbindgen_start();\n");
                 }
+                if let Some(starts) = self.async_start_chain() {
+                    footer.push_str(&format!("\n{};\n", starts));
+                }
             }
 
             // With a browser-native output we're generating an ES module, but
@@ -305,7 +741,8 @@ impl<'a> Context<'a> {
             // as the default export of the module.
             OutputMode::Web => {
                 self.imports_post.push_str("let wasm;\n");
-                init = self.gen_init(needs_manual_start);
+                init = self.gen_init(module_name, needs_manual_start)?;
+                footer.push_str(&format!("export {{ {} }};\n", self.init_exports()));
                 footer.push_str("export default init;\n");
             }
         }
@@ -314,6 +751,13 @@ impl<'a> Context<'a> {
 
         ts.push_str(&init_ts);
 
+        // Helpers are requested while generating bindings, but the bindings
+        // which requested them may have since been removed (for example if
+        // the import they're used for was gc'd from the wasm module). Clean
+        // out any helpers which nothing ends up referencing.
+        let globals = mem::replace(&mut self.globals, Vec::new());
+        self.globals = gc_globals(globals, &[&imports, &self.imports_post, &init_js, &footer]);
+
         // Emit all the JS for importing all our functionality
         assert!(
             !self.config.mode.uses_es_modules() || js.is_empty(),
@@ -325,16 +769,36 @@ impl<'a> Context<'a> {
         js.push_str("\n");
 
         // Emit all our exports from this module
-        js.push_str(&self.globals);
-        js.push_str("\n");
+        for item in self.globals.iter() {
+            js.push_str(item);
+            js.push_str("\n");
+        }
 
         // Generate the initialization glue, if there was any
         js.push_str(&init_js);
         js.push_str("\n");
         js.push_str(&footer);
         js.push_str("\n");
-        if self.config.mode.no_modules() {
-            js.push_str("})();\n");
+        match &self.config.mode {
+            OutputMode::NoModules { umd: true, .. } => js.push_str("});\n"),
+            OutputMode::NoModules { .. } => js.push_str("})();\n"),
+            _ => {}
+        }
+
+        if self.config.minify_internals {
+            let helpers = self
+                .globals
+                .iter()
+                .filter(|item| !is_exported_item(item))
+                .flat_map(|item| top_level_declarations(item))
+                .collect::<BTreeSet<_>>();
+            let offset = self.wasm_import_definitions.len();
+            let renames = helpers
+                .into_iter()
+                .enumerate()
+                .map(|(i, name)| (name, minified_name(offset + i)))
+                .collect::<HashMap<_, _>>();
+            js = rename_identifiers(&js, &renames);
         }
 
         while js.contains("\n\n\n") {
@@ -348,7 +812,7 @@ impl<'a> Context<'a> {
         let mut imports = String::new();
         match &self.config.mode {
             OutputMode::NoModules { .. } => {
-                for (module, _items) in self.js_imports.iter() {
+                for (module, _items) in sorted_iter(&self.js_imports) {
                     bail!(
                         "importing from `{}` isn't supported with `--target no-modules`",
                         module
@@ -372,7 +836,7 @@ impl<'a> Context<'a> {
                         }
                     }
                     imports.push_str(" } = require(String.raw`");
-                    imports.push_str(module);
+                    imports.push_str(&self.module_specifier(module));
                     imports.push_str("`);\n");
                 }
             }
@@ -383,19 +847,35 @@ impl<'a> Context<'a> {
             }
             | OutputMode::Web => {
                 for (module, items) in sorted_iter(&self.js_imports) {
-                    imports.push_str("import { ");
-                    for (i, (item, rename)) in items.iter().enumerate() {
-                        if i > 0 {
+                    // The default export is imported with the
+                    // `import Default from '...'` syntax, and named imports
+                    // go in braces after it.
+                    let (default, named): (Vec<_>, Vec<_>) =
+                        items.iter().partition(|(item, _)| item == "default");
+                    imports.push_str("import ");
+                    if let Some((_, rename)) = default.first() {
+                        // `default` is never used as the local name
+                        imports.push_str(rename.as_ref().unwrap());
+                        if named.len() > 0 {
                             imports.push_str(", ");
                         }
-                        imports.push_str(item);
-                        if let Some(other) = rename {
-                            imports.push_str(" as ");
-                            imports.push_str(other)
+                    }
+                    if named.len() > 0 {
+                        imports.push_str("{ ");
+                        for (i, (item, rename)) in named.iter().enumerate() {
+                            if i > 0 {
+                                imports.push_str(", ");
+                            }
+                            imports.push_str(item);
+                            if let Some(other) = rename {
+                                imports.push_str(" as ");
+                                imports.push_str(other)
+                            }
                         }
+                        imports.push_str(" }");
                     }
-                    imports.push_str(" } from '");
-                    imports.push_str(module);
+                    imports.push_str(" from '");
+                    imports.push_str(&self.module_specifier(module));
                     imports.push_str("';\n");
                 }
             }
@@ -403,11 +883,41 @@ impl<'a> Context<'a> {
         Ok(imports)
     }
 
-    fn ts_for_init_fn(has_memory: bool, has_module_or_path_optional: bool) -> String {
+    /// Returns every module specifier imported by the generated JS, as it's
+    /// emitted, along with where the import comes from.
+    pub fn imported_modules(&self) -> BTreeMap<String, &'static str> {
+        self.js_imports
+            .keys()
+            .map(|module| (self.module_specifier(module), self.js_import_kinds[module]))
+            .collect()
+    }
+
+    /// Returns the specifier that should be emitted in the JS to import
+    /// `module`, taking into account any rewrites that have been configured.
+    fn module_specifier(&self, module: &str) -> String {
+        if let Some(to) = self.config.module_rewrites.get(module) {
+            return to.clone();
+        }
+        let ext = match &self.config.module_extension {
+            Some(ext) => ext,
+            None => return module.to_string(),
+        };
+        // Only paths get an extension appended; bare specifiers are resolved
+        // by the package itself, e.g. through its `exports` map.
+        let is_path =
+            module.starts_with("./") || module.starts_with("../") || module.starts_with("/");
+        let file_name = module.rsplit('/').next().unwrap_or(module);
+        if !is_path || file_name.contains('.') {
+            return module.to_string();
+        }
+        format!("{}.{}", module, ext)
+    }
+
+    fn ts_for_init_fn(has_memory: bool, has_module_or_path_optional: bool, output: &str) -> String {
         let (memory_doc, memory_param) = if has_memory {
             (
                 "* @param {WebAssembly.Memory} maybe_memory\n",
-                ", maybe_memory: WebAssembly.Memory",
+                ", maybe_memory?: WebAssembly.Memory",
             )
         } else {
             ("", "")
@@ -415,26 +925,171 @@ impl<'a> Context<'a> {
         let arg_optional = if has_module_or_path_optional { "?" } else { "" };
         format!(
             "\n\
+            export type InitInput = RequestInfo | URL | Response | Promise<Response> | BufferSource | WebAssembly.Module;\n\
+            \n\
+            /**\n\
+            * The exports of the instantiated wasm module, as resolved by the\n\
+            * initialization functions.\n\
+            */\n\
+            export interface InitOutput {{\n\
+            {3}\
+            }}\n\
+            \n\
+            /**\n\
+            * Compiles `module_or_path` without instantiating it, accepting the\n\
+            * same arguments as `init`. The returned module can be passed to\n\
+            * `instantiate`, `initSync` or `init` later, or posted to a worker.\n\
+            *\n\
+            * @param {{InitInput}} module_or_path\n\
+            *\n\
+            * @returns {{Promise<WebAssembly.Module>}}\n\
+            */\n\
+            export function compile(module_or_path{1}: InitInput): Promise<WebAssembly.Module>;\n\
+            \n\
+            /**\n\
+            * Instantiates a `module` previously returned by `compile`.\n\
+            *\n\
+            * @param {{WebAssembly.Module}} module\n\
+            {0}\
+            *\n\
+            * @returns {{Promise<InitOutput>}}\n\
+            */\n\
+            export function instantiate(module: WebAssembly.Module{2}): Promise<InitOutput>;\n\
+            \n\
+            /**\n\
+            * Synchronously compiles and instantiates `module`, for use when\n\
+            * the bytes are already available, like in a worker.\n\
+            *\n\
+            * @param {{BufferSource | WebAssembly.Module}} module\n\
+            {0}\
+            *\n\
+            * @returns {{InitOutput}}\n\
+            */\n\
+            export function initSync(module: BufferSource | WebAssembly.Module{2}): InitOutput;\n\
+            \n\
             /**\n\
-            * If `module_or_path` is {{RequestInfo}}, makes a request and\n\
-            * for everything else, calls `WebAssembly.instantiate` directly.\n\
+            * If `module_or_path` is {{RequestInfo}} or {{URL}}, makes a request,\n\
+            * and if it is a {{Response}}, or a promise of one, instantiates it\n\
+            * while it's streamed in. For everything else, calls\n\
+            * `WebAssembly.instantiate` directly.\n\
             *\n\
-            * @param {{RequestInfo | BufferSource | WebAssembly.Module}} module_or_path\n\
-            {}\
+            * @param {{InitInput}} module_or_path\n\
+            {0}\
             *\n\
-            * @returns {{Promise<any>}}\n\
+            * @returns {{Promise<InitOutput>}}\n\
             */\n\
-            export default function init \
-                (module_or_path{}: RequestInfo | BufferSource | WebAssembly.Module{}): Promise<any>;
+            export default function init(module_or_path{1}: InitInput{2}): Promise<InitOutput>;
         ",
-            memory_doc, arg_optional, memory_param
+            format!(
+                "{}* @param {{WebAssembly.Imports}} extra_imports\n",
+                memory_doc
+            ),
+            arg_optional,
+            format!("{}, extra_imports?: WebAssembly.Imports", memory_param),
+            output
         )
     }
 
-    fn gen_init(&mut self, needs_manual_start: bool) -> (String, String) {
+    /// Returns the list of functions exported alongside `init` by the `web`
+    /// and `no-modules` targets.
+    fn init_exports(&self) -> &'static str {
+        if self.config.worker_entry {
+            "compile, instantiate, initSync, startWorker"
+        } else {
+            "compile, instantiate, initSync"
+        }
+    }
+
+    /// Returns a promise chaining calls to all `async` start functions, if
+    /// there are any.
+    fn async_start_chain(&self) -> Option<String> {
+        if self.async_starts.is_empty() {
+            return None;
+        }
+        let mut chain = String::from("Promise.resolve()");
+        for start in self.async_starts.iter() {
+            chain.push_str(&format!(".then(() => {}())", start));
+        }
+        Some(chain)
+    }
+
+    /// Generates the `startWorker` function, and its TypeScript declaration,
+    /// which spawns a worker running `{output_name}_worker.js`.
+    fn gen_start_worker(&self, output_name: &str, share_memory: bool) -> (String, String) {
+        // Without modules the URL of the script has to be recorded while it's
+        // first evaluated.
+        let (url, options) = match self.config.mode {
+            OutputMode::Web => (
+                format!("new URL('./{}_worker.js', import.meta.url)", output_name),
+                ", { type: 'module' }",
+            ),
+            _ => (
+                format!(
+                    "new URL('{}_worker.js', typeof document === 'undefined' ? \
+                     location.href : document.currentScript.src)",
+                    output_name
+                ),
+                "",
+            ),
+        };
+        let memory = if share_memory { "memory" } else { "undefined" };
+        let js = format!(
+            "
+                const __wbg_worker_url = {url};
+
+                function startWorker(url) {{
+                    if (wasm === undefined) {{
+                        throw new Error('the module must be initialized before starting a worker');
+                    }}
+                    const worker = new Worker(url === undefined ? __wbg_worker_url : url{options});
+                    let ready;
+                    const channel = {{
+                        worker,
+                        ready: new Promise((resolve, reject) => {{
+                            ready = resolve;
+                            worker.addEventListener('error', reject);
+                        }}),
+                        onmessage: null,
+                        postMessage(message, transfer) {{
+                            worker.postMessage(message, transfer === undefined ? [] : transfer);
+                        }},
+                        terminate() {{
+                            worker.terminate();
+                        }},
+                    }};
+                    worker.addEventListener('message', event => {{
+                        const data = event.data;
+                        if (data !== null && typeof data === 'object' && data.__wbindgen_worker_ready === true) {{
+                            ready();
+                        }} else if (channel.onmessage !== null) {{
+                            channel.onmessage(data);
+                        }}
+                    }});
+                    worker.postMessage({{
+                        __wbindgen_worker_init: true,
+                        module: init.__wbindgen_wasm_module,
+                        memory: {memory},
+                    }});
+                    return channel;
+                }}
+            ",
+            url = url,
+            options = options,
+            memory = memory,
+        );
+        let ts = START_WORKER_TS.to_string();
+        (js, ts)
+    }
+
+    fn gen_init(
+        &mut self,
+        output_name: &str,
+        needs_manual_start: bool,
+    ) -> Result<(String, String), Error> {
         let module_name = "wbg";
         let mem = self.module.memories.get(self.memory);
-        let (init_memory1, init_memory2) = if let Some(id) = mem.import {
+        let share_memory = mem.import.is_some() && mem.shared;
+        let init_memory = if let Some(id) = mem.import {
             self.module.imports.get_mut(id).module = module_name.to_string();
             let mut memory = String::from("new WebAssembly.Memory({");
             memory.push_str(&format!("initial:{}", mem.initial));
@@ -446,30 +1101,206 @@ impl<'a> Context<'a> {
             }
             memory.push_str("})");
             self.imports_post.push_str("let memory;\n");
-            (
-                format!("memory = imports.{}.memory = maybe_memory;", module_name),
-                format!("memory = imports.{}.memory = {};", module_name, memory),
+            format!(
+                "memory = imports.{}.memory = maybe_memory || {};",
+                module_name, memory
             )
         } else {
-            (String::new(), String::new())
+            String::new()
         };
         let init_memory_arg = if mem.import.is_some() {
-            ", maybe_memory"
+            ", maybe_memory, extra_imports"
         } else {
-            ""
+            ", extra_imports"
         };
 
+        // Callers can pass imports of their own, which are merged into ours
+        // and take precedence, to override some of our shims or to provide
+        // imports from other modules, like the `wasi_snapshot_preview1`
+        // imports of a module also using WASI. The latter are required.
+        let mut extra_modules = BTreeSet::new();
+        for import in self.module.imports.iter() {
+            if self.wasm_import_definitions.contains_key(&import.id())
+                || mem.import == Some(import.id())
+            {
+                continue;
+            }
+            extra_modules.insert(format!("{:?}", import.module));
+        }
+        let mut merge_extra_imports = String::from(
+            "\
+                if (extra_imports !== undefined) {
+                    for (const name of Object.keys(extra_imports)) {
+                        imports[name] = Object.assign(imports[name] || {}, extra_imports[name]);
+                    }
+                }",
+        );
+        if !extra_modules.is_empty() {
+            merge_extra_imports.push_str(&format!(
+                "
+                    for (const name of [{}]) {{
+                        if (imports[name] === undefined) {{
+                            throw new Error(`this module imports from \\`${{name}}\\`, whose \
+                                imports must be passed to the initialization function`);
+                        }}
+                    }}",
+                extra_modules.into_iter().collect::<Vec<_>>().join(", ")
+            ));
+        }
+
+        // Resolve the wasm file relative to the JS rather than the page, so
+        // the output works no matter which document imports it.
         let default_module_path = match self.config.mode {
-            OutputMode::Web => {
+            OutputMode::Web => format!(
                 "\
-                    if (typeof module === 'undefined') {
-                        module = import.meta.url.replace(/\\.js$/, '_bg.wasm');
-                    }"
+                    if (typeof module === 'undefined') {{
+                        module = new URL('{}_bg.wasm', import.meta.url);
+                    }}",
+                output_name
+            ),
+            _ => String::new(),
+        };
+
+        let output = crate::wasm2es6js::interface(&self.module)?;
+        let mut ts = Self::ts_for_init_fn(
+            mem.import.is_some(),
+            !default_module_path.is_empty(),
+            &output,
+        );
+
+        // With `--jsdoc` the initialization functions are documented with the
+        // same types as their TypeScript declarations.
+        let jsdoc = self.config.jsdoc;
+        let js_doc = |params: &[&str], ret: &str| {
+            if !jsdoc {
+                return String::new();
+            }
+            let mut doc = String::from("/**\n");
+            for param in params.iter().filter(|p| !p.is_empty()) {
+                doc.push_str(&format!("* @param {}\n", param));
             }
-            _ => "",
+            doc.push_str(&format!("* @returns {{{}}}\n*/\n", ret));
+            doc
         };
+        let memory_param = if mem.import.is_some() {
+            "{WebAssembly.Memory} [maybe_memory]"
+        } else {
+            ""
+        };
+        let extra_imports_param = "{WebAssembly.Imports} [extra_imports]";
+        let input_param = if default_module_path.is_empty() {
+            "{InitInput} module_or_path"
+        } else {
+            "{InitInput} [module_or_path]"
+        };
+        let input_typedef = if jsdoc {
+            "/**\n\
+             * @typedef {RequestInfo | URL | Response | Promise<Response> | BufferSource | \
+             WebAssembly.Module} InitInput\n\
+             */\n"
+        } else {
+            ""
+        };
+        let compile_doc = js_doc(&[input_param], "Promise<WebAssembly.Module>");
+        let instantiate_doc = js_doc(
+            &[
+                "{WebAssembly.Module} module",
+                memory_param,
+                extra_imports_param,
+            ],
+            "Promise<any>",
+        );
+        let init_sync_doc = js_doc(
+            &[
+                "{BufferSource | WebAssembly.Module} module",
+                memory_param,
+                extra_imports_param,
+            ],
+            "any",
+        );
+        let init_doc = js_doc(
+            &[input_param, memory_param, extra_imports_param],
+            "Promise<any>",
+        );
 
-        let ts = Self::ts_for_init_fn(mem.import.is_some(), !default_module_path.is_empty());
+        // In debug mode wrap instantiation failures so link errors explain
+        // which import is missing.
+        let (link_error, link_error_sync) = if self.config.debug {
+            self.global(DESCRIBE_LINK_ERROR);
+            let expected = self
+                .module
+                .imports
+                .iter()
+                .map(|i| format!("[{:?}, {:?}]", i.module, i.name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            (
+                format!(
+                    "result = result.catch(e => {{ throw describeLinkError(e, [{}], imports); }});",
+                    expected
+                ),
+                format!("throw describeLinkError(e, [{}], imports);", expected),
+            )
+        } else {
+            (String::new(), "throw e;".to_string())
+        };
+
+        // Compilation and instantiation are measured separately, so with
+        // performance marks `init` compiles the module up front rather than
+        // streaming it into `WebAssembly.instantiateStreaming`.
+        let (compile_start, compile_end, compile_end_then, mark_start, mark_end) =
+            if self.config.performance_marks {
+                self.expose_performance_marks();
+                (
+                    "markStart('compile');",
+                    "markEnd('compile');",
+                    ".then(module => {\nmarkEnd('compile');\nreturn module;\n})",
+                    "markStart('instantiate');",
+                    "markEnd('instantiate');",
+                )
+            } else {
+                ("", "", "", "", "")
+            };
+
+        // The promises returned by the initialization functions only resolve
+        // once all `async` start functions are done, but `initSync` can only
+        // kick them off.
+        let (async_start_fn, async_start, async_start_sync) = match self.async_start_chain() {
+            Some(starts) => (
+                format!(
+                    "function __wbg_async_start(wasm) {{
+                        return {}.then(() => wasm);
+                    }}",
+                    starts
+                ),
+                ".then(__wbg_async_start)",
+                "__wbg_async_start(wasm);",
+            ),
+            None => (String::new(), "", ""),
+        };
+
+        let init_body = if self.config.performance_marks {
+            format!(
+                "return compile(module).then(module => instantiate(module{}));",
+                init_memory_arg
+            )
+        } else {
+            format!(
+                "\
+                const imports = __wbg_get_imports({memory_arg});
+                // Responses, or promises of them like the result of
+                // `fetch`, are instantiated while they're streamed in.
+                let result = Promise.resolve(__wbg_fetch(module))
+                    .then(module => __wbg_load(module, imports));
+                {link_error}
+                return result
+                    .then(({{instance, module}}) => __wbg_finalize_init(instance, module)){async_start};
+                ",
+                memory_arg = init_memory_arg.trim_start_matches(", "),
+                link_error = link_error,
+                async_start = async_start,
+            )
+        };
 
         // Initialize the `imports` object for all import definitions that we're
         // directed to wire up.
@@ -491,65 +1322,169 @@ impl<'a> Context<'a> {
             imports_init.push_str(";\n");
         }
 
-        let js = format!(
+        let mut js = format!(
             "\
-                function init(module{init_memory_arg}) {{
-                    {default_module_path}
-                    let result;
+                function __wbg_get_imports({memory_arg}) {{
                     const imports = {{}};
                     {imports_init}
-                    if (module instanceof URL || typeof module === 'string' || module instanceof Request) {{
-                        {init_memory2}
-                        const response = fetch(module);
-                        if (typeof WebAssembly.instantiateStreaming === 'function') {{
-                            result = WebAssembly.instantiateStreaming(response, imports)
-                                .catch(e => {{
-                                    console.warn(\"`WebAssembly.instantiateStreaming` failed. Assuming this is \
-                                                    because your server does not serve wasm with \
-                                                    `application/wasm` MIME type. Falling back to \
-                                                    `WebAssembly.instantiate` which is slower. Original \
-                                                    error:\\n\", e);
-                                    return response
-                                        .then(r => r.arrayBuffer())
-                                        .then(bytes => WebAssembly.instantiate(bytes, imports));
-                                }});
-                        }} else {{
-                            result = response
-                                .then(r => r.arrayBuffer())
-                                .then(bytes => WebAssembly.instantiate(bytes, imports));
-                        }}
-                    }} else {{
-                        {init_memory1}
-                        result = WebAssembly.instantiate(module, imports)
-                            .then(result => {{
-                                if (result instanceof WebAssembly.Instance) {{
-                                    return {{ instance: result, module }};
-                                }} else {{
-                                    return result;
-                                }}
-                            }});
+                    {init_memory}
+                    {merge_extra_imports}
+                    return imports;
+                }}
+
+                function __wbg_fetch(module) {{
+                    if (
+                        typeof module === 'string' ||
+                        (typeof Request === 'function' && module instanceof Request) ||
+                        (typeof URL === 'function' && module instanceof URL)
+                    ) {{
+                        return fetch(module);
                     }}
-                    return result.then(({{instance, module}}) => {{
-                        wasm = instance.exports;
-                        init.__wbindgen_wasm_module = module;
-                        {start}
-                        return wasm;
-                    }});
+                    return module;
+                }}
+
+                function __wbg_stream(response, name, streaming, fallback) {{
+                    if (typeof WebAssembly[name] !== 'function') {{
+                        return response.arrayBuffer().then(fallback);
+                    }}
+                    return streaming(response)
+                        .catch(e => {{
+                            if (response.headers.get('Content-Type') === 'application/wasm') {{
+                                throw e;
+                            }}
+                            console.warn(\"`WebAssembly.\" + name + \"` failed \
+                                            because your server does not serve wasm with \
+                                            `application/wasm` MIME type. Falling back to \
+                                            buffering the response which is slower. Original \
+                                            error:\\n\", e);
+                            return response.arrayBuffer().then(fallback);
+                        }});
+                }}
+
+                function __wbg_load(module, imports) {{
+                    if (typeof Response === 'function' && module instanceof Response) {{
+                        return __wbg_stream(
+                            module,
+                            'instantiateStreaming',
+                            r => WebAssembly.instantiateStreaming(r, imports),
+                            bytes => WebAssembly.instantiate(bytes, imports)
+                        );
+                    }}
+                    return WebAssembly.instantiate(module, imports)
+                        .then(result => {{
+                            if (result instanceof WebAssembly.Instance) {{
+                                return {{ instance: result, module }};
+                            }} else {{
+                                return result;
+                            }}
+                        }});
+                }}
+
+                function __wbg_finalize_init(instance, module) {{
+                    {mark_end}
+                    wasm = instance.exports;
+                    init.__wbindgen_wasm_module = module;
+                    {start}
+                    return wasm;
+                }}
+
+                {async_start_fn}
+
+                {input_typedef}
+                {compile_doc}
+                function compile(module) {{
+                    {default_module_path}
+                    {compile_start}
+                    return Promise.resolve(__wbg_fetch(module))
+                        .then(module => {{
+                            if (module instanceof WebAssembly.Module) {{
+                                return module;
+                            }}
+                            if (typeof Response === 'function' && module instanceof Response) {{
+                                return __wbg_stream(
+                                    module,
+                                    'compileStreaming',
+                                    r => WebAssembly.compileStreaming(r),
+                                    bytes => WebAssembly.compile(bytes)
+                                );
+                            }}
+                            return WebAssembly.compile(module);
+                        }}){compile_end_then};
+                }}
+
+                {instantiate_doc}
+                function instantiate(module{init_memory_arg}) {{
+                    {mark_start}
+                    const imports = __wbg_get_imports({memory_arg});
+                    let result = WebAssembly.instantiate(module, imports)
+                        .then(instance => ({{ instance, module }}));
+                    {link_error}
+                    return result
+                        .then(({{instance, module}}) => __wbg_finalize_init(instance, module)){async_start};
+                }}
+
+                {init_sync_doc}
+                function initSync(module{init_memory_arg}) {{
+                    const imports = __wbg_get_imports({memory_arg});
+                    if (!(module instanceof WebAssembly.Module)) {{
+                        {compile_start}
+                        module = new WebAssembly.Module(module);
+                        {compile_end}
+                    }}
+                    {mark_start}
+                    let instance;
+                    try {{
+                        instance = new WebAssembly.Instance(module, imports);
+                    }} catch (e) {{
+                        {link_error_sync}
+                    }}
+                    __wbg_finalize_init(instance, module);
+                    {async_start_sync}
+                    return wasm;
+                }}
+
+                {init_doc}
+                function init(module{init_memory_arg}) {{
+                    {default_module_path}
+                    {init_body}
                 }}
             ",
             init_memory_arg = init_memory_arg,
+            memory_arg = init_memory_arg.trim_start_matches(", "),
+            input_typedef = input_typedef,
+            compile_doc = compile_doc,
+            instantiate_doc = instantiate_doc,
+            init_sync_doc = init_sync_doc,
+            init_doc = init_doc,
             default_module_path = default_module_path,
-            init_memory1 = init_memory1,
-            init_memory2 = init_memory2,
+            init_memory = init_memory,
+            compile_start = compile_start,
+            compile_end = compile_end,
+            compile_end_then = compile_end_then,
+            mark_start = mark_start,
+            init_body = init_body,
+            link_error = link_error,
+            link_error_sync = link_error_sync,
+            mark_end = mark_end,
             start = if needs_manual_start {
                 "wasm.__wbindgen_start();"
             } else {
                 ""
             },
             imports_init = imports_init,
+            merge_extra_imports = merge_extra_imports,
+            async_start_fn = async_start_fn,
+            async_start = async_start,
+            async_start_sync = async_start_sync,
         );
 
-        (js, ts)
+        if self.config.worker_entry {
+            let (worker_js, worker_ts) = self.gen_start_worker(output_name, share_memory);
+            js.push_str(&worker_js);
+            ts.push_str(&worker_ts);
+        }
+
+        Ok((js, ts))
     }
 
     fn write_classes(&mut self) -> Result<(), Error> {
@@ -559,11 +1494,63 @@ impl<'a> Context<'a> {
         Ok(())
     }
 
+    fn write_namespaces(&mut self) -> Result<(), Error> {
+        for (name, namespace) in self.exported_namespaces.take().unwrap() {
+            if self.defined_identifiers.contains_key(&name) {
+                bail!(
+                    "cannot export namespace `{}` as the name is already used by \
+                     another export or import",
+                    name
+                );
+            }
+            let js = namespace.js_object("")?;
+            self.export(&name, &js, None, ExportedItem::Namespace)?;
+            self.typescript.push_str(&namespace.typescript(&name, ""));
+        }
+        Ok(())
+    }
+
     fn write_class(&mut self, name: &str, class: &ExportedClass) -> Result<(), Error> {
         let mut dst = format!("class {} {{\n", name);
         let mut ts_dst = format!("export {}", dst);
+        let this_ptr = self.this_ptr();
+
+        if self.config.js_flavor == JsFlavor::Es2022 {
+            // Private fields can only be installed by running the
+            // constructor, so `__wrap` goes through it with a token that user
+            // code can't get at. The rest of the glue reads the pointer through
+            // an accessor defined in a static block, which can see `#ptr`.
+            self.expose_wrap_token();
+            self.global(&format!("let {}Ptr;", name));
+            dst.push_str(&format!(
+                "
+                #ptr = 0;
 
-        if self.config.debug && !class.has_constructor {
+                static {{
+                    {}Ptr = (obj, take) => {{
+                        const ptr = obj.#ptr;
+                        if (take) {{
+                            obj.#ptr = 0;
+                        }}
+                        return ptr;
+                    }};
+                }}
+                ",
+                name
+            ));
+            if !class.has_constructor {
+                dst.push_str(
+                    "
+                    constructor(token, ptr) {
+                        if (token !== wrapToken) {
+                            throw new Error('cannot invoke `new` directly');
+                        }
+                        this.#ptr = ptr;
+                    }
+                    ",
+                );
+            }
+        } else if self.config.debug && !class.has_constructor {
             dst.push_str(
                 "
                     constructor() {
@@ -573,26 +1560,36 @@ impl<'a> Context<'a> {
             );
         }
 
+        // Classes without a `free` method are never freed from JS, so they're
+        // also not registered for automatic finalization.
+        let weak_refs = self.config.weak_refs && !class.no_free;
+
         if class.wrap_needed {
             dst.push_str(&format!(
                 "
                 static __wrap(ptr) {{
-                    const obj = Object.create({}.prototype);
-                    obj.ptr = ptr;
+                    {}
                     {}
                     return obj;
                 }}
                 ",
-                name,
-                if self.config.weak_refs {
-                    format!("{}FinalizationGroup.register(obj, obj.ptr, obj.ptr);", name)
+                if self.config.js_flavor == JsFlavor::Es2022 {
+                    format!("const obj = new {}(wrapToken, ptr);", name)
+                } else {
+                    format!(
+                        "const obj = Object.create({}.prototype);\nobj.ptr = ptr;",
+                        name
+                    )
+                },
+                if weak_refs {
+                    format!("{}FinalizationGroup.register(obj, ptr, ptr);", name)
                 } else {
                     String::new()
                 },
             ));
         }
 
-        if self.config.weak_refs {
+        if weak_refs {
             self.global(&format!(
                 "
                 const {}FinalizationGroup = new FinalizationGroup((items) => {{
@@ -606,43 +1603,74 @@ impl<'a> Context<'a> {
             ));
         }
 
-        dst.push_str(&format!(
-            "
-            free() {{
-                const ptr = this.ptr;
-                this.ptr = 0;
-                {}
-                wasm.{}(ptr);
-            }}
-            ",
-            if self.config.weak_refs {
-                format!("{}FinalizationGroup.unregister(ptr);", name)
-            } else {
-                String::new()
-            },
-            wasm_bindgen_shared::free_function(&name),
-        ));
-        ts_dst.push_str("  free(): void;\n");
+        if !class.no_free {
+            if self.config.jsdoc {
+                dst.push_str("/**\n* @returns {void}\n*/\n");
+            }
+            dst.push_str(&format!(
+                "
+                free() {{
+                    {}
+                    const ptr = {this_ptr};
+                    {this_ptr} = 0;
+                    {}
+                    wasm.{}(ptr);
+                }}
+                ",
+                match &class.on_free {
+                    Some(method) => format!("this.{}();", method),
+                    None => String::new(),
+                },
+                if weak_refs {
+                    format!("{}FinalizationGroup.unregister(ptr);", name)
+                } else {
+                    String::new()
+                },
+                wasm_bindgen_shared::free_function(&name),
+                this_ptr = this_ptr,
+            ));
+            ts_dst.push_str("  free(): void;\n");
+        }
         dst.push_str(&class.contents);
         ts_dst.push_str(&class.typescript);
 
+        let mut static_fields = class.typescript_static_fields.keys().collect::<Vec<_>>();
+        static_fields.sort(); // make sure we have deterministic output
+        for name in static_fields {
+            let types = &class.typescript_static_fields[name];
+            ts_dst.push_str(&types.declaration("  static ", name));
+        }
+
         let mut fields = class.typescript_fields.keys().collect::<Vec<_>>();
         fields.sort(); // make sure we have deterministic output
         for name in fields {
-            let (ty, has_setter) = &class.typescript_fields[name];
-            ts_dst.push_str("  ");
-            if !has_setter {
-                ts_dst.push_str("readonly ");
-            }
-            ts_dst.push_str(name);
-            ts_dst.push_str(": ");
-            ts_dst.push_str(ty);
-            ts_dst.push_str(";\n");
+            let types = &class.typescript_fields[name];
+            ts_dst.push_str(&types.declaration("  ", name));
         }
         dst.push_str("}\n");
         ts_dst.push_str("}\n");
 
-        self.export(&name, &dst, Some(class.comments.clone()))?;
+        // Like functions, classes in a namespace are defined locally and then
+        // exported as part of the namespace's object. The glue still refers
+        // to them by their name.
+        if class.js_namespace.len() > 0 {
+            if generate_identifier(name, &mut self.defined_identifiers) != name {
+                bail!("cannot shadow already defined class `{}`", name);
+            }
+            self.global(&format!("{}{}", class.comments, dst));
+            let namespace = require_namespace(&mut self.exported_namespaces, &class.js_namespace);
+            namespace.members.push((name.to_string(), name.to_string()));
+            namespace.typescript.push_str(&class.comments);
+            namespace.typescript.push_str(&ts_dst);
+            return Ok(());
+        }
+
+        self.export(
+            &name,
+            &dst,
+            Some(class.comments.clone()),
+            ExportedItem::Class,
+        )?;
         self.typescript.push_str(&ts_dst);
 
         Ok(())
@@ -667,6 +1695,23 @@ impl<'a> Context<'a> {
         }
     }
 
+    /// Deletes the internal exports our JS glue required which the final JS
+    /// in `js` doesn't reference anymore, returning whether any were deleted.
+    fn unexport_unreferenced_internal_exports(&mut self, js: &str) -> bool {
+        let to_remove = self
+            .module
+            .exports
+            .iter()
+            .filter(|e| self.required_internal_exports.contains(e.name.as_str()))
+            .filter(|e| !references(js, &e.name))
+            .map(|e| e.id())
+            .collect::<Vec<_>>();
+        for id in to_remove.iter() {
+            self.module.exports.delete(*id);
+        }
+        to_remove.len() > 0
+    }
+
     fn expose_drop_ref(&mut self) {
         if !self.should_write_global("drop_ref") {
             return;
@@ -682,16 +1727,27 @@ impl<'a> Context<'a> {
         //
         // Otherwise the free operation here is pretty simple, just appending to
         // the linked list of heap slots that are free.
-        self.global(&format!(
-            "
+        let clear_site = if self.record_allocation_sites() {
+            self.expose_global_heap_sites();
+            String::from("heap_sites[idx] = undefined;")
+        } else {
+            String::new()
+        };
+        self.heap_global(
+            &["dropObject"],
+            &format!(
+                "
             function dropObject(idx) {{
                 if (idx < {}) return;
                 heap[idx] = heap_next;
                 heap_next = idx;
+                {}
             }}
             ",
-            INITIAL_HEAP_OFFSET + INITIAL_HEAP_VALUES.len(),
-        ));
+                INITIAL_HEAP_OFFSET + INITIAL_HEAP_VALUES.len(),
+                clear_site,
+            ),
+        );
     }
 
     fn expose_global_heap(&mut self) {
@@ -699,9 +1755,11 @@ impl<'a> Context<'a> {
             return;
         }
         assert!(!self.config.anyref);
-        self.global(&format!("const heap = new Array({});", INITIAL_HEAP_OFFSET));
-        self.global("heap.fill(undefined);");
-        self.global(&format!("heap.push({});", INITIAL_HEAP_VALUES.join(", ")));
+        let heap = format!("const heap = new Array({});", INITIAL_HEAP_OFFSET);
+        self.heap_global(&["heap"], &heap);
+        self.heap_global(&[], "heap.fill(undefined);");
+        let values = format!("heap.push({});", INITIAL_HEAP_VALUES.join(", "));
+        self.heap_global(&[], &values);
     }
 
     fn expose_global_heap_next(&mut self) {
@@ -709,7 +1767,20 @@ impl<'a> Context<'a> {
             return;
         }
         self.expose_global_heap();
-        self.global("let heap_next = heap.length;");
+        self.heap_global(&["heap_next"], "let heap_next = heap.length;");
+    }
+
+    /// Whether the JS stack at which each heap object is allocated is recorded
+    /// in `heap_sites`, only done for the JS heap and in debug mode.
+    fn record_allocation_sites(&self) -> bool {
+        self.config.record_allocation_sites && self.config.debug && !self.config.anyref
+    }
+
+    fn expose_global_heap_sites(&mut self) {
+        if !self.should_write_global("heap_sites") {
+            return;
+        }
+        self.heap_global(&["heap_sites"], "const heap_sites = [];");
     }
 
     fn expose_get_object(&mut self) {
@@ -720,7 +1791,10 @@ impl<'a> Context<'a> {
 
         // Accessing a heap object is just a simple index operation due to how
         // the stack/heap are laid out.
-        self.global("function getObject(idx) { return heap[idx]; }");
+        self.heap_global(
+            &["getObject"],
+            "function getObject(idx) { return heap[idx]; }",
+        );
     }
 
     fn expose_assert_num(&mut self) {
@@ -1296,12 +2370,19 @@ impl<'a> Context<'a> {
         if !self.should_write_global(name) {
             return;
         }
+        let check_memory_grown = if self.memory_growth_hooks {
+            self.expose_check_memory_grown();
+            "checkMemoryGrown();"
+        } else {
+            ""
+        };
         let mem = self.memory();
         self.global(&format!(
             "
             let cache{name} = null;
             function {name}() {{
                 if (cache{name} === null || cache{name}.buffer !== {mem}.buffer) {{
+                    {check}
                     cache{name} = {js}({mem}.buffer);
                 }}
                 return cache{name};
@@ -1310,9 +2391,47 @@ impl<'a> Context<'a> {
             name = name,
             js = js,
             mem = mem,
+            check = check_memory_grown,
+        ));
+    }
+
+    fn expose_check_memory_grown(&mut self) {
+        if !self.should_write_global("check_memory_grown") {
+            return;
+        }
+        // The buffer is only watched once `memory_grown` is first called, so
+        // that the hooks don't fire for growth which happened before they were
+        // registered.
+        let mem = self.memory();
+        self.global(&format!(
+            "
+            let watchedMemoryBuffer = null;
+            function checkMemoryGrown() {{
+                if (watchedMemoryBuffer === null || watchedMemoryBuffer === {mem}.buffer) return;
+                watchedMemoryBuffer = {mem}.buffer;
+                wasm.__wbindgen_memory_grown_impl(watchedMemoryBuffer.byteLength / 65536);
+            }}
+            ",
+            mem = mem,
         ));
     }
 
+    /// Returns the expression for the pointer of `this` in methods of
+    /// exported classes.
+    fn this_ptr(&self) -> &'static str {
+        match self.config.js_flavor {
+            JsFlavor::Legacy => "this.ptr",
+            JsFlavor::Es2022 => "this.#ptr",
+        }
+    }
+
+    fn expose_wrap_token(&mut self) {
+        if !self.should_write_global("wrap_token") {
+            return;
+        }
+        self.global("const wrapToken = Symbol('wrapToken');");
+    }
+
     fn expose_assert_class(&mut self) {
         if !self.should_write_global("assert_class") {
             return;
@@ -1323,17 +2442,127 @@ impl<'a> Context<'a> {
                 if (!(instance instanceof klass)) {
                     throw new Error(`expected instance of ${klass.name}`);
                 }
-                return instance.ptr;
             }
-            ",
-        );
+            ",
+        );
+    }
+
+    fn expose_assert_import_type(&mut self) {
+        if !self.should_write_global("assert_import_type") {
+            return;
+        }
+        self.expose_debug_string();
+        self.expose_has_constructor_named();
+        // This is the same check as the default `JsCast::is_type_of` of
+        // imported types, accepting instances from other realms too.
+        self.global(
+            "
+            function _assertImportType(val, jsName, rustName) {
+                const scope = typeof self !== 'undefined' ? self :
+                    typeof global !== 'undefined' ? global : undefined;
+                const klass = scope === undefined ? undefined : scope[jsName];
+                if (
+                    typeof klass === 'function' &&
+                    !(val instanceof klass) &&
+                    !hasConstructorNamed(val, jsName)
+                ) {
+                    throw new Error(
+                        'expected an instance of `' + jsName + '` for the ' +
+                        'imported Rust type `' + rustName + '`, found ' + debugString(val)
+                    );
+                }
+            }
+            ",
+        );
+    }
+
+    fn expose_map_to_entries(&mut self) {
+        if !self.should_write_global("map_to_entries") {
+            return;
+        }
+        self.global(
+            "
+            function mapToEntries(map) {
+                const entries = [];
+                if (map instanceof Map) {
+                    for (const [key, value] of map) {
+                        entries.push(key, value);
+                    }
+                } else {
+                    for (const key of Object.keys(map)) {
+                        entries.push(key, map[key]);
+                    }
+                }
+                return entries;
+            }
+            ",
+        );
+    }
+
+    fn expose_entries_to_map(&mut self, object: bool) -> &'static str {
+        if object {
+            if self.should_write_global("entries_to_object") {
+                self.global(
+                    "
+                    function entriesToObject(entries) {
+                        const object = {};
+                        for (let i = 0; i < entries.length; i += 2) {
+                            object[entries[i]] = entries[i + 1];
+                        }
+                        return object;
+                    }
+                    ",
+                );
+            }
+            "entriesToObject"
+        } else {
+            if self.should_write_global("entries_to_map") {
+                self.global(
+                    "
+                    function entriesToMap(entries) {
+                        const map = new Map();
+                        for (let i = 0; i < entries.length; i += 2) {
+                            map.set(entries[i], entries[i + 1]);
+                        }
+                        return map;
+                    }
+                    ",
+                );
+            }
+            "entriesToMap"
+        }
     }
 
     fn expose_global_stack_pointer(&mut self) {
         if !self.should_write_global("stack_pointer") {
             return;
         }
-        self.global(&format!("let stack_pointer = {};", INITIAL_HEAP_OFFSET));
+        let stack_pointer = format!("let stack_pointer = {};", INITIAL_HEAP_OFFSET);
+        self.heap_global(&[], &stack_pointer);
+    }
+
+    /// Returns the statement releasing the last object added with
+    /// `addBorrowedObject`.
+    ///
+    /// Modules sharing a heap can't assign the shared `stack_pointer`, so
+    /// they call a function of the shared heap module instead.
+    fn release_borrowed_object(&mut self) -> &'static str {
+        self.expose_global_stack_pointer();
+        if self.config.shared_heap.is_none() {
+            return "heap[stack_pointer++] = undefined;";
+        }
+        if self.should_write_global("release_borrowed_object") {
+            self.expose_global_heap();
+            self.heap_global(
+                &["releaseBorrowedObject"],
+                "
+                function releaseBorrowedObject() {
+                    heap[stack_pointer++] = undefined;
+                }
+                ",
+            );
+        }
+        "releaseBorrowedObject();"
     }
 
     fn expose_borrowed_objects(&mut self) {
@@ -1347,7 +2576,8 @@ impl<'a> Context<'a> {
         // after executing this. Once we've reserved stack space we write the
         // value. Eventually underflow will throw an exception, but JS sort of
         // just handles it today...
-        self.global(
+        self.heap_global(
+            &["addBorrowedObject"],
             "
             function addBorrowedObject(obj) {
                 if (stack_pointer == 1) throw new Error('out of js stack');
@@ -1364,7 +2594,8 @@ impl<'a> Context<'a> {
         }
         self.expose_get_object();
         self.expose_drop_ref();
-        self.global(
+        self.heap_global(
+            &["takeObject"],
             "
             function takeObject(idx) {
                 const ret = getObject(idx);
@@ -1390,24 +2621,34 @@ impl<'a> Context<'a> {
         } else {
             String::new()
         };
+        let record_site = if self.record_allocation_sites() {
+            self.expose_global_heap_sites();
+            String::from("heap_sites[idx] = new Error().stack;")
+        } else {
+            String::new()
+        };
 
         // Allocating a slot on the heap first goes through the linked list
         // (starting at `heap_next`). Once that linked list is exhausted we'll
         // be pointing beyond the end of the array, at which point we'll reserve
         // one more slot and use that.
-        self.global(&format!(
-            "
+        self.heap_global(
+            &["addHeapObject"],
+            &format!(
+                "
             function addHeapObject(obj) {{
                 if (heap_next === heap.length) heap.push(heap.length + 1);
                 const idx = heap_next;
                 heap_next = heap[idx];
                 {}
                 heap[idx] = obj;
+                {}
                 return idx;
             }}
             ",
-            set_heap_next
-        ));
+                set_heap_next, record_site,
+            ),
+        );
     }
 
     fn expose_handle_error(&mut self) -> Result<(), Error> {
@@ -1444,7 +2685,7 @@ impl<'a> Context<'a> {
         }
         self.global(
             "\
-            function logError(e) {
+            function logError(e, name) {
                 let error = (function () {
                     try {
                         return e instanceof Error \
@@ -1454,8 +2695,10 @@ impl<'a> Context<'a> {
                         return \"<failed to stringify thrown value>\";
                     }
                 }());
+                const location = name === undefined ? '' : ' (in `' + name + '`)';
                 console.error(\"wasm-bindgen: imported JS function that \
-                                was not marked as `catch` threw an error:\", \
+                                was not marked as `catch` threw an error\" + \
+                                location + \":\", \
                                 error);
                 throw e;
             }
@@ -1463,6 +2706,155 @@ impl<'a> Context<'a> {
         );
     }
 
+    fn expose_has_constructor_named(&mut self) {
+        if !self.should_write_global("has_constructor_named") {
+            return;
+        }
+        self.global(
+            "\
+            function hasConstructorNamed(val, name) {
+                if (val === null || (typeof(val) !== 'object' && typeof(val) !== 'function')) {
+                    return false;
+                }
+                let proto = Object.getPrototypeOf(val);
+                while (proto !== null) {
+                    const ctor = Object.getOwnPropertyDescriptor(proto, 'constructor');
+                    if (ctor !== undefined && typeof(ctor.value) === 'function' && ctor.value.name === name) {
+                        return true;
+                    }
+                    proto = Object.getPrototypeOf(proto);
+                }
+                return false;
+            }
+            ",
+        );
+    }
+
+    fn expose_poison_on_panic(&mut self) {
+        if !self.should_write_global("poison_on_panic") {
+            return;
+        }
+        self.global(
+            "\
+            let pendingPanic = null;
+            let panicError = null;
+
+            function checkPoisoned() {
+                if (panicError !== null) throw panicError;
+            }
+
+            function poisonOnPanic(e) {
+                if (!(e instanceof WebAssembly.RuntimeError)) return e;
+                if (panicError === null) {
+                    const panic = pendingPanic || { message: e.message, location: undefined };
+                    panicError = new Error(panic.message);
+                    panicError.name = 'PanicError';
+                    panicError.location = panic.location;
+                    panicError.poisoned = true;
+                }
+                return panicError;
+            }
+            ",
+        );
+    }
+
+    fn expose_annotate_rejection(&mut self) {
+        if !self.should_write_global("annotate_rejection") {
+            return;
+        }
+        // With the `panic-exceptions` feature a trap is turned into the same
+        // `PanicError` carrying the panic message as for synchronous calls.
+        let to_panic = if self.panic_exceptions {
+            "e = poisonOnPanic(e);\n"
+        } else {
+            ""
+        };
+        self.global(&format!(
+            "\
+            function annotateRejection(ret, name) {{
+                const thenable = (typeof(ret) === 'object' && ret !== null) || typeof(ret) === 'function';
+                if (!thenable || typeof(ret.then) !== 'function') return ret;
+                return ret.then(undefined, e => {{
+                    {}\
+                    let reason;
+                    if (e instanceof WebAssembly.RuntimeError) {{
+                        reason = 'the wasm instance trapped with `' + e.message + '`, most likely \
+                            because Rust code panicked. Install a panic hook, for example with the \
+                            `console_error_panic_hook` crate, to see the panic message';
+                    }} else if (e instanceof Error && e.name === 'PanicError') {{
+                        reason = 'Rust code panicked at ' + (e.location || '<unknown>') + \
+                            ':\\n' + e.message;
+                    }} else {{
+                        throw e;
+                    }}
+                    const error = new Error('the promise returned by `' + name + '` was \
+                        rejected as ' + reason);
+                    error.cause = e;
+                    throw error;
+                }});
+            }}
+            ",
+            to_panic,
+        ));
+    }
+
+    fn expose_mark_first_call(&mut self) {
+        if !self.should_write_global("mark_first_call") {
+            return;
+        }
+        self.expose_performance_marks();
+        self.global(
+            "\
+            let firstCallMarked = false;
+            function markFirstCall() {
+                if (!firstCallMarked) {
+                    firstCallMarked = true;
+                    const perf = getPerformance();
+                    if (perf !== null) {
+                        perf.mark('wasm-bindgen:first-call');
+                    }
+                }
+            }
+            ",
+        );
+    }
+
+    fn expose_performance_marks(&mut self) {
+        if !self.should_write_global("performance_marks") {
+            return;
+        }
+        let fallback = match self.config.mode {
+            OutputMode::Node {
+                experimental_modules: false,
+            } => Some("require('perf_hooks').performance"),
+            _ => None,
+        };
+        self.global(&performance_marks_js(fallback));
+    }
+
+    fn expose_count_call(&mut self) -> Result<(), Error> {
+        if !self.should_write_global("count_call") {
+            return Ok(());
+        }
+        self.global(
+            "\
+            const callCounts = new Map();
+            function countCall(name) {
+                callCounts.set(name, (callCounts.get(name) || 0) + 1);
+            }
+            ",
+        );
+        self.export(
+            "__wbindgen_call_counts",
+            "function() { return new Map(callCounts); }",
+            None,
+            ExportedItem::Function,
+        )?;
+        self.typescript
+            .push_str("export function __wbindgen_call_counts(): Map<string, number>;\n");
+        Ok(())
+    }
+
     fn pass_to_wasm_function(&mut self, t: VectorKind) -> Result<&'static str, Error> {
         let s = match t {
             VectorKind::String => {
@@ -1633,16 +3025,28 @@ impl<'a> Context<'a> {
         );
     }
 
-    fn global(&mut self, s: &str) {
-        let s = s.trim();
-
-        // Ensure a blank line between adjacent items, and ensure everything is
-        // terminated with a newline.
-        while !self.globals.ends_with("\n\n\n") && !self.globals.ends_with("*/\n") {
-            self.globals.push_str("\n");
+    /// Defines a global of the JS heap, like `global`.
+    ///
+    /// With a heap shared between linked modules, the definition is instead
+    /// recorded in `heap_globals` to be emitted in the shared heap module,
+    /// and the `exports` it defines are imported from there.
+    fn heap_global(&mut self, exports: &[&'static str], s: &str) {
+        let module = match &self.config.shared_heap {
+            Some(module) => module.clone(),
+            None => return self.global(s),
+        };
+        if exports.len() > 0 {
+            let items = self.js_imports.entry(module.clone()).or_insert(Vec::new());
+            items.extend(exports.iter().map(|name| (name.to_string(), None)));
+            self.js_import_kinds.insert(module, "heap");
         }
-        self.globals.push_str(s);
-        self.globals.push_str("\n");
+        self.heap_globals
+            .push((exports.to_vec(), s.trim().to_string()));
+    }
+
+    /// Adds `s` as a new top-level item of the generated JS.
+    fn global(&mut self, s: &str) {
+        self.globals.push(format!("{}\n", s.trim()));
     }
 
     fn memory(&mut self) -> &'static str {
@@ -1667,13 +3071,16 @@ impl<'a> Context<'a> {
             return Ok(name.clone());
         }
 
+        let config = self.config;
         let js_imports = &mut self.js_imports;
-        let mut add_module_import = |module: String, name: &str, actual: &str| {
+        let js_import_kinds = &mut self.js_import_kinds;
+        let mut add_module_import = |kind, module: String, name: &str, actual: &str| {
             let rename = if name == actual {
                 None
             } else {
                 Some(actual.to_string())
             };
+            js_import_kinds.insert(module.clone(), kind);
             js_imports
                 .entry(module)
                 .or_insert(Vec::new())
@@ -1683,53 +3090,78 @@ impl<'a> Context<'a> {
         let mut name = match &import.name {
             JsImportName::Module { module, name } => {
                 let unique_name = generate_identifier(name, &mut self.defined_identifiers);
-                add_module_import(module.clone(), name, &unique_name);
+                add_module_import("module", module.clone(), name, &unique_name);
+                unique_name
+            }
+
+            JsImportName::RawModule { module, name } => {
+                let unique_name = generate_identifier(name, &mut self.defined_identifiers);
+                let module = raw_module_specifier(config, module);
+                add_module_import("raw_module", module, name, &unique_name);
                 unique_name
             }
 
             JsImportName::LocalModule { module, name } => {
                 let unique_name = generate_identifier(name, &mut self.defined_identifiers);
-                add_module_import(format!("./snippets/{}", module), name, &unique_name);
+                let module = format!("./snippets/{}", module);
+                add_module_import("snippet", module, name, &unique_name);
                 unique_name
             }
 
-            JsImportName::InlineJs {
-                unique_crate_identifier,
-                snippet_idx_in_crate,
-                name,
-            } => {
+            JsImportName::InlineJs { snippet, name } => {
                 let unique_name = generate_identifier(name, &mut self.defined_identifiers);
-                let module = format!(
-                    "./snippets/{}/inline{}.js",
-                    unique_crate_identifier, snippet_idx_in_crate,
-                );
-                add_module_import(module, name, &unique_name);
+                let module = format!("./snippets/{}", snippet);
+                add_module_import("snippet", module, name, &unique_name);
                 unique_name
             }
 
-            JsImportName::VendorPrefixed { name, prefixes } => {
-                self.imports_post.push_str("const l");
-                self.imports_post.push_str(&name);
-                self.imports_post.push_str(" = ");
-                switch(&mut self.imports_post, name, "", prefixes);
-                self.imports_post.push_str(";\n");
+            JsImportName::VendorPrefixed {
+                namespace,
+                name,
+                prefixes,
+            } => {
+                // The first letter of the name is uppercased after a prefix,
+                // like in `webkitRequestAnimationFrame`.
+                let mut candidates = vec![name.clone()];
+                for prefix in prefixes {
+                    let mut chars = name.chars();
+                    let first = chars.next().map(|c| c.to_uppercase().to_string());
+                    let rest = chars.as_str();
+                    candidates.push(format!("{}{}{}", prefix, first.unwrap_or_default(), rest));
+                }
+
+                // Globals are looked up once and cached, but properties of
+                // namespaces are called through the namespace so that they
+                // receive it as `this`, so only the found name is cached.
+                let (tests, found): (Vec<_>, Vec<_>) = match namespace {
+                    Some(ns) => candidates
+                        .iter()
+                        .map(|c| (format!("{}.{}", ns, c), format!("'{}'", c)))
+                        .unzip(),
+                    None => (candidates.clone(), candidates.clone()),
+                };
+                let mut value = found[found.len() - 1].clone();
+                for i in (0..found.len() - 1).rev() {
+                    value = format!(
+                        "(typeof {} !== 'undefined' ? {} : {})",
+                        tests[i], found[i], value
+                    );
+                }
 
-                fn switch(dst: &mut String, name: &str, prefix: &str, left: &[String]) {
-                    if left.len() == 0 {
-                        dst.push_str(prefix);
-                        return dst.push_str(name);
+                match namespace {
+                    Some(ns) => {
+                        let local = format!("l{}", name);
+                        let local = generate_identifier(&local, &mut self.defined_identifiers);
+                        self.imports_post
+                            .push_str(&format!("const {} = {};\n", local, value));
+                        format!("{}[{}]", ns, local)
+                    }
+                    None => {
+                        self.imports_post
+                            .push_str(&format!("const l{} = {};\n", name, value));
+                        format!("l{}", name)
                     }
-                    dst.push_str("(typeof ");
-                    dst.push_str(prefix);
-                    dst.push_str(name);
-                    dst.push_str(" !== 'undefined' ? ");
-                    dst.push_str(prefix);
-                    dst.push_str(name);
-                    dst.push_str(" : ");
-                    switch(dst, name, &left[0], &left[1..]);
-                    dst.push_str(")");
                 }
-                format!("l{}", name)
             }
 
             JsImportName::Global { name } => {
@@ -1806,10 +3238,23 @@ impl<'a> Context<'a> {
         aux: &WasmBindgenAux,
         bindings: &NonstandardWebidlSection,
     ) -> Result<(), Error> {
+        self.memory_growth_hooks = aux.import_map.values().any(|import| match import {
+            AuxImport::Intrinsic(Intrinsic::MemoryWatch) => true,
+            _ => false,
+        });
+        if self.memory_growth_hooks {
+            self.require_internal_export("__wbindgen_memory_grown_impl")?;
+        }
+
         for (i, (idx, binding)) in bindings.elems.iter().enumerate() {
             self.generate_elem_binding(i, *idx, binding, bindings)?;
         }
 
+        self.panic_exceptions = aux.import_map.values().any(|import| match import {
+            AuxImport::Intrinsic(Intrinsic::PanicError) => true,
+            _ => false,
+        });
+
         let mut pairs = aux.export_map.iter().collect::<Vec<_>>();
         pairs.sort_by_key(|(k, _)| *k);
         check_duplicated_getter_and_setter_names(&pairs)?;
@@ -1822,6 +3267,23 @@ impl<'a> Context<'a> {
                     )
                 })?;
         }
+        // Exports which were compiled out can't be called, but they're noted
+        // so that it's clear why they're missing.
+        for omitted in aux.omitted_exports.iter() {
+            let note = format!(
+                "// `{}` isn't available, it's only compiled with `cfg({})`\n",
+                omitted.name, omitted.cfg,
+            );
+            self.global(&note);
+            self.typescript.push_str(&note);
+        }
+        if self.config.emit_start {
+            self.async_starts = aux
+                .async_starts
+                .iter()
+                .map(|id| self.function_references[id].clone())
+                .collect();
+        }
 
         for (id, import) in sorted_iter(&aux.import_map) {
             let variadic = aux.imports_with_variadic.contains(&id);
@@ -1841,7 +3303,9 @@ impl<'a> Context<'a> {
 
         self.typescript.push_str(&aux.extra_typescript);
 
-        for path in aux.package_jsons.iter() {
+        let mut package_jsons = aux.package_jsons.iter().collect::<Vec<_>>();
+        package_jsons.sort();
+        for path in package_jsons {
             self.process_package_json(path)?;
         }
 
@@ -1876,8 +3340,7 @@ impl<'a> Context<'a> {
                 args.join(", ")
             ))
         })?;
-        self.globals
-            .push_str(&format!("function __wbg_elem_binding{}{}\n", idx, js));
+        self.global(&format!("function __wbg_elem_binding{}{}", idx, js));
         Ok(())
     }
 
@@ -1894,15 +3357,41 @@ impl<'a> Context<'a> {
             .get::<ast::WebidlFunction>(binding.webidl_ty)
             .unwrap();
 
+        // The name of the export's function in the name section is used in
+        // debug mode to point at the Rust function when something goes wrong.
+        let debug_name = match self.module.exports.get(id).item {
+            walrus::ExportItem::Function(f) => self.module.funcs.get(f).name.clone(),
+            _ => None,
+        };
+
         // Construct a JS shim builder, and configure it based on the kind of
         // export that we're generating.
         let mut builder = binding::Builder::new(self);
+        builder.debug_name(debug_name.as_ref().unwrap_or(&export.debug_name));
+        builder.arg_defaults(&export.arg_defaults);
+        if self.panic_exceptions {
+            builder.poison_on_panic();
+        }
+        if self.config.debug {
+            builder.annotate_rejections();
+        }
         match &export.kind {
             AuxExportKind::Function(_) => {}
             AuxExportKind::StaticFunction { .. } => {}
+            AuxExportKind::StaticGetter { .. } | AuxExportKind::StaticSetter { .. } => {}
             AuxExportKind::Constructor(class) => builder.constructor(class),
             AuxExportKind::Getter { .. } | AuxExportKind::Setter { .. } => builder.method(false),
-            AuxExportKind::Method { consumed, .. } => builder.method(*consumed),
+            AuxExportKind::Method {
+                class,
+                consumed,
+                chain,
+                ..
+            } => {
+                builder.method(*consumed);
+                if *chain {
+                    builder.chain(class);
+                }
+            }
         }
 
         // Process the `binding` and generate a bunch of JS/TypeScript/etc.
@@ -1913,16 +3402,42 @@ impl<'a> Context<'a> {
             &export.arg_names,
             &mut |_, _, args| Ok(format!("wasm.{}({})", wasm_name, args.join(", "))),
         )?;
+        builder.typescript_types(&export.typescript_types);
         let ts = builder.typescript_signature();
         let js_doc = builder.js_doc_comments();
         let docs = format_doc_comments(&export.comments, Some(js_doc));
+        *self
+            .export_sizes
+            .entry(export.debug_name.clone())
+            .or_insert(0) += js.len();
 
         // Once we've got all the JS then put it in the right location dependin
         // on what's being exported.
         match &export.kind {
+            // Functions in a namespace are defined locally and then exported
+            // as part of the namespace's object, see `write_namespaces`.
+            AuxExportKind::Function(name) if export.js_namespace.len() > 0 => {
+                let definition = format!("{}_{}", export.js_namespace.join("_"), name);
+                let definition = generate_identifier(&definition, &mut self.defined_identifiers);
+                self.global(&format!("{}function {}{}", docs, definition, js));
+                self.function_references.insert(id, definition.clone());
+                let namespace =
+                    require_namespace(&mut self.exported_namespaces, &export.js_namespace);
+                namespace.members.push((name.clone(), definition));
+                namespace.typescript.push_str(&docs);
+                namespace.typescript.push_str("export function ");
+                namespace.typescript.push_str(&name);
+                namespace.typescript.push_str(&ts);
+                namespace.typescript.push_str(";\n");
+            }
             AuxExportKind::Function(name) => {
-                self.export(&name, &format!("function{}", js), Some(docs))?;
-                self.globals.push_str("\n");
+                let reference = self.export(
+                    &name,
+                    &format!("function{}", js),
+                    Some(docs),
+                    ExportedItem::Function,
+                )?;
+                self.function_references.insert(id, reference);
                 self.typescript.push_str("export function ");
                 self.typescript.push_str(&name);
                 self.typescript.push_str(&ts);
@@ -1937,12 +3452,22 @@ impl<'a> Context<'a> {
                 exported.push(&docs, "constructor", "", &js, &ts);
             }
             AuxExportKind::Getter { class, field } => {
-                let ret_ty = builder.ts_ret.as_ref().unwrap().ty.clone();
+                let ret_ty = builder.ts_ret.as_ref().unwrap().full_ty();
                 let exported = require_class(&mut self.exported_classes, class);
                 exported.push_getter(&docs, field, &js, &ret_ty);
             }
+            AuxExportKind::StaticGetter { class, field } => {
+                let ret_ty = builder.ts_ret.as_ref().unwrap().full_ty();
+                let exported = require_class(&mut self.exported_classes, class);
+                exported.push_static_getter(&docs, field, &js, &ret_ty);
+            }
+            AuxExportKind::StaticSetter { class, field } => {
+                let arg_ty = builder.ts_args[0].full_ty();
+                let exported = require_class(&mut self.exported_classes, class);
+                exported.push_static_setter(&docs, field, &js, &arg_ty);
+            }
             AuxExportKind::Setter { class, field } => {
-                let arg_ty = builder.ts_args[0].ty.clone();
+                let arg_ty = builder.ts_args[0].full_ty();
                 let exported = require_class(&mut self.exported_classes, class);
                 exported.push_setter(&docs, field, &js, &arg_ty);
             }
@@ -1971,7 +3496,16 @@ impl<'a> Context<'a> {
             .types
             .get::<ast::WebidlFunction>(binding.webidl_ty)
             .unwrap();
+        let name = {
+            let import = self.module.imports.get(id);
+            let func_name = match import.kind {
+                walrus::ImportKind::Function(f) => self.module.funcs.get(f).name.clone(),
+                _ => None,
+            };
+            func_name.unwrap_or_else(|| import.name.clone())
+        };
         let mut builder = binding::Builder::new(self);
+        builder.debug_name(&name);
         builder.catch(catch)?;
         let js = builder.process(&binding, &webidl, false, &None, &mut |cx, prelude, args| {
             cx.invoke_import(&binding, import, bindings, args, variadic, prelude)
@@ -2039,21 +3573,11 @@ impl<'a> Context<'a> {
                             let class = self.import_name(class)?;
                             descriptor(&class, ".prototype", field, "get")
                         }
-                        AuxValue::ClassGetter(class, field) => {
-                            self.expose_get_inherited_descriptor();
-                            let class = self.import_name(class)?;
-                            descriptor(&class, "", field, "get")
-                        }
                         AuxValue::Setter(class, field) => {
                             self.expose_get_inherited_descriptor();
                             let class = self.import_name(class)?;
                             descriptor(&class, ".prototype", field, "set")
                         }
-                        AuxValue::ClassSetter(class, field) => {
-                            self.expose_get_inherited_descriptor();
-                            let class = self.import_name(class)?;
-                            descriptor(&class, "", field, "set")
-                        }
                     };
                     Ok(format!("{}.call({})", js, variadic_args(&args)?))
                 }
@@ -2081,6 +3605,28 @@ impl<'a> Context<'a> {
                 self.import_name(js)
             }
 
+            AuxImport::ThreadLocalStatic(js) => {
+                assert!(webidl_ty.kind == ast::WebidlFunctionKind::Static);
+                assert!(!variadic);
+                assert_eq!(args.len(), 0);
+                let name = self.import_name(js)?;
+                match &js.name {
+                    JsImportName::Global { name: global } => Ok(format!(
+                        "(typeof {} === 'undefined' ? undefined : {})",
+                        global, name
+                    )),
+                    _ => Ok(name),
+                }
+            }
+
+            AuxImport::StaticSetter(js) => {
+                assert!(webidl_ty.kind == ast::WebidlFunctionKind::Static);
+                assert!(!variadic);
+                assert_eq!(args.len(), 1);
+                let js = self.import_name(js)?;
+                Ok(format!("{} = {}", js, args[0]))
+            }
+
             AuxImport::Closure {
                 dtor,
                 mutable,
@@ -2231,41 +3777,169 @@ impl<'a> Context<'a> {
                 Ok(format!("delete {}[{}]", class, args[0]))
             }
 
-            AuxImport::IndexingDeleterOfObject => {
-                assert!(webidl_ty.kind == ast::WebidlFunctionKind::Static);
-                assert!(!variadic);
+            AuxImport::IndexingDeleterOfObject => {
+                assert!(webidl_ty.kind == ast::WebidlFunctionKind::Static);
+                assert!(!variadic);
+                assert_eq!(args.len(), 2);
+                Ok(format!("delete {}[{}]", args[0], args[1]))
+            }
+
+            AuxImport::WrapInExportedClass(class) => {
+                assert!(webidl_ty.kind == ast::WebidlFunctionKind::Static);
+                assert!(!variadic);
+                assert_eq!(args.len(), 1);
+                self.require_class_wrap(class);
+                Ok(format!("{}.__wrap({})", class, args[0]))
+            }
+
+            AuxImport::Intrinsic(intrinsic) => {
+                assert!(webidl_ty.kind == ast::WebidlFunctionKind::Static);
+                assert!(!variadic);
+                self.invoke_intrinsic(intrinsic, args, prelude)
+            }
+
+            AuxImport::LinkTo(name) => {
+                assert!(webidl_ty.kind == ast::WebidlFunctionKind::Static);
+                assert!(!variadic);
+                assert_eq!(args.len(), 0);
+                Ok(self.linked_file_url(name))
+            }
+        }
+    }
+
+    /// Returns a JS expression for the URL of the file `name`, which is copied
+    /// next to the generated JS.
+    fn linked_file_url(&mut self, name: &str) -> String {
+        if self.config.mode.uses_es_modules() {
+            return format!("new URL('./{}', import.meta.url).href", name);
+        }
+        if self.config.mode.nodejs() {
+            return format!(
+                "require('url').pathToFileURL(require('path').join(__dirname, '{}')).href",
+                name
+            );
+        }
+        // Without modules, the URL of the script has to be recorded while
+        // it's first evaluated.
+        if self.should_write_global("link_base") {
+            self.global(
+                "
+                const linkBase = typeof document === 'undefined' ?
+                    location.href :
+                    document.currentScript.src;
+                ",
+            );
+        }
+        format!("new URL('{}', linkBase).href", name)
+    }
+
+    /// Same as `invoke_import` above, except more specialized and only used for
+    /// generating the JS expression needed to implement a particular intrinsic.
+    fn invoke_intrinsic(
+        &mut self,
+        intrinsic: &Intrinsic,
+        args: &[String],
+        prelude: &mut String,
+    ) -> Result<String, Error> {
+        let expr = match intrinsic {
+            Intrinsic::JsvalEq => {
+                assert_eq!(args.len(), 2);
+                format!("{} === {}", args[0], args[1])
+            }
+
+            Intrinsic::JsvalLooseEq => {
+                assert_eq!(args.len(), 2);
+                format!("{} == {}", args[0], args[1])
+            }
+
+            Intrinsic::ObjectIs => {
+                assert_eq!(args.len(), 2);
+                format!("Object.is({}, {})", args[0], args[1])
+            }
+
+            Intrinsic::Lt => {
+                assert_eq!(args.len(), 2);
+                format!("{} < {}", args[0], args[1])
+            }
+
+            Intrinsic::Le => {
+                assert_eq!(args.len(), 2);
+                format!("{} <= {}", args[0], args[1])
+            }
+
+            Intrinsic::Gt => {
+                assert_eq!(args.len(), 2);
+                format!("{} > {}", args[0], args[1])
+            }
+
+            Intrinsic::Ge => {
+                assert_eq!(args.len(), 2);
+                format!("{} >= {}", args[0], args[1])
+            }
+
+            Intrinsic::Add => {
+                assert_eq!(args.len(), 2);
+                format!("{} + {}", args[0], args[1])
+            }
+
+            Intrinsic::Sub => {
+                assert_eq!(args.len(), 2);
+                format!("{} - {}", args[0], args[1])
+            }
+
+            Intrinsic::Mul => {
+                assert_eq!(args.len(), 2);
+                format!("{} * {}", args[0], args[1])
+            }
+
+            Intrinsic::Div => {
+                assert_eq!(args.len(), 2);
+                format!("{} / {}", args[0], args[1])
+            }
+
+            Intrinsic::Rem => {
+                assert_eq!(args.len(), 2);
+                format!("{} % {}", args[0], args[1])
+            }
+
+            Intrinsic::Pow => {
+                assert_eq!(args.len(), 2);
+                format!("{} ** {}", args[0], args[1])
+            }
+
+            Intrinsic::BitAnd => {
+                assert_eq!(args.len(), 2);
+                format!("{} & {}", args[0], args[1])
+            }
+
+            Intrinsic::BitOr => {
                 assert_eq!(args.len(), 2);
-                Ok(format!("delete {}[{}]", args[0], args[1]))
+                format!("{} | {}", args[0], args[1])
             }
 
-            AuxImport::WrapInExportedClass(class) => {
-                assert!(webidl_ty.kind == ast::WebidlFunctionKind::Static);
-                assert!(!variadic);
-                assert_eq!(args.len(), 1);
-                self.require_class_wrap(class);
-                Ok(format!("{}.__wrap({})", class, args[0]))
+            Intrinsic::BitXor => {
+                assert_eq!(args.len(), 2);
+                format!("{} ^ {}", args[0], args[1])
             }
 
-            AuxImport::Intrinsic(intrinsic) => {
-                assert!(webidl_ty.kind == ast::WebidlFunctionKind::Static);
-                assert!(!variadic);
-                self.invoke_intrinsic(intrinsic, args, prelude)
+            Intrinsic::Shl => {
+                assert_eq!(args.len(), 2);
+                format!("{} << {}", args[0], args[1])
             }
-        }
-    }
 
-    /// Same as `invoke_import` above, except more specialized and only used for
-    /// generating the JS expression needed to implement a particular intrinsic.
-    fn invoke_intrinsic(
-        &mut self,
-        intrinsic: &Intrinsic,
-        args: &[String],
-        prelude: &mut String,
-    ) -> Result<String, Error> {
-        let expr = match intrinsic {
-            Intrinsic::JsvalEq => {
+            Intrinsic::Shr => {
                 assert_eq!(args.len(), 2);
-                format!("{} === {}", args[0], args[1])
+                format!("{} >> {}", args[0], args[1])
+            }
+
+            Intrinsic::Neg => {
+                assert_eq!(args.len(), 1);
+                format!("-{}", args[0])
+            }
+
+            Intrinsic::BitNot => {
+                assert_eq!(args.len(), 1);
+                format!("~{}", args[0])
             }
 
             Intrinsic::IsFunction => {
@@ -2299,6 +3973,28 @@ impl<'a> Context<'a> {
                 format!("typeof({}) === 'string'", args[0])
             }
 
+            Intrinsic::IsBigInt => {
+                assert_eq!(args.len(), 1);
+                format!("typeof({}) === 'bigint'", args[0])
+            }
+
+            // Keep this in sync with the discriminants of `JsType` in
+            // `src/lib.rs`.
+            Intrinsic::Typeof => {
+                assert_eq!(args.len(), 1);
+                format!(
+                    "['undefined', 'object', 'boolean', 'number', 'bigint', 'string', \
+                     'symbol', 'function'].indexOf(typeof({}))",
+                    args[0]
+                )
+            }
+
+            Intrinsic::HasConstructorNamed => {
+                assert_eq!(args.len(), 2);
+                self.expose_has_constructor_named();
+                format!("hasConstructorNamed({}, {})", args[0], args[1])
+            }
+
             Intrinsic::ObjectCloneRef => {
                 assert_eq!(args.len(), 1);
                 args[0].clone()
@@ -2329,6 +4025,27 @@ impl<'a> Context<'a> {
                 args[0].clone()
             }
 
+            Intrinsic::BigIntFromI64 | Intrinsic::BigIntFromU64 => {
+                assert_eq!(args.len(), 2);
+                let n = format!("BigInt({}) << BigInt(32) | BigInt({})", args[1], args[0]);
+                match intrinsic {
+                    Intrinsic::BigIntFromI64 => format!("BigInt.asIntN(64, {})", n),
+                    _ => n,
+                }
+            }
+
+            Intrinsic::BigIntFromStr => {
+                assert_eq!(args.len(), 1);
+                // Invalid strings make `BigInt` throw a `SyntaxError`, which
+                // Rust checks for by looking at the type of the result.
+                prelude.push_str("let n;\n");
+                prelude.push_str(&format!(
+                    "try {{ n = BigInt({}); }} catch (_) {{}}\n",
+                    args[0]
+                ));
+                "n".to_string()
+            }
+
             Intrinsic::StringNew => {
                 assert_eq!(args.len(), 1);
                 args[0].clone()
@@ -2353,6 +4070,32 @@ impl<'a> Context<'a> {
                 "0".to_string()
             }
 
+            Intrinsic::BigIntGetAsI64 | Intrinsic::BigIntGetAsU64 => {
+                assert_eq!(args.len(), 2);
+                self.expose_uint32_memory();
+                let as_n = match intrinsic {
+                    Intrinsic::BigIntGetAsI64 => "asIntN",
+                    _ => "asUintN",
+                };
+                prelude.push_str(&format!("const obj = {};\n", args[0]));
+                prelude.push_str(&format!(
+                    "const ok = typeof(obj) === 'bigint' && BigInt.{}(64, obj) === obj;\n",
+                    as_n,
+                ));
+                prelude.push_str("if (ok) {\n");
+                prelude.push_str(&format!(
+                    "getUint32Memory()[{} / 4] = Number(BigInt.asUintN(32, obj));\n",
+                    args[1],
+                ));
+                prelude.push_str(&format!(
+                    "getUint32Memory()[{} / 4 + 1] = \
+                     Number(BigInt.asUintN(32, obj >> BigInt(32)));\n",
+                    args[1],
+                ));
+                prelude.push_str("}\n");
+                "ok".to_string()
+            }
+
             Intrinsic::StringGet => {
                 self.expose_pass_string_to_wasm()?;
                 self.expose_uint32_memory();
@@ -2378,6 +4121,23 @@ impl<'a> Context<'a> {
                 format!("throw new Error({})", args[0])
             }
 
+            Intrinsic::PanicError => {
+                assert_eq!(args.len(), 2);
+                self.expose_poison_on_panic();
+                format!(
+                    "pendingPanic = {{ message: {}, location: {} || undefined }}",
+                    args[0], args[1]
+                )
+            }
+
+            Intrinsic::ErrorNew => {
+                assert_eq!(args.len(), 2);
+                prelude.push_str(&format!("const error = new Error({});\n", args[0]));
+                prelude.push_str(&format!("const trace = {};\n", args[1]));
+                prelude.push_str("if (trace.length > 0) error.stack += trace;\n");
+                "error".to_string()
+            }
+
             Intrinsic::Rethrow => {
                 assert_eq!(args.len(), 1);
                 format!("throw {}", args[0])
@@ -2421,21 +4181,63 @@ impl<'a> Context<'a> {
                 format!("JSON.stringify({})", args[0])
             }
 
+            Intrinsic::ObjectNew => {
+                assert_eq!(args.len(), 0);
+                "{}".to_string()
+            }
+
+            Intrinsic::ObjectKeys => {
+                assert_eq!(args.len(), 1);
+                format!("Object.keys({})", args[0])
+            }
+
+            Intrinsic::ObjectGet => {
+                assert_eq!(args.len(), 2);
+                format!("{}[{}]", args[0], args[1])
+            }
+
+            Intrinsic::ObjectSet => {
+                assert_eq!(args.len(), 3);
+                format!("{}[{}] = {}", args[0], args[1], args[2])
+            }
+
+            Intrinsic::ArrayNew => {
+                assert_eq!(args.len(), 0);
+                "[]".to_string()
+            }
+
+            Intrinsic::IsArray => {
+                assert_eq!(args.len(), 1);
+                format!("Array.isArray({})", args[0])
+            }
+
+            Intrinsic::ArrayLength => {
+                assert_eq!(args.len(), 1);
+                format!("{}.length", args[0])
+            }
+
+            Intrinsic::ArrayGet => {
+                assert_eq!(args.len(), 2);
+                format!("{}[{}]", args[0], args[1])
+            }
+
+            Intrinsic::ArrayPush => {
+                assert_eq!(args.len(), 2);
+                format!("{}.push({})", args[0], args[1])
+            }
+
             Intrinsic::AnyrefHeapLiveCount => {
                 assert_eq!(args.len(), 0);
                 if self.config.anyref {
-                    // Eventually we should add support to the anyref-xform to
-                    // re-write calls to the imported
-                    // `__wbindgen_anyref_heap_live_count` function into calls to
-                    // the exported `__wbindgen_anyref_heap_live_count_impl`
-                    // function, and to un-export that function.
-                    //
-                    // But for now, we just bounce wasm -> js -> wasm because it is
-                    // easy.
+                    // The anyref pass rewrites calls to this import into calls
+                    // to `__wbindgen_anyref_heap_live_count_impl`, but when no
+                    // `anyref` values cross the boundary it leaves both the
+                    // import and that export alone, so bounce wasm -> js ->
+                    // wasm.
                     self.require_internal_export("__wbindgen_anyref_heap_live_count_impl")?;
                     "wasm.__wbindgen_anyref_heap_live_count_impl()".into()
                 } else {
-                    self.expose_global_heap();
+                    self.expose_global_heap_next();
                     prelude.push_str(
                         "
                             let free_count = 0;
@@ -2454,6 +4256,42 @@ impl<'a> Context<'a> {
                 }
             }
 
+            Intrinsic::HeapCapacity => {
+                assert_eq!(args.len(), 0);
+                if self.config.anyref {
+                    // Same as `AnyrefHeapLiveCount` above, bounce back into
+                    // wasm which owns the slab.
+                    self.require_internal_export("__wbindgen_anyref_heap_capacity_impl")?;
+                    "wasm.__wbindgen_anyref_heap_capacity_impl()".into()
+                } else {
+                    self.expose_global_heap();
+                    format!(
+                        "heap.length - {} - {}",
+                        INITIAL_HEAP_OFFSET,
+                        INITIAL_HEAP_VALUES.len(),
+                    )
+                }
+            }
+
+            Intrinsic::HeapAllocationSites => {
+                assert_eq!(args.len(), 0);
+                if self.record_allocation_sites() {
+                    self.expose_global_heap_sites();
+                    "heap_sites.filter(site => site !== undefined)".into()
+                } else {
+                    "undefined".into()
+                }
+            }
+
+            Intrinsic::MemoryWatch => {
+                assert_eq!(args.len(), 0);
+                self.expose_check_memory_grown();
+                format!(
+                    "if (watchedMemoryBuffer === null) watchedMemoryBuffer = {}.buffer;",
+                    self.memory()
+                )
+            }
+
             Intrinsic::InitAnyrefTable => {
                 self.expose_anyref_table();
                 String::from(
@@ -2473,26 +4311,65 @@ impl<'a> Context<'a> {
 
     fn generate_enum(&mut self, enum_: &AuxEnum) -> Result<(), Error> {
         let mut variants = String::new();
-
-        self.typescript
-            .push_str(&format!("export enum {} {{", enum_.name));
         for (name, value) in enum_.variants.iter() {
             variants.push_str(&format!("{}:{},", name, value));
-            self.typescript.push_str(&format!("\n  {},", name));
         }
-        self.typescript.push_str("\n}\n");
+        let js_doc = if self.config.jsdoc {
+            Some("@enum {number}".to_string())
+        } else {
+            None
+        };
+        let object = format!("Object.freeze({{ {} }})", variants);
         self.export(
             &enum_.name,
-            &format!("Object.freeze({{ {} }})", variants),
-            Some(format_doc_comments(&enum_.comments, None)),
+            &object,
+            Some(format_doc_comments(&enum_.comments, js_doc)),
+            ExportedItem::Enum(object.clone()),
         )?;
 
+        // By default the values are declared as a frozen object along with a
+        // union of their literal types, which is what the JS actually is, so
+        // `switch`es over the type are checked for exhaustiveness.
+        if self.config.typescript_const_enums {
+            self.typescript
+                .push_str(&format!("export const enum {} {{", enum_.name));
+            for (name, value) in enum_.variants.iter() {
+                self.typescript
+                    .push_str(&format!("\n  {} = {},", name, value));
+            }
+            self.typescript.push_str("\n}\n");
+        } else {
+            self.typescript
+                .push_str(&format!("export const {}: {{", enum_.name));
+            for (name, value) in enum_.variants.iter() {
+                self.typescript
+                    .push_str(&format!("\n  readonly {}: {},", name, value));
+            }
+            self.typescript.push_str("\n};\n");
+            let mut values = enum_
+                .variants
+                .iter()
+                .map(|(_, value)| value.to_string())
+                .collect::<Vec<_>>();
+            if values.is_empty() {
+                values.push("never".to_string());
+            }
+            self.typescript.push_str(&format!(
+                "export type {} = {};\n",
+                enum_.name,
+                values.join(" | ")
+            ));
+        }
+
         Ok(())
     }
 
     fn generate_struct(&mut self, struct_: &AuxStruct) -> Result<(), Error> {
         let class = require_class(&mut self.exported_classes, &struct_.name);
         class.comments = format_doc_comments(&struct_.comments, None);
+        class.no_free = struct_.no_free;
+        class.on_free = struct_.on_free.clone();
+        class.js_namespace = struct_.js_namespace.clone();
         Ok(())
     }
 
@@ -2686,6 +4563,26 @@ fn check_duplicated_getter_and_setter_names(
                         field: second_field,
                     },
                 ) => verify_exports(first_class, first_field, second_class, second_field)?,
+                (
+                    AuxExportKind::StaticGetter {
+                        class: first_class,
+                        field: first_field,
+                    },
+                    AuxExportKind::StaticGetter {
+                        class: second_class,
+                        field: second_field,
+                    },
+                ) => verify_exports(first_class, first_field, second_class, second_field)?,
+                (
+                    AuxExportKind::StaticSetter {
+                        class: first_class,
+                        field: first_field,
+                    },
+                    AuxExportKind::StaticSetter {
+                        class: second_class,
+                        field: second_field,
+                    },
+                ) => verify_exports(first_class, first_field, second_class, second_field)?,
                 _ => {}
             }
         }
@@ -2726,6 +4623,67 @@ fn require_class<'a>(
         .or_insert_with(ExportedClass::default)
 }
 
+fn require_namespace<'a>(
+    exported_namespaces: &'a mut Option<BTreeMap<String, ExportedNamespace>>,
+    path: &[String],
+) -> &'a mut ExportedNamespace {
+    let mut namespaces = exported_namespaces
+        .as_mut()
+        .expect("namespaces already written");
+    let (last, parents) = path.split_last().unwrap();
+    for name in parents {
+        namespaces = &mut namespaces
+            .entry(name.to_string())
+            .or_insert_with(ExportedNamespace::default)
+            .namespaces;
+    }
+    namespaces
+        .entry(last.to_string())
+        .or_insert_with(ExportedNamespace::default)
+}
+
+impl ExportedNamespace {
+    /// Returns the object literal defining this namespace, whose contents are
+    /// indented relative to `indent`.
+    fn js_object(&self, indent: &str) -> Result<String, Error> {
+        let inner = format!("{}    ", indent);
+        let mut dst = "{\n".to_string();
+        for (name, reference) in self.members.iter() {
+            if self.namespaces.contains_key(name) {
+                bail!(
+                    "cannot export both `{}` and a namespace of the same name",
+                    name
+                );
+            }
+            dst.push_str(&format!("{}{}: {},\n", inner, name, reference));
+        }
+        for (name, namespace) in self.namespaces.iter() {
+            let object = namespace.js_object(&inner)?;
+            dst.push_str(&format!("{}{}: {},\n", inner, name, object));
+        }
+        dst.push_str(indent);
+        dst.push_str("}");
+        Ok(dst)
+    }
+
+    /// Returns the TypeScript declaration of this namespace as `name`.
+    fn typescript(&self, name: &str, indent: &str) -> String {
+        let inner = format!("{}  ", indent);
+        let mut dst = format!("{}export namespace {} {{\n", indent, name);
+        for line in self.typescript.lines() {
+            dst.push_str(&inner);
+            dst.push_str(line);
+            dst.push_str("\n");
+        }
+        for (name, namespace) in self.namespaces.iter() {
+            dst.push_str(&namespace.typescript(name, &inner));
+        }
+        dst.push_str(indent);
+        dst.push_str("}\n");
+        dst
+    }
+}
+
 impl ExportedClass {
     fn push(&mut self, docs: &str, function_name: &str, function_prefix: &str, js: &str, ts: &str) {
         self.contents.push_str(docs);
@@ -2744,14 +4702,25 @@ impl ExportedClass {
     /// Used for adding a getter to a class, mainly to ensure that TypeScript
     /// generation is handled specially.
     fn push_getter(&mut self, docs: &str, field: &str, js: &str, ret_ty: &str) {
-        self.push_accessor(docs, field, js, "get ", ret_ty);
+        self.push_accessor(docs, field, js, "get ").getter = Some(ret_ty.to_string());
+    }
+
+    /// Used for adding a getter to the class itself, generating a `static`
+    /// property in TypeScript.
+    fn push_static_getter(&mut self, docs: &str, field: &str, js: &str, ret_ty: &str) {
+        self.push_accessor(docs, field, js, "static get ").getter = Some(ret_ty.to_string());
+    }
+
+    /// Used for adding a setter to the class itself, generating a `static`
+    /// property in TypeScript.
+    fn push_static_setter(&mut self, docs: &str, field: &str, js: &str, arg_ty: &str) {
+        self.push_accessor(docs, field, js, "static set ").setter = Some(arg_ty.to_string());
     }
 
     /// Used for adding a setter to a class, mainly to ensure that TypeScript
     /// generation is handled specially.
-    fn push_setter(&mut self, docs: &str, field: &str, js: &str, ret_ty: &str) {
-        let has_setter = self.push_accessor(docs, field, js, "set ", ret_ty);
-        *has_setter = true;
+    fn push_setter(&mut self, docs: &str, field: &str, js: &str, arg_ty: &str) {
+        self.push_accessor(docs, field, js, "set ").setter = Some(arg_ty.to_string());
     }
 
     fn push_accessor(
@@ -2760,20 +4729,93 @@ impl ExportedClass {
         field: &str,
         js: &str,
         prefix: &str,
-        ret_ty: &str,
-    ) -> &mut bool {
+    ) -> &mut FieldTypes {
         self.contents.push_str(docs);
         self.contents.push_str(prefix);
         self.contents.push_str(field);
         self.contents.push_str(js);
         self.contents.push_str("\n");
-        let (ty, has_setter) = self
-            .typescript_fields
+        let fields = if prefix.starts_with("static ") {
+            &mut self.typescript_static_fields
+        } else {
+            &mut self.typescript_fields
+        };
+        fields
             .entry(field.to_string())
-            .or_insert_with(Default::default);
-        *ty = ret_ty.to_string();
-        has_setter
+            .or_insert_with(Default::default)
+    }
+}
+
+impl FieldTypes {
+    /// Returns the TypeScript declaration of the field `name`, with each line
+    /// starting with `prefix`.
+    ///
+    /// Fields with only a getter are `readonly`, and fields whose getter and
+    /// setter have different types, like `Option<T>` fields of types that
+    /// are moved into the setter, are declared as accessors.
+    fn declaration(&self, prefix: &str, name: &str) -> String {
+        match (&self.getter, &self.setter) {
+            (Some(getter), None) => format!("{}readonly {}: {};\n", prefix, name, getter),
+            (Some(getter), Some(setter)) if getter == setter => {
+                format!("{}{}: {};\n", prefix, name, getter)
+            }
+            (getter, setter) => {
+                let mut ret = String::new();
+                if let Some(getter) = getter {
+                    ret.push_str(&format!("{}get {}(): {};\n", prefix, name, getter));
+                }
+                if let Some(setter) = setter {
+                    ret.push_str(&format!("{}set {}(value: {});\n", prefix, name, setter));
+                }
+                ret
+            }
+        }
+    }
+}
+
+/// Returns the module specifier to import the `raw_module` path `module` from.
+///
+/// Relative `raw_module` paths are relative to the generated JS, so if it's
+/// going to be moved, for example by a bundler, they can be prefixed with the
+/// path from the new location of the JS back to where they're resolved from.
+fn raw_module_specifier(config: &Bindgen, module: &str) -> String {
+    let prefix = match &config.raw_module_prefix {
+        Some(prefix) => prefix,
+        None => return module.to_string(),
+    };
+    // Explicit rewrites of the specifier take precedence, and are applied
+    // along with `--module-extension` when the import is emitted.
+    if config.module_rewrites.contains_key(module) {
+        return module.to_string();
+    }
+    if !module.starts_with("./") && !module.starts_with("../") {
+        return module.to_string();
+    }
+    format!(
+        "{}/{}",
+        prefix.trim_end_matches('/'),
+        module.trim_start_matches("./")
+    )
+}
+
+/// Generates JS assigning `value` to the `--no-modules-global` name `global`
+/// on `root`, creating any namespaces the name is nested in along the way.
+fn assign_global(root: &str, global: &str, value: &str) -> String {
+    let mut namespaces = global.split('.').collect::<Vec<_>>();
+    let name = namespaces.pop().unwrap();
+    if namespaces.is_empty() {
+        return format!("{}.{} = {};\n", root, name, value);
     }
+    let namespaces = namespaces
+        .iter()
+        .map(|ns| format!("'{}'", ns))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "const __namespace = [{}].reduce((ns, name) => ns[name] || (ns[name] = {{}}), {});\n\
+         __namespace.{} = {};\n",
+        namespaces, root, name, value
+    )
 }
 
 /// Returns a sorted iterator over a hash map, sorted based on key.
@@ -2791,6 +4833,239 @@ where
     pairs.into_iter()
 }
 
+/// Removes all top-level helper definitions in `globals` which aren't
+/// referenced from any other item in `globals` or any of the `roots`.
+///
+/// An item is only a candidate for removal if it isn't exported and it
+/// declares at least one top-level binding. It's removed when none of its
+/// declared names are referenced anywhere else, repeating until no more items
+/// can be removed. Items and roots which can't be tokenized are assumed to
+/// reference every name they contain as a whole word.
+fn gc_globals(globals: Vec<String>, roots: &[&str]) -> Vec<String> {
+    let roots = roots
+        .iter()
+        .map(|root| References::new(root))
+        .collect::<Vec<_>>();
+    let mut items = globals
+        .into_iter()
+        .map(|item| {
+            let names = top_level_declarations(&item);
+            let references = References::new(&item);
+            (item, names, references)
+        })
+        .collect::<Vec<_>>();
+    loop {
+        let before = items.len();
+        let mut i = 0;
+        while i < items.len() {
+            let (item, names, _) = &items[i];
+            let used = is_exported_item(item)
+                || names.is_empty()
+                || names.iter().any(|name| {
+                    roots.iter().any(|root| root.contains(name))
+                        || items
+                            .iter()
+                            .enumerate()
+                            .any(|(j, (_, _, other))| j != i && other.contains(name))
+                });
+            if used {
+                i += 1;
+            } else {
+                items.remove(i);
+            }
+        }
+        if items.len() == before {
+            break;
+        }
+    }
+    items.into_iter().map(|(item, _, _)| item).collect()
+}
+
+/// The identifiers some JS refers to, falling back to its text if it can't be
+/// tokenized.
+enum References {
+    Identifiers(HashSet<String>),
+    Text(String),
+}
+
+impl References {
+    fn new(js: &str) -> References {
+        match referenced_identifiers(js) {
+            Some(names) => References::Identifiers(names),
+            None => References::Text(js.to_string()),
+        }
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        match self {
+            References::Identifiers(names) => names.contains(name),
+            References::Text(js) => references(js, name),
+        }
+    }
+}
+
+/// Returns whether the item of `globals` in `item` exports something from the
+/// generated JS.
+fn is_exported_item(item: &str) -> bool {
+    item.lines().any(|l| {
+        let l = l.trim_start();
+        l.starts_with("export") || l.starts_with("module.exports") || l.starts_with("__exports")
+    })
+}
+
+/// Returns a short identifier for the `n`th minified name, which can't clash
+/// with the names of exports since `$` never shows up in Rust identifiers.
+fn minified_name(n: usize) -> String {
+    const DIGITS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+    let mut name = String::from("$");
+    let mut n = n;
+    loop {
+        name.push(DIGITS[n % DIGITS.len()] as char);
+        n /= DIGITS.len();
+        if n == 0 {
+            break name;
+        }
+    }
+}
+
+/// Renames the identifiers in `js` which are keys of `renames`, skipping over
+/// strings, comments, and property accesses.
+fn rename_identifiers(js: &str, renames: &HashMap<String, String>) -> String {
+    let mut ret = String::with_capacity(js.len());
+    let mut chars = js.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                let mut end = js.len();
+                let mut escaped = false;
+                while let Some((j, d)) = chars.next() {
+                    match d {
+                        '\\' if !escaped => escaped = true,
+                        d if d == c && !escaped => {
+                            end = j + 1;
+                            break;
+                        }
+                        _ => escaped = false,
+                    }
+                }
+                ret.push_str(&js[i..end]);
+            }
+            '/' if chars.peek().map(|p| p.1) == Some('/') => {
+                let end = js[i..].find('\n').map(|n| i + n).unwrap_or(js.len());
+                while chars.peek().map_or(false, |p| p.0 < end) {
+                    chars.next();
+                }
+                ret.push_str(&js[i..end]);
+            }
+            '/' if chars.peek().map(|p| p.1) == Some('*') => {
+                let end = js[i + 2..]
+                    .find("*/")
+                    .map(|n| i + n + 4)
+                    .unwrap_or(js.len());
+                while chars.peek().map_or(false, |p| p.0 < end) {
+                    chars.next();
+                }
+                ret.push_str(&js[i..end]);
+            }
+            c if is_ident_char(c) => {
+                let mut end = i + c.len_utf8();
+                while let Some((j, d)) = chars.peek().cloned() {
+                    if !is_ident_char(d) {
+                        break;
+                    }
+                    end = j + d.len_utf8();
+                    chars.next();
+                }
+                let word = &js[i..end];
+                let is_property = js[..i].trim_end().ends_with('.');
+                match renames.get(word) {
+                    Some(name) if !is_property => ret.push_str(name),
+                    _ => ret.push_str(word),
+                }
+            }
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+/// Returns whether the identifier `name` shows up in `js` as a whole word.
+fn references(js: &str, name: &str) -> bool {
+    js.match_indices(name).any(|(i, _)| {
+        let before = js[..i].chars().next_back();
+        let after = js[i + name.len()..].chars().next();
+        !before.map_or(false, is_ident_char) && !after.map_or(false, is_ident_char)
+    })
+}
+
+#[test]
+fn test_gc_globals() {
+    let globals = vec![
+        "const heap = new Array(32);\n".to_string(),
+        "function getObject(idx) { return heap[idx]; }\n".to_string(),
+        "function unused(a) { const heap = a; return heap; }\n".to_string(),
+        "export function foo() { return getObject(1); }\n".to_string(),
+    ];
+    assert_eq!(
+        gc_globals(globals.clone(), &[]),
+        [globals[0].clone(), globals[1].clone(), globals[3].clone()]
+    );
+
+    let globals = vec![
+        "let cachedTextDecoder = new TextDecoder('utf-8');\n".to_string(),
+        "function getStringFromWasm(ptr, len) { return cachedTextDecoder.decode(); }\n".to_string(),
+    ];
+    assert!(gc_globals(globals.clone(), &[]).is_empty());
+    assert_eq!(
+        gc_globals(globals.clone(), &["imports.wbg.x = getStringFromWasm;"]),
+        globals
+    );
+
+    // Names only mentioned in strings, templates, regular expressions or
+    // property accesses don't keep helpers alive, while names used inside of
+    // template interpolations do.
+    let globals = vec![
+        "function getObject(idx) { return heap[idx]; }\n".to_string(),
+        "function passString(arg) { return arg; }\n".to_string(),
+        "export function foo(x) {\n\
+         \x20   const re = /getObject(}`/;\n\
+         \x20   return `${passString(x)} getObject ${x.getObject}`;\n\
+         }\n"
+        .to_string(),
+    ];
+    assert_eq!(
+        gc_globals(globals.clone(), &["'getObject'"]),
+        [globals[1].clone(), globals[2].clone()]
+    );
+}
+
+#[test]
+fn test_rename_identifiers() {
+    let mut renames = HashMap::new();
+    renames.insert("getObject".to_string(), "$a".to_string());
+    renames.insert("heap".to_string(), "$b".to_string());
+    assert_eq!(
+        rename_identifiers(
+            "\
+             // getObject reads from the heap\n\
+             function getObject(idx) { return heap[idx]; }\n\
+             x.getObject = 'getObject'; /* heap */ getObject(1);\n",
+            &renames
+        ),
+        "\
+         // getObject reads from the heap\n\
+         function $a(idx) { return $b[idx]; }\n\
+         x.getObject = 'getObject'; /* heap */ $a(1);\n"
+    );
+}
+
+#[test]
+fn test_minified_name() {
+    assert_eq!(minified_name(0), "$a");
+    assert_eq!(minified_name(35), "$9");
+    assert_eq!(minified_name(36), "$ab");
+}
+
 #[test]
 fn test_generate_identifier() {
     let mut used_names: HashMap<String, usize> = HashMap::new();
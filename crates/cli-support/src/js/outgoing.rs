@@ -130,6 +130,29 @@ impl<'a, 'b> Outgoing<'a, 'b> {
                 Ok(format!("v{}", i))
             }
 
+            // Same as `Vector` above for a list of `anyref` values, except
+            // the keys and values are then collected into a map.
+            NonstandardOutgoing::Map {
+                offset,
+                length,
+                object,
+            } => {
+                let ptr = self.arg(*offset);
+                let len = self.arg(*length);
+                if *object {
+                    self.js.typescript_required("{ [key: string]: any }");
+                } else {
+                    self.js.typescript_required("Map<string, any>");
+                }
+                let f = self.cx.expose_get_vector_from_wasm(VectorKind::Anyref)?;
+                let i = self.js.tmp();
+                self.js
+                    .prelude(&format!("const v{} = {}({}, {}).slice();", i, f, ptr, len));
+                self.prelude_free_vector(*offset, *length, VectorKind::Anyref)?;
+                let collect = self.cx.expose_entries_to_map(*object);
+                Ok(format!("{}(v{})", collect, i))
+            }
+
             NonstandardOutgoing::StackClosure {
                 a,
                 b,
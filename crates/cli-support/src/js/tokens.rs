@@ -0,0 +1,297 @@
+//! A small tokenizer for the JS we generate, which is enough to tell which
+//! names each top-level item declares and refers to.
+//!
+//! Strings, template literals, regular expressions and comments are skipped
+//! over as a whole, except for expressions interpolated in templates, so that
+//! names showing up in them aren't mistaken for code.
+
+use std::collections::HashSet;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+type Chars<'a> = Peekable<CharIndices<'a>>;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Token<'a> {
+    /// An identifier, a keyword or a number
+    Word(&'a str),
+    /// A punctuation character, like `{` or `.`
+    Punct(char),
+    /// A string, template or regular expression literal, or the text of a
+    /// template around its interpolations
+    Literal,
+}
+
+/// Keywords after which a `/` starts a regular expression rather than being a
+/// division.
+const REGEX_KEYWORDS: &[&str] = &[
+    "await",
+    "case",
+    "delete",
+    "do",
+    "else",
+    "in",
+    "instanceof",
+    "new",
+    "of",
+    "return",
+    "throw",
+    "typeof",
+    "void",
+    "yield",
+];
+
+/// Returns the tokens of `js`, or `None` if it isn't well formed, like when a
+/// string isn't terminated or brackets aren't balanced.
+pub fn tokenize(js: &str) -> Option<Vec<Token<'_>>> {
+    let mut tokens = Vec::new();
+    // The closing brackets we expect, where `None` is the `}` ending an
+    // interpolation in a template literal.
+    let mut brackets = Vec::new();
+    let mut chars = js.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|p| p.1);
+        match c {
+            c if c.is_whitespace() => {}
+            '\'' | '"' => {
+                skip_string(&mut chars, c)?;
+                tokens.push(Token::Literal);
+            }
+            '`' => {
+                if skip_template(&mut chars)? {
+                    brackets.push(None);
+                }
+                tokens.push(Token::Literal);
+            }
+            '/' if next == Some('/') => {
+                while chars.peek().map_or(false, |p| p.1 != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if next == Some('*') => {
+                chars.next();
+                let mut prev = ' ';
+                loop {
+                    let (_, d) = chars.next()?;
+                    if prev == '*' && d == '/' {
+                        break;
+                    }
+                    prev = d;
+                }
+            }
+            '/' if regex_allowed(tokens.last()) => {
+                skip_regex(&mut chars)?;
+                tokens.push(Token::Literal);
+            }
+            '{' => {
+                brackets.push(Some('}'));
+                tokens.push(Token::Punct(c));
+            }
+            '(' => {
+                brackets.push(Some(')'));
+                tokens.push(Token::Punct(c));
+            }
+            '[' => {
+                brackets.push(Some(']'));
+                tokens.push(Token::Punct(c));
+            }
+            '}' if brackets.last() == Some(&None) => {
+                brackets.pop();
+                if skip_template(&mut chars)? {
+                    brackets.push(None);
+                }
+                tokens.push(Token::Literal);
+            }
+            '}' | ')' | ']' => {
+                if brackets.pop() != Some(Some(c)) {
+                    return None;
+                }
+                tokens.push(Token::Punct(c));
+            }
+            c if is_ident_char(c) => {
+                let mut end = i + c.len_utf8();
+                while let Some(&(j, d)) = chars.peek() {
+                    if !is_ident_char(d) {
+                        break;
+                    }
+                    end = j + d.len_utf8();
+                    chars.next();
+                }
+                tokens.push(Token::Word(&js[i..end]));
+            }
+            c => tokens.push(Token::Punct(c)),
+        }
+    }
+    if brackets.len() > 0 {
+        return None;
+    }
+    Some(tokens)
+}
+
+/// Returns the names declared at the top level of `js` with `function`,
+/// `class`, `const`, `let` or `var`, or an empty list if it can't be
+/// tokenized.
+pub fn top_level_declarations(js: &str) -> Vec<String> {
+    let tokens = match tokenize(js) {
+        Some(tokens) => tokens,
+        None => return Vec::new(),
+    };
+    let mut names = Vec::new();
+    let mut depth = 0;
+    for pair in tokens.windows(2) {
+        match pair {
+            [Token::Punct('{'), _] | [Token::Punct('('), _] | [Token::Punct('['), _] => depth += 1,
+            [Token::Punct('}'), _] | [Token::Punct(')'), _] | [Token::Punct(']'), _] => depth -= 1,
+            [Token::Word(keyword), Token::Word(name)] if depth == 0 => match *keyword {
+                "function" | "class" | "const" | "let" | "var" => names.push(name.to_string()),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Returns the identifiers used in `js`, leaving out the names of properties
+/// accessed with `.`, or `None` if it can't be tokenized.
+pub fn referenced_identifiers(js: &str) -> Option<HashSet<String>> {
+    let tokens = tokenize(js)?;
+    let mut prev = None;
+    let mut names = HashSet::new();
+    for token in tokens {
+        if let Token::Word(word) = token {
+            if prev != Some(Token::Punct('.')) {
+                names.insert(word.to_string());
+            }
+        }
+        prev = Some(token);
+    }
+    Some(names)
+}
+
+pub fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Whether a `/` following `prev` starts a regular expression literal.
+fn regex_allowed(prev: Option<&Token>) -> bool {
+    match prev {
+        None => true,
+        Some(Token::Punct(')')) | Some(Token::Punct(']')) => false,
+        Some(Token::Punct(_)) => true,
+        Some(Token::Word(word)) => REGEX_KEYWORDS.contains(word),
+        Some(Token::Literal) => false,
+    }
+}
+
+fn skip_string(chars: &mut Chars, quote: char) -> Option<()> {
+    loop {
+        match chars.next()?.1 {
+            '\\' => {
+                chars.next()?;
+            }
+            '\n' => return None,
+            c if c == quote => return Some(()),
+            _ => {}
+        }
+    }
+}
+
+/// Skips the text of a template literal up to its end or the start of an
+/// interpolation, returning whether an interpolation was started.
+fn skip_template(chars: &mut Chars) -> Option<bool> {
+    loop {
+        match chars.next()?.1 {
+            '\\' => {
+                chars.next()?;
+            }
+            '`' => return Some(false),
+            '$' if chars.peek().map(|p| p.1) == Some('{') => {
+                chars.next();
+                return Some(true);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn skip_regex(chars: &mut Chars) -> Option<()> {
+    let mut in_class = false;
+    loop {
+        match chars.next()?.1 {
+            '\\' => {
+                chars.next()?;
+            }
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '/' if !in_class => break,
+            '\n' => return None,
+            _ => {}
+        }
+    }
+    while chars.peek().map_or(false, |p| is_ident_char(p.1)) {
+        chars.next();
+    }
+    Some(())
+}
+
+#[test]
+fn test_tokenize() {
+    use self::Token::*;
+
+    assert_eq!(
+        tokenize("a.b(`x${c + `${d}`}y`, /[/]\\//g, e / f)"),
+        Some(vec![
+            Word("a"),
+            Punct('.'),
+            Word("b"),
+            Punct('('),
+            Literal,
+            Word("c"),
+            Punct('+'),
+            Literal,
+            Word("d"),
+            Literal,
+            Literal,
+            Punct(','),
+            Literal,
+            Punct(','),
+            Word("e"),
+            Punct('/'),
+            Word("f"),
+            Punct(')'),
+        ])
+    );
+    assert_eq!(tokenize("return /}'/.test(x)").unwrap()[1], Literal);
+    assert_eq!(tokenize("f('unterminated)"), None);
+    assert_eq!(tokenize("function f() {"), None);
+    assert_eq!(tokenize("`${a`"), None);
+}
+
+#[test]
+fn test_top_level_declarations() {
+    assert_eq!(
+        top_level_declarations(
+            "\
+             const re = /[{(]/;\n\
+             function f(x) { const inner = `${x}}`; return inner; }\n\
+             let a = 1; class B {}\n"
+        ),
+        ["re", "f", "a", "B"]
+    );
+    assert!(top_level_declarations("function f() { return '}").is_empty());
+}
+
+#[test]
+fn test_referenced_identifiers() {
+    let names = referenced_identifiers(
+        "\
+         // heap\n\
+         x.getObject = `getObject ${getString(1)}`; /* heap */\n\
+         const re = /heap/;\n",
+    )
+    .unwrap();
+    let mut names = names.into_iter().collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["1", "const", "getString", "re", "x"]);
+}
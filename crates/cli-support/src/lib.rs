@@ -15,6 +15,7 @@ mod descriptor;
 mod descriptors;
 mod intrinsic;
 mod js;
+mod profile;
 pub mod wasm2es6js;
 mod webidl;
 
@@ -24,6 +25,8 @@ pub struct Bindgen {
     mode: OutputMode,
     debug: bool,
     typescript: bool,
+    typescript_const_enums: bool,
+    jsdoc: bool,
     demangle: bool,
     keep_debug: bool,
     remove_name_section: bool,
@@ -36,13 +39,38 @@ pub struct Bindgen {
     // module to be "ready to be instantiated on any thread"
     threads: Option<wasm_bindgen_threads_xform::Config>,
     anyref: bool,
+    // Experimental support for the WebIDL bindings proposal, emitting a
+    // standard custom section describing bindings instead of JS glue.
+    interface_types: bool,
     encode_into: EncodeInto,
+    js_flavor: JsFlavor,
+    module_rewrites: BTreeMap<String, String>,
+    module_extension: Option<String>,
+    raw_module_prefix: Option<String>,
+    package_json: bool,
+    import_memory: bool,
+    lazy_entry: bool,
+    worker_entry: bool,
+    profile_bindings: bool,
+    list_imports: bool,
+    cfg_manifest: bool,
+    import_map_base: Option<String>,
+    split_debug_info: bool,
+    performance_marks: bool,
+    minify_internals: bool,
+    record_allocation_sites: bool,
+    schema_compat: bool,
+    demangle_exports: bool,
+    linked_inputs: Vec<PathBuf>,
+    // The specifier of the module defining the JS heap while generating the
+    // bindings of linked modules, see `heap_global` in `js/mod.rs`.
+    shared_heap: Option<String>,
 }
 
 enum OutputMode {
     Bundler { browser_only: bool },
     Web,
-    NoModules { global: String },
+    NoModules { global: String, umd: bool },
     Node { experimental_modules: bool },
 }
 
@@ -61,16 +89,36 @@ impl OutputMode {
 
 enum Input {
     Path(PathBuf),
+    Bytes(Vec<u8>, String),
     Module(Module, String),
     None,
 }
 
+/// The files generated by `Bindgen`, keyed by their path relative to the output
+/// directory.
+pub struct Output {
+    files: BTreeMap<PathBuf, Vec<u8>>,
+    heap_globals: Vec<(Vec<&'static str>, String)>,
+    exported_names: Vec<String>,
+}
+
 pub enum EncodeInto {
     Test,
     Always,
     Never,
 }
 
+/// The flavor of JS syntax used for the generated glue.
+#[derive(PartialEq)]
+pub enum JsFlavor {
+    /// Syntax supported by older engines, where exported classes store their
+    /// pointer in a public `ptr` property.
+    Legacy,
+    /// ES2022 syntax, where exported classes store their pointer in a `#ptr`
+    /// private field which is only reachable from the generated glue.
+    Es2022,
+}
+
 impl Bindgen {
     pub fn new() -> Bindgen {
         Bindgen {
@@ -81,6 +129,8 @@ impl Bindgen {
             },
             debug: false,
             typescript: false,
+            typescript_const_enums: false,
+            jsdoc: false,
             demangle: true,
             keep_debug: false,
             remove_name_section: false,
@@ -89,7 +139,28 @@ impl Bindgen {
             weak_refs: env::var("WASM_BINDGEN_WEAKREF").is_ok(),
             threads: threads_config(),
             anyref: env::var("WASM_BINDGEN_ANYREF").is_ok(),
+            interface_types: env::var("WASM_INTERFACE_TYPES").is_ok(),
             encode_into: EncodeInto::Test,
+            js_flavor: JsFlavor::Legacy,
+            module_rewrites: BTreeMap::new(),
+            module_extension: None,
+            raw_module_prefix: None,
+            package_json: false,
+            import_memory: false,
+            lazy_entry: false,
+            worker_entry: false,
+            profile_bindings: false,
+            list_imports: false,
+            cfg_manifest: false,
+            import_map_base: None,
+            split_debug_info: false,
+            performance_marks: false,
+            minify_internals: false,
+            record_allocation_sites: false,
+            schema_compat: false,
+            demangle_exports: false,
+            linked_inputs: Vec::new(),
+            shared_heap: None,
         }
     }
 
@@ -98,11 +169,20 @@ impl Bindgen {
         self
     }
 
+    /// Sets the stem of the names of all output files, which otherwise
+    /// defaults to the name of the input.
     pub fn out_name(&mut self, name: &str) -> &mut Bindgen {
         self.out_name = Some(name.to_string());
         self
     }
 
+    /// Specify the contents of the input wasm file, with `name` used for the
+    /// output files unless `out_name` is set.
+    pub fn input_bytes(&mut self, name: &str, bytes: Vec<u8>) -> &mut Bindgen {
+        self.input = Input::Bytes(bytes, name.to_string());
+        self
+    }
+
     /// Explicitly specify the already parsed input module.
     pub fn input_module(&mut self, name: &str, module: Module) -> &mut Bindgen {
         let name = name.to_string();
@@ -169,6 +249,7 @@ impl Bindgen {
             self.switch_mode(
                 OutputMode::NoModules {
                     global: "wasm_bindgen".to_string(),
+                    umd: false,
                 },
                 "--target no-modules",
             )?;
@@ -187,13 +268,39 @@ impl Bindgen {
     }
 
     pub fn no_modules_global(&mut self, name: &str) -> Result<&mut Bindgen, Error> {
+        // The global may be nested in namespaces, like `MyLib.wasm`, but each
+        // component needs to be usable as a property name without quoting.
+        let valid = |part: &str| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(c) if c.is_alphabetic() || c == '_' || c == '$' => {}
+                _ => return false,
+            }
+            chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+        };
+        if !name.split('.').all(valid) {
+            bail!("`{}` is not a valid name for `--no-modules-global`", name);
+        }
         match &mut self.mode {
-            OutputMode::NoModules { global } => *global = name.to_string(),
+            OutputMode::NoModules { global, .. } => *global = name.to_string(),
             _ => bail!("can only specify `--no-modules-global` with `--target no-modules`"),
         }
         Ok(self)
     }
 
+    /// Whether or not to wrap `--target no-modules` output in a UMD wrapper,
+    /// so it can also be loaded through CommonJS `require` or an AMD loader
+    /// instead of only being assigned to the global.
+    pub fn no_modules_umd(&mut self, umd: bool) -> Result<&mut Bindgen, Error> {
+        if umd {
+            match &mut self.mode {
+                OutputMode::NoModules { umd, .. } => *umd = true,
+                _ => bail!("can only specify `--no-modules-umd` with `--target no-modules`"),
+            }
+        }
+        Ok(self)
+    }
+
     pub fn debug(&mut self, debug: bool) -> &mut Bindgen {
         self.debug = debug;
         self
@@ -204,6 +311,21 @@ impl Bindgen {
         self
     }
 
+    /// Whether or not to declare exported enums as `const enum`s in the
+    /// generated TypeScript, instead of an object of constants along with a
+    /// union of their values.
+    pub fn typescript_const_enums(&mut self, const_enums: bool) -> &mut Bindgen {
+        self.typescript_const_enums = const_enums;
+        self
+    }
+
+    /// Whether or not to annotate the generated JS with complete JSDoc types,
+    /// for editors of plain JS consumers which don't read the `.d.ts` file.
+    pub fn jsdoc(&mut self, jsdoc: bool) -> &mut Bindgen {
+        self.jsdoc = jsdoc;
+        self
+    }
+
     pub fn demangle(&mut self, demangle: bool) -> &mut Bindgen {
         self.demangle = demangle;
         self
@@ -234,39 +356,359 @@ impl Bindgen {
         self
     }
 
+    pub fn js_flavor(&mut self, flavor: JsFlavor) -> &mut Bindgen {
+        self.js_flavor = flavor;
+        self
+    }
+
+    /// Configures all imports of the module specifier `from` to instead import
+    /// from `to` in the generated JS.
+    pub fn rewrite_module(&mut self, from: &str, to: &str) -> &mut Bindgen {
+        self.module_rewrites
+            .insert(from.to_string(), to.to_string());
+        self
+    }
+
+    /// Appends `.{ext}` to relative module specifiers in the generated JS
+    /// which don't already have an extension, as required by strict ES module
+    /// resolution such as in Node.js.
+    ///
+    /// Bare specifiers like `some-pkg/sub` are never modified so they continue
+    /// to be resolved through the `exports` map of the package.
+    pub fn module_extension(&mut self, ext: Option<&str>) -> &mut Bindgen {
+        self.module_extension = ext.map(|s| s.trim_start_matches('.').to_string());
+        self
+    }
+
+    /// Prefixes relative `raw_module` specifiers (those starting with `./` or
+    /// `../`) in the generated JS with `prefix`, so they still resolve to the
+    /// same files once the JS has been moved to another directory.
+    ///
+    /// Specifiers which are rewritten with `rewrite_module` are left alone.
+    pub fn raw_module_prefix(&mut self, prefix: Option<&str>) -> &mut Bindgen {
+        self.raw_module_prefix = prefix.map(|s| s.to_string());
+        self
+    }
+
+    /// Whether or not to emit a `package.json` next to the generated output
+    /// which describes all of the emitted files.
+    pub fn package_json(&mut self, emit: bool) -> &mut Bindgen {
+        self.package_json = emit;
+        self
+    }
+
+    /// Whether or not the wasm module should import its memory rather than
+    /// define and export it, allowing the host to provide (and pre-size) the
+    /// memory when calling the generated `init` function.
+    ///
+    /// This is only supported with `--target web` and `--target no-modules`.
+    pub fn import_memory(&mut self, import: bool) -> &mut Bindgen {
+        self.import_memory = import;
+        self
+    }
+
+    /// Whether or not to additionally emit a `{name}_lazy.js` entry point
+    /// which loads the generated bindings on demand with a dynamic `import()`,
+    /// allowing bundlers to split them out of the initial bundle.
+    ///
+    /// This is only supported with the `bundler` target and with Node's
+    /// experimental ES modules.
+    pub fn lazy_entry(&mut self, lazy: bool) -> &mut Bindgen {
+        self.lazy_entry = lazy;
+        self
+    }
+
+    /// Whether or not to additionally emit a `{name}_worker.js` script which
+    /// bootstraps the generated bindings in a web worker, along with a
+    /// `startWorker` function in the generated JS which spawns such a worker
+    /// and hands it the compiled module, and the shared memory in threads
+    /// builds.
+    ///
+    /// This is only supported with `--target web` and `--target no-modules`.
+    pub fn worker_entry(&mut self, worker: bool) -> &mut Bindgen {
+        self.worker_entry = worker;
+        self
+    }
+
+    /// Whether or not to describe the bindings of exports and imports with a
+    /// standard `webidl-bindings` custom section instead of generating JS
+    /// glue, so hosts implementing the WebIDL bindings proposal can use the
+    /// module directly.
+    ///
+    /// This is experimental, only a subset of `#[wasm_bindgen]` features are
+    /// supported, and all other output options are ignored.
+    pub fn interface_types(&mut self, enable: bool) -> &mut Bindgen {
+        self.interface_types = self.interface_types || enable;
+        self
+    }
+
+    /// Whether or not to emit `{name}_profile.json` and `{name}_profile.md`
+    /// reports of how many bytes of JS glue and wasm shims each export, import,
+    /// intrinsic, and JS helper contributes.
+    pub fn profile_bindings(&mut self, profile: bool) -> &mut Bindgen {
+        self.profile_bindings = profile;
+        self
+    }
+
+    /// Whether or not to emit a `{name}_imports.json` file listing every
+    /// module specifier imported by the generated JS, and whether it comes
+    /// from a `module` or `raw_module` attribute or a JS snippet.
+    pub fn list_imports(&mut self, list: bool) -> &mut Bindgen {
+        self.list_imports = list;
+        self
+    }
+
+    /// Whether or not to emit a `{name}_cfg.json` manifest of the `#[cfg]`
+    /// conditions exports are gated on, split by whether they held when the
+    /// input was compiled, like which optional features it was built with.
+    pub fn cfg_manifest(&mut self, manifest: bool) -> &mut Bindgen {
+        self.cfg_manifest = manifest;
+        self
+    }
+
+    /// Emits a `{name}_import_map.json` import map which maps each bare module
+    /// specifier imported by the generated JS to the URL `base` joined with
+    /// the specifier, for loading the JS in browsers without a bundler.
+    pub fn import_map_base(&mut self, base: Option<&str>) -> &mut Bindgen {
+        self.import_map_base = base.map(|s| s.to_string());
+        self
+    }
+
+    /// Whether or not to move the DWARF debug sections kept with `keep_debug`
+    /// into a separate `{name}_bg.debug.wasm` file, a copy of the main wasm
+    /// file which is referenced from it through an `external_debug_info`
+    /// custom section.
+    pub fn split_debug_info(&mut self, split: bool) -> &mut Bindgen {
+        self.split_debug_info = split;
+        self
+    }
+
+    /// Whether or not to emit `performance.mark` and `performance.measure`
+    /// calls around compilation and instantiation of the module and the first
+    /// call into an export. In debug mode this also counts calls of each generated shim,
+    /// which can be read through an exported `__wbindgen_call_counts`
+    /// function.
+    pub fn performance_marks(&mut self, marks: bool) -> &mut Bindgen {
+        self.performance_marks = marks;
+        self
+    }
+
+    /// Whether or not to rename the internal helpers of the generated JS and
+    /// the wasm imports it defines to short identifiers, to save on size.
+    pub fn minify_internals(&mut self, minify: bool) -> &mut Bindgen {
+        self.minify_internals = minify;
+        self
+    }
+
+    /// Whether or not to record the JS stack trace at which each JS value
+    /// handed to wasm was allocated, to be read through
+    /// `wasm_bindgen::debug::allocation_sites`. Only takes effect in debug
+    /// mode and when `anyref` is disabled.
+    pub fn record_allocation_sites(&mut self, record: bool) -> &mut Bindgen {
+        self.record_allocation_sites = record;
+        self
+    }
+
+    /// Whether or not to process inputs generated by a different version of
+    /// wasm-bindgen, as long as it encodes bindings the same way as this one.
+    ///
+    /// This is only a best-effort mode: other parts of the interface between
+    /// the two versions, like intrinsics, may still be incompatible.
+    pub fn schema_compat(&mut self, compat: bool) -> &mut Bindgen {
+        self.schema_compat = compat;
+        self
+    }
+
+    /// Whether or not to export free functions in nested JS namespaces
+    /// mirroring the Rust modules they're defined in, so that `add` defined in
+    /// `math::vectors` is exported as `math.vectors.add`.
+    ///
+    /// Export names must still be unique in the wasm file, so a prefix of the
+    /// name of the innermost module followed by `_` is stripped, exporting
+    /// `math::vectors::vectors_add` as `math.vectors.add` too. This requires
+    /// the `name` section of the input.
+    pub fn demangle_exports(&mut self, demangle: bool) -> &mut Bindgen {
+        self.demangle_exports = demangle;
+        self
+    }
+
+    /// Links the wasm file at `path` with the input, generating its bindings
+    /// alongside the input's, which re-export them.
+    ///
+    /// All linked modules share the same JS heap, so that JS values can be
+    /// passed from one to the other through JS. This is only supported with
+    /// the bundler target.
+    pub fn link_input_path<P: AsRef<Path>>(&mut self, path: P) -> &mut Bindgen {
+        self.linked_inputs.push(path.as_ref().to_path_buf());
+        self
+    }
+
     pub fn generate<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
-        self._generate(path.as_ref())
+        self.generate_output()?.emit(path.as_ref())
     }
 
-    fn _generate(&mut self, out_dir: &Path) -> Result<(), Error> {
-        let (mut module, stem) = match self.input {
-            Input::None => bail!("must have an input by now"),
-            Input::Module(ref mut m, ref name) => {
-                let blank_module = Module::default();
-                (mem::replace(m, blank_module), &name[..])
+    /// Generates all output files in memory instead of writing them to an
+    /// output directory, see `Output`.
+    pub fn generate_output(&mut self) -> Result<Output, Error> {
+        if self.linked_inputs.len() == 0 {
+            return self.generate_module();
+        }
+        if !self.mode.bundler() {
+            bail!("linking several wasm modules is only supported with `--target bundler`");
+        }
+        if self.anyref || self.interface_types || self.lazy_entry {
+            bail!(
+                "linking several wasm modules isn't supported with anyref, \
+                 interface types or a lazy entry point"
+            );
+        }
+
+        let stem = self.stem()?.to_string();
+        self.shared_heap = Some(format!("./{}_heap.js", stem));
+        let main = self.generate_module();
+
+        // Linked modules are generated with the same configuration, except
+        // for what only applies to the package as a whole.
+        let input = mem::replace(&mut self.input, Input::None);
+        let out_name = self.out_name.take();
+        let package_json = mem::replace(&mut self.package_json, false);
+        let linked = self
+            .linked_inputs
+            .clone()
+            .into_iter()
+            .map(|path| {
+                self.input = Input::Path(path.clone());
+                let output = self.generate_module().with_context(|_| {
+                    format!("failed to generate bindings for `{}`", path.display())
+                });
+                (path, output)
+            })
+            .collect::<Vec<_>>();
+        self.input = input;
+        self.out_name = out_name;
+        self.package_json = package_json;
+        self.shared_heap = None;
+
+        let mut output = main?;
+        let mut heap_globals = mem::replace(&mut output.heap_globals, Vec::new());
+        let mut reexports = String::new();
+        // `export *` silently drops names exported by several modules, so
+        // make sure there's no such name, recording which module exports each.
+        let mut exported_names = output
+            .exported_names
+            .iter()
+            .map(|name| (name.clone(), stem.clone()))
+            .collect::<BTreeMap<_, _>>();
+        let mut duplicates = Vec::new();
+        for (path, linked) in linked {
+            let linked = linked?;
+            let linked_stem = path.file_stem().unwrap().to_str().unwrap();
+            for name in linked.exported_names {
+                if let Some(other) = exported_names.get(&name) {
+                    duplicates.push(format!(
+                        "`{}` (exported by both `{}` and `{}`)",
+                        name, other, linked_stem
+                    ));
+                } else {
+                    exported_names.insert(name, linked_stem.to_string());
+                }
+            }
+            for (file, contents) in linked.files {
+                let conflicts = output.files.get(&file).map_or(false, |p| *p != contents);
+                if conflicts {
+                    bail!(
+                        "cannot link `{}`, its output file `{}` conflicts with \
+                         another module's",
+                        path.display(),
+                        file.display()
+                    );
+                }
+                output.add(file, contents);
+            }
+            for global in linked.heap_globals {
+                if !heap_globals.contains(&global) {
+                    heap_globals.push(global);
+                }
+            }
+            reexports.push_str(&format!("export * from './{}.js';\n", linked_stem));
+        }
+        if duplicates.len() > 0 {
+            bail!(
+                "cannot link modules exporting the same names, which would \
+                 conflict when re-exported by the main module: {}",
+                duplicates.join(", ")
+            );
+        }
+
+        // The main module's JS and TypeScript re-export everything exported by
+        // linked modules, so that there's still a single entry point.
+        for extension in &["js", "d.ts"] {
+            let path = PathBuf::from(&stem).with_extension(extension);
+            if let Some(contents) = output.files.get_mut(&path) {
+                contents.extend_from_slice(reexports.as_bytes());
+            }
+        }
+
+        if heap_globals.len() > 0 {
+            let mut heap_js = String::new();
+            let mut exports = Vec::new();
+            for (names, js) in heap_globals {
+                heap_js.push_str(&js);
+                heap_js.push_str("\n\n");
+                exports.extend(names);
             }
+            heap_js.push_str(&format!("export {{ {} }};\n", exports.join(", ")));
+            let heap_path = PathBuf::from(format!("{}_heap.js", stem));
+            output.add(heap_path, reset_indentation(&heap_js));
+        }
+
+        Ok(output)
+    }
+
+    /// Returns the stem of the names of the output files for the input.
+    fn stem(&self) -> Result<&str, Error> {
+        if let Some(name) = &self.out_name {
+            return Ok(name);
+        }
+        Ok(match &self.input {
+            Input::None => bail!("must have an input by now"),
+            Input::Module(_, name) | Input::Bytes(_, name) => name.as_str(),
+            Input::Path(path) => path.file_stem().unwrap().to_str().unwrap(),
+        })
+    }
+
+    fn generate_module(&mut self) -> Result<Output, Error> {
+        let mut config = walrus::ModuleConfig::new();
+        // Skip validation of the module as LLVM's output is generally already
+        // well-formed and so we won't gain much from re-validating.
+        // Additionally LLVM's current output for threads includes atomic
+        // instructions but doesn't include shared memory, so it fails that
+        // part of validation!
+        config
+            .strict_validate(false)
+            .generate_dwarf(self.keep_debug)
+            .generate_name_section(!self.remove_name_section)
+            .generate_producers_section(!self.remove_producers_section);
+        let stem = self.stem()?.to_string();
+        let stem = &stem[..];
+        let mut module = match self.input {
+            Input::None => bail!("must have an input by now"),
+            Input::Module(ref mut m, _) => mem::replace(m, Module::default()),
             Input::Path(ref path) => {
                 let contents = fs::read(&path)
                     .with_context(|_| format!("failed to read `{}`", path.display()))?;
-                let module = walrus::ModuleConfig::new()
-                    // Skip validation of the module as LLVM's output is
-                    // generally already well-formed and so we won't gain much
-                    // from re-validating. Additionally LLVM's current output
-                    // for threads includes atomic instructions but doesn't
-                    // include shared memory, so it fails that part of
-                    // validation!
-                    .strict_validate(false)
-                    .generate_dwarf(self.keep_debug)
-                    .generate_name_section(!self.remove_name_section)
-                    .generate_producers_section(!self.remove_producers_section)
+                config
                     .parse(&contents)
-                    .context("failed to parse input file as wasm")?;
-                let stem = match &self.out_name {
-                    Some(name) => &name,
-                    None => path.file_stem().unwrap().to_str().unwrap(),
-                };
-                (module, stem)
+                    .context("failed to parse input file as wasm")?
             }
+            Input::Bytes(ref contents, _) => config
+                .parse(contents)
+                .context("failed to parse input as wasm")?,
+        };
+        let mut output = Output {
+            files: BTreeMap::new(),
+            heap_globals: Vec::new(),
+            exported_names: Vec::new(),
         };
 
         // This isn't the hardest thing in the world too support but we
@@ -291,6 +733,38 @@ impl Bindgen {
                 .with_context(|_| "failed to prepare module for threading")?;
         }
 
+        if self.split_debug_info && !self.keep_debug {
+            bail!("splitting out debug info requires `--keep-debug`");
+        }
+
+        if self.lazy_entry && !self.mode.bundler() && !self.mode.nodejs_experimental_modules() {
+            bail!(
+                "a lazy entry point can only be generated with `--target bundler` \
+                 or Node's experimental modules"
+            );
+        }
+
+        if self.lazy_entry && self.demangle_exports {
+            bail!("a lazy entry point can't be generated with `--demangle-exports`");
+        }
+
+        if self.worker_entry && !self.mode.web() && !self.mode.no_modules() {
+            bail!(
+                "a worker entry point can only be generated with `--target web` \
+                 and `--target no-modules`"
+            );
+        }
+
+        if self.import_memory {
+            if !self.mode.web() && !self.mode.no_modules() {
+                bail!(
+                    "importing memory is only supported with `--target web` \
+                     and `--target no-modules`"
+                );
+            }
+            import_memory(&mut module)?;
+        }
+
         // If requested, turn all mangled symbols into prettier unmangled
         // symbols with the help of `rustc-demangle`.
         if self.demangle {
@@ -307,7 +781,7 @@ impl Bindgen {
         // exports by executing `__wbindgen_describe_*` functions. This'll
         // effectively move all the descriptor functions to their own custom
         // sections.
-        descriptors::execute(&mut module)?;
+        descriptors::execute(&mut module, self.demangle_exports)?;
 
         // Process and remove our raw custom sections emitted by the
         // #[wasm_bindgen] macro and the compiler. In their stead insert a
@@ -315,14 +789,27 @@ impl Bindgen {
         // the webidl bindings proposal) as well as an auxiliary section for all
         // sorts of miscellaneous information and features #[wasm_bindgen]
         // supports that aren't covered by WebIDL bindings.
-        webidl::process(&mut module)?;
+        let input = match &self.input {
+            Input::Path(path) => format!("`{}`", path.display()),
+            Input::Module(_, name) | Input::Bytes(_, name) => format!("`{}`", name),
+            Input::None => unreachable!(),
+        };
+        webidl::process(
+            &mut module,
+            &input,
+            self.schema_compat,
+            self.demangle_exports,
+        )?;
 
         // Now that we've got type information from the webidl processing pass,
         // touch up the output of rustc to insert anyref shims where necessary.
         // This is only done if the anyref pass is enabled, which it's
         // currently off-by-default since `anyref` is still in development in
         // engines.
-        if self.anyref {
+        //
+        // Standard bindings pass `anyref` values directly, so the pass is
+        // always enabled for them.
+        if self.anyref || self.interface_types {
             anyref::process(&mut module)?;
         }
 
@@ -332,9 +819,31 @@ impl Bindgen {
             module.start = None;
         }
 
+        // Without JS glue, all that's left is to describe the bindings in a
+        // standard custom section of the module.
+        if self.interface_types {
+            let aux = module
+                .customs
+                .delete_typed::<webidl::WasmBindgenAux>()
+                .expect("aux section should be present");
+            let bindings = module
+                .customs
+                .delete_typed::<webidl::NonstandardWebidlSection>()
+                .unwrap();
+            webidl::standard::add_section(&mut module, &aux, &bindings)
+                .context("failed to generate a standard WebIDL bindings custom section")?;
+            if self.cfg_manifest {
+                let manifest_path = PathBuf::from(format!("{}_cfg.json", stem));
+                output.add(manifest_path, cfg_manifest(&aux)?);
+            }
+            let wasm_path = PathBuf::from(stem).with_extension("wasm");
+            output.add(wasm_path, module.emit_wasm()?);
+            return Ok(output);
+        }
+
         // Now that our module is massaged and good to go, feed it into the JS
         // shim generation which will actually generate JS for all this.
-        let (js, ts) = {
+        let (js, ts, lazy, worker, mut profile, imported_modules) = {
             let mut cx = js::Context::new(&mut module, self)?;
 
             let aux = cx
@@ -351,74 +860,226 @@ impl Bindgen {
 
             // Write out all local JS snippets to the final destination now that
             // we've collected them from all the programs.
-            for (identifier, list) in aux.snippets.iter() {
-                for (i, js) in list.iter().enumerate() {
-                    let name = format!("inline{}.js", i);
-                    let path = out_dir.join("snippets").join(identifier).join(name);
-                    fs::create_dir_all(path.parent().unwrap())?;
-                    fs::write(&path, js)
-                        .with_context(|_| format!("failed to write `{}`", path.display()))?;
-                }
+            // Inline snippets are already deduplicated by content, so each
+            // one is written exactly once.
+            for (name, js) in aux.snippets.iter() {
+                output.add(Path::new("snippets").join(name), js.clone());
             }
             for (path, contents) in aux.local_modules.iter() {
-                let path = out_dir.join("snippets").join(path);
-                fs::create_dir_all(path.parent().unwrap())?;
-                fs::write(&path, contents)
-                    .with_context(|_| format!("failed to write `{}`", path.display()))?;
+                output.add(Path::new("snippets").join(path), contents.clone());
+            }
+            // Files linked with `link_to!` sit next to the generated JS, which
+            // resolves their URLs relative to itself.
+            for (name, contents) in aux.linked_files.iter() {
+                output.add(PathBuf::from(name), contents.clone());
+            }
+
+            let dependencies = cx
+                .npm_dependencies
+                .iter()
+                .map(|(k, v)| (k.clone(), v.1.clone()))
+                .collect::<BTreeMap<_, _>>();
+            if self.package_json {
+                let has_snippets = aux.snippets.len() > 0 || aux.local_modules.len() > 0;
+                let has_start = cx.module.start.is_some() || aux.async_starts.len() > 0;
+                let mut linked_files = aux.linked_files.keys().cloned().collect::<Vec<_>>();
+                linked_files.sort();
+                let json = self.generate_package_json(
+                    stem,
+                    has_snippets,
+                    has_start,
+                    &linked_files,
+                    &dependencies,
+                );
+                let json = serde_json::to_string_pretty(&json)?;
+                output.add(PathBuf::from("package.json"), json);
+            } else if dependencies.len() > 0 {
+                let json = serde_json::to_string_pretty(&dependencies)?;
+                output.add(PathBuf::from("package.json"), json);
             }
 
-            if cx.npm_dependencies.len() > 0 {
-                let map = cx
-                    .npm_dependencies
-                    .iter()
-                    .map(|(k, v)| (k, &v.1))
-                    .collect::<BTreeMap<_, _>>();
-                let json = serde_json::to_string_pretty(&map)?;
-                fs::write(out_dir.join("package.json"), json)?;
+            if self.cfg_manifest {
+                let manifest_path = PathBuf::from(format!("{}_cfg.json", stem));
+                output.add(manifest_path, cfg_manifest(&aux)?);
             }
 
-            cx.finalize(stem)?
+            let (js, ts) = cx.finalize(stem)?;
+            output.heap_globals = mem::replace(&mut cx.heap_globals, Vec::new());
+            output.exported_names = cx.exported_names();
+            let lazy = if self.lazy_entry {
+                Some(cx.generate_lazy_entry(stem)?)
+            } else {
+                None
+            };
+            let worker = if self.worker_entry {
+                Some(cx.generate_worker_entry(stem))
+            } else {
+                None
+            };
+            let profile = if self.profile_bindings {
+                Some(cx.bindings_profile())
+            } else {
+                None
+            };
+            (js, ts, lazy, worker, profile, cx.imported_modules())
         };
 
-        // And now that we've got all our JS and TypeScript, actually write it
-        // out to the filesystem.
+        // And now that we've got all our JS and TypeScript, add it to the
+        // output.
         let extension = if self.mode.nodejs_experimental_modules() {
             "mjs"
         } else {
             "js"
         };
-        fs::create_dir_all(out_dir)?;
-        let js_path = out_dir.join(stem).with_extension(extension);
-        fs::write(&js_path, reset_indentation(&js))
-            .with_context(|_| format!("failed to write `{}`", js_path.display()))?;
+        let js_path = PathBuf::from(stem).with_extension(extension);
+        let js = reset_indentation(&js);
+        let js_len = js.len();
+        output.add(js_path.clone(), js);
 
         if self.typescript {
-            let ts_path = js_path.with_extension("d.ts");
-            fs::write(&ts_path, ts)
-                .with_context(|_| format!("failed to write `{}`", ts_path.display()))?;
+            output.add(js_path.with_extension("d.ts"), ts);
+        }
+
+        if let Some((lazy_js, lazy_ts)) = lazy {
+            let js_path = PathBuf::from(format!("{}_lazy", stem)).with_extension(extension);
+            output.add(js_path.clone(), reset_indentation(&lazy_js));
+            if self.typescript {
+                output.add(js_path.with_extension("d.ts"), lazy_ts);
+            }
+        }
+
+        if let Some(worker_js) = worker {
+            let js_path = PathBuf::from(format!("{}_worker.js", stem));
+            output.add(js_path, reset_indentation(&worker_js));
+        }
+
+        if self.list_imports {
+            let list = imported_modules
+                .iter()
+                .map(|(spec, kind)| serde_json::json!({ "specifier": spec, "kind": kind }))
+                .collect::<Vec<_>>();
+            let list_path = PathBuf::from(format!("{}_imports.json", stem));
+            output.add(list_path, serde_json::to_string_pretty(&list)?);
         }
 
-        let wasm_path = out_dir.join(format!("{}_bg", stem)).with_extension("wasm");
+        if let Some(base) = &self.import_map_base {
+            // Relative and absolute paths resolve without an import map, so
+            // only bare specifiers are mapped.
+            let imports = imported_modules
+                .keys()
+                .filter(|s| !s.starts_with("./") && !s.starts_with("../") && !s.starts_with('/'))
+                .filter(|s| !s.contains("://"))
+                .map(|s| (s.clone(), format!("{}/{}", base.trim_end_matches('/'), s)))
+                .collect::<BTreeMap<_, _>>();
+            let map = serde_json::json!({ "imports": imports });
+            let map_path = PathBuf::from(format!("{}_import_map.json", stem));
+            output.add(map_path, serde_json::to_string_pretty(&map)?);
+        }
+
+        let wasm_path = PathBuf::from(format!("{}_bg", stem)).with_extension("wasm");
 
         if self.mode.nodejs() {
             let js_path = wasm_path.with_extension(extension);
             let shim = self.generate_node_wasm_import(&module, &wasm_path);
-            fs::write(&js_path, shim)
-                .with_context(|_| format!("failed to write `{}`", js_path.display()))?;
+            output.add(js_path, shim);
         }
 
         if self.typescript {
             let ts_path = wasm_path.with_extension("d.ts");
-            let ts = wasm2es6js::typescript(&module)?;
-            fs::write(&ts_path, ts)
-                .with_context(|_| format!("failed to write `{}`", ts_path.display()))?;
+            output.add(ts_path, wasm2es6js::typescript(&module)?);
+        }
+
+        if self.split_debug_info {
+            let debug_path = wasm_path.with_extension("debug.wasm");
+            let debug_bytes = split_debug_info(&mut module, &debug_path)?;
+            output.add(debug_path, debug_bytes);
         }
 
         let wasm_bytes = module.emit_wasm()?;
-        fs::write(&wasm_path, wasm_bytes)
-            .with_context(|_| format!("failed to write `{}`", wasm_path.display()))?;
 
-        Ok(())
+        if let Some(profile) = &mut profile {
+            profile["js_total"] = js_len.into();
+            profile["wasm_total"] = wasm_bytes.len().into();
+            profile::add_wasm_sizes(profile, &wasm_bytes)?;
+            let profile_path = PathBuf::from(format!("{}_profile.json", stem));
+            output.add(profile_path, serde_json::to_string_pretty(profile)?);
+            let report_path = PathBuf::from(format!("{}_profile.md", stem));
+            output.add(report_path, profile::markdown(profile));
+        }
+
+        output.add(wasm_path, wasm_bytes);
+
+        Ok(output)
+    }
+
+    /// Generates a `package.json` manifest for all the files written out by
+    /// `_generate`, pointing `main`, `module`, `types` and `exports` at the
+    /// right files for the current output mode.
+    ///
+    /// `sideEffects` is only `false` when importing the JS has no observable
+    /// effect. Otherwise bundlers could drop the snippets, which may run code
+    /// when imported, or the initialization running the start function.
+    fn generate_package_json(
+        &self,
+        stem: &str,
+        has_snippets: bool,
+        has_start: bool,
+        linked_files: &[String],
+        dependencies: &BTreeMap<String, String>,
+    ) -> serde_json::Value {
+        let extension = if self.mode.nodejs_experimental_modules() {
+            "mjs"
+        } else {
+            "js"
+        };
+        let js = format!("{}.{}", stem, extension);
+        let mut files = vec![js.clone(), format!("{}_bg.wasm", stem)];
+        if self.mode.nodejs() {
+            files.push(format!("{}_bg.{}", stem, extension));
+        }
+        if self.typescript {
+            files.push(format!("{}.d.ts", stem));
+            files.push(format!("{}_bg.d.ts", stem));
+        }
+        if self.worker_entry {
+            files.push(format!("{}_worker.js", stem));
+        }
+        if has_snippets {
+            files.push("snippets".to_string());
+        }
+        files.extend(linked_files.iter().cloned());
+
+        let entry = format!("./{}", js);
+        let mut json = serde_json::json!({
+            "name": stem,
+            "files": files,
+            "main": entry,
+            "exports": {
+                ".": entry,
+                "./package.json": "./package.json",
+            },
+            "sideEffects": false,
+        });
+        if has_snippets || has_start {
+            let mut side_effects = vec![entry.clone()];
+            if self.mode.nodejs() {
+                side_effects.push(format!("./{}_bg.{}", stem, extension));
+            }
+            if has_snippets {
+                side_effects.push("./snippets/*".to_string());
+            }
+            json["sideEffects"] = side_effects.into();
+        }
+        if self.mode.uses_es_modules() {
+            json["module"] = entry.into();
+        }
+        if self.typescript {
+            json["types"] = format!("./{}.d.ts", stem).into();
+        }
+        if dependencies.len() > 0 {
+            json["dependencies"] = serde_json::json!(dependencies);
+        }
+        json
     }
 
     fn generate_node_wasm_import(&self, m: &Module, path: &Path) -> String {
@@ -468,12 +1129,60 @@ impl Bindgen {
             }
         }
 
-        shim.push_str(&format!(
-            "
-                const wasmModule = new WebAssembly.Module(bytes);
-                const wasmInstance = new WebAssembly.Instance(wasmModule, imports);
-            ",
-        ));
+        // The module is compiled and instantiated here, so this is where
+        // their performance marks are recorded for Node.js.
+        let (compile_start, compile_end, instantiate_start, instantiate_end) =
+            if self.performance_marks {
+                let fallback = if self.mode.nodejs_experimental_modules() {
+                    shim.push_str("import * as perf_hooks from 'perf_hooks';\n");
+                    "perf_hooks.performance"
+                } else {
+                    "require('perf_hooks').performance"
+                };
+                shim.push_str(&js::performance_marks_js(Some(fallback)));
+                (
+                    "markStart('compile');",
+                    "markEnd('compile');",
+                    "markStart('instantiate');",
+                    "markEnd('instantiate');",
+                )
+            } else {
+                ("", "", "", "")
+            };
+
+        if self.debug {
+            shim.push_str(js::DESCRIBE_LINK_ERROR);
+            shim.push_str(&format!(
+                "
+                    {}
+                    const wasmModule = new WebAssembly.Module(bytes);
+                    {}
+                    {}
+                    let wasmInstance;
+                    try {{
+                        wasmInstance = new WebAssembly.Instance(wasmModule, imports);
+                    }} catch (e) {{
+                        const expected = WebAssembly.Module.imports(wasmModule)
+                            .map(i => [i.module, i.name]);
+                        throw describeLinkError(e, expected, imports);
+                    }}
+                    {}
+                ",
+                compile_start, compile_end, instantiate_start, instantiate_end,
+            ));
+        } else {
+            shim.push_str(&format!(
+                "
+                    {}
+                    const wasmModule = new WebAssembly.Module(bytes);
+                    {}
+                    {}
+                    const wasmInstance = new WebAssembly.Instance(wasmModule, imports);
+                    {}
+                ",
+                compile_start, compile_end, instantiate_start, instantiate_end,
+            ));
+        }
 
         if self.mode.nodejs_experimental_modules() {
             for entry in m.exports.iter() {
@@ -491,6 +1200,34 @@ impl Bindgen {
     }
 }
 
+impl Output {
+    fn add(&mut self, path: PathBuf, contents: impl Into<Vec<u8>>) {
+        self.files.insert(path, contents.into());
+    }
+
+    /// Returns an iterator over the generated files and their contents, in
+    /// order of their paths relative to the output directory.
+    pub fn files(&self) -> impl Iterator<Item = (&Path, &[u8])> {
+        self.files
+            .iter()
+            .map(|(path, contents)| (path.as_path(), contents.as_slice()))
+    }
+
+    /// Writes all generated files to `out_dir`, creating directories as
+    /// needed.
+    pub fn emit<P: AsRef<Path>>(&self, out_dir: P) -> Result<(), Error> {
+        let out_dir = out_dir.as_ref();
+        fs::create_dir_all(out_dir)?;
+        for (path, contents) in self.files.iter() {
+            let path = out_dir.join(path);
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(&path, contents)
+                .with_context(|_| format!("failed to write `{}`", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
 fn reset_indentation(s: &str) -> String {
     let mut indent: u32 = 0;
     let mut dst = String::new();
@@ -536,18 +1273,120 @@ fn threads_config() -> Option<wasm_bindgen_threads_xform::Config> {
     Some(cfg)
 }
 
+/// Switches the single memory of `module` to be imported instead of defined
+/// locally, if it isn't already imported.
+fn import_memory(module: &mut Module) -> Result<(), Error> {
+    let mut memories = module.memories.iter_mut();
+    let memory = match memories.next() {
+        Some(memory) => memory,
+        None => bail!("cannot import memory in a module without a memory"),
+    };
+    if memories.next().is_some() {
+        bail!("only one memory is currently supported");
+    }
+    if memory.import.is_none() {
+        let id = module
+            .imports
+            .add("env", "memory", walrus::ImportKind::Memory(memory.id()));
+        memory.import = Some(id);
+    }
+    Ok(())
+}
+
+/// Generates the `{name}_cfg.json` manifest listing the `#[cfg]` conditions of
+/// exports which held, under `enabled`, and those which didn't, under
+/// `disabled`.
+fn cfg_manifest(aux: &webidl::WasmBindgenAux) -> Result<String, Error> {
+    let (enabled, disabled): (Vec<_>, Vec<_>) = aux.cfgs.iter().partition(|(_, held)| **held);
+    let manifest = serde_json::json!({
+        "enabled": enabled.into_iter().map(|(cfg, _)| cfg).collect::<Vec<_>>(),
+        "disabled": disabled.into_iter().map(|(cfg, _)| cfg).collect::<Vec<_>>(),
+    });
+    Ok(serde_json::to_string_pretty(&manifest)?)
+}
+
+/// Returns `module` encoded along with its DWARF custom sections, for the
+/// debug file at `path`, and then removes those sections from `module` and
+/// adds an `external_debug_info` section to it which points at `path`.
+///
+/// The debug file is a full copy of the module rather than only its DWARF
+/// sections, as debuggers map the code offsets referenced in DWARF to the
+/// code section of the file the DWARF is found in, which is then the same as
+/// the one of the main file.
+///
+/// Note that walrus doesn't currently rewrite the code offsets referenced in
+/// DWARF when it re-encodes the code section, so source locations may be
+/// imprecise, especially in functions that `wasm-bindgen` has modified.
+fn split_debug_info(module: &mut Module, path: &Path) -> Result<Vec<u8>, Error> {
+    const DEBUG_SECTIONS: &[&str] = &[
+        ".debug_abbrev",
+        ".debug_addr",
+        ".debug_aranges",
+        ".debug_frame",
+        ".debug_info",
+        ".debug_line",
+        ".debug_line_str",
+        ".debug_loc",
+        ".debug_loclists",
+        ".debug_macinfo",
+        ".debug_pubnames",
+        ".debug_pubtypes",
+        ".debug_ranges",
+        ".debug_rnglists",
+        ".debug_str",
+        ".debug_str_offsets",
+        ".debug_types",
+    ];
+
+    fn leb128(dst: &mut Vec<u8>, mut val: u32) {
+        loop {
+            let byte = (val & 0x7f) as u8;
+            val >>= 7;
+            if val == 0 {
+                dst.push(byte);
+                break;
+            }
+            dst.push(byte | 0x80);
+        }
+    }
+
+    let debug = module.emit_wasm()?;
+    for name in DEBUG_SECTIONS {
+        while module.customs.remove_raw(name).is_some() {}
+    }
+
+    let url = path.file_name().unwrap().to_str().unwrap();
+    let mut data = Vec::new();
+    leb128(&mut data, url.len() as u32);
+    data.extend_from_slice(url.as_bytes());
+    module.customs.add(walrus::RawCustomSection {
+        name: "external_debug_info".to_string(),
+        data,
+    });
+
+    Ok(debug)
+}
+
 fn demangle(module: &mut Module) {
     for func in module.funcs.iter_mut() {
         let name = match &func.name {
-            Some(name) => name,
+            Some(name) => demangled_name(name),
             None => continue,
         };
-        if let Ok(sym) = rustc_demangle::try_demangle(name) {
-            func.name = Some(sym.to_string());
+        if name.is_some() {
+            func.name = name;
         }
     }
 }
 
+/// Demangles the Rust symbol `name`, using the alternate format to drop the
+/// trailing hash, which yields names like `my_crate::module::function` in
+/// stack traces and in the errors reported in debug mode.
+fn demangled_name(name: &str) -> Option<String> {
+    let sym = rustc_demangle::try_demangle(name).ok()?;
+    Some(format!("{:#}", sym))
+}
+
 impl OutputMode {
     fn nodejs_experimental_modules(&self) -> bool {
         match self {
@@ -613,3 +1452,12 @@ fn unexported_unused_lld_things(module: &mut Module) {
         module.exports.delete(id);
     }
 }
+
+#[test]
+fn test_demangled_name() {
+    assert_eq!(
+        demangled_name("_ZN8my_crate6module8function17h0123456789abcdefE"),
+        Some("my_crate::module::function".to_string())
+    );
+    assert_eq!(demangled_name("__wbg_alert_0123456789abcdef"), None);
+}
@@ -0,0 +1,263 @@
+//! Support for `--profile-bindings`, which reports how many bytes of glue each
+//! part of the generated bindings contributes.
+//!
+//! The JS side of the report is put together by `js::Context`, while the wasm
+//! side is measured here from the final wasm file, since only then do we know
+//! how large each function ends up being.
+
+use failure::{bail, Error};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// The sections of a profile in the order they're rendered in the markdown
+/// report, along with their titles and the unit of their sizes.
+const SECTIONS: &[(&str, &str, &str)] = &[
+    ("exports", "Exports", "JS bytes"),
+    ("wasm_exports", "Exports", "wasm bytes"),
+    ("imports", "Imports", "JS bytes"),
+    ("wasm_imports", "Imports", "wasm bytes"),
+    ("intrinsics", "Intrinsics", "JS bytes"),
+    ("helpers", "JS helpers", "JS bytes"),
+];
+
+/// Adds the size of the wasm shims of each export and import of `wasm`, the
+/// final wasm file, to `profile`.
+///
+/// The shim of an export is the function it exports, and the shim of an
+/// import is its entry in the import section along with the adapter function
+/// wrapping it, if any, like the ones generated by the anyref pass. Adapters
+/// are found through the name section, so they're only counted if it's kept.
+pub fn add_wasm_sizes(profile: &mut Value, wasm: &[u8]) -> Result<(), Error> {
+    let mut imported_functions = 0;
+    let mut imports = BTreeMap::new();
+    let mut exports = Vec::new();
+    let mut bodies = Vec::new();
+    let mut names = HashMap::new();
+
+    let mut reader = Reader::new(wasm);
+    if reader.bytes(8)? != b"\0asm\x01\0\0\0" {
+        bail!("not a wasm file");
+    }
+    while !reader.is_empty() {
+        let id = reader.byte()?;
+        let len = reader.u32()? as usize;
+        let mut section = Reader::new(reader.bytes(len)?);
+        match id {
+            // custom sections, where we're only interested in function names
+            0 => {
+                if section.name()? != "name" {
+                    continue;
+                }
+                while !section.is_empty() {
+                    let kind = section.byte()?;
+                    let len = section.u32()? as usize;
+                    let mut subsection = Reader::new(section.bytes(len)?);
+                    if kind != 1 {
+                        continue;
+                    }
+                    for _ in 0..subsection.u32()? {
+                        let index = subsection.u32()?;
+                        names.insert(subsection.name()?.to_string(), index);
+                    }
+                }
+            }
+            // the import section
+            2 => {
+                for _ in 0..section.u32()? {
+                    let start = section.pos;
+                    section.name()?;
+                    let name = section.name()?.to_string();
+                    match section.byte()? {
+                        0 => {
+                            section.u32()?;
+                            imported_functions += 1;
+                            imports.insert(name, section.pos - start);
+                        }
+                        1 => {
+                            section.byte()?;
+                            section.limits()?;
+                        }
+                        2 => section.limits()?,
+                        3 => {
+                            section.bytes(2)?;
+                        }
+                        kind => bail!("unknown import kind {}", kind),
+                    }
+                }
+            }
+            // the export section
+            7 => {
+                for _ in 0..section.u32()? {
+                    let name = section.name()?.to_string();
+                    let kind = section.byte()?;
+                    let index = section.u32()?;
+                    if kind == 0 {
+                        exports.push((name, index));
+                    }
+                }
+            }
+            // the code section
+            10 => {
+                for _ in 0..section.u32()? {
+                    let len = section.u32()? as usize;
+                    section.bytes(len)?;
+                    bodies.push(len);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let body_size = |index: u32| {
+        (index as usize)
+            .checked_sub(imported_functions)
+            .and_then(|i| bodies.get(i).cloned())
+            .unwrap_or(0)
+    };
+    let exports = exports
+        .into_iter()
+        .map(|(name, index)| (name, body_size(index)))
+        .collect::<BTreeMap<_, _>>();
+    let imports = imports
+        .into_iter()
+        .map(|(name, size)| {
+            let adapter = names
+                .get(&format!("{}_anyref_shim", name))
+                .map_or(0, |index| body_size(*index));
+            (name, size + adapter)
+        })
+        .collect::<BTreeMap<_, _>>();
+    profile["wasm_exports"] = serde_json::to_value(exports)?;
+    profile["wasm_imports"] = serde_json::to_value(imports)?;
+    Ok(())
+}
+
+/// Renders `profile` as a markdown report, listing the largest items of each
+/// section first.
+pub fn markdown(profile: &Value) -> String {
+    let mut dst = String::from("# Bindings profile\n\n");
+    dst.push_str("| File | Bytes |\n|------|------:|\n");
+    for (key, title) in [("js_total", "JS"), ("wasm_total", "wasm")].iter() {
+        if let Some(size) = profile[*key].as_u64() {
+            dst.push_str(&format!("| {} | {} |\n", title, size));
+        }
+    }
+    for (key, title, unit) in SECTIONS {
+        let items = match profile[*key].as_object() {
+            Some(items) if items.len() > 0 => items,
+            _ => continue,
+        };
+        let mut items = items
+            .iter()
+            .map(|(name, size)| (size.as_u64().unwrap_or(0), name))
+            .collect::<Vec<_>>();
+        items.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+        dst.push_str(&format!("\n## {} ({})\n\n", title, unit));
+        dst.push_str("| Name | Bytes |\n|------|------:|\n");
+        for (size, name) in items {
+            dst.push_str(&format!("| `{}` | {} |\n", name, size));
+        }
+    }
+    dst
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Reader<'a> {
+        Reader { data, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() - self.pos < len {
+            bail!("unexpected end of wasm file");
+        }
+        let ret = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(ret)
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let mut ret = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            ret |= ((byte & 0x7f) as u32) << shift;
+            if byte & 0x80 == 0 {
+                break Ok(ret);
+            }
+            shift += 7;
+            if shift > 28 {
+                bail!("invalid LEB128 integer in wasm file");
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, Error> {
+        let len = self.u32()? as usize;
+        Ok(std::str::from_utf8(self.bytes(len)?)?)
+    }
+
+    fn limits(&mut self) -> Result<(), Error> {
+        let flags = self.byte()?;
+        self.u32()?;
+        if flags & 1 != 0 {
+            self.u32()?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_markdown() {
+    let profile = serde_json::json!({
+        "exports": { "greet": 120, "add": 40, "Foo::new": 120 },
+        "wasm_exports": { "greet": 300 },
+        "imports": {},
+        "helpers": { "getObject": 50 },
+        "js_total": 1000,
+        "wasm_total": 2000,
+    });
+    assert_eq!(
+        markdown(&profile),
+        "\
+# Bindings profile
+
+| File | Bytes |
+|------|------:|
+| JS | 1000 |
+| wasm | 2000 |
+
+## Exports (JS bytes)
+
+| Name | Bytes |
+|------|------:|
+| `Foo::new` | 120 |
+| `greet` | 120 |
+| `add` | 40 |
+
+## Exports (wasm bytes)
+
+| Name | Bytes |
+|------|------:|
+| `greet` | 300 |
+
+## JS helpers (JS bytes)
+
+| Name | Bytes |
+|------|------:|
+| `getObject` | 50 |
+"
+    );
+}
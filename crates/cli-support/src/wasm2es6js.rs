@@ -4,19 +4,33 @@ use walrus::Module;
 
 pub struct Config {
     base64: bool,
+    sync: bool,
     fetch_path: Option<String>,
 }
 
 pub struct Output {
     module: Module,
     base64: bool,
+    sync: bool,
     fetch_path: Option<String>,
 }
 
+/// The imports of the wasm module from one module specifier.
+struct ImportedModule {
+    specifier: String,
+    // The name of the namespace import of the JS module, if some imports are
+    // provided by it.
+    namespace: Option<String>,
+    // The names of the imported memories and tables, which are defined by
+    // the generated JS instead, see `Output::defined_imports`.
+    defined: Vec<String>,
+}
+
 impl Config {
     pub fn new() -> Config {
         Config {
             base64: false,
+            sync: false,
             fetch_path: None,
         }
     }
@@ -26,6 +40,17 @@ impl Config {
         self
     }
 
+    /// Whether or not to compile and instantiate the inlined wasm module
+    /// synchronously, so that its exports can be used as soon as the
+    /// generated module is evaluated. Requires `base64`.
+    ///
+    /// Note that browsers only allow compiling small modules synchronously
+    /// on the main thread.
+    pub fn sync(&mut self, sync: bool) -> &mut Self {
+        self.sync = sync;
+        self
+    }
+
     pub fn fetch(&mut self, path: Option<String>) -> &mut Self {
         self.fetch_path = path;
         self
@@ -35,10 +60,14 @@ impl Config {
         if !self.base64 && !self.fetch_path.is_some() {
             bail!("one of --base64 or --fetch is required");
         }
+        if self.sync && !self.base64 {
+            bail!("--sync requires --base64, as fetching the module is asynchronous");
+        }
         let module = Module::from_buffer(wasm)?;
         Ok(Output {
             module,
             base64: self.base64,
+            sync: self.sync,
             fetch_path: self.fetch_path.clone(),
         })
     }
@@ -64,7 +93,13 @@ pub fn typescript(module: &Module) -> Result<String, Error> {
                 ));
                 continue;
             }
-            walrus::ExportItem::Global(_) => continue,
+            walrus::ExportItem::Global(_) => {
+                exports.push_str(&format!(
+                    "export const {}: WebAssembly.Global;\n",
+                    entry.name,
+                ));
+                continue;
+            }
         };
 
         let func = module.funcs.get(id);
@@ -93,12 +128,61 @@ pub fn typescript(module: &Module) -> Result<String, Error> {
     Ok(exports)
 }
 
+/// Returns the members of a TypeScript interface describing the exports of
+/// an instance of `module`.
+pub fn interface(module: &Module) -> Result<String, Error> {
+    let mut exports = String::new();
+
+    for entry in module.exports.iter() {
+        let id = match entry.item {
+            walrus::ExportItem::Function(i) => i,
+            walrus::ExportItem::Memory(_) => {
+                exports.push_str(&format!("  readonly {}: WebAssembly.Memory;\n", entry.name));
+                continue;
+            }
+            walrus::ExportItem::Table(_) => {
+                exports.push_str(&format!("  readonly {}: WebAssembly.Table;\n", entry.name));
+                continue;
+            }
+            walrus::ExportItem::Global(_) => {
+                exports.push_str(&format!("  readonly {}: WebAssembly.Global;\n", entry.name));
+                continue;
+            }
+        };
+
+        let func = module.funcs.get(id);
+        let ty = module.types.get(func.ty());
+        let mut args = String::new();
+        for (i, _) in ty.params().iter().enumerate() {
+            if i > 0 {
+                args.push_str(", ");
+            }
+            args.push((b'a' + (i as u8)) as char);
+            args.push_str(": number");
+        }
+
+        exports.push_str(&format!(
+            "  readonly {name}: ({args}) => {ret};\n",
+            name = entry.name,
+            args = args,
+            ret = match ty.results().len() {
+                0 => "void",
+                1 => "number",
+                _ => bail!("cannot support multi-return yet"),
+            },
+        ));
+    }
+
+    Ok(exports)
+}
+
 impl Output {
     pub fn typescript(&self) -> Result<String, Error> {
         let mut ts = typescript(&self.module)?;
-        if self.base64 {
-            ts.push_str("export const booted: Promise<boolean>;\n");
+        for (name, ty, _) in self.defined_imports()? {
+            ts.push_str(&format!("export const {}: {};\n", name, ty));
         }
+        ts.push_str("export const booted: Promise<boolean>;\n");
         Ok(ts)
     }
 
@@ -108,21 +192,58 @@ impl Output {
         let mut set_exports = String::new();
         let mut imports = String::new();
 
-        let mut set = HashSet::new();
+        // Bundlers can't import memories and tables from JS modules yet, so
+        // imported ones are defined here instead, and exported so they can
+        // still be shared with JS.
+        let mut modules = Vec::<ImportedModule>::new();
+        let defined = self.defined_imports()?;
         for entry in self.module.imports.iter() {
-            if !set.insert(&entry.module) {
-                continue;
+            let i = match modules.iter().position(|m| m.specifier == entry.module) {
+                Some(i) => i,
+                None => {
+                    modules.push(ImportedModule {
+                        specifier: entry.module.clone(),
+                        namespace: None,
+                        defined: Vec::new(),
+                    });
+                    modules.len() - 1
+                }
+            };
+            let module = &mut modules[i];
+            let is_defined = match entry.kind {
+                walrus::ImportKind::Memory(_) | walrus::ImportKind::Table(_) => true,
+                _ => false,
+            };
+            if is_defined {
+                let (_, _, js) = defined.iter().find(|d| d.0 == entry.name).unwrap();
+                js_imports.push_str(&format!("export const {} = {};\n", entry.name, js));
+                module.defined.push(entry.name.clone());
+            } else if module.namespace.is_none() {
+                let name = format!("import_{}", i);
+                js_imports.push_str(&format!("import * as {} from '{}';\n", name, entry.module));
+                module.namespace = Some(name);
             }
-
-            let name = (b'a' + (set.len() as u8)) as char;
-            js_imports.push_str(&format!(
-                "import * as import_{} from '{}';\n",
-                name, entry.module
-            ));
-            imports.push_str(&format!("'{}': import_{}, ", entry.module, name));
+        }
+        for module in modules {
+            let defined = module.defined.join(", ");
+            let value = match module.namespace {
+                Some(namespace) if defined.len() > 0 => {
+                    format!("Object.assign({{}}, {}, {{ {} }})", namespace, defined)
+                }
+                Some(namespace) => namespace,
+                None => format!("{{ {} }}", defined),
+            };
+            imports.push_str(&format!("'{}': {}, ", module.specifier, value));
         }
 
         for entry in self.module.exports.iter() {
+            if self.sync {
+                exports.push_str(&format!(
+                    "export const {0} = wasm.exports.{0};\n",
+                    entry.name
+                ));
+                continue;
+            }
             exports.push_str("export let ");
             exports.push_str(&entry.name);
             exports.push_str(";\n");
@@ -151,7 +272,12 @@ impl Output {
         // If we remove the start function here (via `unstart`) then we'll
         // reexport it as `__wasm2es6js_start` so be manually executed here.
         if self.unstart() {
-            set_exports.push_str("wasm.exports.__wasm2es6js_start();\n");
+            let start = "wasm.exports.__wasm2es6js_start();\n";
+            if self.sync {
+                exports.push_str(start);
+            } else {
+                set_exports.push_str(start);
+            }
         }
 
         let inst = format!(
@@ -167,21 +293,32 @@ impl Output {
         );
         let wasm = self.module.emit_wasm().expect("failed to serialize");
         let (bytes, booted) = if self.base64 {
-            (
-                format!(
+            let mut bytes = format!(
+                "
+                let bytes;
+                const base64 = \"{base64}\";
+                if (typeof Buffer === 'undefined') {{
+                    bytes = Uint8Array.from(atob(base64), c => c.charCodeAt(0));
+                }} else {{
+                    bytes = Buffer.from(base64, 'base64');
+                }}
+                ",
+                base64 = base64::encode(&wasm)
+            );
+            if self.sync {
+                bytes.push_str(&format!(
                     "
-                    let bytes;
-                    const base64 = \"{base64}\";
-                    if (typeof Buffer === 'undefined') {{
-                        bytes = Uint8Array.from(atob(base64), c => c.charCodeAt(0));
-                    }} else {{
-                        bytes = Buffer.from(base64, 'base64');
-                    }}
+                    const wasm = new WebAssembly.Instance(
+                        new WebAssembly.Module(bytes),
+                        {{ {imports} }}
+                    );
                     ",
-                    base64 = base64::encode(&wasm)
-                ),
-                inst,
-            )
+                    imports = imports,
+                ));
+                (bytes, "Promise.resolve(true)".to_string())
+            } else {
+                (bytes, inst)
+            }
         } else if let Some(ref path) = self.fetch_path {
             (
                 String::new(),
@@ -214,6 +351,58 @@ impl Output {
         Ok((js, wasm))
     }
 
+    /// Returns the name, TypeScript type and constructor of each memory and
+    /// table imported by the module, which the generated JS defines from the
+    /// limits declared in the module and exports under the same name.
+    fn defined_imports(&self) -> Result<Vec<(&str, &'static str, String)>, Error> {
+        let mut defined = Vec::new();
+        let mut names = HashSet::new();
+        for entry in self.module.imports.iter() {
+            let (ty, js) = match entry.kind {
+                walrus::ImportKind::Memory(id) => {
+                    let memory = self.module.memories.get(id);
+                    let mut js = format!("initial: {}", memory.initial);
+                    if let Some(max) = memory.maximum {
+                        js.push_str(&format!(", maximum: {}", max));
+                    }
+                    if memory.shared {
+                        js.push_str(", shared: true");
+                    }
+                    (
+                        "WebAssembly.Memory",
+                        format!("new WebAssembly.Memory({{ {} }})", js),
+                    )
+                }
+                walrus::ImportKind::Table(id) => {
+                    let table = self.module.tables.get(id);
+                    let element = match table.kind {
+                        walrus::TableKind::Function(_) => "anyfunc",
+                        walrus::TableKind::Anyref(_) => "anyref",
+                    };
+                    let mut js = format!("element: '{}', initial: {}", element, table.initial);
+                    if let Some(max) = table.maximum {
+                        js.push_str(&format!(", maximum: {}", max));
+                    }
+                    (
+                        "WebAssembly.Table",
+                        format!("new WebAssembly.Table({{ {} }})", js),
+                    )
+                }
+                _ => continue,
+            };
+            let name = entry.name.as_str();
+            if !names.insert(name) || self.module.exports.iter().any(|e| e.name == name) {
+                bail!(
+                    "cannot define the imported memory or table `{}`, another \
+                     import or export has the same name",
+                    name
+                );
+            }
+            defined.push((name, ty, js));
+        }
+        Ok(defined)
+    }
+
     /// See comments above for what this is doing, but in a nutshell this
     /// removes the start section, if any, and moves it to an exported function.
     /// Returns whether a start function was found and removed.
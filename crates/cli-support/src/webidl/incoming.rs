@@ -47,6 +47,10 @@ pub enum NonstandardIncoming {
         expr: Box<ast::IncomingBindingExpression>,
     },
 
+    /// JS is passing a `Map` or plain object into Rust, which is flattened into
+    /// an array of alternating keys and values before being copied in.
+    Map { val: ast::IncomingBindingExpression },
+
     /// A mutable slice of values going from JS to Rust, and after Rust finishes
     /// the JS slice is updated with the current value of the slice.
     MutableSlice {
@@ -125,6 +129,15 @@ pub enum NonstandardIncoming {
     /// An arbitrary `anyref` being passed into Rust, but explicitly one that's
     /// borrowed and doesn't need to be persisted in a heap table.
     BorrowedAnyref { val: ast::IncomingBindingExpression },
+
+    /// An owned `anyref` of an imported JS type being passed into Rust. This
+    /// is the same as a `Standard` binding except that in debug mode the value
+    /// is checked to actually be an instance of the imported type.
+    CheckedAnyref {
+        val: ast::IncomingBindingExpression,
+        js_name: String,
+        rust_name: String,
+    },
 }
 
 /// Builder used to create a incomig binding from a `Descriptor`.
@@ -181,12 +194,22 @@ impl IncomingBuilder {
                 self.webidl.push(ast::WebidlScalarType::DomString);
                 self.bindings.push(NonstandardIncoming::Char { val: expr });
             }
-            Descriptor::Anyref => {
+            Descriptor::Anyref | Descriptor::Callback(_) => {
                 let expr = self.expr_as(ValType::Anyref);
                 self.wasm.push(ValType::Anyref);
                 self.webidl.push(ast::WebidlScalarType::Any);
                 self.bindings.push(NonstandardIncoming::Standard(expr));
             }
+            Descriptor::NamedAnyref { js_name, rust_name } => {
+                let expr = self.expr_as(ValType::Anyref);
+                self.wasm.push(ValType::Anyref);
+                self.webidl.push(ast::WebidlScalarType::Any);
+                self.bindings.push(NonstandardIncoming::CheckedAnyref {
+                    val: expr,
+                    js_name: js_name.clone(),
+                    rust_name: rust_name.clone(),
+                });
+            }
             Descriptor::RustStruct(class) => {
                 let expr = self.expr_get();
                 self.wasm.push(ValType::I32);
@@ -219,6 +242,15 @@ impl IncomingBuilder {
                 self.alloc_copy_kind(kind)
             }
 
+            // Whether JS hands over a `Map` or a plain object doesn't matter
+            // here, both are accepted.
+            Descriptor::Map { .. } => {
+                let expr = self.expr_get();
+                self.wasm.extend(&[ValType::I32; 2]);
+                self.webidl.push(ast::WebidlScalarType::Any);
+                self.bindings.push(NonstandardIncoming::Map { val: expr });
+            }
+
             // Can't be passed from JS to Rust yet
             Descriptor::Function(_) |
             Descriptor::Closure(_) |
@@ -249,7 +281,7 @@ impl IncomingBuilder {
                     class: class.to_string(),
                 });
             }
-            Descriptor::Anyref => {
+            Descriptor::Anyref | Descriptor::NamedAnyref { .. } | Descriptor::Callback(_) => {
                 let expr = self.expr_get();
                 self.wasm.push(ValType::Anyref);
                 self.webidl.push(ast::WebidlScalarType::Any);
@@ -284,7 +316,7 @@ impl IncomingBuilder {
 
     fn process_option(&mut self, arg: &Descriptor) -> Result<(), Error> {
         match arg {
-            Descriptor::Anyref => {
+            Descriptor::Anyref | Descriptor::NamedAnyref { .. } | Descriptor::Callback(_) => {
                 self.wasm.push(ValType::I32);
                 self.bindings.push(NonstandardIncoming::OptionAnyref {
                     val: self.expr_get(),
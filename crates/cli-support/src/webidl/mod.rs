@@ -29,7 +29,8 @@ use crate::descriptors::WasmBindgenDescriptorsSection;
 use crate::intrinsic::Intrinsic;
 use failure::{bail, Error};
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::str;
 use walrus::{ExportId, FunctionId, ImportId, Module, TypedCustomSectionId};
@@ -41,6 +42,7 @@ const PLACEHOLDER_MODULE: &str = "__wbindgen_placeholder__";
 mod bindings;
 mod incoming;
 mod outgoing;
+pub mod standard;
 
 pub use self::incoming::NonstandardIncoming;
 pub use self::outgoing::NonstandardOutgoing;
@@ -134,9 +136,18 @@ pub struct WasmBindgenAux {
     /// the `#[wasm_bindgen(module = "/foo.js")]` import options.
     pub local_modules: HashMap<String, String>,
 
-    /// A map from unique crate identifier to the list of inline JS snippets for
-    /// that crate identifier.
-    pub snippets: HashMap<String, Vec<String>>,
+    /// A map from the file name of each inline JS snippet to its contents.
+    ///
+    /// File names are derived from a hash of the snippet's contents, so
+    /// identical snippets (even across crates) are only emitted once and keep
+    /// the same name from one build to the next.
+    pub snippets: HashMap<String, String>,
+
+    /// A map from the file name of each file linked with `link_to!` to its
+    /// contents, which are copied to the output directory.
+    ///
+    /// Like snippets, file names include a hash of the contents.
+    pub linked_files: HashMap<String, Vec<u8>>,
 
     /// A list of all `package.json` files that are intended to be included in
     /// the final build.
@@ -160,6 +171,18 @@ pub struct WasmBindgenAux {
     /// Auxiliary information to go into JS/TypeScript bindings describing the
     /// exported structs from Rust and their fields they've got exported.
     pub structs: Vec<AuxStruct>,
+
+    /// The `async` start functions, in the order in which the JS
+    /// initialization function awaits them, after the wasm start function.
+    pub async_starts: Vec<ExportId>,
+
+    /// Exports which were compiled out because of their `#[cfg]` attributes,
+    /// documented in the JS and TypeScript bindings.
+    pub omitted_exports: Vec<AuxOmittedExport>,
+
+    /// The conditions of `#[cfg]` attributes on exports, and whether they held
+    /// when compiling, which is the case if they held for any export.
+    pub cfgs: BTreeMap<String, bool>,
 }
 
 pub type WasmBindgenAuxId = TypedCustomSectionId<WasmBindgenAux>;
@@ -174,8 +197,17 @@ pub struct AuxExport {
     /// Argument names in Rust forwarded here to configure the names that show
     /// up in TypeScript bindings.
     pub arg_names: Option<Vec<String>>,
+    /// TypeScript types configured in Rust for arguments (by name) or the
+    /// return value (as `return`), overriding the inferred ones.
+    pub typescript_types: Vec<(String, String)>,
+    /// Default values, as JS expressions, for arguments (by name) which are
+    /// substituted when the argument is `undefined`.
+    pub arg_defaults: Vec<(String, String)>,
     /// What kind of function this is and where it shows up
     pub kind: AuxExportKind,
+    /// The path of nested JS objects a free function is exported in, empty to
+    /// export it from the module itself.
+    pub js_namespace: Vec<String>,
 }
 
 /// All possible kinds of exports from a wasm module.
@@ -208,6 +240,14 @@ pub enum AuxExportKind {
     /// expected to be the field.
     Getter { class: String, field: String },
 
+    /// Same as `Getter`, except that the getter is defined on the class itself
+    /// rather than its instances, so there's no internal pointer argument.
+    StaticGetter { class: String, field: String },
+
+    /// Same as `Setter`, except that the setter is defined on the class itself
+    /// rather than its instances, so the only argument is the new value.
+    StaticSetter { class: String, field: String },
+
     /// This function is intended to be a setter for a field on a class. The
     /// first argument is the internal pointer and the second argument is
     /// expected to be the field's new value.
@@ -224,9 +264,21 @@ pub enum AuxExportKind {
         /// Whether or not this is calling a by-value method in Rust and should
         /// clear the internal pointer in JS automatically.
         consumed: bool,
+        /// Whether or not this is a by-value method returning `Self` which
+        /// writes its result back in place, so JS returns `this`.
+        chain: bool,
     },
 }
 
+#[derive(Debug)]
+pub struct AuxOmittedExport {
+    /// The name the export would have in JS, prefixed with its class and a
+    /// `.` for methods
+    pub name: String,
+    /// The condition it's compiled under, like `feature = "advanced"`
+    pub cfg: String,
+}
+
 #[derive(Debug)]
 pub struct AuxEnum {
     /// The name of this enum
@@ -243,6 +295,13 @@ pub struct AuxStruct {
     pub name: String,
     /// The copied Rust comments to forward to JS
     pub comments: String,
+    /// Whether the JS class should be generated without a `free` method
+    pub no_free: bool,
+    /// A method of the JS class to call at the start of `free`
+    pub on_free: Option<String>,
+    /// The path of nested JS objects the class is exported in, empty to
+    /// export it from the module itself.
+    pub js_namespace: Vec<String>,
 }
 
 /// All possible types of imports that can be imported by a wasm module.
@@ -277,6 +336,16 @@ pub enum AuxImport {
     /// `JsImport`.
     Static(JsImport),
 
+    /// Same as `Static`, except that the shim is invoked on every access from
+    /// Rust, so if the base of the import is a global which doesn't exist (for
+    /// example `document` in a worker) `undefined` is returned instead of
+    /// throwing.
+    ThreadLocalStatic(JsImport),
+
+    /// This import is expected to be a shim that assigns its only argument to
+    /// the JS binding or property named by `JsImport`.
+    StaticSetter(JsImport),
+
     /// This import is intended to manufacture a JS closure with the given
     /// signature and then return that back to Rust.
     Closure {
@@ -387,6 +456,10 @@ pub enum AuxImport {
     /// This is an intrinsic function expected to be implemented with a JS glue
     /// shim. Each intrinsic has its own expected signature and implementation.
     Intrinsic(Intrinsic),
+
+    /// This import returns the URL of the file with the given name, copied to
+    /// the output directory, as linked with `link_to!`.
+    LinkTo(String),
 }
 
 /// Values that can be imported verbatim to hook up to an import.
@@ -399,15 +472,8 @@ pub enum AuxValue {
     /// `getOwnPropertyDescriptor`.
     Getter(JsImport, String),
 
-    /// Like `Getter`, but accesses a field of a class instead of an instance
-    /// of the class.
-    ClassGetter(JsImport, String),
-
     /// Like `Getter`, except the `set` property.
     Setter(JsImport, String),
-
-    /// Like `Setter`, but for class fields instead of instance fields.
-    ClassSetter(JsImport, String),
 }
 
 /// What can actually be imported and typically a value in each of the variants
@@ -436,38 +502,52 @@ pub enum JsImportName {
     /// Same as `Global`, except the `name` is imported via an ESM import from
     /// the specified `module` path.
     Module { module: String, name: String },
+    /// Same as `Module`, except the `module` path comes from a `raw_module`
+    /// attribute and is relative to the generated JS.
+    RawModule { module: String, name: String },
     /// Same as `Module`, except we're importing from a local module defined in
     /// a local JS snippet.
     LocalModule { module: String, name: String },
-    /// Same as `Module`, except we're importing from an `inline_js` attribute
-    InlineJs {
-        unique_crate_identifier: String,
-        snippet_idx_in_crate: usize,
-        name: String,
-    },
+    /// Same as `Module`, except we're importing from an `inline_js` attribute.
+    /// The `snippet` is the hashed file name of the snippet in the
+    /// `snippets` directory.
+    InlineJs { snippet: String, name: String },
     /// A global import which may have a number of vendor prefixes associated
     /// with it, like `webkitAudioPrefix`. The `name` is the name to test
-    /// whether it's prefixed.
-    VendorPrefixed { name: String, prefixes: Vec<String> },
+    /// whether it's prefixed, optionally as a property of the global
+    /// `namespace`.
+    VendorPrefixed {
+        namespace: Option<String>,
+        name: String,
+        prefixes: Vec<String>,
+    },
 }
 
 struct Context<'a> {
-    start_found: bool,
+    /// Start functions, along with their priority, in declaration order.
+    starts: Vec<(i32, FunctionId)>,
+    async_starts: Vec<(i32, ExportId)>,
     module: &'a mut Module,
     bindings: NonstandardWebidlSection,
     aux: WasmBindgenAux,
     function_exports: HashMap<String, (ExportId, FunctionId)>,
     function_imports: HashMap<String, (ImportId, FunctionId)>,
     vendor_prefixes: HashMap<String, Vec<String>>,
-    unique_crate_identifier: &'a str,
+    inline_js: Vec<String>,
+    linked_files: HashMap<String, String>,
     descriptors: HashMap<String, Descriptor>,
+    module_paths: HashMap<String, Vec<String>>,
+    demangle_exports: bool,
 }
 
 pub fn process(
     module: &mut Module,
+    input: &str,
+    schema_compat: bool,
+    demangle_exports: bool,
 ) -> Result<(NonstandardWebidlSectionId, WasmBindgenAuxId), Error> {
     let mut storage = Vec::new();
-    let programs = extract_programs(module, &mut storage)?;
+    let programs = extract_programs(module, &mut storage, input, schema_compat)?;
 
     let mut cx = Context {
         bindings: Default::default(),
@@ -476,9 +556,13 @@ pub fn process(
         function_imports: Default::default(),
         vendor_prefixes: Default::default(),
         descriptors: Default::default(),
-        unique_crate_identifier: "",
+        module_paths: Default::default(),
+        demangle_exports,
+        inline_js: Vec::new(),
+        linked_files: HashMap::new(),
         module,
-        start_found: false,
+        starts: Vec::new(),
+        async_starts: Vec::new(),
     };
     cx.init()?;
 
@@ -486,6 +570,7 @@ pub fn process(
         cx.program(program)?;
     }
 
+    cx.add_start_functions();
     cx.verify()?;
 
     let bindings = cx.module.customs.add(cx.bindings);
@@ -540,10 +625,12 @@ impl<'a> Context<'a> {
             let WasmBindgenDescriptorsSection {
                 descriptors,
                 closure_imports,
+                module_paths,
             } = *custom;
             // Store all the executed descriptors in our own field so we have
             // access to them while processing programs.
             self.descriptors.extend(descriptors);
+            self.module_paths.extend(module_paths);
 
             // Register all the injected closure imports as that they're expected
             // to manufacture a particular type of closure.
@@ -559,6 +646,11 @@ impl<'a> Context<'a> {
             // Finally we store all this metadata in the import map which we've
             // learned so when a binding for the import is generated we can
             // generate all the appropriate shims.
+            //
+            // Imports are processed in order so that the table elements, and
+            // the JS generated for them, don't depend on hash map iteration.
+            let mut closure_imports = closure_imports.into_iter().collect::<Vec<_>>();
+            closure_imports.sort_by_key(|(id, _)| *id);
             for (id, descriptor) in closure_imports {
                 let binding = Function {
                     shim_idx: 0,
@@ -601,7 +693,6 @@ impl<'a> Context<'a> {
     }
 
     fn program(&mut self, program: decode::Program<'a>) -> Result<(), Error> {
-        self.unique_crate_identifier = program.unique_crate_identifier;
         let decode::Program {
             exports,
             enums,
@@ -609,11 +700,37 @@ impl<'a> Context<'a> {
             structs,
             typescript_custom_sections,
             local_modules,
+            linked_files,
             inline_js,
-            unique_crate_identifier,
+            unique_crate_identifier: _,
             package_json,
         } = program;
 
+        // Inline JS snippets are named after a hash of their contents, so
+        // register them all up front before imports from this program refer
+        // to them by index.
+        self.inline_js.clear();
+        for js in inline_js {
+            let name = inline_js_file_name(js);
+            self.aux
+                .snippets
+                .entry(name.clone())
+                .or_insert_with(|| js.to_string());
+            self.inline_js.push(name);
+        }
+
+        // Linked files are referred to by their path within the crate, which
+        // is only unique within this program.
+        self.linked_files.clear();
+        for file in linked_files {
+            let name = linked_file_name(file.path, file.contents);
+            self.aux
+                .linked_files
+                .entry(name.clone())
+                .or_insert_with(|| file.contents.to_vec());
+            self.linked_files.insert(file.path.to_string(), name);
+        }
+
         for module in local_modules {
             // All local modules we find should be unique, but the same module
             // may have showed up in a few different blocks. If that's the case
@@ -661,11 +778,6 @@ impl<'a> Context<'a> {
             self.aux.extra_typescript.push_str(section);
             self.aux.extra_typescript.push_str("\n\n");
         }
-        self.aux
-            .snippets
-            .entry(unique_crate_identifier.to_string())
-            .or_insert(Vec::new())
-            .extend(inline_js.iter().map(|s| s.to_string()));
         Ok(())
     }
 
@@ -675,20 +787,78 @@ impl<'a> Context<'a> {
             None => export.function.name.to_string(),
         };
         let mut descriptor = match self.descriptors.remove(&wasm_name) {
-            None => return Ok(()),
+            // Exports gated on a `#[cfg]` that didn't hold are still listed in
+            // the program, but have neither a descriptor nor a wasm function.
+            // An export of the same name may be compiled in their stead though.
+            None => {
+                if let Some(cfg) = export.cfg {
+                    self.aux.cfgs.entry(cfg.to_string()).or_insert(false);
+                    if !self.function_exports.contains_key(&wasm_name) {
+                        let name = match export.class {
+                            Some(class) => format!("{}.{}", class, export.function.name),
+                            None => export.function.name.to_string(),
+                        };
+                        self.aux.omitted_exports.push(AuxOmittedExport {
+                            name,
+                            cfg: cfg.to_string(),
+                        });
+                    }
+                }
+                return Ok(());
+            }
             Some(d) => d.unwrap_function(),
         };
+        let mut comments = concatenate_comments(&export.comments);
+        if let Some(cfg) = export.cfg {
+            self.aux.cfgs.insert(cfg.to_string(), true);
+            if comments.len() > 0 {
+                comments.push_str("\n\n");
+            }
+            comments.push_str(&format!(" Only available with `cfg({})`.", cfg));
+        }
         let (export_id, id) = self.function_exports[&wasm_name];
-        if export.start {
-            self.add_start_function(id)?;
+        if let Some(start) = &export.start {
+            if start.asyncness {
+                self.async_starts.push((start.priority, export_id));
+            } else {
+                self.starts.push((start.priority, id));
+            }
         }
 
+        // Callbacks are plain JS functions, but since their signature is
+        // known it's used in TypeScript rather than `any`. Types given with
+        // `typescript_type` still take precedence.
+        let mut typescript_types = export
+            .function
+            .arg_names
+            .iter()
+            .zip(&descriptor.arguments)
+            .filter_map(|(name, arg)| Some((name.clone(), arg.callback_typescript()?)))
+            .collect::<Vec<_>>();
+        if let Some(ts) = descriptor.ret.callback_typescript() {
+            typescript_types.push(("return".to_string(), ts));
+        }
+        typescript_types.extend(
+            export
+                .function
+                .typescript_types
+                .iter()
+                .map(|t| (t.name.to_string(), t.ty.to_string())),
+        );
+
+        let mut js_namespace = Vec::new();
         let kind = match export.class {
             Some(class) => {
                 let class = class.to_string();
                 match export.method_kind {
                     decode::MethodKind::Constructor => AuxExportKind::Constructor(class),
                     decode::MethodKind::Operation(op) => match op.kind {
+                        decode::OperationKind::Getter(f) if op.is_static => {
+                            AuxExportKind::StaticGetter {
+                                class,
+                                field: f.to_string(),
+                            }
+                        }
                         decode::OperationKind::Getter(f) => {
                             descriptor.arguments.insert(0, Descriptor::I32);
                             AuxExportKind::Getter {
@@ -696,6 +866,12 @@ impl<'a> Context<'a> {
                                 field: f.to_string(),
                             }
                         }
+                        decode::OperationKind::Setter(f) if op.is_static => {
+                            AuxExportKind::StaticSetter {
+                                class,
+                                field: f.to_string(),
+                            }
+                        }
                         decode::OperationKind::Setter(f) => {
                             descriptor.arguments.insert(0, Descriptor::I32);
                             AuxExportKind::Setter {
@@ -713,52 +889,95 @@ impl<'a> Context<'a> {
                                 class,
                                 name: export.function.name.to_string(),
                                 consumed: export.consumed,
+                                chain: export.chain,
                             }
                         }
                     },
                 }
             }
-            None => AuxExportKind::Function(export.function.name.to_string()),
+            None => {
+                let mut name = export.function.name.to_string();
+                if export.js_namespace.len() > 0 {
+                    js_namespace = export.js_namespace.iter().map(|s| s.to_string()).collect();
+                } else if self.demangle_exports {
+                    js_namespace = match self.module_paths.remove(&wasm_name) {
+                        Some(path) => path,
+                        None => bail!(
+                            "failed to find the Rust module of `{}` to demangle its \
+                             export, perhaps the `name` section was stripped from \
+                             the wasm file?",
+                            wasm_name
+                        ),
+                    };
+                    // Exports must have unique names in the wasm file, so a
+                    // prefix of the innermost module's name is stripped, which
+                    // lets `vectors::vectors_add` be exported as `vectors.add`.
+                    if let Some(module) = js_namespace.last() {
+                        let prefix = format!("{}_", module);
+                        if name.starts_with(&prefix) && name.len() > prefix.len() {
+                            name = name[prefix.len()..].to_string();
+                        }
+                    }
+                }
+                AuxExportKind::Function(name)
+            }
         };
 
         self.aux.export_map.insert(
             export_id,
             AuxExport {
                 debug_name: wasm_name,
-                comments: concatenate_comments(&export.comments),
+                comments,
                 arg_names: Some(export.function.arg_names),
+                typescript_types,
+                arg_defaults: export
+                    .function
+                    .arg_defaults
+                    .iter()
+                    .map(|d| (d.name.to_string(), d.value.to_string()))
+                    .collect(),
                 kind,
+                js_namespace,
             },
         );
         bindings::register_export(self.module, &mut self.bindings, export_id, descriptor)?;
         Ok(())
     }
 
-    fn add_start_function(&mut self, id: FunctionId) -> Result<(), Error> {
-        if self.start_found {
-            bail!("cannot specify two `start` functions");
+    /// Schedules all start functions, highest priority first and in
+    /// declaration order otherwise. Synchronous ones are all called from the
+    /// wasm start function, and `async` ones are recorded for the JS
+    /// initialization function to await afterwards.
+    fn add_start_functions(&mut self) {
+        self.async_starts
+            .sort_by_key(|&(priority, _)| Reverse(priority));
+        self.aux.async_starts = self.async_starts.iter().map(|(_, id)| *id).collect();
+
+        self.starts.sort_by_key(|&(priority, _)| Reverse(priority));
+        let mut starts = self.starts.iter().map(|(_, id)| *id).collect::<Vec<_>>();
+        if starts.is_empty() {
+            return;
         }
-        self.start_found = true;
-
-        let prev_start = match self.module.start {
-            Some(f) => f,
-            None => {
-                self.module.start = Some(id);
-                return Ok(());
-            }
-        };
 
         // Note that we call the previous start function, if any, first. This is
         // because the start function currently only shows up when it's injected
         // through thread/anyref transforms. These injected start functions need
         // to happen before user code, so we always schedule them first.
+        if let Some(prev_start) = self.module.start {
+            starts.insert(0, prev_start);
+        }
+        if starts.len() == 1 {
+            self.module.start = Some(starts[0]);
+            return;
+        }
         let mut builder = walrus::FunctionBuilder::new();
-        let call1 = builder.call(prev_start, Box::new([]));
-        let call2 = builder.call(id, Box::new([]));
-        let ty = self.module.funcs.get(id).ty();
-        let new_start = builder.finish(ty, Vec::new(), vec![call1, call2], self.module);
+        let calls = starts
+            .iter()
+            .map(|id| builder.call(*id, Box::new([])))
+            .collect();
+        let ty = self.module.funcs.get(starts[0]).ty();
+        let new_start = builder.finish(ty, Vec::new(), calls, self.module);
         self.module.start = Some(new_start);
-        Ok(())
     }
 
     fn import(&mut self, import: decode::Import<'_>) -> Result<(), Error> {
@@ -782,6 +1001,8 @@ impl<'a> Context<'a> {
             method,
             structural,
             function,
+            vendor_prefixes,
+            link_to,
         } = function;
         let (import_id, _id) = match self.function_imports.get(*shim) {
             Some(pair) => *pair,
@@ -801,6 +1022,21 @@ impl<'a> Context<'a> {
             self.aux.imports_with_catch.insert(import_id);
         }
 
+        if let Some(path) = link_to {
+            bindings::register_import(
+                self.module,
+                &mut self.bindings,
+                import_id,
+                descriptor,
+                ast::WebidlFunctionKind::Static,
+            )?;
+            let name = self.linked_files[*path].clone();
+            self.aux
+                .import_map
+                .insert(import_id, AuxImport::LinkTo(name));
+            return Ok(());
+        }
+
         // Perform two functions here. First we're saving off our WebIDL
         // bindings signature, indicating what we think our import is going to
         // be. Next we're saving off other metadata indicating where this item
@@ -857,7 +1093,12 @@ impl<'a> Context<'a> {
                     descriptor,
                     ast::WebidlFunctionKind::Static,
                 )?;
-                let name = self.determine_import(import, function.name)?;
+                let name = if vendor_prefixes.len() > 0 {
+                    let prefixes = vendor_prefixes.iter().map(|s| s.to_string()).collect();
+                    self.determine_vendor_prefixed_import(import, function.name, prefixes)?
+                } else {
+                    self.determine_import(import, function.name)?
+                };
                 AuxImport::Value(AuxValue::Bare(name))
             }
         };
@@ -893,42 +1134,35 @@ impl<'a> Context<'a> {
                 }
             }
 
+            // Static accessors always access the property on the class
+            // directly, even if `final` is used. There's no receiver to pass
+            // through as `this`, and many static properties are plain data
+            // properties (like `Number.MAX_SAFE_INTEGER`) which don't have a
+            // getter or setter function to extract in the first place.
             decode::OperationKind::Getter(field) => {
-                if structural {
-                    if op.is_static {
-                        Ok((
-                            AuxImport::StructuralClassGetter(class, field.to_string()),
-                            false,
-                        ))
-                    } else {
-                        Ok((AuxImport::StructuralGetter(field.to_string()), false))
-                    }
+                if op.is_static {
+                    Ok((
+                        AuxImport::StructuralClassGetter(class, field.to_string()),
+                        false,
+                    ))
+                } else if structural {
+                    Ok((AuxImport::StructuralGetter(field.to_string()), false))
                 } else {
-                    let val = if op.is_static {
-                        AuxValue::ClassGetter(class, field.to_string())
-                    } else {
-                        AuxValue::Getter(class, field.to_string())
-                    };
+                    let val = AuxValue::Getter(class, field.to_string());
                     Ok((AuxImport::Value(val), true))
                 }
             }
 
             decode::OperationKind::Setter(field) => {
-                if structural {
-                    if op.is_static {
-                        Ok((
-                            AuxImport::StructuralClassSetter(class, field.to_string()),
-                            false,
-                        ))
-                    } else {
-                        Ok((AuxImport::StructuralSetter(field.to_string()), false))
-                    }
+                if op.is_static {
+                    Ok((
+                        AuxImport::StructuralClassSetter(class, field.to_string()),
+                        false,
+                    ))
+                } else if structural {
+                    Ok((AuxImport::StructuralSetter(field.to_string()), false))
                 } else {
-                    let val = if op.is_static {
-                        AuxValue::ClassSetter(class, field.to_string())
-                    } else {
-                        AuxValue::Setter(class, field.to_string())
-                    };
+                    let val = AuxValue::Setter(class, field.to_string());
                     Ok((AuxImport::Value(val), true))
                 }
             }
@@ -973,30 +1207,71 @@ impl<'a> Context<'a> {
         import: &decode::Import<'_>,
         static_: &decode::ImportStatic<'_>,
     ) -> Result<(), Error> {
-        let (import_id, _id) = match self.function_imports.get(static_.shim) {
+        if let Some((import_id, _id)) = self.function_imports.get(static_.shim).cloned() {
+            // Register the signature of this imported shim
+            bindings::register_import(
+                self.module,
+                &mut self.bindings,
+                import_id,
+                Function {
+                    arguments: Vec::new(),
+                    shim_idx: 0,
+                    ret: Descriptor::Anyref,
+                },
+                ast::WebidlFunctionKind::Static,
+            )?;
+
+            // And then save off that this function is is an accessor shim for
+            // an imported item.
+            let js = self.determine_import(import, &static_.name)?;
+            let js = if static_.thread_local {
+                AuxImport::ThreadLocalStatic(js)
+            } else {
+                AuxImport::Static(js)
+            };
+            self.aux.import_map.insert(import_id, js);
+        }
+
+        // Mutable statics additionally have a setter shim, which takes
+        // ownership of the value being assigned.
+        let setter = match static_.setter {
+            Some(setter) => setter,
+            None => return Ok(()),
+        };
+        let (import_id, _id) = match self.function_imports.get(setter) {
             Some(pair) => *pair,
             None => return Ok(()),
         };
-
-        // Register the signature of this imported shim
         bindings::register_import(
             self.module,
             &mut self.bindings,
             import_id,
             Function {
-                arguments: Vec::new(),
+                arguments: vec![Descriptor::Anyref],
                 shim_idx: 0,
-                ret: Descriptor::Anyref,
+                ret: Descriptor::Unit,
             },
             ast::WebidlFunctionKind::Static,
         )?;
-
-        // And then save off that this function is is an instanceof shim for an
-        // imported item.
-        let import = self.determine_import(import, &static_.name)?;
+        let js = self.determine_import(import, &static_.name)?;
+        match &js.name {
+            JsImportName::Global { .. } => {}
+            JsImportName::VendorPrefixed { .. } => {
+                bail!(
+                    "cannot assign to `{}` which has vendor prefixes",
+                    static_.name
+                )
+            }
+            _ if js.fields.is_empty() => bail!(
+                "cannot assign to `{}` because bindings imported from modules \
+                 are read-only, import it with `js_namespace` instead",
+                static_.name
+            ),
+            _ => {}
+        }
         self.aux
             .import_map
-            .insert(import_id, AuxImport::Static(import));
+            .insert(import_id, AuxImport::StaticSetter(js));
         Ok(())
     }
 
@@ -1073,11 +1348,14 @@ impl<'a> Context<'a> {
                 AuxExport {
                     debug_name: format!("getter for `{}::{}`", struct_.name, field.name),
                     arg_names: None,
+                    typescript_types: Vec::new(),
+                    arg_defaults: Vec::new(),
                     comments: concatenate_comments(&field.comments),
                     kind: AuxExportKind::Getter {
                         class: struct_.name.to_string(),
                         field: field.name.to_string(),
                     },
+                    js_namespace: Vec::new(),
                 },
             );
 
@@ -1103,17 +1381,23 @@ impl<'a> Context<'a> {
                 AuxExport {
                     debug_name: format!("setter for `{}::{}`", struct_.name, field.name),
                     arg_names: None,
+                    typescript_types: Vec::new(),
+                    arg_defaults: Vec::new(),
                     comments: concatenate_comments(&field.comments),
                     kind: AuxExportKind::Setter {
                         class: struct_.name.to_string(),
                         field: field.name.to_string(),
                     },
+                    js_namespace: Vec::new(),
                 },
             );
         }
         let aux = AuxStruct {
             name: struct_.name.to_string(),
             comments: concatenate_comments(&struct_.comments),
+            no_free: struct_.no_free,
+            on_free: struct_.on_free.map(|s| s.to_string()),
+            js_namespace: struct_.js_namespace.iter().map(|s| s.to_string()).collect(),
         };
         self.aux.structs.push(aux);
 
@@ -1141,52 +1425,11 @@ impl<'a> Context<'a> {
     }
 
     fn determine_import(&self, import: &decode::Import<'_>, item: &str) -> Result<JsImport, Error> {
-        let is_local_snippet = match import.module {
-            decode::ImportModule::Named(s) => self.aux.local_modules.contains_key(s),
-            decode::ImportModule::RawNamed(_) => false,
-            decode::ImportModule::Inline(_) => true,
-            decode::ImportModule::None => false,
-        };
+        let is_local_snippet = self.is_local_snippet(import);
 
-        // Similar to `--target no-modules`, only allow vendor prefixes
-        // basically for web apis, shouldn't be necessary for things like npm
-        // packages or other imported items.
-        let vendor_prefixes = self.vendor_prefixes.get(item);
-        if let Some(vendor_prefixes) = vendor_prefixes {
+        if let Some(vendor_prefixes) = self.vendor_prefixes.get(item) {
             assert!(vendor_prefixes.len() > 0);
-
-            if is_local_snippet {
-                bail!(
-                    "local JS snippets do not support vendor prefixes for \
-                     the import of `{}` with a polyfill of `{}`",
-                    item,
-                    &vendor_prefixes[0]
-                );
-            }
-            if let decode::ImportModule::Named(module) = &import.module {
-                bail!(
-                    "import of `{}` from `{}` has a polyfill of `{}` listed, but
-                     vendor prefixes aren't supported when importing from modules",
-                    item,
-                    module,
-                    &vendor_prefixes[0],
-                );
-            }
-            if let Some(ns) = &import.js_namespace {
-                bail!(
-                    "import of `{}` through js namespace `{}` isn't supported \
-                     right now when it lists a polyfill",
-                    item,
-                    ns
-                );
-            }
-            return Ok(JsImport {
-                name: JsImportName::VendorPrefixed {
-                    name: item.to_string(),
-                    prefixes: vendor_prefixes.clone(),
-                },
-                fields: Vec::new(),
-            });
+            return self.determine_vendor_prefixed_import(import, item, vendor_prefixes.clone());
         }
 
         let (name, fields) = match import.js_namespace {
@@ -1199,25 +1442,18 @@ impl<'a> Context<'a> {
                 module: module.to_string(),
                 name: name.to_string(),
             },
-            decode::ImportModule::Named(module) | decode::ImportModule::RawNamed(module) => {
-                JsImportName::Module {
-                    module: module.to_string(),
-                    name: name.to_string(),
-                }
-            }
-            decode::ImportModule::Inline(idx) => {
-                let offset = self
-                    .aux
-                    .snippets
-                    .get(self.unique_crate_identifier)
-                    .map(|s| s.len())
-                    .unwrap_or(0);
-                JsImportName::InlineJs {
-                    unique_crate_identifier: self.unique_crate_identifier.to_string(),
-                    snippet_idx_in_crate: idx as usize + offset,
-                    name: name.to_string(),
-                }
-            }
+            decode::ImportModule::Named(module) => JsImportName::Module {
+                module: module.to_string(),
+                name: name.to_string(),
+            },
+            decode::ImportModule::RawNamed(module) => JsImportName::RawModule {
+                module: module.to_string(),
+                name: name.to_string(),
+            },
+            decode::ImportModule::Inline(idx) => JsImportName::InlineJs {
+                snippet: self.inline_js[idx as usize].clone(),
+                name: name.to_string(),
+            },
             decode::ImportModule::None => JsImportName::Global {
                 name: name.to_string(),
             },
@@ -1225,6 +1461,53 @@ impl<'a> Context<'a> {
         Ok(JsImport { name, fields })
     }
 
+    fn is_local_snippet(&self, import: &decode::Import<'_>) -> bool {
+        match import.module {
+            decode::ImportModule::Named(s) => self.aux.local_modules.contains_key(s),
+            decode::ImportModule::RawNamed(_) => false,
+            decode::ImportModule::Inline(_) => true,
+            decode::ImportModule::None => false,
+        }
+    }
+
+    /// Same as `determine_import`, except for an item which falls back to the
+    /// vendor prefixed names listed in `prefixes` if it isn't defined.
+    fn determine_vendor_prefixed_import(
+        &self,
+        import: &decode::Import<'_>,
+        item: &str,
+        prefixes: Vec<String>,
+    ) -> Result<JsImport, Error> {
+        // Similar to `--target no-modules`, only allow vendor prefixes
+        // basically for web apis, shouldn't be necessary for things like npm
+        // packages or other imported items.
+        if self.is_local_snippet(import) {
+            bail!(
+                "local JS snippets do not support vendor prefixes for \
+                 the import of `{}` with a polyfill of `{}`",
+                item,
+                &prefixes[0]
+            );
+        }
+        if let decode::ImportModule::Named(module) = &import.module {
+            bail!(
+                "import of `{}` from `{}` has a polyfill of `{}` listed, but \
+                 vendor prefixes aren't supported when importing from modules",
+                item,
+                module,
+                &prefixes[0],
+            );
+        }
+        Ok(JsImport {
+            name: JsImportName::VendorPrefixed {
+                namespace: import.js_namespace.map(|s| s.to_string()),
+                name: item.to_string(),
+                prefixes,
+            },
+            fields: Vec::new(),
+        })
+    }
+
     /// Perform a small verification pass over the module to perform some
     /// internal sanity checks.
     fn verify(&self) -> Result<(), Error> {
@@ -1303,8 +1586,9 @@ impl walrus::CustomSection for WasmBindgenAux {
 fn extract_programs<'a>(
     module: &mut Module,
     program_storage: &'a mut Vec<Vec<u8>>,
+    input: &str,
+    schema_compat: bool,
 ) -> Result<Vec<decode::Program<'a>>, Error> {
-    let my_version = wasm_bindgen_shared::version();
     assert!(program_storage.is_empty());
 
     while let Some(raw) = module.customs.remove_raw("__wasm_bindgen_unstable") {
@@ -1334,32 +1618,22 @@ fn extract_programs<'a>(
             // can just delete this entirely. The `wasm-pack` project already
             // manages versions for us, so we in theory should need this check
             // less and less over time.
-            if let Some(their_version) = verify_schema_matches(data)? {
-                bail!(
-                    "
-
-it looks like the Rust project used to create this wasm file was linked against
-a different version of wasm-bindgen than this binary:
-
-  rust wasm file: {}
-     this binary: {}
-
-Currently the bindgen format is unstable enough that these two version must
-exactly match, so it's required that these two version are kept in sync by
-either updating the wasm-bindgen dependency or this binary. You should be able
-to update the wasm-bindgen dependency with:
-
-    cargo update -p wasm-bindgen
-
-or you can update the binary with
-
-    cargo install -f wasm-bindgen-cli
-
-if this warning fails to go away though and you're not sure what to do feel free
-to open an issue at https://github.com/rustwasm/wasm-bindgen/issues!
-",
-                    their_version,
-                    my_version,
+            let theirs = ProgramVersion::parse(data)?;
+            if theirs.schema_version != wasm_bindgen_shared::SCHEMA_VERSION {
+                // Versions which encode programs the same way can usually be
+                // mixed, but that's only done on request as the rest of the
+                // ABI, like descriptors and intrinsics, may still differ.
+                let my_hash = wasm_bindgen_shared::schema_hash();
+                let same_encoding = theirs.schema_hash == Some(&my_hash[..]);
+                if !schema_compat || !same_encoding {
+                    let compat = schema_compat;
+                    bail!(schema_mismatch(&theirs, input, same_encoding, compat));
+                }
+                log::warn!(
+                    "decoding bindings of wasm-bindgen {} with wasm-bindgen {}, \
+                     which encode them the same way",
+                    theirs.version,
+                    wasm_bindgen_shared::version(),
                 );
             }
             let next = get_remaining(&mut payload).unwrap();
@@ -1383,42 +1657,105 @@ fn get_remaining<'a>(data: &mut &'a [u8]) -> Option<&'a [u8]> {
     Some(a)
 }
 
-fn verify_schema_matches<'a>(data: &'a [u8]) -> Result<Option<&'a str>, Error> {
-    macro_rules! bad {
-        () => {
-            bail!("failed to decode what looked like wasm-bindgen data")
+/// The versions of wasm-bindgen described by the JSON preceding each program
+/// in the custom section.
+struct ProgramVersion<'a> {
+    schema_version: &'a str,
+    version: &'a str,
+    // Missing from the output of older versions, see `schema_hash` in the
+    // shared crate.
+    schema_hash: Option<&'a str>,
+}
+
+impl<'a> ProgramVersion<'a> {
+    fn parse(data: &'a [u8]) -> Result<ProgramVersion<'a>, Error> {
+        macro_rules! bad {
+            () => {
+                bail!("failed to decode what looked like wasm-bindgen data")
+            };
+        }
+        let data = match str::from_utf8(data) {
+            Ok(s) => s,
+            Err(_) => bad!(),
         };
+        log::debug!("found version specifier {}", data);
+        if !data.starts_with("{") || !data.ends_with("}") {
+            bad!()
+        }
+        let schema_version = match field(data, "schema_version") {
+            Some(v) => v,
+            None => bad!(),
+        };
+        let version = match field(data, "version") {
+            Some(v) => v,
+            None => bad!(),
+        };
+        return Ok(ProgramVersion {
+            schema_version,
+            version,
+            schema_hash: field(data, "schema_hash"),
+        });
+
+        fn field<'a>(data: &'a str, name: &str) -> Option<&'a str> {
+            let needle = format!("\"{}\":\"", name);
+            let rest = &data[data.find(&needle)? + needle.len()..];
+            Some(&rest[..rest.find("\"")?])
+        }
     }
-    let data = match str::from_utf8(data) {
-        Ok(s) => s,
-        Err(_) => bad!(),
-    };
-    log::debug!("found version specifier {}", data);
-    if !data.starts_with("{") || !data.ends_with("}") {
-        bad!()
-    }
-    let needle = "\"schema_version\":\"";
-    let rest = match data.find(needle) {
-        Some(i) => &data[i + needle.len()..],
-        None => bad!(),
-    };
-    let their_schema_version = match rest.find("\"") {
-        Some(i) => &rest[..i],
-        None => bad!(),
-    };
-    if their_schema_version == wasm_bindgen_shared::SCHEMA_VERSION {
-        return Ok(None);
-    }
-    let needle = "\"version\":\"";
-    let rest = match data.find(needle) {
-        Some(i) => &data[i + needle.len()..],
-        None => bad!(),
-    };
-    let their_version = match rest.find("\"") {
-        Some(i) => &rest[..i],
-        None => bad!(),
+}
+
+fn schema_mismatch(
+    theirs: &ProgramVersion,
+    input: &str,
+    same_encoding: bool,
+    schema_compat: bool,
+) -> String {
+    let hint = if same_encoding {
+        "Both versions encode bindings the same way though, so passing
+`--schema-compat` may work on a best-effort basis.
+
+"
+    } else if schema_compat {
+        "The two versions encode bindings differently, so `--schema-compat` can't be
+used to bridge them.
+
+"
+    } else {
+        ""
     };
-    Ok(Some(their_version))
+    format!(
+        "
+
+it looks like the Rust project used to create this wasm file was linked against
+a different version of wasm-bindgen than this binary:
+
+  rust wasm file: {}
+     this binary: {}
+  custom section: `__wasm_bindgen_unstable` in {}
+
+Currently the bindgen format is unstable enough that these two version must
+exactly match, so it's required that these two version are kept in sync by
+either updating the wasm-bindgen dependency or this binary. You should be able
+to update the wasm-bindgen dependency to the version this binary expects with:
+
+    cargo update -p wasm-bindgen --precise {}
+
+which can be kept that way by depending on `wasm-bindgen = \"={}\"`, or you can
+install the binary matching the dependency with
+
+    cargo install -f wasm-bindgen-cli --version {}
+
+{}if this warning fails to go away though and you're not sure what to do feel free
+to open an issue at https://github.com/rustwasm/wasm-bindgen/issues!
+",
+        theirs.version,
+        wasm_bindgen_shared::version(),
+        input,
+        wasm_bindgen_shared::SCHEMA_VERSION,
+        wasm_bindgen_shared::SCHEMA_VERSION,
+        theirs.schema_version,
+        hint,
+    )
 }
 
 fn concatenate_comments(comments: &[&str]) -> String {
@@ -1428,3 +1765,41 @@ fn concatenate_comments(comments: &[&str]) -> String {
         .collect::<Vec<_>>()
         .join("\n")
 }
+
+/// Returns the file name, relative to the `snippets` directory, that an
+/// `inline_js` snippet with the given contents is written to.
+///
+/// The name only depends on the contents of the snippet, hashed with
+/// `stable_hash`, so it's stable across builds and toolchains, and identical
+/// snippets share the same file.
+fn inline_js_file_name(contents: &str) -> String {
+    format!("inline-{:016x}.js", stable_hash(contents.as_bytes()))
+}
+
+/// Returns the file name that a file linked with `link_to!` from `path` with
+/// the given contents is copied to.
+///
+/// The original name is kept for readability, with a hash of the contents
+/// mixed in so that caches can be told to keep the file forever.
+fn linked_file_name(path: &str, contents: &[u8]) -> String {
+    let hash = stable_hash(contents);
+    let file = path.rsplit('/').next().unwrap_or(path);
+    match file.rfind('.') {
+        Some(i) if i > 0 => format!("{}-{:016x}{}", &file[..i], hash, &file[i..]),
+        _ => format!("{}-{:016x}", file, hash),
+    }
+}
+
+/// Hashes `contents` with 64-bit FNV-1a.
+///
+/// Unlike `DefaultHasher`, whose algorithm may change between Rust releases,
+/// this always produces the same hash so that file names derived from it are
+/// reproducible whichever compiler built `wasm-bindgen`.
+fn stable_hash(contents: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for byte in contents {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
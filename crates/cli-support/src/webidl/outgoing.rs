@@ -60,6 +60,15 @@ pub enum NonstandardOutgoing {
         kind: VectorKind,
     },
 
+    /// An owned map with string keys is passed from Rust to JS as a list of
+    /// alternating keys and values, which is turned into a `Map` or, if
+    /// `object` is set, a plain object.
+    Map {
+        offset: u32,
+        length: u32,
+        object: bool,
+    },
+
     /// A `&[u64]` or `&[i64]` is being passed to JS, and the 64-bit sizes here
     /// aren't supported by WebIDL bindings yet.
     View64 {
@@ -193,7 +202,9 @@ impl OutgoingBuilder<'_> {
     fn _process(&mut self, arg: &Descriptor) -> Result<(), Error> {
         match arg {
             Descriptor::Boolean => self.standard_as(ValType::I32, ast::WebidlScalarType::Boolean),
-            Descriptor::Anyref => self.standard_as(ValType::Anyref, ast::WebidlScalarType::Any),
+            Descriptor::Anyref | Descriptor::NamedAnyref { .. } | Descriptor::Callback(_) => {
+                self.standard_as(ValType::Anyref, ast::WebidlScalarType::Any)
+            }
             Descriptor::I8 => self.standard_as(ValType::I32, ast::WebidlScalarType::Byte),
             Descriptor::U8 => self.standard_as(ValType::I32, ast::WebidlScalarType::Octet),
             Descriptor::I16 => self.standard_as(ValType::I32, ast::WebidlScalarType::Short),
@@ -253,6 +264,17 @@ impl OutgoingBuilder<'_> {
                 })
             }
 
+            Descriptor::Map { object } => {
+                let offset = self.push_wasm(ValType::I32);
+                let length = self.push_wasm(ValType::I32);
+                self.webidl.push(ast::WebidlScalarType::Any);
+                self.bindings.push(NonstandardOutgoing::Map {
+                    offset,
+                    length,
+                    object: *object,
+                })
+            }
+
             Descriptor::Option(d) => self.process_option(d)?,
 
             Descriptor::Function(_) | Descriptor::Closure(_) | Descriptor::Slice(_) => bail!(
@@ -271,7 +293,7 @@ impl OutgoingBuilder<'_> {
 
     fn process_ref(&mut self, mutable: bool, arg: &Descriptor) -> Result<(), Error> {
         match arg {
-            Descriptor::Anyref => {
+            Descriptor::Anyref | Descriptor::NamedAnyref { .. } | Descriptor::Callback(_) => {
                 let idx = self.push_wasm(ValType::Anyref);
                 self.webidl.push(ast::WebidlScalarType::Any);
                 self.bindings
@@ -368,7 +390,9 @@ impl OutgoingBuilder<'_> {
 
     fn process_option(&mut self, arg: &Descriptor) -> Result<(), Error> {
         match arg {
-            Descriptor::Anyref => self.standard_as(ValType::Anyref, ast::WebidlScalarType::Any),
+            Descriptor::Anyref | Descriptor::NamedAnyref { .. } | Descriptor::Callback(_) => {
+                self.standard_as(ValType::Anyref, ast::WebidlScalarType::Any)
+            }
             Descriptor::I8 => self.option_sentinel(),
             Descriptor::U8 => self.option_sentinel(),
             Descriptor::I16 => self.option_sentinel(),
@@ -445,7 +469,7 @@ impl OutgoingBuilder<'_> {
 
     fn process_option_ref(&mut self, _mutable: bool, arg: &Descriptor) -> Result<(), Error> {
         match arg {
-            Descriptor::Anyref => {
+            Descriptor::Anyref | Descriptor::NamedAnyref { .. } | Descriptor::Callback(_) => {
                 let idx = self.push_wasm(ValType::Anyref);
                 self.webidl.push(ast::WebidlScalarType::Any);
                 self.bindings
@@ -0,0 +1,265 @@
+//! Conversion of the nonstandard WebIDL bindings section to a standard one.
+//!
+//! This is an experimental output mode where instead of generating JS glue the
+//! bindings of exports and imports are described with a `webidl-bindings`
+//! custom section, following the WebIDL bindings proposal (now on its way to
+//! become interface types), so hosts implementing the proposal can instantiate
+//! the module directly.
+//!
+//! Only a subset of what `#[wasm_bindgen]` supports can be expressed this way:
+//! free functions taking and returning numbers, strings and `JsValue`s,
+//! imported from ES modules. Everything else relies on JS glue, and is reported
+//! as an error here. Imports of intrinsics are bound as well, but are left in
+//! the placeholder module for the host to provide.
+
+use crate::webidl::{AuxExportKind, AuxImport, AuxValue, JsImport, JsImportName};
+use crate::webidl::{Binding, NonstandardIncoming, NonstandardOutgoing};
+use crate::webidl::{NonstandardWebidlSection, WasmBindgenAux, PLACEHOLDER_MODULE};
+use failure::{bail, Error, ResultExt};
+use std::collections::{HashMap, HashSet};
+use walrus::Module;
+use wasm_webidl_bindings::ast;
+
+/// Adds a standard `webidl-bindings` custom section to `module` which
+/// describes all the bindings of the `nonstandard` section.
+///
+/// Note that the anyref pass must have run on `module`, as the standard
+/// bindings pass `anyref` values directly.
+pub fn add_section(
+    module: &mut Module,
+    aux: &WasmBindgenAux,
+    nonstandard: &NonstandardWebidlSection,
+) -> Result<(), Error> {
+    let mut section = ast::WebidlBindings::default();
+
+    if let Some(path) = aux.local_modules.keys().next() {
+        bail!(
+            "generating a WebIDL bindings section is not supported with \
+             local JS snippets, like `{}`",
+            path
+        );
+    }
+    if let Some(name) = aux.snippets.keys().next() {
+        bail!(
+            "generating a WebIDL bindings section is not supported with \
+             `inline_js` snippets, like `{}`",
+            name
+        );
+    }
+    if nonstandard.elems.len() > 0 {
+        bail!(
+            "generating a WebIDL bindings section is not supported with \
+             closures passed to JS"
+        );
+    }
+    if aux.async_starts.len() > 0 {
+        bail!(
+            "generating a WebIDL bindings section is not supported with \
+             `async` start functions"
+        );
+    }
+
+    for (export, binding) in sorted(&nonstandard.exports) {
+        let export_info = &aux.export_map[export];
+        match &export_info.kind {
+            AuxExportKind::Function(_) => {}
+            _ => bail!(
+                "cannot export `{}` in a WebIDL bindings section as it's \
+                 part of an exported class, which requires JS glue",
+                export_info.debug_name
+            ),
+        }
+        if export_info.js_namespace.len() > 0 {
+            bail!(
+                "cannot export `{}` in a WebIDL bindings section as it's \
+                 exported in a JS namespace, which requires JS glue",
+                export_info.debug_name
+            );
+        }
+        let params = extract_incoming(&binding.incoming)
+            .with_context(|_| format!("failed to map arguments of `{}`", export_info.debug_name))?;
+        let result = extract_outgoing(&binding.outgoing).with_context(|_| {
+            format!("failed to map return value of `{}`", export_info.debug_name)
+        })?;
+        check_no_outptr(binding, &export_info.debug_name)?;
+        let webidl_ty = copy_ty(&mut section.types, binding, nonstandard);
+        let id = section.bindings.insert(ast::ExportBinding {
+            wasm_ty: binding.wasm_ty,
+            webidl_ty,
+            params: ast::IncomingBindingMap { bindings: params },
+            result: ast::OutgoingBindingMap { bindings: result },
+        });
+        let func = match module.exports.get(*export).item {
+            walrus::ExportItem::Function(f) => f,
+            _ => unreachable!(),
+        };
+        section.binds.insert(ast::Bind {
+            func,
+            binding: id.into(),
+        });
+    }
+
+    let mut bound = HashSet::new();
+    for (import, binding) in sorted(&nonstandard.imports) {
+        let target = standard_import(&aux.import_map[import])?;
+        let debug_name = {
+            let import = module.imports.get(*import);
+            format!("{}::{}", import.module, import.name)
+        };
+        let params = extract_outgoing(&binding.outgoing)
+            .with_context(|_| format!("failed to map arguments of `{}`", debug_name))?;
+        let result = extract_incoming(&binding.incoming)
+            .with_context(|_| format!("failed to map return value of `{}`", debug_name))?;
+        check_no_outptr(binding, &debug_name)?;
+        let webidl_ty = copy_ty(&mut section.types, binding, nonstandard);
+        let id = section.bindings.insert(ast::ImportBinding {
+            wasm_ty: binding.wasm_ty,
+            webidl_ty,
+            params: ast::OutgoingBindingMap { bindings: params },
+            result: ast::IncomingBindingMap { bindings: result },
+        });
+
+        // Without JS glue in between, the host resolves the import itself, so
+        // point it at the actual module and name.
+        let import = module.imports.get_mut(*import);
+        if let Some((module_name, name)) = target {
+            import.module = module_name;
+            import.name = name;
+        }
+        let func = match import.kind {
+            walrus::ImportKind::Function(f) => f,
+            _ => unreachable!(),
+        };
+        bound.insert(import.id());
+        section.binds.insert(ast::Bind {
+            func,
+            binding: id.into(),
+        });
+    }
+
+    // All imports are bound by now, except for the initialization of the
+    // anyref table which has no arguments to bind.
+    for import in module.imports.iter() {
+        match import.kind {
+            walrus::ImportKind::Function(_) => {}
+            _ => continue,
+        }
+        if bound.contains(&import.id())
+            || (import.module == PLACEHOLDER_MODULE
+                && import.name == "__wbindgen_init_anyref_table")
+        {
+            continue;
+        }
+        bail!(
+            "cannot generate a WebIDL bindings section for the import of \
+             `{}::{}`, which requires JS glue",
+            import.module,
+            import.name
+        );
+    }
+
+    module.customs.add(section);
+    Ok(())
+}
+
+/// Returns the module and name of imports which can be resolved by the host
+/// without JS glue, which are functions imported directly from ES modules.
+///
+/// Intrinsics keep their name in the placeholder module, to be provided by
+/// the host, and `None` is returned for them.
+fn standard_import(import: &AuxImport) -> Result<Option<(String, String)>, Error> {
+    let js = match import {
+        AuxImport::Value(AuxValue::Bare(js)) => js,
+        AuxImport::Intrinsic(_) => return Ok(None),
+        _ => bail!(
+            "cannot generate a WebIDL bindings section for imports of methods, \
+             constructors, statics or other JS values which require JS glue, \
+             only functions imported from an ES module are supported"
+        ),
+    };
+    match js {
+        JsImport {
+            name: JsImportName::Module { module, name },
+            fields,
+        } if fields.len() == 0 => Ok(Some((module.clone(), name.clone()))),
+        JsImport {
+            name: JsImportName::Module { module, name },
+            ..
+        } => bail!(
+            "cannot import `{}` from `{}` in a WebIDL bindings section as it's \
+             namespaced with `js_namespace`",
+            name,
+            module
+        ),
+        _ => bail!(
+            "cannot generate a WebIDL bindings section for imports from the \
+             global scope, `raw_module` or snippets, only functions imported \
+             with `#[wasm_bindgen(module = \"...\")]` are supported"
+        ),
+    }
+}
+
+/// Returns the entries of `map` sorted by key, so that the output doesn't
+/// depend on hash map iteration.
+fn sorted<K: Ord, V>(map: &HashMap<K, V>) -> Vec<(&K, &V)> {
+    let mut pairs = map.iter().collect::<Vec<_>>();
+    pairs.sort_by_key(|(k, _)| *k);
+    pairs
+}
+
+fn extract_incoming(
+    nonstandard: &[NonstandardIncoming],
+) -> Result<Vec<ast::IncomingBindingExpression>, Error> {
+    let mut exprs = Vec::new();
+    for expr in nonstandard {
+        match expr {
+            NonstandardIncoming::Standard(e) => exprs.push(e.clone()),
+            other => bail!("{:?} requires JS glue, which isn't supported yet", other),
+        }
+    }
+    Ok(exprs)
+}
+
+fn extract_outgoing(
+    nonstandard: &[NonstandardOutgoing],
+) -> Result<Vec<ast::OutgoingBindingExpression>, Error> {
+    let mut exprs = Vec::new();
+    for expr in nonstandard {
+        match expr {
+            NonstandardOutgoing::Standard(e) => exprs.push(e.clone()),
+            other => bail!("{:?} requires JS glue, which isn't supported yet", other),
+        }
+    }
+    Ok(exprs)
+}
+
+/// Aggregates are returned through an out-pointer since multi-value isn't
+/// available yet, and the JS glue reads them from memory.
+fn check_no_outptr(binding: &Binding, name: &str) -> Result<(), Error> {
+    if binding.return_via_outptr.is_some() {
+        bail!(
+            "cannot describe `{}` in a WebIDL bindings section as its return \
+             value is returned through memory, which requires JS glue",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Copies the WebIDL function type of `binding` over to the standard section.
+fn copy_ty(
+    types: &mut ast::WebidlTypes,
+    binding: &Binding,
+    nonstandard: &NonstandardWebidlSection,
+) -> ast::WebidlTypeRef {
+    let func = nonstandard
+        .types
+        .get::<ast::WebidlFunction>(binding.webidl_ty)
+        .unwrap();
+    let id: ast::WebidlFunctionId = types.insert(ast::WebidlFunction {
+        kind: func.kind.clone(),
+        params: func.params.clone(),
+        result: func.result.clone(),
+    });
+    id.into()
+}
@@ -0,0 +1,363 @@
+use docopt::Docopt;
+use failure::{bail, Error};
+use serde::Deserialize;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use wasm_bindgen_cli_support::{Bindgen, EncodeInto, JsFlavor, Output};
+
+// no need for jemalloc bloat in this binary (and we don't need speed)
+#[global_allocator]
+static ALLOC: std::alloc::System = std::alloc::System;
+
+mod verify;
+
+const USAGE: &'static str = "
+Generating JS bindings for a wasm file
+
+Usage:
+    wasm-bindgen verify <out-dir>
+    wasm-bindgen [options] <input>...
+    wasm-bindgen -h | --help
+    wasm-bindgen -V | --version
+
+Options:
+    -h --help                    Show this screen.
+    --out-dir DIR                Output directory, or `-` to write all output
+                                 files to stdout as a bundle
+    --out-format FORMAT          Format of the bundle written to stdout, valid
+                                 values are [tar, json], and the default is
+                                 [tar]
+    --out-name VAR               Set a custom output filename (Without extension. Defaults to crate name)
+    --target TARGET              What type of output to generate, valid
+                                 values are [web, bundler, nodejs, no-modules],
+                                 and the default is [bundler]
+    --no-modules-global VAR      Name of the global variable to initialize
+    --no-modules-umd             Wrap `no-modules` output in a UMD wrapper so
+                                 it can also be loaded with CommonJS or AMD
+    --browser                    Hint that JS should only be compatible with a browser
+    --typescript                 Output a TypeScript definition file (on by default)
+    --no-typescript              Don't emit a *.d.ts file
+    --typescript-const-enums     Declare exported enums as `const enum`s in the
+                                 *.d.ts file
+    --jsdoc                      Annotate the generated JS with complete JSDoc
+                                 types
+    --debug                      Include otherwise-extraneous debug checks in output
+    --no-demangle                Don't demangle Rust symbol names
+    --keep-debug                 Keep debug sections in wasm files
+    --split-debug-info           Move debug sections kept with `--keep-debug`
+                                 into a separate `*_bg.debug.wasm` file
+    --remove-name-section        Remove the debugging `name` section of the file
+    --remove-producers-section   Remove the telemetry `producers` section
+    --encode-into MODE           Whether or not to use TextEncoder#encodeInto,
+                                 valid values are [test, always, never]
+    --js-flavor FLAVOR           Which JS syntax to generate, valid values are
+                                 [legacy, es2022], and the default is [legacy]
+    --module-extension EXT       Append `.EXT` to relative JS import specifiers
+                                 that don't already have an extension
+    --import-memory              Import the wasm memory instead of exporting it,
+                                 only valid with [web, no-modules] targets
+    --lazy-entry                 Also emit an entry point which loads the
+                                 bindings on demand, only valid with the
+                                 [bundler] target
+    --worker-entry               Also emit a script bootstrapping the bindings
+                                 in a web worker, only valid with [web,
+                                 no-modules] targets
+    --profile-bindings           Emit JSON and markdown reports of the size of
+                                 the JS glue and wasm shims generated for each
+                                 export and import
+    --performance-marks          Emit `performance.mark` calls around
+                                 compilation, instantiation and the first
+                                 export call
+    --minify-internals           Rename internal JS helpers and wasm imports
+                                 to short identifiers
+    --record-allocation-sites    Record where each JS value owned by wasm was
+                                 allocated, only valid with `--debug`
+    --interface-types            Experimental: describe bindings with a standard
+                                 WebIDL bindings custom section instead of
+                                 generating JS
+    --package-json               Emit a package.json describing the output
+    --rewrite-module SPECS       Comma-separated list of `FROM=TO` pairs to
+                                 rewrite JS import specifiers with
+    --raw-module-prefix PREFIX   Prefix relative `raw_module` import specifiers
+                                 with PREFIX
+    --list-imports               Emit a JSON list of every module specifier the
+                                 generated JS imports
+    --cfg-manifest               Emit a JSON manifest of the `#[cfg]` conditions
+                                 of exports which held when compiling
+    --import-map-base URL        Emit an import map mapping bare module
+                                 specifiers to URL
+    --schema-compat              Best-effort processing of wasm files built
+                                 with another version of wasm-bindgen, when
+                                 both encode bindings the same way
+    --demangle-exports           Export free functions in JS namespaces
+                                 mirroring their Rust module paths
+    --nodejs                     Deprecated, use `--target nodejs`
+    --web                        Deprecated, use `--target web`
+    --no-modules                 Deprecated, use `--target no-modules`
+    -V --version                 Print the version number of wasm-bindgen
+";
+
+#[derive(Debug, Deserialize)]
+struct Args {
+    flag_nodejs: bool,
+    flag_browser: bool,
+    flag_web: bool,
+    flag_no_modules: bool,
+    flag_typescript: bool,
+    flag_no_typescript: bool,
+    flag_typescript_const_enums: bool,
+    flag_jsdoc: bool,
+    flag_out_dir: Option<PathBuf>,
+    flag_out_format: Option<String>,
+    flag_out_name: Option<String>,
+    flag_debug: bool,
+    flag_version: bool,
+    flag_no_demangle: bool,
+    flag_no_modules_global: Option<String>,
+    flag_no_modules_umd: bool,
+    flag_remove_name_section: bool,
+    flag_remove_producers_section: bool,
+    flag_keep_debug: bool,
+    flag_encode_into: Option<String>,
+    flag_js_flavor: Option<String>,
+    flag_target: Option<String>,
+    flag_module_extension: Option<String>,
+    flag_rewrite_module: Option<String>,
+    flag_raw_module_prefix: Option<String>,
+    flag_package_json: bool,
+    flag_import_memory: bool,
+    flag_lazy_entry: bool,
+    flag_worker_entry: bool,
+    flag_profile_bindings: bool,
+    flag_list_imports: bool,
+    flag_cfg_manifest: bool,
+    flag_import_map_base: Option<String>,
+    flag_split_debug_info: bool,
+    flag_performance_marks: bool,
+    flag_minify_internals: bool,
+    flag_record_allocation_sites: bool,
+    flag_interface_types: bool,
+    flag_schema_compat: bool,
+    flag_demangle_exports: bool,
+    cmd_verify: bool,
+    arg_out_dir: Option<PathBuf>,
+    arg_input: Vec<PathBuf>,
+}
+
+fn main() {
+    env_logger::init();
+    let args: Args = Docopt::new(USAGE)
+        .and_then(|d| d.deserialize())
+        .unwrap_or_else(|e| e.exit());
+
+    if args.flag_version {
+        println!("wasm-bindgen {}", wasm_bindgen_shared::version());
+        return;
+    }
+    let err = match rmain(&args) {
+        Ok(()) => return,
+        Err(e) => e,
+    };
+    eprintln!("error: {}", err);
+    for cause in err.iter_causes() {
+        eprintln!("    caused by: {}", cause);
+    }
+    process::exit(1);
+}
+
+fn rmain(args: &Args) -> Result<(), Error> {
+    if args.cmd_verify {
+        return verify::run(args.arg_out_dir.as_ref().unwrap());
+    }
+
+    let (input, linked) = match args.arg_input.split_first() {
+        Some(inputs) => inputs,
+        None => bail!("input file expected"),
+    };
+
+    let typescript = args.flag_typescript || !args.flag_no_typescript;
+
+    let mut b = Bindgen::new();
+    // Reading the input from stdin and writing the output to stdout lets build
+    // systems run `wasm-bindgen` without temporary files.
+    if input == Path::new("-") {
+        let name = match &args.flag_out_name {
+            Some(name) => name,
+            None => bail!("the `--out-name` argument is required when reading from stdin"),
+        };
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+        b.input_bytes(name, bytes);
+    } else {
+        b.input_path(input);
+    }
+    // Any other input is linked into the first one's bindings.
+    for path in linked {
+        if path == Path::new("-") {
+            bail!("only the first input can be read from stdin");
+        }
+        b.link_input_path(path);
+    }
+    if let Some(name) = &args.flag_target {
+        match name.as_str() {
+            "bundler" => b.bundler(true)?,
+            "web" => b.web(true)?,
+            "no-modules" => b.no_modules(true)?,
+            "nodejs" => b.nodejs(true)?,
+            s => bail!("invalid encode-into mode: `{}`", s),
+        };
+    }
+    b.nodejs(args.flag_nodejs)?
+        .web(args.flag_web)?
+        .browser(args.flag_browser)?
+        .no_modules(args.flag_no_modules)?
+        .debug(args.flag_debug)
+        .demangle(!args.flag_no_demangle)
+        .keep_debug(args.flag_keep_debug)
+        .split_debug_info(args.flag_split_debug_info)
+        .performance_marks(args.flag_performance_marks)
+        .minify_internals(args.flag_minify_internals)
+        .record_allocation_sites(args.flag_record_allocation_sites)
+        .interface_types(args.flag_interface_types)
+        .schema_compat(args.flag_schema_compat)
+        .demangle_exports(args.flag_demangle_exports)
+        .remove_name_section(args.flag_remove_name_section)
+        .remove_producers_section(args.flag_remove_producers_section)
+        .package_json(args.flag_package_json)
+        .import_memory(args.flag_import_memory)
+        .lazy_entry(args.flag_lazy_entry)
+        .worker_entry(args.flag_worker_entry)
+        .profile_bindings(args.flag_profile_bindings)
+        .list_imports(args.flag_list_imports)
+        .cfg_manifest(args.flag_cfg_manifest)
+        .import_map_base(args.flag_import_map_base.as_ref().map(|s| s.as_str()))
+        .typescript(typescript)
+        .typescript_const_enums(args.flag_typescript_const_enums)
+        .jsdoc(args.flag_jsdoc);
+    if let Some(ref name) = args.flag_no_modules_global {
+        b.no_modules_global(name)?;
+    }
+    b.no_modules_umd(args.flag_no_modules_umd)?;
+    if let Some(ref name) = args.flag_out_name {
+        b.out_name(name);
+    }
+    if let Some(mode) = &args.flag_encode_into {
+        match mode.as_str() {
+            "test" => b.encode_into(EncodeInto::Test),
+            "always" => b.encode_into(EncodeInto::Always),
+            "never" => b.encode_into(EncodeInto::Never),
+            s => bail!("invalid encode-into mode: `{}`", s),
+        };
+    }
+    if let Some(flavor) = &args.flag_js_flavor {
+        match flavor.as_str() {
+            "legacy" => b.js_flavor(JsFlavor::Legacy),
+            "es2022" => b.js_flavor(JsFlavor::Es2022),
+            s => bail!("invalid js-flavor: `{}`", s),
+        };
+    }
+
+    b.module_extension(args.flag_module_extension.as_ref().map(|s| s.as_str()));
+    b.raw_module_prefix(args.flag_raw_module_prefix.as_ref().map(|s| s.as_str()));
+    if let Some(specs) = &args.flag_rewrite_module {
+        for spec in specs.split(',') {
+            let mut parts = spec.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(from), Some(to)) if !from.is_empty() => b.rewrite_module(from, to),
+                _ => bail!("invalid module rewrite `{}`, expected `FROM=TO`", spec),
+            };
+        }
+    }
+
+    let out_dir = match args.flag_out_dir {
+        Some(ref p) => p,
+        None => bail!("the `--out-dir` argument is now required"),
+    };
+
+    if out_dir != Path::new("-") {
+        if args.flag_out_format.is_some() {
+            bail!("the `--out-format` argument requires `--out-dir -`");
+        }
+        return b.generate(out_dir);
+    }
+    let output = b.generate_output()?;
+    let bundle = match args.flag_out_format.as_ref().map(|s| s.as_str()) {
+        None | Some("tar") => tar_bundle(&output)?,
+        Some("json") => json_bundle(&output)?,
+        Some(s) => bail!("invalid out-format: `{}`", s),
+    };
+    io::stdout().write_all(&bundle)?;
+    Ok(())
+}
+
+/// Returns the path of an output file with `/` separators, as found in
+/// bundles.
+fn bundle_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Encodes all output files as an uncompressed ustar archive.
+///
+/// The archive is reproducible: all files have the same owner, permissions
+/// and modification time.
+fn tar_bundle(output: &Output) -> Result<Vec<u8>, Error> {
+    fn octal(field: &mut [u8], value: u64) {
+        let digits = format!("{:0width$o}", value, width = field.len() - 1);
+        field[..digits.len()].copy_from_slice(digits.as_bytes());
+    }
+
+    let mut tar = Vec::new();
+    for (path, contents) in output.files() {
+        let name = bundle_path(path);
+        if name.len() > 100 {
+            bail!("cannot bundle `{}`, its path is too long", name);
+        }
+        let mut header = [0; 512];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        octal(&mut header[100..108], 0o644);
+        octal(&mut header[108..116], 0);
+        octal(&mut header[116..124], 0);
+        octal(&mut header[124..136], contents.len() as u64);
+        octal(&mut header[136..148], 0);
+        header[156] = b'0';
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        // The checksum is computed with its own field filled with spaces.
+        header[148..156].copy_from_slice(b"        ");
+        let checksum = header.iter().map(|b| u64::from(*b)).sum::<u64>();
+        octal(&mut header[148..155], checksum);
+
+        tar.extend_from_slice(&header);
+        tar.extend_from_slice(contents);
+        let padding = (512 - contents.len() % 512) % 512;
+        tar.extend(std::iter::repeat(0).take(padding));
+    }
+    // An archive ends with two empty blocks.
+    tar.extend(std::iter::repeat(0).take(1024));
+    Ok(tar)
+}
+
+/// Encodes all output files as a JSON manifest, with text files as strings and
+/// binary files like wasm as base64.
+fn json_bundle(output: &Output) -> Result<Vec<u8>, Error> {
+    let files = output
+        .files()
+        .map(|(path, contents)| {
+            let (encoding, contents) = match std::str::from_utf8(contents) {
+                Ok(s) => ("utf-8", s.to_string()),
+                Err(_) => ("base64", base64::encode(contents)),
+            };
+            serde_json::json!({
+                "path": bundle_path(path),
+                "encoding": encoding,
+                "contents": contents,
+            })
+        })
+        .collect::<Vec<_>>();
+    let json = serde_json::json!({ "files": files });
+    Ok(serde_json::to_vec_pretty(&json)?)
+}
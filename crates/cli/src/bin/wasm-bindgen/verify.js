@@ -0,0 +1,454 @@
+// Loads the bindings generated by wasm-bindgen in an output directory under
+// Node, calls every export with sentinel values and round-trips them through
+// the fields of exported classes, see `verify.rs`.
+//
+// Imports from JS modules which can't be found, like npm packages the
+// bindings depend on, are replaced by stubs returning `undefined`.
+
+const fs = require('fs');
+const path = require('path');
+const url = require('url');
+const Module = require('module');
+
+const [dir, stem] = process.argv.slice(1);
+
+// Names of the generated initialization functions of the `web` and
+// `no-modules` targets, which aren't bindings of Rust exports.
+const INIT_FUNCTIONS = ['compile', 'instantiate', 'initSync', 'startWorker'];
+
+// Import shims are named after the import followed by a hash, which lets
+// failures of JS APIs missing under Node be told apart from broken glue.
+const IMPORT_SHIM = /__wbg_\w+_[0-9a-f]{16}\b/;
+
+const TYPED_ARRAYS = [
+    'Int8Array', 'Uint8Array', 'Uint8ClampedArray', 'Int16Array', 'Uint16Array',
+    'Int32Array', 'Uint32Array', 'Float32Array', 'Float64Array',
+];
+
+function stub() {
+    const f = function () {};
+    return new Proxy({}, { get: () => f });
+}
+
+// ES modules are evaluated by rewriting their imports and exports, which the
+// generated glue only uses in a few simple forms, as Node can't import wasm
+// files the way bundlers do.
+const modules = new Map();
+
+function importFrom(file, specifier) {
+    if (specifier.startsWith('./') || specifier.startsWith('../')) {
+        const target = path.resolve(path.dirname(file), specifier);
+        if (target.endsWith('.wasm')) {
+            return instantiate(target);
+        }
+        if (fs.existsSync(target)) {
+            return evaluateModule(target);
+        }
+    }
+    return stub();
+}
+
+function evaluateModule(file) {
+    if (modules.has(file)) {
+        return modules.get(file);
+    }
+    const exports = {};
+    modules.set(file, exports);
+
+    const names = [];
+    const importCall = specifier => `__import(${JSON.stringify(specifier)})`;
+    const source = fs.readFileSync(file, 'utf8')
+        .replace(/^import \* as (\w+) from '([^']+)';$/mg,
+            (_, local, specifier) => `const ${local} = ${importCall(specifier)};`)
+        .replace(/^import \{([^}]*)\} from '([^']+)';$/mg,
+            (_, items, specifier) => `const {${items.replace(/ as /g, ': ')}} = ${importCall(specifier)};`)
+        .replace(/^export (async function|function|class|const|let|var) (\w+)/mg,
+            (_, keyword, name) => {
+                names.push([name, name]);
+                return `${keyword} ${name}`;
+            })
+        .replace(/^export \{([^}]*)\};$/mg, (_, items) => {
+            for (const item of items.split(',')) {
+                const [local, exported = local] = item.trim().split(/\s+as\s+/);
+                names.push([exported, local]);
+            }
+            return '';
+        })
+        .replace(/^export default (\w+);$/mg, (_, local) => {
+            names.push(['default', local]);
+            return '';
+        })
+        .replace(/^export \* from '([^']+)';$/mg,
+            (_, specifier) => `__reexport(${importCall(specifier)});`)
+        .replace(/import\.meta\.url/g, JSON.stringify(url.pathToFileURL(file).href));
+
+    // Exports are defined before evaluating the module so that circular
+    // imports, like the ones between the glue and the wasm module, resolve.
+    const getters = names
+        .map(([exported, local]) => `__export(${JSON.stringify(exported)}, () => ${local});`)
+        .join('\n');
+    const body = `'use strict';\n${getters}\n${source}`;
+    const run = new Function('__import', '__export', '__reexport', body);
+    run(
+        specifier => importFrom(file, specifier),
+        (name, get) => Object.defineProperty(exports, name, { enumerable: true, get }),
+        other => {
+            for (const name of Object.keys(other)) {
+                Object.defineProperty(exports, name, { enumerable: true, get: () => other[name] });
+            }
+        },
+    );
+    return exports;
+}
+
+// Instantiates a wasm file imported by an ES module. Function imports are
+// looked up lazily, as the module providing them is usually still being
+// evaluated at this point.
+function instantiate(file) {
+    if (modules.has(file)) {
+        return modules.get(file);
+    }
+    const module = new WebAssembly.Module(fs.readFileSync(file));
+    const imports = {};
+    for (const { module: specifier, name, kind } of WebAssembly.Module.imports(module)) {
+        if (kind !== 'function') {
+            throw new Error(`cannot instantiate \`${file}\`, it imports a ${kind}`);
+        }
+        imports[specifier] = imports[specifier] || {};
+        imports[specifier][name] = function () {
+            return importFrom(file, specifier)[name].apply(this, arguments);
+        };
+    }
+    const exports = new WebAssembly.Instance(module, imports).exports;
+    modules.set(file, exports);
+    return exports;
+}
+
+// Stubs all imports of `module` which aren't provided by the glue itself.
+function extraImports(module) {
+    const imports = {};
+    for (const { module: specifier } of WebAssembly.Module.imports(module)) {
+        if (specifier !== 'wbg') {
+            imports[specifier] = stub();
+        }
+    }
+    return imports;
+}
+
+async function initialize(init, module) {
+    const args = [module];
+    // The extra imports are always the last parameter, after the memory if
+    // the module imports it.
+    while (args.length < init.length - 1) {
+        args.push(undefined);
+    }
+    args.push(extraImports(module));
+    await init(...args);
+}
+
+async function load(js, wasm) {
+    const source = fs.readFileSync(js, 'utf8');
+    if (source.includes(`from './${stem}_bg.wasm';`)) {
+        return { target: 'bundler', exports: evaluateModule(js) };
+    }
+    if (/^export default init;$/m.test(source)) {
+        const exports = evaluateModule(js);
+        await initialize(exports.default, new WebAssembly.Module(fs.readFileSync(wasm)));
+        return { target: 'web', exports };
+    }
+    if (source.includes(`require('./${stem}_bg')`)) {
+        const load = Module._load;
+        Module._load = function (request) {
+            try {
+                return load.apply(this, arguments);
+            } catch (e) {
+                if (e.code !== 'MODULE_NOT_FOUND') {
+                    throw e;
+                }
+                return stub();
+            }
+        };
+        return { target: 'nodejs', exports: require(js) };
+    }
+    // With `no-modules` the bindings are assigned to a global, possibly
+    // namespaced, which is found on a fresh `self` object.
+    const root = {};
+    new Function('self', 'module', 'define', source)(root, undefined, undefined);
+    let exports = root;
+    while (typeof exports !== 'function') {
+        const keys = Object.keys(exports);
+        if (typeof exports !== 'object' || keys.length !== 1) {
+            throw new Error(`cannot find the bindings defined by \`${js}\``);
+        }
+        exports = exports[keys[0]];
+    }
+    await initialize(exports, new WebAssembly.Module(fs.readFileSync(wasm)));
+    return { target: 'no-modules', exports };
+}
+
+// Parses the declarations of exported functions and classes from the
+// TypeScript definitions generated along with the glue.
+//
+// For classes this records whether they have a `free` method, which classes
+// marked `no_free` don't, how instances can be created, and their fields
+// which can be both read and written with the same type.
+function declarations(dts) {
+    const functions = [];
+    const classes = [];
+    let class_ = null;
+    const params = list => list === '' ? [] : list.split(', ').map(param => {
+        const [name, ty] = param.split(/\??: /);
+        return { name, ty };
+    });
+    for (const line of dts.split('\n')) {
+        if (class_ !== null) {
+            let m;
+            if (line === '}') {
+                class_ = null;
+            } else if (line === '  free(): void;') {
+                class_.free = true;
+            } else if ((m = /^  constructor\((.*)\);$/.exec(line))) {
+                class_.factories.push({ name: null, params: params(m[1]) });
+            } else if ((m = /^  static (\w+)\((.*)\): (\w+);$/.exec(line)) && m[3] === class_.name) {
+                class_.factories.push({ name: m[1], params: params(m[2]) });
+            } else if ((m = /^  (\w+): (.*);$/.exec(line))) {
+                class_.fields.push({ name: m[1], ty: m[2] });
+            }
+            continue;
+        }
+        let m = /^export function (\w+) ?\((.*)\): (.*);$/.exec(line);
+        if (m) {
+            functions.push({ name: m[1], params: params(m[2]), ret: m[3] });
+            continue;
+        }
+        m = /^export class (\w+) \{/.exec(line);
+        if (m) {
+            class_ = { name: m[1], free: false, factories: [], fields: [] };
+            classes.push(class_);
+        }
+    }
+    return { functions, classes };
+}
+
+function sentinel(ty) {
+    ty = ty.replace(/ \| undefined$/, '');
+    switch (ty) {
+        case 'number': return 42;
+        case 'BigInt': return BigInt(42);
+        case 'string': return 'wasm-bindgen';
+        case 'boolean': return true;
+        case 'any': return { sentinel: true };
+        case 'any[]': return [{ sentinel: true }];
+        case 'Function': return function () {};
+    }
+    if (TYPED_ARRAYS.includes(ty)) {
+        return new global[ty]([1, 2, 3]);
+    }
+    throw new Error(`no sentinel value of type \`${ty}\``);
+}
+
+// Returns whether `value` matches `ty`, for the types this knows about.
+function matches(ty, value, exports) {
+    if (ty.endsWith(' | undefined')) {
+        return value === undefined || matches(ty.replace(/ \| undefined$/, ''), value, exports);
+    }
+    switch (ty) {
+        case 'void': return value === undefined;
+        case 'number': return typeof value === 'number';
+        case 'BigInt': return typeof value === 'bigint';
+        case 'string': return typeof value === 'string';
+        case 'boolean': return typeof value === 'boolean';
+        case 'Function': return typeof value === 'function';
+        case 'any': return true;
+        case 'any[]': return Array.isArray(value);
+    }
+    if (ty.startsWith('Promise<')) {
+        return value instanceof Promise;
+    }
+    if (TYPED_ARRAYS.includes(ty)) {
+        return value instanceof global[ty];
+    }
+    if (typeof exports[ty] === 'function') {
+        return value instanceof exports[ty];
+    }
+    return true;
+}
+
+// Returns whether `value` is what was read back after passing `sentinel`
+// of type `ty` to wasm. JS values are passed by reference, and everything
+// else by value.
+function roundTripped(ty, sentinel, value) {
+    ty = ty.replace(/ \| undefined$/, '');
+    if (ty === 'any[]') {
+        return Array.isArray(value)
+            && value.length === sentinel.length
+            && value.every((item, i) => item === sentinel[i]);
+    }
+    if (TYPED_ARRAYS.includes(ty)) {
+        return value instanceof global[ty]
+            && value.length === sentinel.length
+            && value.every((item, i) => item === sentinel[i]);
+    }
+    return value === sentinel;
+}
+
+// Classifies an exception thrown by the export or field `what`.
+function thrown(e, what) {
+    if (e instanceof WebAssembly.RuntimeError) {
+        return ['trapped', what, `${e.message}, the sentinel values may be invalid for it`];
+    }
+    const shim = e instanceof Error && IMPORT_SHIM.exec(e.stack || '');
+    if (shim) {
+        return ['skipped', what, `\`${shim[0]}\` failed under Node: ${e.message}`];
+    }
+    if (e instanceof Error) {
+        return ['FAILED', what, `${e.name}: ${e.message}`];
+    }
+    // Rust code may throw any JS value, like the error of a `Result`.
+    return ['ok', what, 'threw a JS value'];
+}
+
+function check(f, exports) {
+    const signature = `${f.name}(${f.params.map(p => p.ty).join(', ')}): ${f.ret}`;
+    if (typeof exports[f.name] !== 'function') {
+        return ['FAILED', signature, 'not exported by the glue'];
+    }
+    let args;
+    try {
+        args = f.params.map(p => sentinel(p.ty));
+    } catch (e) {
+        return ['skipped', signature, e.message];
+    }
+    let value;
+    try {
+        value = exports[f.name](...args);
+    } catch (e) {
+        return thrown(e, signature);
+    }
+    if (value instanceof Promise) {
+        value.catch(() => {});
+    }
+    if (!matches(f.ret, value, exports)) {
+        return ['FAILED', signature, `returned ${String(value)}, which isn't a \`${f.ret}\``];
+    }
+    return ['ok', signature, ''];
+}
+
+// Creates an instance of the class with the first of its constructor or
+// static functions returning it whose arguments all have sentinel values.
+function instance(class_, exports) {
+    for (const factory of class_.factories) {
+        let args;
+        try {
+            args = factory.params.map(p => sentinel(p.ty));
+        } catch (e) {
+            continue;
+        }
+        const what = `${class_.name}.${factory.name || 'constructor'}`;
+        try {
+            const value = factory.name === null
+                ? new exports[class_.name](...args)
+                : exports[class_.name][factory.name](...args);
+            return { value };
+        } catch (e) {
+            return { error: thrown(e, what) };
+        }
+    }
+    return null;
+}
+
+// Checks that the class is exported and that sentinel values written to its
+// fields are read back unchanged.
+function checkClass(class_, exports) {
+    const what = `class ${class_.name}`;
+    const constructor = exports[class_.name];
+    if (typeof constructor !== 'function') {
+        return [['FAILED', what, 'not exported by the glue']];
+    }
+    if (class_.free && typeof constructor.prototype.free !== 'function') {
+        return [['FAILED', what, 'has no `free` method']];
+    }
+    const results = [['ok', what, '']];
+    const fields = class_.fields.filter(field => {
+        try {
+            sentinel(field.ty);
+            return true;
+        } catch (e) {
+            return false;
+        }
+    });
+    if (fields.length === 0) {
+        return results;
+    }
+    const created = instance(class_, exports);
+    if (created === null) {
+        for (const field of fields) {
+            const signature = `${class_.name}.${field.name}: ${field.ty}`;
+            results.push(['skipped', signature, 'no way to create an instance with sentinel values']);
+        }
+        return results;
+    }
+    if (created.error) {
+        results.push(created.error);
+        return results;
+    }
+    const object = created.value;
+    for (const field of fields) {
+        const signature = `${class_.name}.${field.name}: ${field.ty}`;
+        const value = sentinel(field.ty);
+        try {
+            object[field.name] = value;
+            const read = object[field.name];
+            if (roundTripped(field.ty, value, read)) {
+                results.push(['ok', signature, 'round-tripped']);
+            } else {
+                results.push(['FAILED', signature, `wrote ${String(value)} but read back ${String(read)}`]);
+            }
+        } catch (e) {
+            results.push(thrown(e, signature));
+        }
+    }
+    if (class_.free) {
+        object.free();
+    }
+    return results;
+}
+
+async function main() {
+    const js = path.resolve(dir, `${stem}.js`);
+    const wasm = path.resolve(dir, `${stem}_bg.wasm`);
+    const dts = path.resolve(dir, `${stem}.d.ts`);
+    if (!fs.existsSync(dts)) {
+        throw new Error(`\`${dts}\` is required to know the types of the exports, \
+so the bindings must be generated without \`--no-typescript\``);
+    }
+
+    const { target, exports } = await load(js, wasm);
+    const { functions, classes } = declarations(fs.readFileSync(dts, 'utf8'));
+    const results = [];
+    for (const f of functions) {
+        if (target === 'web' || target === 'no-modules') {
+            if (INIT_FUNCTIONS.includes(f.name)) {
+                continue;
+            }
+        }
+        results.push(check(f, exports));
+    }
+    for (const class_ of classes) {
+        results.push(...checkClass(class_, exports));
+    }
+
+    for (const [status, what, note] of results) {
+        console.log(`${status.padEnd(7)} ${what}${note ? ` (${note})` : ''}`);
+    }
+    const failed = results.filter(([status]) => status === 'FAILED').length;
+    console.log(`\nverified the exports of \`${stem}\` (${target}): ${results.length} checks, ${failed} failed`);
+    if (failed > 0) {
+        process.exit(1);
+    }
+}
+
+main().catch(e => {
+    console.error(e);
+    process.exit(1);
+});
@@ -0,0 +1,67 @@
+//! Implementation of `wasm-bindgen verify`, which checks the bindings in an
+//! output directory before they're deployed.
+//!
+//! All the work is done by `verify.js` under Node: it loads the generated JS
+//! for whichever target it was generated for, instantiating the wasm file
+//! with stubs for the imports of JS modules it can't find, and then calls
+//! every export declared in the TypeScript definitions with sentinel values
+//! of their argument types, checking the type of what they return, and
+//! round-trips sentinel values through the fields of exported classes. This
+//! catches mismatches between the JS glue and the wasm file, like missing
+//! exports or imports, which would otherwise only show up at runtime.
+
+use failure::{bail, Error, ResultExt};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const VERIFY_JS: &str = include_str!("verify.js");
+
+pub fn run(out_dir: &Path) -> Result<(), Error> {
+    let entries = fs::read_dir(out_dir)
+        .with_context(|_| format!("failed to read `{}`", out_dir.display()))?;
+    let mut stems = Vec::new();
+    for entry in entries {
+        let name = entry?.file_name();
+        let name = match name.to_str() {
+            Some(name) => name,
+            None => continue,
+        };
+        if name.ends_with("_bg.wasm") {
+            stems.push(name[..name.len() - "_bg.wasm".len()].to_string());
+        }
+    }
+    stems.sort();
+    if stems.is_empty() {
+        bail!(
+            "no `*_bg.wasm` file found in `{}`, which should be an output \
+             directory of wasm-bindgen",
+            out_dir.display()
+        );
+    }
+
+    let mut failed = Vec::new();
+    for stem in stems {
+        if !out_dir.join(format!("{}.js", stem)).is_file() {
+            bail!(
+                "found `{0}_bg.wasm` but no `{0}.js`, note that verifying Node's \
+                 experimental modules isn't supported",
+                stem
+            );
+        }
+        let status = Command::new("node")
+            .arg("-e")
+            .arg(VERIFY_JS)
+            .arg(out_dir)
+            .arg(&stem)
+            .status()
+            .context("failed to execute `node`")?;
+        if !status.success() {
+            failed.push(stem);
+        }
+    }
+    if failed.len() > 0 {
+        bail!("failed to verify the bindings of `{}`", failed.join("`, `"));
+    }
+    Ok(())
+}
@@ -22,6 +22,8 @@ Options:
     --out-dir DIR           Directory to place ouptut in
     --typescript            Output a `*.d.ts` file next to the JS output
     --base64                Inline the wasm module using base64 encoding
+    --sync                  Instantiate the inlined wasm module synchronously,
+                            requires `--base64`
     --fetch PATH            Load module by passing the PATH argument to `fetch()`
 
 Note that this is not intended to produce a production-ready output module
@@ -35,6 +37,7 @@ struct Args {
     flag_out_dir: Option<PathBuf>,
     flag_typescript: bool,
     flag_base64: bool,
+    flag_sync: bool,
     flag_fetch: Option<String>,
     arg_input: PathBuf,
 }
@@ -60,6 +63,7 @@ fn rmain(args: &Args) -> Result<(), Error> {
 
     let object = wasm_bindgen_cli_support::wasm2es6js::Config::new()
         .base64(args.flag_base64)
+        .sync(args.flag_sync)
         .fetch(args.flag_fetch.clone())
         .generate(&wasm)?;
 
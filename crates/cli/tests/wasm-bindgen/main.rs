@@ -15,7 +15,7 @@ use assert_cmd::prelude::*;
 use predicates::str;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 fn target_dir() -> PathBuf {
@@ -151,3 +151,1797 @@ fn one_export_works() {
         .wasm_bindgen("");
     cmd.assert().success();
 }
+
+#[test]
+fn identical_inline_js_snippets_are_deduplicated() {
+    let (mut cmd, out_dir) = Project::new("identical_inline_js_snippets_are_deduplicated")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(inline_js = "export function foo() {}")]
+                extern "C" {
+                    fn foo();
+                }
+
+                #[wasm_bindgen(inline_js = "export function foo() {}")]
+                extern "C" {
+                    #[wasm_bindgen(js_name = foo)]
+                    fn foo2();
+                }
+
+                #[wasm_bindgen]
+                pub fn run() {
+                    foo();
+                    foo2();
+                }
+            "#,
+        )
+        .wasm_bindgen("");
+    cmd.assert().success();
+    let snippets = fs::read_dir(out_dir.join("snippets"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    // The name is the FNV-1a hash of the snippet, which doesn't depend on the
+    // toolchain `wasm-bindgen` was built with.
+    assert_eq!(snippets, ["inline-9cb4c60e61971032.js"]);
+}
+
+#[test]
+fn module_specifiers_can_be_rewritten() {
+    let (mut cmd, out_dir) = Project::new("module_specifiers_can_be_rewritten")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(raw_module = "./foo")]
+                extern "C" {
+                    fn foo();
+                }
+
+                #[wasm_bindgen(module = "some-pkg/sub")]
+                extern "C" {
+                    fn bar();
+                }
+
+                #[wasm_bindgen(raw_module = "../baz")]
+                extern "C" {
+                    fn baz();
+                }
+
+                #[wasm_bindgen]
+                pub fn run() {
+                    foo();
+                    bar();
+                    baz();
+                }
+            "#,
+        )
+        .wasm_bindgen("--module-extension js --rewrite-module ../baz=baz-pkg");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("module_specifiers_can_be_rewritten.js")).unwrap();
+    assert!(js.contains("from './foo.js'"));
+    assert!(js.contains("from 'some-pkg/sub'"));
+    assert!(js.contains("from 'baz-pkg'"));
+}
+
+#[test]
+fn raw_module_specifiers_can_be_prefixed() {
+    let (mut cmd, out_dir) = Project::new("raw_module_specifiers_can_be_prefixed")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(raw_module = "./foo.js")]
+                extern "C" {
+                    fn foo();
+                }
+
+                #[wasm_bindgen(raw_module = "../bar")]
+                extern "C" {
+                    fn bar();
+                }
+
+                #[wasm_bindgen(raw_module = "some-pkg")]
+                extern "C" {
+                    fn baz();
+                }
+
+                #[wasm_bindgen(raw_module = "./qux.js")]
+                extern "C" {
+                    fn qux();
+                }
+
+                #[wasm_bindgen]
+                pub fn run() {
+                    foo();
+                    bar();
+                    baz();
+                    qux();
+                }
+            "#,
+        )
+        .wasm_bindgen(
+            "--raw-module-prefix ../../src/ --module-extension js \
+             --rewrite-module ./qux.js=qux-pkg",
+        );
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("raw_module_specifiers_can_be_prefixed.js")).unwrap();
+    assert!(js.contains("from '../../src/foo.js'"));
+    assert!(js.contains("from '../../src/../bar.js'"));
+    assert!(js.contains("from 'some-pkg'"));
+    assert!(js.contains("from 'qux-pkg'"));
+}
+
+#[test]
+fn imported_modules_can_be_listed() {
+    let (mut cmd, out_dir) = Project::new("imported_modules_can_be_listed")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(module = "some-pkg/sub")]
+                extern "C" {
+                    fn foo();
+                }
+
+                #[wasm_bindgen(raw_module = "./bar.js")]
+                extern "C" {
+                    fn bar();
+                }
+
+                #[wasm_bindgen(inline_js = "export function baz() {}")]
+                extern "C" {
+                    fn baz();
+                }
+
+                #[wasm_bindgen]
+                pub fn run() {
+                    foo();
+                    bar();
+                    baz();
+                }
+            "#,
+        )
+        .wasm_bindgen("--list-imports --import-map-base https://cdn.example.com/npm/");
+    cmd.assert().success();
+    let list = fs::read_to_string(out_dir.join("imported_modules_can_be_listed_imports.json"));
+    let list: serde_json::Value = serde_json::from_str(&list.unwrap()).unwrap();
+    let list = list.as_array().unwrap();
+    assert_eq!(list.len(), 3);
+    assert_eq!(list[0]["specifier"], "./bar.js");
+    assert_eq!(list[0]["kind"], "raw_module");
+    assert!(list[1]["specifier"]
+        .as_str()
+        .unwrap()
+        .starts_with("./snippets/"));
+    assert_eq!(list[1]["kind"], "snippet");
+    assert_eq!(list[2]["specifier"], "some-pkg/sub");
+    assert_eq!(list[2]["kind"], "module");
+
+    let map = fs::read_to_string(out_dir.join("imported_modules_can_be_listed_import_map.json"));
+    let map: serde_json::Value = serde_json::from_str(&map.unwrap()).unwrap();
+    assert_eq!(
+        map,
+        serde_json::json!({
+            "imports": {
+                "some-pkg/sub": "https://cdn.example.com/npm/some-pkg/sub",
+            },
+        })
+    );
+}
+
+#[test]
+fn package_json_describes_output() {
+    let (mut cmd, out_dir) = Project::new("package_json_describes_output")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+                #[wasm_bindgen]
+                pub fn foo() {}
+            "#,
+        )
+        .wasm_bindgen("--package-json");
+    cmd.assert().success();
+    let json = fs::read_to_string(out_dir.join("package.json")).unwrap();
+    assert!(json.contains(r#""main": "./package_json_describes_output.js""#));
+    assert!(json.contains(r#""module": "./package_json_describes_output.js""#));
+    assert!(json.contains(r#""types": "./package_json_describes_output.d.ts""#));
+    assert!(json.contains(r#""sideEffects": false"#));
+    assert!(json.contains(r#""package_json_describes_output_bg.wasm""#));
+}
+
+#[test]
+fn package_json_keeps_side_effects() {
+    let (mut cmd, out_dir) = Project::new("package_json_keeps_side_effects")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(inline_js = "export function foo() {}")]
+                extern "C" {
+                    fn foo();
+                }
+
+                #[wasm_bindgen(start)]
+                pub fn main() {
+                    foo();
+                }
+            "#,
+        )
+        .wasm_bindgen("--package-json");
+    cmd.assert().success();
+    let json = fs::read_to_string(out_dir.join("package.json")).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        json["sideEffects"],
+        serde_json::json!(["./package_json_keeps_side_effects.js", "./snippets/*"])
+    );
+}
+
+#[test]
+fn import_memory_requires_web_or_no_modules() {
+    let (mut cmd, _out_dir) = Project::new("import_memory_requires_web_or_no_modules")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+                #[wasm_bindgen]
+                pub fn foo() {}
+            "#,
+        )
+        .wasm_bindgen("--import-memory");
+    cmd.assert()
+        .stderr(str::contains("importing memory is only supported"))
+        .failure();
+}
+
+#[test]
+fn jsdoc_annotates_output() {
+    let (mut cmd, out_dir) = Project::new("jsdoc_annotates_output")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub enum Color {
+                    Red,
+                    Green,
+                }
+
+                #[wasm_bindgen]
+                pub fn paint(color: Color, alpha: Option<f64>) {
+                    drop((color, alpha));
+                }
+            "#,
+        )
+        .wasm_bindgen("--target web --jsdoc");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("jsdoc_annotates_output.js")).unwrap();
+    assert!(js.contains("* @enum {number}"));
+    assert!(js.contains("* @param {number} color"));
+    assert!(js.contains("* @param {number} [alpha]"));
+    assert!(js.contains("* @returns {void}"));
+    assert!(js.contains("} InitInput"));
+    assert!(js.contains("* @param {InitInput} [module_or_path]"));
+}
+
+#[test]
+fn minify_internals_renames_helpers() {
+    let (mut cmd, out_dir) = Project::new("minify_internals_renames_helpers")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                extern "C" {
+                    fn alert(s: &str);
+                }
+
+                #[wasm_bindgen]
+                pub fn greet(name: &str) {
+                    alert(name);
+                }
+            "#,
+        )
+        .wasm_bindgen("--minify-internals");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("minify_internals_renames_helpers.js")).unwrap();
+    assert!(js.contains("export function greet(name)"));
+    assert!(!js.contains("passStringToWasm"));
+    assert!(!js.contains("getStringFromWasm"));
+    assert!(!js.contains("__wbg_alert"));
+    assert!(js.contains("export const $a = "));
+}
+
+#[test]
+fn unused_helpers_and_internal_exports_are_removed() {
+    let (mut cmd, out_dir) = Project::new("unused_helpers_and_internal_exports_are_removed")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+            "#,
+        )
+        .wasm_bindgen("--target web");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("unused_helpers_and_internal_exports_are_removed.js"))
+        .unwrap();
+    assert!(js.contains("export function add(a, b)"));
+    assert!(!js.contains("TextDecoder"));
+    assert!(!js.contains("getObject"));
+    assert!(!js.contains("__wbindgen_malloc"));
+    let wasm =
+        fs::read(out_dir.join("unused_helpers_and_internal_exports_are_removed_bg.wasm")).unwrap();
+    assert!(!wasm.windows(17).any(|w| w == b"__wbindgen_malloc"));
+}
+
+#[test]
+fn es2022_flavor_uses_private_fields() {
+    let (mut cmd, out_dir) = Project::new("es2022_flavor_uses_private_fields")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub struct Foo(u32);
+
+                #[wasm_bindgen]
+                impl Foo {
+                    #[wasm_bindgen(constructor)]
+                    pub fn new() -> Foo {
+                        Foo(0)
+                    }
+
+                    pub fn get(&self) -> u32 {
+                        self.0
+                    }
+                }
+
+                #[wasm_bindgen]
+                pub fn consume(foo: Foo) -> u32 {
+                    foo.0
+                }
+            "#,
+        )
+        .wasm_bindgen("--js-flavor es2022");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("es2022_flavor_uses_private_fields.js")).unwrap();
+    assert!(js.contains("#ptr = 0;"));
+    assert!(js.contains("static {"));
+    assert!(js.contains("if (arguments[0] === wrapToken) {"));
+    assert!(js.contains("const obj = new Foo(wrapToken, ptr);"));
+    assert!(js.contains("= FooPtr(foo, true);"));
+    assert!(!js.contains(".ptr"));
+}
+
+#[test]
+fn web_target_exports_init_sync() {
+    let (mut cmd, out_dir) = Project::new("web_target_exports_init_sync")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+                #[wasm_bindgen]
+                pub fn foo() {}
+            "#,
+        )
+        .wasm_bindgen("--target web");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("web_target_exports_init_sync.js")).unwrap();
+    assert!(js.contains("export { compile, instantiate, initSync };"));
+    assert!(js.contains("export default init;"));
+    assert!(js.contains("new URL('web_target_exports_init_sync_bg.wasm', import.meta.url)"));
+    assert!(js.contains("WebAssembly.instantiateStreaming(r, imports)"));
+    let ts = fs::read_to_string(out_dir.join("web_target_exports_init_sync.d.ts")).unwrap();
+    assert!(ts.contains("export function initSync("));
+    assert!(ts.contains("export function compile"));
+    assert!(ts.contains(
+        "export function instantiate(module: WebAssembly.Module, \
+         extra_imports?: WebAssembly.Imports)"
+    ));
+    assert!(ts.contains("export type InitInput = RequestInfo | URL | Response"));
+    assert!(ts.contains("export interface InitOutput {"));
+    assert!(ts.contains("  readonly memory: WebAssembly.Memory;"));
+    assert!(ts.contains("  readonly foo: () => void;"));
+    assert!(ts.contains(
+        "export default function init(module_or_path?: InitInput, \
+         extra_imports?: WebAssembly.Imports): Promise<InitOutput>;"
+    ));
+}
+
+#[test]
+fn no_modules_umd_wrapper() {
+    let (mut cmd, out_dir) = Project::new("no_modules_umd_wrapper")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+                #[wasm_bindgen]
+                pub fn foo() {}
+            "#,
+        )
+        .wasm_bindgen("--target no-modules --no-modules-global MyLib.wasm --no-modules-umd");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("no_modules_umd_wrapper.js")).unwrap();
+    assert!(js.starts_with("(function(root, factory) {"));
+    assert!(js.contains("define([], factory);"));
+    assert!(js.contains("module.exports = factory();"));
+    assert!(js.contains("['MyLib'].reduce("));
+    assert!(js.contains("__namespace.wasm = factory();"));
+    assert!(
+        js.contains("return Object.assign(init, { compile, instantiate, initSync }, __exports);")
+    );
+}
+
+#[test]
+fn no_modules_global_must_be_identifier() {
+    let (mut cmd, _out_dir) = Project::new("no_modules_global_must_be_identifier")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+                #[wasm_bindgen]
+                pub fn foo() {}
+            "#,
+        )
+        .wasm_bindgen("--target no-modules --no-modules-global my-lib");
+    cmd.assert()
+        .stderr(str::contains(
+            "is not a valid name for `--no-modules-global`",
+        ))
+        .failure();
+}
+
+#[test]
+fn lazy_entry_wraps_exports() {
+    let (mut cmd, out_dir) = Project::new("lazy_entry_wraps_exports")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+                #[wasm_bindgen]
+                pub fn foo() {}
+            "#,
+        )
+        .wasm_bindgen("--lazy-entry");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("lazy_entry_wraps_exports_lazy.js")).unwrap();
+    assert!(js.contains("import('./lazy_entry_wraps_exports.js')"));
+    assert!(js.contains("export function foo(...args)"));
+    assert!(out_dir.join("lazy_entry_wraps_exports_lazy.d.ts").exists());
+}
+
+#[test]
+fn lazy_entry_defines_enums() {
+    let (mut cmd, out_dir) = Project::new("lazy_entry_defines_enums")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub enum Color {
+                    Red,
+                    Green,
+                }
+
+                #[wasm_bindgen]
+                pub fn paint(color: Color) -> Color {
+                    color
+                }
+            "#,
+        )
+        .wasm_bindgen("--lazy-entry");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("lazy_entry_defines_enums_lazy.js")).unwrap();
+    assert!(js.contains("export const Color = Object.freeze({ Red:0,Green:1, });"));
+    assert!(js.contains("export function paint(...args)"));
+    let ts = fs::read_to_string(out_dir.join("lazy_entry_defines_enums_lazy.d.ts")).unwrap();
+    assert!(ts.contains("export type Color = import('./lazy_entry_defines_enums').Color;"));
+}
+
+#[test]
+fn lazy_entry_rejects_classes() {
+    let (mut cmd, _out_dir) = Project::new("lazy_entry_rejects_classes")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub struct Counter {}
+            "#,
+        )
+        .wasm_bindgen("--lazy-entry");
+    cmd.assert().failure().stderr(str::contains(
+        "cannot generate a lazy entry point exporting the class `Counter`",
+    ));
+}
+
+#[test]
+fn profile_bindings_reports_exports() {
+    let (mut cmd, out_dir) = Project::new("profile_bindings_reports_exports")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                extern "C" {
+                    fn alert(s: &str);
+                }
+
+                #[wasm_bindgen]
+                pub fn greet(name: &str) -> String {
+                    alert(name);
+                    name.to_string()
+                }
+            "#,
+        )
+        .wasm_bindgen("--profile-bindings");
+    cmd.assert().success();
+    let json =
+        fs::read_to_string(out_dir.join("profile_bindings_reports_exports_profile.json")).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(json["exports"]["greet"].as_u64().unwrap() > 0);
+    assert!(json["wasm_exports"]["greet"].as_u64().unwrap() > 0);
+    assert!(json["wasm_total"].as_u64().unwrap() > 0);
+    let imports = json["wasm_imports"].as_object().unwrap();
+    assert!(imports.keys().any(|name| name.starts_with("__wbg_alert_")));
+
+    let md =
+        fs::read_to_string(out_dir.join("profile_bindings_reports_exports_profile.md")).unwrap();
+    assert!(md.starts_with("# Bindings profile\n"));
+    assert!(md.contains("## Exports (JS bytes)\n"));
+    assert!(md.contains("## Exports (wasm bytes)\n"));
+    assert!(md.contains("## Imports (wasm bytes)\n"));
+    assert!(md.contains("| `greet` | "));
+}
+
+#[test]
+fn deprecated_exports_are_documented() {
+    let (mut cmd, out_dir) = Project::new("deprecated_exports_are_documented")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                #[deprecated(note = "use `Point` instead")]
+                pub struct Pair {
+                    pub x: u32,
+                }
+
+                /// Adds one.
+                #[wasm_bindgen]
+                #[deprecated]
+                pub fn increment(x: u32) -> u32 {
+                    x + 1
+                }
+            "#,
+        )
+        .wasm_bindgen("");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("deprecated_exports_are_documented.js")).unwrap();
+    assert!(js.contains("* @deprecated use `Point` instead\n"));
+    assert!(js.contains("* Adds one.\n* @deprecated\n"));
+    let ts = fs::read_to_string(out_dir.join("deprecated_exports_are_documented.d.ts")).unwrap();
+    assert!(ts.contains("* @deprecated use `Point` instead\n"));
+    assert!(ts.contains("* Adds one.\n* @deprecated\n"));
+}
+
+#[test]
+fn record_allocation_sites_in_debug_mode() {
+    let (mut cmd, out_dir) = Project::new("record_allocation_sites_in_debug_mode")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn sites() -> usize {
+                    wasm_bindgen::debug::allocation_sites().map_or(0, |s| s.len())
+                }
+
+                #[wasm_bindgen]
+                pub fn keep(x: JsValue) -> JsValue {
+                    x
+                }
+            "#,
+        )
+        .wasm_bindgen("--debug --record-allocation-sites");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("record_allocation_sites_in_debug_mode.js")).unwrap();
+    assert!(js.contains("heap_sites[idx] = new Error().stack;"));
+    assert!(js.contains("heap_sites[idx] = undefined;"));
+    assert!(js.contains("heap_sites.filter(site => site !== undefined)"));
+}
+
+#[test]
+fn link_to_copies_files() {
+    let (mut cmd, out_dir) = Project::new("link_to_copies_files")
+        .file("assets/style.css", "body { color: red; }\n")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn style() -> String {
+                    wasm_bindgen::link_to!("assets/style.css")
+                }
+            "#,
+        )
+        .wasm_bindgen("--target web");
+    cmd.assert().success();
+    let copied = fs::read_dir(&out_dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .find(|name| name.starts_with("style-") && name.ends_with(".css"))
+        .expect("linked file should be copied to the output directory");
+    let contents = fs::read_to_string(out_dir.join(&copied)).unwrap();
+    assert_eq!(contents, "body { color: red; }\n");
+    let js = fs::read_to_string(out_dir.join("link_to_copies_files.js")).unwrap();
+    assert!(js.contains(&format!("new URL('./{}', import.meta.url).href", copied)));
+}
+
+#[test]
+fn multiple_start_functions_are_allowed() {
+    let (mut cmd, out_dir) = Project::new("multiple_start_functions_are_allowed")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(start)]
+                pub fn first() {}
+
+                #[wasm_bindgen(start = -1)]
+                pub fn last() {}
+
+                #[wasm_bindgen(start = 10)]
+                pub fn before_first() {}
+            "#,
+        )
+        .wasm_bindgen("--target web");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("multiple_start_functions_are_allowed.js")).unwrap();
+    assert!(js.contains("wasm.__wbindgen_start();"));
+    assert!(!js.contains("__wbg_async_start"));
+}
+
+#[test]
+fn worker_entry_bootstraps_bindings() {
+    let (mut cmd, out_dir) = Project::new("worker_entry_bootstraps_bindings")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+                #[wasm_bindgen]
+                pub fn foo() {}
+            "#,
+        )
+        .wasm_bindgen("--target web --worker-entry");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("worker_entry_bootstraps_bindings.js")).unwrap();
+    assert!(js.contains("export { compile, instantiate, initSync, startWorker };"));
+    assert!(js.contains("new URL('./worker_entry_bootstraps_bindings_worker.js', import.meta.url)"));
+    assert!(js.contains("module: init.__wbindgen_wasm_module,"));
+    let worker =
+        fs::read_to_string(out_dir.join("worker_entry_bootstraps_bindings_worker.js")).unwrap();
+    assert!(worker.contains("import init from './worker_entry_bootstraps_bindings.js';"));
+    assert!(worker.contains("init(data.module)"));
+    let ts = fs::read_to_string(out_dir.join("worker_entry_bootstraps_bindings.d.ts")).unwrap();
+    assert!(ts.contains("export function startWorker<Send = any, Receive = any>"));
+}
+
+#[test]
+fn worker_entry_requires_web_or_no_modules() {
+    let (mut cmd, _out_dir) = Project::new("worker_entry_requires_web_or_no_modules")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+                #[wasm_bindgen]
+                pub fn foo() {}
+            "#,
+        )
+        .wasm_bindgen("--target nodejs --worker-entry");
+    cmd.assert()
+        .stderr(str::is_match("a worker entry point can only be generated").unwrap())
+        .failure();
+}
+
+#[test]
+fn debug_annotates_rejected_promises() {
+    let (mut cmd, out_dir) = Project::new("debug_annotates_rejected_promises")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn run(x: JsValue) -> JsValue {
+                    x
+                }
+
+                #[wasm_bindgen]
+                pub fn count() -> u32 {
+                    1
+                }
+            "#,
+        )
+        .wasm_bindgen("--debug --target nodejs");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("debug_annotates_rejected_promises.js")).unwrap();
+    assert!(js.contains("function annotateRejection(ret, name) {"));
+    assert!(js.contains("console_error_panic_hook"));
+    assert_eq!(js.matches("return annotateRejection(").count(), 1);
+
+    // `JsValue`s which aren't promises, even objects with a `then` property
+    // which isn't a function, are returned as is.
+    fs::write(
+        out_dir.join("test.js"),
+        r#"
+            const assert = require('assert');
+            const wasm = require('./debug_annotates_rejected_promises.js');
+
+            const plain = {};
+            assert.strictEqual(wasm.run(plain), plain);
+            const notThenable = { then: 1 };
+            assert.strictEqual(wasm.run(notThenable), notThenable);
+            assert.strictEqual(wasm.run(3), 3);
+            assert.strictEqual(wasm.run(null), null);
+
+            const trap = new WebAssembly.RuntimeError('unreachable');
+            wasm.run(Promise.reject(trap)).then(
+                () => assert.fail('the promise should be rejected'),
+                e => {
+                    assert.ok(/`(\w+::)*run`/.test(e.message), e.message);
+                    assert.ok(e.message.includes('console_error_panic_hook'));
+                    assert.strictEqual(e.cause, trap);
+                },
+            );
+        "#,
+    )
+    .unwrap();
+    Command::new("node")
+        .current_dir(&out_dir)
+        .arg("test.js")
+        .assert()
+        .success();
+}
+
+#[test]
+fn interface_types_without_js() {
+    let (mut cmd, out_dir) = Project::new("interface_types_without_js")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+            "#,
+        )
+        .wasm_bindgen("--interface-types");
+    cmd.assert().success();
+    assert!(out_dir.join("interface_types_without_js.wasm").exists());
+    assert!(!out_dir.join("interface_types_without_js.js").exists());
+}
+
+#[test]
+fn interface_types_reject_classes() {
+    let (mut cmd, _out_dir) = Project::new("interface_types_reject_classes")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub struct Counter(u32);
+
+                #[wasm_bindgen]
+                impl Counter {
+                    pub fn get(&self) -> u32 {
+                        self.0
+                    }
+                }
+            "#,
+        )
+        .wasm_bindgen("--interface-types");
+    cmd.assert()
+        .stderr(str::is_match("part of an exported class, which requires JS glue").unwrap())
+        .failure();
+}
+
+#[test]
+fn init_merges_extra_imports() {
+    let (mut cmd, out_dir) = Project::new("init_merges_extra_imports")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[link(wasm_import_module = "wasi_snapshot_preview1")]
+                extern "C" {
+                    fn sched_yield() -> u16;
+                }
+
+                #[wasm_bindgen]
+                pub fn yield_now() -> u16 {
+                    unsafe { sched_yield() }
+                }
+            "#,
+        )
+        .wasm_bindgen("--target web");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("init_merges_extra_imports.js")).unwrap();
+    assert!(js.contains("function __wbg_get_imports(extra_imports) {"));
+    assert!(js.contains("imports[name] = Object.assign(imports[name] || {}, extra_imports[name]);"));
+    assert!(js.contains("for (const name of [\"wasi_snapshot_preview1\"]) {"));
+    assert!(js.contains("function init(module, extra_imports) {"));
+    let ts = fs::read_to_string(out_dir.join("init_merges_extra_imports.d.ts")).unwrap();
+    assert!(ts.contains("extra_imports?: WebAssembly.Imports): Promise<InitOutput>;"));
+}
+
+#[test]
+fn nodejs_byte_slices_accept_buffers() {
+    let (mut cmd, out_dir) = Project::new("nodejs_byte_slices_accept_buffers")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn sum(bytes: &[u8], words: &[u16]) -> u32 {
+                    bytes.len() as u32 + words.len() as u32
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs");
+    cmd.assert().success();
+    let ts = fs::read_to_string(out_dir.join("nodejs_byte_slices_accept_buffers.d.ts")).unwrap();
+    assert!(ts.contains("sum(bytes: Buffer | Uint8Array, words: Uint16Array): number;"));
+}
+
+#[test]
+fn init_accepts_import_overrides() {
+    let (mut cmd, out_dir) = Project::new("init_accepts_import_overrides")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                extern "C" {
+                    fn alert(s: &str);
+                }
+
+                #[wasm_bindgen]
+                pub fn greet() {
+                    alert("hello");
+                }
+            "#,
+        )
+        .wasm_bindgen("--target no-modules");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("init_accepts_import_overrides.js")).unwrap();
+    assert!(js.contains("function __wbg_get_imports(extra_imports) {"));
+    assert!(js.contains("if (extra_imports !== undefined) {"));
+    assert!(js.contains("function initSync(module, extra_imports) {"));
+    assert!(!js.contains("whose imports must be passed"));
+}
+
+#[test]
+fn stdin_to_stdout_bundle() {
+    let wasm = Project::new("stdin_to_stdout_bundle")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn answer() -> u32 {
+                    42
+                }
+            "#,
+        )
+        .build();
+    let bundle = |format: &str| {
+        Command::cargo_bin("wasm-bindgen")
+            .unwrap()
+            .args(&["-", "--out-dir", "-", "--out-name", "answer"])
+            .args(&["--out-format", format])
+            .with_stdin()
+            .buffer(fs::read(&wasm).unwrap())
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone()
+    };
+
+    let json: serde_json::Value = serde_json::from_slice(&bundle("json")).unwrap();
+    let files = json["files"].as_array().unwrap();
+    let paths = files
+        .iter()
+        .map(|f| f["path"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        [
+            "answer.d.ts",
+            "answer.js",
+            "answer_bg.d.ts",
+            "answer_bg.wasm"
+        ]
+    );
+    assert_eq!(files[1]["encoding"], "utf-8");
+    assert!(files[1]["contents"]
+        .as_str()
+        .unwrap()
+        .contains("export function answer() {"));
+    assert_eq!(files[3]["encoding"], "base64");
+
+    let tar = bundle("tar");
+    assert_eq!(tar.len() % 512, 0);
+    assert!(tar.starts_with(b"answer.d.ts\0"));
+    assert!(tar.windows(14).any(|w| w == b"answer_bg.wasm"));
+}
+
+#[test]
+fn output_is_reproducible() {
+    let (mut cmd, out_dir) = Project::new("output_is_reproducible")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(inline_js = "export function call(f, x) { f(x); }")]
+                extern "C" {
+                    fn call(f: &Closure<dyn FnMut(u32)>, x: u32);
+                    #[wasm_bindgen(js_name = call)]
+                    fn call_str(f: &Closure<dyn FnMut(String)>, x: &str);
+                }
+
+                #[wasm_bindgen]
+                pub fn run() {
+                    let f = Closure::wrap(Box::new(|_| {}) as Box<dyn FnMut(u32)>);
+                    call(&f, 1);
+                    let g = Closure::wrap(Box::new(|_| {}) as Box<dyn FnMut(String)>);
+                    call_str(&g, "a");
+                }
+            "#,
+        )
+        .wasm_bindgen("--out-name app --debug");
+    fn read_all(dir: &Path, files: &mut Vec<(PathBuf, Vec<u8>)>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                read_all(&path, files);
+            } else {
+                files.push((path.clone(), fs::read(&path).unwrap()));
+            }
+        }
+    }
+
+    cmd.assert().success();
+    let mut first = Vec::new();
+    read_all(&out_dir, &mut first);
+    first.sort();
+    assert!(out_dir.join("app.js").exists());
+    assert!(out_dir.join("app_bg.wasm").exists());
+
+    fs::remove_dir_all(&out_dir).unwrap();
+    cmd.assert().success();
+    let mut second = Vec::new();
+    read_all(&out_dir, &mut second);
+    second.sort();
+    assert!(first == second, "output differs between runs");
+}
+
+#[test]
+fn links_several_modules() {
+    let linked = Project::new("links_several_modules_util")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn identity(x: JsValue) -> JsValue {
+                    x
+                }
+            "#,
+        )
+        .build();
+    let (mut cmd, out_dir) = Project::new("links_several_modules")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn duplicate(x: &JsValue) -> JsValue {
+                    x.clone()
+                }
+            "#,
+        )
+        .wasm_bindgen(&format!("--target bundler {}", linked.display()));
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("links_several_modules.js")).unwrap();
+    assert!(js.contains("from './links_several_modules_heap.js';"));
+    assert!(js.contains("export * from './links_several_modules_util.js';"));
+    assert!(!js.contains("const heap = new Array"));
+    let util = fs::read_to_string(out_dir.join("links_several_modules_util.js")).unwrap();
+    assert!(util.contains("from './links_several_modules_heap.js';"));
+    assert!(out_dir.join("links_several_modules_util_bg.wasm").exists());
+    let heap = fs::read_to_string(out_dir.join("links_several_modules_heap.js")).unwrap();
+    assert!(heap.contains("const heap = new Array"));
+    assert!(heap.contains("export {"));
+}
+
+#[test]
+fn linking_modules_with_the_same_exports_fails() {
+    let linked = Project::new("linking_same_exports_util")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn greet() {}
+
+                #[wasm_bindgen]
+                pub struct Counter;
+            "#,
+        )
+        .build();
+    let (mut cmd, _out_dir) = Project::new("linking_same_exports")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn greet() {}
+
+                #[wasm_bindgen]
+                pub fn farewell() {}
+            "#,
+        )
+        .wasm_bindgen(&format!("--target bundler {}", linked.display()));
+    cmd.assert().failure().stderr(str::contains(
+        "`greet` (exported by both `linking_same_exports` and `linking_same_exports_util`)",
+    ));
+}
+
+#[test]
+fn schema_mismatch_diagnostics() {
+    let wasm = Project::new("schema_mismatch_diagnostics")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+            "#,
+        )
+        .build();
+
+    // Pretend the wasm file was built with another version of wasm-bindgen,
+    // which still encodes bindings the same way.
+    let version = env!("CARGO_PKG_VERSION");
+    let other = version.replace(|c: char| c.is_ascii_digit(), "9");
+    let from = format!("\"schema_version\":\"{}\"", version);
+    let to = format!("\"schema_version\":\"{}\"", other);
+    let mut bytes = fs::read(&wasm).unwrap();
+    let mut patched = 0;
+    for i in 0..bytes.len() - from.len() {
+        if bytes[i..].starts_with(from.as_bytes()) {
+            bytes[i..i + to.len()].copy_from_slice(to.as_bytes());
+            patched += 1;
+        }
+    }
+    assert!(patched > 0);
+    let input = wasm.with_file_name("other_version.wasm");
+    fs::write(&input, &bytes).unwrap();
+
+    let out_dir = wasm.parent().unwrap().join("pkg");
+    Command::cargo_bin("wasm-bindgen")
+        .unwrap()
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg(&input)
+        .assert()
+        .stderr(str::contains(format!("--precise {}", version)))
+        .stderr(str::contains(format!("--version {}", other)))
+        .stderr(str::contains(input.display().to_string()))
+        .stderr(str::contains("passing\n`--schema-compat` may work"))
+        .failure();
+    Command::cargo_bin("wasm-bindgen")
+        .unwrap()
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg(&input)
+        .arg("--schema-compat")
+        .assert()
+        .success();
+    assert!(out_dir.join("other_version.js").exists());
+}
+
+#[test]
+fn verify_checks_generated_bindings() {
+    let (mut cmd, out_dir) = Project::new("verify_checks_generated_bindings")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+
+                #[wasm_bindgen]
+                pub fn greet(name: &str) -> String {
+                    format!("Hello, {}!", name)
+                }
+
+                #[wasm_bindgen]
+                pub struct Counter {
+                    pub count: u32,
+                    pub enabled: bool,
+                }
+
+                #[wasm_bindgen]
+                impl Counter {
+                    #[wasm_bindgen(constructor)]
+                    pub fn new(count: u32) -> Counter {
+                        Counter { count, enabled: false }
+                    }
+
+                    pub fn get(&self) -> u32 {
+                        self.count
+                    }
+                }
+
+                #[wasm_bindgen(no_free)]
+                pub struct Singleton {
+                    pub id: u32,
+                }
+            "#,
+        )
+        .wasm_bindgen("");
+    cmd.assert().success();
+    Command::cargo_bin("wasm-bindgen")
+        .unwrap()
+        .arg("verify")
+        .arg(&out_dir)
+        .assert()
+        .stdout(str::contains("ok      add(number, number): number"))
+        .stdout(str::contains("ok      greet(string): string"))
+        .stdout(str::contains("ok      class Counter"))
+        .stdout(str::contains("ok      Counter.count: number"))
+        .stdout(str::contains("ok      Counter.enabled: boolean"))
+        .stdout(str::contains("ok      class Singleton"))
+        .stdout(str::contains("skipped Singleton.id: number"))
+        .success();
+
+    // Break the glue by making it call an export the wasm file doesn't have.
+    let js_path = out_dir.join("verify_checks_generated_bindings.js");
+    let js = fs::read_to_string(&js_path).unwrap();
+    fs::write(&js_path, js.replace("wasm.add(", "wasm.sub(")).unwrap();
+    Command::cargo_bin("wasm-bindgen")
+        .unwrap()
+        .arg("verify")
+        .arg(&out_dir)
+        .assert()
+        .stdout(str::contains("FAILED  add(number, number): number"))
+        .failure();
+}
+
+#[test]
+fn demangle_exports_into_namespaces() {
+    let (mut cmd, out_dir) = Project::new("demangle_exports_into_namespaces")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn version() -> u32 {
+                    1
+                }
+
+                pub mod math {
+                    pub mod vectors {
+                        use wasm_bindgen::prelude::*;
+
+                        #[wasm_bindgen]
+                        pub fn vectors_add(a: u32, b: u32) -> u32 {
+                            a + b
+                        }
+
+                        #[wasm_bindgen]
+                        pub fn dot(a: u32, b: u32) -> u32 {
+                            a * b
+                        }
+                    }
+                }
+            "#,
+        )
+        .wasm_bindgen("--target bundler --demangle-exports");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("demangle_exports_into_namespaces.js")).unwrap();
+    assert!(js.contains("export function version("));
+    assert!(js.contains("function math_vectors_add("));
+    assert!(js.contains("export const math = {"));
+    assert!(js.contains("add: math_vectors_add,"));
+    assert!(js.contains("dot: math_vectors_dot,"));
+    let ts = fs::read_to_string(out_dir.join("demangle_exports_into_namespaces.d.ts")).unwrap();
+    assert!(ts.contains("export namespace math {"));
+    assert!(ts.contains("export function add(a: number, b: number): number;"));
+}
+
+#[test]
+fn demangle_exports_needs_name_section() {
+    let mut project = Project::new("demangle_exports_needs_name_section");
+    project.file(
+        "src/lib.rs",
+        r#"
+            pub mod math {
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+            }
+        "#,
+    );
+    let (mut cmd, out_dir) = project.wasm_bindgen("");
+    cmd.assert().success();
+
+    // The functions only used by `--demangle-exports` are always stripped.
+    let wasm = fs::read(out_dir.join("demangle_exports_needs_name_section_bg.wasm")).unwrap();
+    let marker = b"__wbindgen_module_path_";
+    assert!(!wasm.windows(marker.len()).any(|w| w == marker));
+
+    let wasm = fs::read(project.build()).unwrap();
+    let mut stripped = wasm[..8].to_vec();
+    for (id, name, mut data) in sections(&wasm) {
+        if name == "name" {
+            continue;
+        }
+        if id == 0 {
+            let mut prefix = Vec::new();
+            leb128(&mut prefix, name.len());
+            prefix.extend_from_slice(name.as_bytes());
+            data.splice(0..0, prefix);
+        }
+        stripped.push(id);
+        leb128(&mut stripped, data.len());
+        stripped.extend_from_slice(&data);
+    }
+    let input = project.root.join("stripped.wasm");
+    fs::write(&input, stripped).unwrap();
+    Command::cargo_bin("wasm-bindgen")
+        .unwrap()
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg(&input)
+        .arg("--demangle-exports")
+        .assert()
+        .stderr(str::contains("needs the `name` section"))
+        .failure();
+}
+
+#[test]
+fn js_namespace_on_exports() {
+    let (mut cmd, out_dir) = Project::new("js_namespace_on_exports")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen(js_namespace = MyLib.debug)]
+                pub fn dump_state() -> u32 {
+                    0
+                }
+
+                #[wasm_bindgen(js_namespace = MyLib)]
+                pub struct Renderer {}
+
+                #[wasm_bindgen]
+                impl Renderer {
+                    #[wasm_bindgen(constructor)]
+                    pub fn new() -> Renderer {
+                        Renderer {}
+                    }
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("js_namespace_on_exports.js")).unwrap();
+    assert!(js.contains("function MyLib_debug_dump_state("));
+    assert!(js.contains("module.exports.MyLib = {"));
+    assert!(js.contains("Renderer: Renderer,"));
+    assert!(js.contains("dump_state: MyLib_debug_dump_state,"));
+    assert!(!js.contains("module.exports.Renderer"));
+    let ts = fs::read_to_string(out_dir.join("js_namespace_on_exports.d.ts")).unwrap();
+    assert!(ts.contains("export namespace MyLib {"));
+    assert!(ts.contains("  export class Renderer {"));
+    assert!(ts.contains("    export function dump_state(): number;"));
+}
+
+#[test]
+fn cfg_gated_exports() {
+    let (mut cmd, out_dir) = Project::new("cfg_gated_exports")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                /// Always there.
+                #[wasm_bindgen]
+                #[cfg(all())]
+                pub fn compiled() {}
+
+                #[wasm_bindgen]
+                #[cfg(any())]
+                pub fn compiled_out() {}
+
+                #[wasm_bindgen]
+                pub struct Counter {}
+
+                #[wasm_bindgen]
+                impl Counter {
+                    #[cfg(any())]
+                    pub fn reset(&mut self) {}
+                }
+            "#,
+        )
+        .wasm_bindgen("--cfg-manifest");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("cfg_gated_exports.js")).unwrap();
+    assert!(js.contains("* Only available with `cfg(all())`."));
+    assert!(js.contains("export function compiled("));
+    assert!(!js.contains("export function compiled_out("));
+    assert!(!js.contains("reset("));
+    let ts = fs::read_to_string(out_dir.join("cfg_gated_exports.d.ts")).unwrap();
+    assert!(ts.contains("// `compiled_out` isn't available, it's only compiled with `cfg(any())`"));
+    assert!(ts.contains("// `Counter.reset` isn't available, it's only compiled with `cfg(any())`"));
+
+    let manifest = fs::read_to_string(out_dir.join("cfg_gated_exports_cfg.json"));
+    let manifest: serde_json::Value = serde_json::from_str(&manifest.unwrap()).unwrap();
+    assert_eq!(
+        manifest,
+        serde_json::json!({
+            "enabled": ["all()"],
+            "disabled": ["any()"],
+        })
+    );
+}
+
+/// Returns the ids and contents of the sections of `wasm`, along with the
+/// names of custom sections, which are excluded from their contents.
+fn sections(wasm: &[u8]) -> Vec<(u8, String, Vec<u8>)> {
+    fn leb128(wasm: &[u8], pos: &mut usize) -> usize {
+        let mut ret = 0;
+        let mut shift = 0;
+        loop {
+            let byte = wasm[*pos];
+            *pos += 1;
+            ret |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return ret;
+            }
+            shift += 7;
+        }
+    }
+
+    assert_eq!(&wasm[..8], b"\0asm\x01\0\0\0");
+    let mut sections = Vec::new();
+    let mut pos = 8;
+    while pos < wasm.len() {
+        let id = wasm[pos];
+        pos += 1;
+        let len = leb128(wasm, &mut pos);
+        let end = pos + len;
+        let mut name = String::new();
+        if id == 0 {
+            let name_len = leb128(wasm, &mut pos);
+            name = String::from_utf8(wasm[pos..pos + name_len].to_vec()).unwrap();
+            pos += name_len;
+        }
+        sections.push((id, name, wasm[pos..end].to_vec()));
+        pos = end;
+    }
+    sections
+}
+
+/// Appends `n` encoded as an unsigned LEB128 to `dst`.
+fn leb128(dst: &mut Vec<u8>, mut n: usize) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            dst.push(byte);
+            return;
+        }
+        dst.push(byte | 0x80);
+    }
+}
+
+/// Returns the names and contents of the custom sections of `wasm`.
+fn custom_sections(wasm: &[u8]) -> Vec<(String, Vec<u8>)> {
+    sections(wasm)
+        .into_iter()
+        .filter(|(id, _, _)| *id == 0)
+        .map(|(_, name, data)| (name, data))
+        .collect()
+}
+
+#[test]
+fn split_debug_info_round_trips_dwarf() {
+    let (mut cmd, out_dir) = Project::new("split_debug_info_round_trips_dwarf")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+            "#,
+        )
+        .wasm_bindgen("--keep-debug --split-debug-info");
+    cmd.assert().success();
+
+    let input = target_dir()
+        .join("wasm32-unknown-unknown")
+        .join("debug")
+        .join("split_debug_info_round_trips_dwarf.wasm");
+    let input = custom_sections(&fs::read(input).unwrap());
+    let wasm = fs::read(out_dir.join("split_debug_info_round_trips_dwarf_bg.wasm")).unwrap();
+    let debug = fs::read(out_dir.join("split_debug_info_round_trips_dwarf_bg.debug.wasm"));
+    let debug = debug.unwrap();
+
+    // The debug file is a copy of the main file, with the same code section
+    // for the DWARF code offsets to refer to.
+    let code = |wasm: &[u8]| sections(wasm).into_iter().find(|(id, _, _)| *id == 10);
+    assert!(code(&wasm).is_some());
+    assert_eq!(code(&wasm), code(&debug));
+    let wasm = custom_sections(&wasm);
+    let debug = custom_sections(&debug);
+
+    // All DWARF sections of the input are moved to the debug file as is.
+    let dwarf = input
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(".debug_"))
+        .collect::<Vec<_>>();
+    assert!(dwarf.iter().any(|(name, _)| name == ".debug_info"));
+    assert!(dwarf.iter().any(|(name, _)| name == ".debug_line"));
+    let debug_dwarf = debug.iter().filter(|(name, _)| name.starts_with(".debug_"));
+    assert_eq!(debug_dwarf.count(), dwarf.len());
+    for section in dwarf.iter() {
+        assert!(debug.contains(section), "missing {}", section.0);
+    }
+    assert!(!debug.iter().any(|(name, _)| name == "external_debug_info"));
+
+    // The main file only points at the debug file.
+    assert!(!wasm.iter().any(|(name, _)| name.starts_with(".debug_")));
+    let url = "split_debug_info_round_trips_dwarf_bg.debug.wasm";
+    let mut external = vec![url.len() as u8];
+    external.extend_from_slice(url.as_bytes());
+    assert!(wasm.contains(&("external_debug_info".to_string(), external)));
+}
+
+#[test]
+fn performance_marks_web() {
+    let (mut cmd, out_dir) = Project::new("performance_marks_web")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+            "#,
+        )
+        .wasm_bindgen("--target web --performance-marks --debug");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("performance_marks_web.js")).unwrap();
+    assert!(js.contains("function getPerformance()"));
+    assert!(!js.contains("performance.mark("));
+    assert!(js.contains("markStart('compile');"));
+    assert!(js.contains("markEnd('compile');"));
+    assert!(js.contains("markStart('instantiate');"));
+    assert!(js.contains("markEnd('instantiate');"));
+    assert!(js.contains("return compile(module).then(module => instantiate(module"));
+    assert!(js.contains("markFirstCall();"));
+    assert!(js.contains("countCall("));
+    assert!(js.contains("export function __wbindgen_call_counts("));
+}
+
+#[test]
+fn performance_marks_nodejs() {
+    let (mut cmd, out_dir) = Project::new("performance_marks_nodejs")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs --performance-marks");
+    cmd.assert().success();
+    let js = fs::read_to_string(out_dir.join("performance_marks_nodejs.js")).unwrap();
+    assert!(js.contains("markFirstCall();"));
+    assert!(js.contains("require('perf_hooks').performance"));
+    assert!(!js.contains("performance.mark("));
+    let shim = fs::read_to_string(out_dir.join("performance_marks_nodejs_bg.js")).unwrap();
+    assert!(shim.contains("require('perf_hooks').performance"));
+    let compile_start = shim.find("markStart('compile');").unwrap();
+    let compile_end = shim.find("markEnd('compile');").unwrap();
+    let instantiate_start = shim.find("markStart('instantiate');").unwrap();
+    let instantiate_end = shim.find("markEnd('instantiate');").unwrap();
+    assert!(compile_start < compile_end);
+    assert!(compile_end < instantiate_start);
+    assert!(instantiate_start < instantiate_end);
+}
+
+#[test]
+fn panic_exceptions_poison_the_instance() {
+    let (mut cmd, out_dir) = Project::new("panic_exceptions_poison_the_instance")
+        .file(
+            "Cargo.toml",
+            &format!(
+                "
+                    [package]
+                    name = \"panic_exceptions_poison_the_instance\"
+                    authors = []
+                    version = \"1.0.0\"
+                    edition = '2018'
+
+                    [dependencies]
+                    wasm-bindgen = {{ path = '{}', features = ['panic-exceptions'] }}
+
+                    [lib]
+                    crate-type = ['cdylib']
+
+                    [workspace]
+                ",
+                repo_root().display(),
+            ),
+        )
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn check(i: u32) -> u32 {
+                    if i > 2 {
+                        panic!("index {} is too large", i);
+                    }
+                    i
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs");
+    cmd.assert().success();
+    fs::write(
+        out_dir.join("test.js"),
+        r#"
+            const assert = require('assert');
+            const wasm = require('./panic_exceptions_poison_the_instance.js');
+
+            assert.strictEqual(wasm.check(1), 1);
+            let error;
+            assert.throws(() => wasm.check(3), e => (error = e) instanceof Error);
+            assert.strictEqual(error.name, 'PanicError');
+            assert.strictEqual(error.message, 'index 3 is too large');
+            assert.ok(/^src\/lib\.rs:\d+:\d+$/.test(error.location), error.location);
+            assert.strictEqual(error.poisoned, true);
+
+            // Later calls throw the same error without running any Rust code.
+            assert.throws(() => wasm.check(1), e => e === error);
+        "#,
+    )
+    .unwrap();
+    Command::new("node")
+        .current_dir(&out_dir)
+        .arg("test.js")
+        .assert()
+        .success();
+}
+
+#[test]
+fn anyref_heap_stats() {
+    let (mut cmd, out_dir) = Project::new("anyref_heap_stats")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                extern "C" {
+                    fn call_with(f: &Closure<dyn Fn(JsValue) -> JsValue>, x: &JsValue) -> JsValue;
+                }
+
+                #[wasm_bindgen]
+                pub fn heap_stats(count: u32) -> Vec<u32> {
+                    let before = wasm_bindgen::debug::heap_stats();
+                    let values = (0..count)
+                        .map(|i| JsValue::from_f64(i as f64))
+                        .collect::<Vec<_>>();
+                    let during = wasm_bindgen::debug::heap_stats();
+                    drop(values);
+                    let after = wasm_bindgen::debug::heap_stats();
+                    vec![before.live, during.live, during.capacity, after.live]
+                }
+
+                #[wasm_bindgen]
+                pub fn call_closure(x: JsValue) -> JsValue {
+                    let f = Closure::wrap(Box::new(|x: JsValue| x) as Box<dyn Fn(JsValue) -> JsValue>);
+                    call_with(&f, &x)
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs");
+    cmd.env("WASM_BINDGEN_ANYREF", "1").assert().success();
+
+    // With the anyref pass JS values, including the ones passed to closures,
+    // live in the wasm table, so there's no JS heap, and the statistics'
+    // implementations are called directly from wasm rather than through JS.
+    let js = fs::read_to_string(out_dir.join("anyref_heap_stats.js")).unwrap();
+    assert!(!js.contains("const heap"));
+    assert!(!js.contains("getObject"));
+    assert!(!js.contains("takeObject"));
+    assert!(!js.contains("__wbindgen_anyref_heap_live_count"));
+    assert!(!js.contains("__wbindgen_heap_capacity"));
+
+    fs::write(
+        out_dir.join("test.js"),
+        r#"
+            const assert = require('assert');
+            global.call_with = (f, x) => f(x);
+            const wasm = require('./anyref_heap_stats.js');
+
+            const [before, live, capacity, after] = wasm.heap_stats(10);
+            assert.strictEqual(live, before + 10);
+            assert.ok(capacity >= live);
+            assert.strictEqual(after, before);
+
+            const obj = {};
+            assert.strictEqual(wasm.call_closure(obj), obj);
+            assert.strictEqual(wasm.heap_stats(0)[0], before);
+        "#,
+    )
+    .unwrap();
+    Command::new("node")
+        .current_dir(&out_dir)
+        .arg("test.js")
+        .assert()
+        .success();
+}
+
+#[test]
+fn anyref_heap_stats_without_anyrefs() {
+    let (mut cmd, out_dir) = Project::new("anyref_heap_stats_without_anyrefs")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                pub fn heap_live_count() -> u32 {
+                    wasm_bindgen::debug::heap_stats().live
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs");
+    cmd.env("WASM_BINDGEN_ANYREF", "1").assert().success();
+
+    // No `anyref` values cross the boundary here so the anyref pass leaves
+    // the module alone, and JS calls back into wasm for the statistics.
+    let js = fs::read_to_string(out_dir.join("anyref_heap_stats_without_anyrefs.js")).unwrap();
+    assert!(js.contains("__wbindgen_anyref_heap_live_count_impl"));
+
+    fs::write(
+        out_dir.join("test.js"),
+        r#"
+            const assert = require('assert');
+            const wasm = require('./anyref_heap_stats_without_anyrefs.js');
+
+            assert.strictEqual(wasm.heap_live_count(), 0);
+        "#,
+    )
+    .unwrap();
+    Command::new("node")
+        .current_dir(&out_dir)
+        .arg("test.js")
+        .assert()
+        .success();
+}
+
+#[test]
+fn missing_import_is_described() {
+    let (mut cmd, out_dir) = Project::new("missing_import_is_described")
+        .file(
+            "src/lib.rs",
+            r#"
+                use wasm_bindgen::prelude::*;
+
+                #[wasm_bindgen]
+                extern "C" {
+                    fn alert(s: &str);
+                }
+
+                #[wasm_bindgen]
+                pub fn greet(name: &str) {
+                    alert(name);
+                }
+            "#,
+        )
+        .wasm_bindgen("--target nodejs --debug");
+    cmd.assert().success();
+
+    // Rename the definition of the import in the generated JS so it's no
+    // longer provided when the wasm module is instantiated.
+    let js_path = out_dir.join("missing_import_is_described.js");
+    let js = fs::read_to_string(&js_path).unwrap();
+    assert!(js.contains("module.exports.__wbg_alert_"));
+    let js = js.replace(
+        "module.exports.__wbg_alert_",
+        "module.exports.__wbg_renamed_",
+    );
+    fs::write(&js_path, js).unwrap();
+
+    Command::new("node")
+        .current_dir(&out_dir)
+        .arg("-e")
+        .arg("require('./missing_import_is_described.js')")
+        .assert()
+        .failure()
+        .stderr(str::contains("LinkError"))
+        .stderr(str::contains("the following imports were not provided:"))
+        .stderr(str::contains(
+            "* `./missing_import_is_described.js.__wbg_alert_",
+        ))
+        .stderr(str::contains(
+            "likely from a `#[wasm_bindgen]` extern block importing `alert`",
+        ));
+}
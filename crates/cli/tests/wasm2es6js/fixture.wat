@@ -0,0 +1,17 @@
+;; The source of `fixture.wasm`, which can be regenerated with
+;; `wat2wasm fixture.wat`.
+(module
+  (import "./imports.mjs" "log" (func $log (param i32)))
+  (import "env" "memory" (memory 1 2))
+  (import "env" "table" (table 1 anyfunc))
+  (global $answer i32 (i32.const 42))
+  (func $add (export "add") (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.add)
+  (func (export "log_sum") (param i32 i32)
+    local.get 0
+    local.get 1
+    call $add
+    call $log)
+  (export "answer" (global $answer)))
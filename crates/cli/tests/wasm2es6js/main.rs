@@ -0,0 +1,171 @@
+//! Tests for the `wasm2es6js` binary, run on `fixture.wasm` whose source is
+//! `fixture.wat`.
+//!
+//! The fixture imports a function from a JS module along with a memory and a
+//! table, which `wasm2es6js` defines itself, and exports functions and a
+//! global. The generated JS is run with node to check that it works.
+
+use assert_cmd::prelude::*;
+use predicates::str;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn out_dir(name: &str) -> PathBuf {
+    let mut dir = PathBuf::from(env::current_exe().unwrap());
+    dir.pop(); // current exe
+    if dir.ends_with("deps") {
+        dir.pop();
+    }
+    let dir = dir.join("wasm2es6js-tests").join(name);
+    drop(fs::remove_dir_all(&dir));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs `wasm2es6js` on the fixture with `args`, returning the output
+/// directory along with the generated JS and TypeScript.
+fn wasm2es6js(name: &str, args: &str) -> (PathBuf, String, String) {
+    let out_dir = out_dir(name);
+    Command::cargo_bin("wasm2es6js")
+        .unwrap()
+        .arg("--typescript")
+        .args(args.split_whitespace())
+        .arg("-o")
+        .arg(out_dir.join("fixture.js"))
+        .arg(
+            env::current_dir()
+                .unwrap()
+                .join("tests/wasm2es6js/fixture.wasm"),
+        )
+        .assert()
+        .success();
+    let js = fs::read_to_string(out_dir.join("fixture.js")).unwrap();
+    let ts = fs::read_to_string(out_dir.join("fixture.d.ts")).unwrap();
+    (out_dir, js, ts)
+}
+
+/// Runs `test`, an ES module importing the generated JS, in `out_dir`.
+fn run(out_dir: &PathBuf, js: &str, test: &str) {
+    // Node only treats files ending in `.mjs` as ES modules.
+    fs::write(out_dir.join("fixture.mjs"), js).unwrap();
+    fs::write(
+        out_dir.join("imports.mjs"),
+        "
+            export let logged = null;
+            export function log(x) {
+                logged = x;
+            }
+        ",
+    )
+    .unwrap();
+    fs::write(out_dir.join("test.mjs"), test).unwrap();
+    Command::new("node")
+        .current_dir(out_dir)
+        .arg("test.mjs")
+        .assert()
+        .success();
+}
+
+fn assert_typed_exports(ts: &str) {
+    assert!(ts.contains("export function add(a: number, b: number): number;\n"));
+    assert!(ts.contains("export function log_sum(a: number, b: number): void;\n"));
+    assert!(ts.contains("export const answer: WebAssembly.Global;\n"));
+    assert!(ts.contains("export const memory: WebAssembly.Memory;\n"));
+    assert!(ts.contains("export const table: WebAssembly.Table;\n"));
+    assert!(ts.contains("export const booted: Promise<boolean>;\n"));
+}
+
+fn assert_defined_imports(js: &str) {
+    assert!(
+        js.contains("export const memory = new WebAssembly.Memory({ initial: 1, maximum: 2 });")
+    );
+    assert!(js.contains(
+        "export const table = new WebAssembly.Table({ element: 'anyfunc', initial: 1 });"
+    ));
+    assert!(js.contains("import * as import_0 from './imports.mjs';"));
+    assert!(js.contains("'env': { memory, table }"));
+}
+
+const CHECK_EXPORTS: &str = "
+    assert.strictEqual(add(1, 2), 3);
+    log_sum(2, 3);
+    assert.strictEqual(logged, 5);
+    assert.strictEqual(answer.value, 42);
+    assert.ok(memory instanceof WebAssembly.Memory);
+    assert.strictEqual(memory.buffer.byteLength, 65536);
+    assert.ok(table instanceof WebAssembly.Table);
+    assert.strictEqual(table.length, 1);
+";
+
+#[test]
+fn async_output() {
+    let (out_dir, js, ts) = wasm2es6js("async_output", "--base64");
+    assert_typed_exports(&ts);
+    assert_defined_imports(&js);
+    assert!(js.contains("WebAssembly.instantiate(bytes,"));
+    assert!(!js.contains("new WebAssembly.Instance("));
+    run(
+        &out_dir,
+        &js,
+        &format!(
+            "
+                import assert from 'assert';
+                import {{ add, log_sum, answer, memory, table, booted }} from './fixture.mjs';
+                import {{ logged }} from './imports.mjs';
+
+                // Exports are only bound once the module is instantiated.
+                assert.strictEqual(add, undefined);
+                await booted;
+                {}
+            ",
+            CHECK_EXPORTS
+        ),
+    );
+}
+
+#[test]
+fn sync_output() {
+    let (out_dir, js, ts) = wasm2es6js("sync_output", "--base64 --sync");
+    assert_typed_exports(&ts);
+    assert_defined_imports(&js);
+    assert!(js.contains("new WebAssembly.Instance("));
+    assert!(!js.contains("WebAssembly.instantiate("));
+    run(
+        &out_dir,
+        &js,
+        &format!(
+            "
+                import assert from 'assert';
+                import {{ add, log_sum, answer, memory, table, booted }} from './fixture.mjs';
+                import {{ logged }} from './imports.mjs';
+
+                // Exports can be used as soon as the module is evaluated.
+                {}
+                assert.strictEqual(await booted, true);
+            ",
+            CHECK_EXPORTS
+        ),
+    );
+}
+
+#[test]
+fn sync_requires_base64() {
+    let out_dir = out_dir("sync_requires_base64");
+    Command::cargo_bin("wasm2es6js")
+        .unwrap()
+        .arg("--sync")
+        .arg("--fetch")
+        .arg("fixture.wasm")
+        .arg("-o")
+        .arg(out_dir.join("fixture.js"))
+        .arg(
+            env::current_dir()
+                .unwrap()
+                .join("tests/wasm2es6js/fixture.wasm"),
+        )
+        .assert()
+        .failure()
+        .stderr(str::contains("--sync requires --base64"));
+}
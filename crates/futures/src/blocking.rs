@@ -0,0 +1,117 @@
+//! Blocking the current thread until a future completes.
+//!
+//! JavaScript can't block, so futures are normally driven by the event loop
+//! through `future_to_promise` and `spawn_local`. In a dedicated worker of a
+//! multithreaded module, however, code ported from native platforms often
+//! expects to be able to wait for a future to complete, for example one
+//! completed by another thread through a channel. The executor in this module
+//! does so by parking the worker with `Atomics.wait` while the future isn't
+//! ready, and waking it up with `Atomics.notify` when the future's task is
+//! notified.
+//!
+//! Note that the JS event loop doesn't run while the worker is blocked, so a
+//! future which can only make progress through it, like a `JsFuture` or one
+//! woken by a JS callback, never completes when passed to `block_on`.
+
+use std::sync::atomic::{AtomicI32, Ordering::SeqCst};
+use std::sync::Arc;
+
+use futures::executor::{self, Notify};
+use futures::prelude::*;
+use js_sys::{Atomics, Int32Array, WebAssembly};
+use wasm_bindgen::JsCast;
+
+const EMPTY: i32 = 0;
+const NOTIFIED: i32 = 1;
+
+/// Returns whether the current thread can be blocked with `block_on`.
+///
+/// Blocking requires the wasm memory to be shared, which is only the case in
+/// modules built with the `atomics` target feature, and is only allowed in
+/// some contexts: browsers typically allow it in workers but not on the main
+/// thread.
+pub fn can_block() -> bool {
+    // Waiting for a value other than the current one returns right away
+    // where waiting is allowed, and throws otherwise.
+    let probe = AtomicI32::new(EMPTY);
+    Atomics::wait(&memory_view(), index_of(&probe), NOTIFIED).is_ok()
+}
+
+/// Runs `future` to completion on the current thread, blocking it while the
+/// future isn't ready.
+///
+/// This is the equivalent of `Future::wait` for workers of a multithreaded
+/// module. The future is polled on the current thread, and the thread is put
+/// to sleep with `Atomics.wait` until the future's task is notified, which
+/// can happen from any thread.
+///
+/// # Panics
+///
+/// Panics if the future needs to wait and blocking isn't allowed on the
+/// current thread, see `can_block`. Futures which are ready right away can be
+/// passed to `block_on` anywhere.
+pub fn block_on<F: Future>(future: F) -> Result<F::Item, F::Error> {
+    let parker = Arc::new(Parker::new());
+    let mut spawn = executor::spawn(future);
+    loop {
+        if let Async::Ready(value) = spawn.poll_future_notify(&parker, 0)? {
+            return Ok(value);
+        }
+        parker.park();
+    }
+}
+
+/// A flag on which a thread blocks until another thread, or itself, sets it.
+pub(crate) struct Parker {
+    state: AtomicI32,
+}
+
+impl Parker {
+    pub(crate) fn new() -> Parker {
+        Parker {
+            state: AtomicI32::new(EMPTY),
+        }
+    }
+
+    /// Blocks the current thread until `unpark` is called, or returns right
+    /// away if it was called since the last time this returned.
+    pub(crate) fn park(&self) {
+        while self.state.swap(EMPTY, SeqCst) != NOTIFIED {
+            // This returns right away if `unpark` was called since the swap
+            // above, otherwise it sleeps until the next call.
+            if let Err(e) = Atomics::wait(&memory_view(), index_of(&self.state), EMPTY) {
+                panic!(
+                    "cannot block the current thread, `Atomics.wait` is only \
+                     allowed with shared memory and off the main thread: {:?}",
+                    e
+                );
+            }
+        }
+    }
+
+    /// Wakes up the thread blocked in `park`, if any.
+    pub(crate) fn unpark(&self) {
+        if self.state.swap(NOTIFIED, SeqCst) == EMPTY {
+            // Without shared memory no other thread can be waiting, so the
+            // error is ignored.
+            drop(Atomics::notify(&memory_view(), index_of(&self.state), 1));
+        }
+    }
+}
+
+impl Notify for Parker {
+    fn notify(&self, _id: usize) {
+        self.unpark();
+    }
+}
+
+/// Returns a view of the whole wasm memory. It's created anew every time as
+/// views are detached when the memory grows.
+fn memory_view() -> Int32Array {
+    let memory = wasm_bindgen::memory().unchecked_into::<WebAssembly::Memory>();
+    Int32Array::new(&memory.buffer())
+}
+
+fn index_of(state: &AtomicI32) -> u32 {
+    state as *const AtomicI32 as u32 / 4
+}
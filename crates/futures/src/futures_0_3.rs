@@ -2,6 +2,7 @@ use std::cell::{Cell, RefCell};
 use std::collections::VecDeque;
 use std::fmt;
 use std::future::Future;
+use std::marker::PhantomData;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -13,8 +14,10 @@ use futures_util::task::ArcWake;
 
 use lazy_static::lazy_static;
 
+use crate::blocking::Parker;
 use js_sys::Promise;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 
 /// A Rust `Future` backed by a JavaScript `Promise`.
 ///
@@ -23,11 +26,26 @@ use wasm_bindgen::prelude::*;
 /// `futures` crate and will either succeed or fail depending on what happens
 /// with the JavaScript `Promise`.
 ///
-/// Currently this type is constructed with `JsFuture::from`.
+/// Currently this type is constructed with `JsFuture::from`, and
+/// `JsFuture::typed` converts it into a future resolving to a more specific
+/// type than `JsValue`.
 pub struct JsFuture {
     rx: oneshot::Receiver<Result<JsValue, JsValue>>,
 }
 
+impl JsFuture {
+    /// Converts this future into one which resolves to a `T`, failing with a
+    /// `TypeError` if the promise resolves to anything else.
+    ///
+    /// See `TypedJsFuture` for more details.
+    pub fn typed<T: JsCast>(self) -> TypedJsFuture<T> {
+        TypedJsFuture {
+            inner: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
 impl fmt::Debug for JsFuture {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "JsFuture {{ ... }}")
@@ -83,6 +101,45 @@ impl Future for JsFuture {
     }
 }
 
+/// A Rust `Future` backed by a JavaScript `Promise` which resolves to a `T`.
+///
+/// This is the Futures 0.3 version of
+/// [`TypedJsFuture`](../struct.TypedJsFuture.html), which saves a
+/// `dyn_into` after each awaited promise:
+///
+/// ```rust,ignore
+/// let text: js_sys::JsString = TypedJsFuture::from(promise).await?;
+/// ```
+///
+/// This type is constructed with `TypedJsFuture::from` or `JsFuture::typed`.
+pub struct TypedJsFuture<T> {
+    inner: JsFuture,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for TypedJsFuture<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TypedJsFuture {{ ... }}")
+    }
+}
+
+impl<T: JsCast> From<Promise> for TypedJsFuture<T> {
+    fn from(js: Promise) -> TypedJsFuture<T> {
+        JsFuture::from(js).typed()
+    }
+}
+
+impl<T: JsCast> Future for TypedJsFuture<T> {
+    type Output = Result<T, JsValue>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        match self.inner.poll_unpin(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(val) => Poll::Ready(val.and_then(crate::cast_resolved)),
+        }
+    }
+}
+
 /// Converts a Rust `Future` into a JavaScript `Promise`.
 ///
 /// This function will take any future in Rust and schedule it to be executed,
@@ -122,6 +179,48 @@ where
     })
 }
 
+/// Converts the future of an `async` `#[wasm_bindgen(start)]` function into
+/// the promise awaited by the JS initialization function.
+#[doc(hidden)]
+pub fn __wbindgen_start_to_promise<F>(future: F) -> JsValue
+where
+    F: Future<Output = ()> + 'static,
+{
+    future_to_promise(future.map(|()| Ok(JsValue::UNDEFINED))).into()
+}
+
+/// Runs `future` to completion on the current thread, blocking it while the
+/// future isn't ready.
+///
+/// This is the Futures 0.3 version of
+/// [`blocking::block_on`](../blocking/fn.block_on.html), see there for
+/// details.
+///
+/// # Panics
+///
+/// Panics if the future needs to wait and blocking isn't allowed on the
+/// current thread, see [`blocking::can_block`](../blocking/fn.can_block.html).
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unparker(Parker);
+
+    impl ArcWake for Unparker {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.unpark();
+        }
+    }
+
+    let unparker = Arc::new(Unparker(Parker::new()));
+    let waker = ArcWake::into_waker(unparker.clone());
+    let cx = &mut Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(value) = future.as_mut().poll(cx) {
+            return value;
+        }
+        unparker.0.park();
+    }
+}
+
 /// Runs a Rust `Future` on a local task queue.
 ///
 /// The `future` provided must adhere to `'static` because it'll be scheduled
@@ -13,7 +13,9 @@
 //!
 //!    A type that is constructed with a `Promise` and can then be used as a
 //!    `Future<Item = JsValue, Error = JsValue>`. This Rust future will resolve
-//!    or reject with the value coming out of the `Promise`.
+//!    or reject with the value coming out of the `Promise`. Its
+//!    [`TypedJsFuture`](./struct.TypedJsFuture.html) counterpart additionally
+//!    casts the resolved value to a specific type.
 //!
 //! 2. [**`future_to_promise`**](./fn.future_to_promise.html)
 //!
@@ -21,6 +23,11 @@
 //!    JavaScript `Promise`. The future's result will translate to either a
 //!    rejected or resolved `Promise` in JavaScript.
 //!
+//! Additionally the [`stream`](./stream/index.html) module bridges JavaScript
+//! `ReadableStream`s and Rust `Stream`s in both directions, and the
+//! [`blocking`](./blocking/index.html) module blocks workers of multithreaded
+//! modules on futures.
+//!
 //! These two items should provide enough of a bridge to interoperate the two
 //! systems and make sure that Rust/JavaScript can work together with
 //! asynchronous and I/O work.
@@ -107,8 +114,12 @@
 /// Contains a Futures 0.3 implementation of this crate.
 pub mod futures_0_3;
 
+pub mod blocking;
+pub mod stream;
+
 use std::cell::{Cell, RefCell};
 use std::fmt;
+use std::marker::PhantomData;
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -116,8 +127,9 @@ use futures::executor::{self, Notify, Spawn};
 use futures::future;
 use futures::prelude::*;
 use futures::sync::oneshot;
-use js_sys::{Function, Promise};
+use js_sys::{Function, Promise, TypeError};
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 
 /// A Rust `Future` backed by a JavaScript `Promise`.
 ///
@@ -126,11 +138,26 @@ use wasm_bindgen::prelude::*;
 /// `futures` crate and will either succeed or fail depending on what happens
 /// with the JavaScript `Promise`.
 ///
-/// Currently this type is constructed with `JsFuture::from`.
+/// Currently this type is constructed with `JsFuture::from`, and
+/// `JsFuture::typed` converts it into a future resolving to a more specific
+/// type than `JsValue`.
 pub struct JsFuture {
     rx: oneshot::Receiver<Result<JsValue, JsValue>>,
 }
 
+impl JsFuture {
+    /// Converts this future into one which resolves to a `T`, failing with a
+    /// `TypeError` if the promise resolves to anything else.
+    ///
+    /// See `TypedJsFuture` for more details.
+    pub fn typed<T: JsCast>(self) -> TypedJsFuture<T> {
+        TypedJsFuture {
+            inner: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
 impl fmt::Debug for JsFuture {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "JsFuture {{ ... }}")
@@ -200,6 +227,64 @@ impl Future for JsFuture {
     }
 }
 
+/// A Rust `Future` backed by a JavaScript `Promise` which resolves to a `T`.
+///
+/// This is the same as a `JsFuture` followed by a checked cast with
+/// `JsCast::dyn_into`: the future resolves to the value of the `Promise` cast
+/// to `T`, or fails with the value the `Promise` is rejected with. If the
+/// `Promise` resolves to a value which isn't a `T` the future fails with a
+/// JavaScript `TypeError`.
+///
+/// ```rust,no_run
+/// # use futures::Future;
+/// # use wasm_bindgen_futures::TypedJsFuture;
+/// # fn fetch_text(promise: js_sys::Promise) {
+/// let text = TypedJsFuture::<js_sys::JsString>::from(promise)
+///     .map(|text| String::from(text));
+/// # }
+/// ```
+///
+/// This type is constructed with `TypedJsFuture::from` or `JsFuture::typed`.
+pub struct TypedJsFuture<T> {
+    inner: JsFuture,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for TypedJsFuture<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TypedJsFuture {{ ... }}")
+    }
+}
+
+impl<T: JsCast> From<Promise> for TypedJsFuture<T> {
+    fn from(js: Promise) -> TypedJsFuture<T> {
+        JsFuture::from(js).typed()
+    }
+}
+
+impl<T: JsCast> Future for TypedJsFuture<T> {
+    type Item = T;
+    type Error = JsValue;
+
+    fn poll(&mut self) -> Poll<T, JsValue> {
+        match self.inner.poll()? {
+            Async::Ready(val) => cast_resolved(val).map(Async::Ready),
+            Async::NotReady => Ok(Async::NotReady),
+        }
+    }
+}
+
+// Casts the value a `Promise` resolved to for a `TypedJsFuture`.
+fn cast_resolved<T: JsCast>(val: JsValue) -> Result<T, JsValue> {
+    val.dyn_into::<T>().map_err(|val| {
+        TypeError::new(&format!(
+            "promise resolved to a value of an unexpected type: {:?}",
+            val
+        ))
+        .into()
+    })
+}
+
 /// Converts a Rust `Future` into a JavaScript `Promise`.
 ///
 /// This function will take any future in Rust and schedule it to be executed,
@@ -0,0 +1,241 @@
+//! Converting between JavaScript `ReadableStream`s and Rust `Stream`s.
+//!
+//! The [`JsStream`](./struct.JsStream.html) type reads chunks out of a
+//! JavaScript `ReadableStream` as a Rust
+//! `Stream<Item = JsValue, Error = JsValue>`, and
+//! [`stream_to_readable_stream`](./fn.stream_to_readable_stream.html) goes the
+//! other way around, backing a new JavaScript `ReadableStream` with a Rust
+//! `Stream`.
+//!
+//! Both of these work with plain `JsValue`s so they can be used with the
+//! `ReadableStream` type from `web-sys` as well as with values coming straight
+//! out of JavaScript.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use futures::prelude::*;
+use js_sys::{Object, Promise, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::{future_to_promise, JsFuture};
+
+#[wasm_bindgen]
+extern "C" {
+    type ReadableStream;
+
+    #[wasm_bindgen(constructor, catch)]
+    fn new(underlying_source: &Object) -> Result<ReadableStream, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = getReader)]
+    fn get_reader(this: &ReadableStream) -> Result<ReadableStreamDefaultReader, JsValue>;
+
+    type ReadableStreamDefaultReader;
+
+    #[wasm_bindgen(method)]
+    fn read(this: &ReadableStreamDefaultReader) -> Promise;
+
+    #[wasm_bindgen(method, catch, js_name = releaseLock)]
+    fn release_lock(this: &ReadableStreamDefaultReader) -> Result<(), JsValue>;
+
+    type ReadableStreamDefaultController;
+
+    #[wasm_bindgen(method, catch)]
+    fn enqueue(this: &ReadableStreamDefaultController, chunk: &JsValue) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn close(this: &ReadableStreamDefaultController) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn error(this: &ReadableStreamDefaultController, e: &JsValue) -> Result<(), JsValue>;
+}
+
+/// A Rust `Stream` backed by a JavaScript `ReadableStream`.
+///
+/// This type locks the `ReadableStream` it's constructed with by acquiring a
+/// default reader, and then yields each chunk read from it. The stream ends
+/// once JavaScript reports that it's done, and fails with the reason the
+/// JavaScript stream errored with.
+///
+/// If the `JsStream` is dropped while no read is in flight the reader's lock
+/// is released, so the `ReadableStream` can be read from again.
+pub struct JsStream {
+    reader: Option<ReadableStreamDefaultReader>,
+    next: Option<JsFuture>,
+}
+
+impl JsStream {
+    /// Creates a new `JsStream` reading from the `ReadableStream` provided.
+    ///
+    /// Returns an error if `stream` isn't a `ReadableStream` or if it's
+    /// already locked to another reader.
+    pub fn from_readable_stream(stream: &JsValue) -> Result<JsStream, JsValue> {
+        let reader = stream.unchecked_ref::<ReadableStream>().get_reader()?;
+        Ok(JsStream {
+            reader: Some(reader),
+            next: None,
+        })
+    }
+}
+
+impl fmt::Debug for JsStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "JsStream {{ ... }}")
+    }
+}
+
+impl Stream for JsStream {
+    type Item = JsValue;
+    type Error = JsValue;
+
+    fn poll(&mut self) -> Poll<Option<JsValue>, JsValue> {
+        let reader = match &self.reader {
+            Some(reader) => reader,
+            None => return Ok(Async::Ready(None)),
+        };
+        let result = self
+            .next
+            .get_or_insert_with(|| JsFuture::from(reader.read()))
+            .poll();
+        let result = match result {
+            Ok(Async::Ready(result)) => result,
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Err(e) => {
+                self.next = None;
+                self.reader = None;
+                return Err(e);
+            }
+        };
+        self.next = None;
+
+        // Each read resolves to a `{ value, done }` object, where `done` being
+        // true means the stream has been closed and there's nothing left.
+        if Reflect::get(&result, &JsValue::from_str("done"))?.as_bool() == Some(true) {
+            self.reader = None;
+            return Ok(Async::Ready(None));
+        }
+        let value = Reflect::get(&result, &JsValue::from_str("value"))?;
+        Ok(Async::Ready(Some(value)))
+    }
+}
+
+impl Drop for JsStream {
+    fn drop(&mut self) {
+        // Releasing the lock with a read still pending throws, in which case
+        // the stream simply stays locked.
+        if self.next.is_none() {
+            if let Some(reader) = &self.reader {
+                drop(reader.release_lock());
+            }
+        }
+    }
+}
+
+/// Converts a Rust `Stream` into a JavaScript `ReadableStream`.
+///
+/// The returned object is a new `ReadableStream` whose underlying source pulls
+/// items out of `stream` whenever JavaScript asks for more data. Each item is
+/// enqueued as a chunk, the end of `stream` closes the `ReadableStream`, and an
+/// error from `stream` errors it. Cancelling the `ReadableStream` from
+/// JavaScript drops `stream`.
+///
+/// The `stream` provided must adhere to `'static` because it'll be polled in
+/// the background and cannot contain any stack references.
+///
+/// # Panics
+///
+/// This function has the same panic behavior as `future_to_promise`. Note
+/// also that the closures backing the underlying source are only freed once
+/// `stream` finishes or the `ReadableStream` is cancelled, so a stream that's
+/// neither read to completion nor cancelled will be leaked.
+pub fn stream_to_readable_stream<S>(stream: S) -> Object
+where
+    S: Stream<Item = JsValue, Error = JsValue> + 'static,
+{
+    _stream_to_readable_stream(Box::new(stream))
+}
+
+type Callbacks = (
+    Closure<dyn FnMut(ReadableStreamDefaultController) -> Promise>,
+    Closure<dyn FnMut(JsValue)>,
+);
+
+struct Source {
+    // The Rust stream, which is taken out while a `pull` is in progress and
+    // put back once it's yielded an item.
+    stream: Option<Box<dyn Stream<Item = JsValue, Error = JsValue>>>,
+
+    // The `pull` and `cancel` callbacks handed to JavaScript. These are
+    // dropped once the stream is done, which also breaks the reference cycle
+    // between them and this state.
+    callbacks: Option<Callbacks>,
+}
+
+fn _stream_to_readable_stream(stream: Box<dyn Stream<Item = JsValue, Error = JsValue>>) -> Object {
+    let state = Rc::new(RefCell::new(Source {
+        stream: Some(stream),
+        callbacks: None,
+    }));
+
+    let state2 = state.clone();
+    let pull = Closure::wrap(
+        Box::new(move |controller: ReadableStreamDefaultController| {
+            let stream = match state2.borrow_mut().stream.take() {
+                Some(stream) => stream,
+                None => return Promise::resolve(&JsValue::undefined()),
+            };
+            let state = state2.clone();
+            future_to_promise(stream.into_future().then(move |result| {
+                let result = match result {
+                    Ok((Some(chunk), stream)) => {
+                        state.borrow_mut().stream = Some(stream);
+                        return controller.enqueue(&chunk).map(|()| JsValue::undefined());
+                    }
+                    Ok((None, _)) => controller.close(),
+                    Err((e, _)) => controller.error(&e),
+                };
+                finish(&state);
+                result.map(|()| JsValue::undefined())
+            }))
+        }) as Box<dyn FnMut(ReadableStreamDefaultController) -> Promise>,
+    );
+
+    let state2 = state.clone();
+    let cancel = Closure::wrap(Box::new(move |_reason: JsValue| {
+        finish(&state2);
+    }) as Box<dyn FnMut(JsValue)>);
+
+    let source = Object::new();
+    drop(Reflect::set(
+        &source,
+        &JsValue::from_str("pull"),
+        pull.as_ref(),
+    ));
+    drop(Reflect::set(
+        &source,
+        &JsValue::from_str("cancel"),
+        cancel.as_ref(),
+    ));
+    state.borrow_mut().callbacks = Some((pull, cancel));
+
+    match ReadableStream::new(&source) {
+        Ok(stream) => stream.unchecked_into(),
+        Err(e) => {
+            finish(&state);
+            wasm_bindgen::throw_val(e)
+        }
+    }
+}
+
+fn finish(state: &RefCell<Source>) {
+    // Take everything out before dropping it so the `RefCell` isn't borrowed
+    // while the closures get destroyed.
+    let (stream, callbacks) = {
+        let mut state = state.borrow_mut();
+        (state.stream.take(), state.callbacks.take())
+    };
+    drop(stream);
+    drop(callbacks);
+}
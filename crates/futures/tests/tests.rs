@@ -7,9 +7,12 @@ extern crate wasm_bindgen_futures;
 extern crate wasm_bindgen_test;
 
 use futures::unsync::oneshot;
-use futures::Future;
+use futures::{Future, Stream};
 use wasm_bindgen::prelude::*;
-use wasm_bindgen_futures::{future_to_promise, spawn_local, JsFuture};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::blocking;
+use wasm_bindgen_futures::stream::{stream_to_readable_stream, JsStream};
+use wasm_bindgen_futures::{future_to_promise, spawn_local, JsFuture, TypedJsFuture};
 use wasm_bindgen_test::*;
 
 #[wasm_bindgen_test(async)]
@@ -116,3 +119,90 @@ fn can_create_multiple_futures_from_same_promise() -> impl Future<Item = (), Err
     let b = JsFuture::from(promise);
     futures::future::join_all(vec![a, b]).map(|_| ())
 }
+
+#[wasm_bindgen_test(async)]
+fn stream_round_trips_through_readable_stream() -> impl Future<Item = (), Error = JsValue> {
+    let items = (1..=3).map(JsValue::from).collect::<Vec<_>>();
+    let readable = stream_to_readable_stream(futures::stream::iter_ok(items));
+    JsStream::from_readable_stream(&readable)
+        .unwrap()
+        .collect()
+        .map(|items| {
+            assert_eq!(items, [1, 2, 3]);
+        })
+}
+
+#[wasm_bindgen_test(async)]
+fn stream_error_errors_readable_stream() -> impl Future<Item = (), Error = JsValue> {
+    let items = vec![Ok(JsValue::from(1)), Err(JsValue::from("oops"))];
+    let readable = stream_to_readable_stream(futures::stream::iter_result(items));
+    let stream = JsStream::from_readable_stream(&readable).unwrap();
+    stream
+        .into_future()
+        .map_err(|_| unreachable!())
+        .and_then(|(first, rest)| {
+            assert_eq!(first.unwrap(), 1);
+            rest.into_future()
+                .map(|_| unreachable!())
+                .or_else(|(e, _)| {
+                    assert_eq!(e, "oops");
+                    Ok(())
+                })
+        })
+}
+
+#[wasm_bindgen_test]
+fn readable_stream_is_locked_by_js_stream() {
+    let readable = stream_to_readable_stream(futures::stream::empty());
+    let stream = JsStream::from_readable_stream(&readable).unwrap();
+    assert!(JsStream::from_readable_stream(&readable).is_err());
+    drop(stream);
+    assert!(JsStream::from_readable_stream(&readable).is_ok());
+}
+
+#[wasm_bindgen_test]
+fn block_on_ready_future_never_blocks() {
+    // Tests aren't built with shared memory, so blocking isn't possible, but
+    // futures which are ready right away don't need to.
+    assert!(!blocking::can_block());
+    assert_eq!(blocking::block_on(futures::future::ok::<u32, ()>(1)), Ok(1));
+    assert_eq!(
+        blocking::block_on(futures::future::err::<u32, ()>(())),
+        Err(())
+    );
+    let (tx, rx) = oneshot::channel();
+    tx.send(2).unwrap();
+    assert_eq!(blocking::block_on(rx), Ok(2));
+}
+
+#[wasm_bindgen_test(async)]
+fn typed_js_future_casts_resolved_value() -> impl Future<Item = (), Error = JsValue> {
+    let p = js_sys::Promise::resolve(&js_sys::Array::of1(&JsValue::from(42)));
+    TypedJsFuture::<js_sys::Array>::from(p).map(|array| {
+        assert_eq!(array.length(), 1);
+        assert_eq!(array.pop(), 42);
+    })
+}
+
+#[wasm_bindgen_test(async)]
+fn typed_js_future_fails_on_unexpected_type() -> impl Future<Item = (), Error = JsValue> {
+    let p = js_sys::Promise::resolve(&JsValue::from(42));
+    JsFuture::from(p)
+        .typed::<js_sys::Array>()
+        .map(|_| unreachable!())
+        .or_else(|e| {
+            assert!(e.is_instance_of::<js_sys::TypeError>());
+            Ok(())
+        })
+}
+
+#[wasm_bindgen_test(async)]
+fn typed_js_future_forwards_rejection() -> impl Future<Item = (), Error = JsValue> {
+    let p = js_sys::Promise::reject(&JsValue::from(42));
+    TypedJsFuture::<js_sys::Array>::from(p)
+        .map(|_| unreachable!())
+        .or_else(|e| {
+            assert_eq!(e, 42);
+            Ok(())
+        })
+}
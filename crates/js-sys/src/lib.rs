@@ -19,8 +19,13 @@
 #![doc(html_root_url = "https://docs.rs/js-sys/0.2")]
 
 use std::fmt;
+use std::marker::PhantomData;
 use std::mem;
+use std::ops;
 
+use wasm_bindgen::convert::RefFromWasmAbi;
+use wasm_bindgen::convert::{FromWasmAbi, IntoWasmAbi, OptionFromWasmAbi, OptionIntoWasmAbi};
+use wasm_bindgen::describe::{inform, WasmDescribe, CALLBACK};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 
@@ -121,6 +126,110 @@ extern "C" {
     /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/unescape)
     #[wasm_bindgen]
     pub fn unescape(string: &str) -> JsString;
+
+    /// The global `structuredClone()` method creates a deep clone of a given
+    /// value using the structured clone algorithm, returning a
+    /// `DataCloneError` if the value isn't serializable.
+    ///
+    /// Note that this function is provided by Web and Node environments
+    /// rather than by the ECMAScript standard.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/API/structuredClone)
+    #[wasm_bindgen(catch, js_name = structuredClone)]
+    pub fn structured_clone(value: &JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_name = structuredClone)]
+    fn structured_clone_with_options(value: &JsValue, options: &Object)
+        -> Result<JsValue, JsValue>;
+
+    type MessageTarget;
+
+    #[wasm_bindgen(catch, method, structural, js_name = postMessage)]
+    fn post_message(
+        this: &MessageTarget,
+        message: &JsValue,
+        options: &Object,
+    ) -> Result<(), JsValue>;
+}
+
+/// Like `structured_clone`, except that the objects in `transfer` are
+/// transferred to the returned clone rather than copied, leaving the
+/// originals detached.
+///
+/// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/API/structuredClone)
+pub fn structured_clone_with_transfer(
+    value: &JsValue,
+    transfer: &TransferList,
+) -> Result<JsValue, JsValue> {
+    structured_clone_with_options(value, &transfer.options())
+}
+
+/// Sends `message` with `target.postMessage(message, { transfer })`, which
+/// transfers the objects in `transfer` to the receiving side instead of
+/// copying them.
+///
+/// The `target` is usually a `Worker`, a `MessagePort` or the global scope
+/// of a worker, but any object with a compatible `postMessage` method
+/// works.
+///
+/// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/API/Worker/postMessage)
+pub fn post_message_with_transfer(
+    target: &JsValue,
+    message: &JsValue,
+    transfer: &TransferList,
+) -> Result<(), JsValue> {
+    target
+        .unchecked_ref::<MessageTarget>()
+        .post_message(message, &transfer.options())
+}
+
+/// Types whose instances are transferable objects, which can be moved to
+/// another realm with `structured_clone_with_transfer` or
+/// `post_message_with_transfer`.
+///
+/// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/API/Web_Workers_API/Transferable_objects)
+pub trait Transferable: AsRef<JsValue> {}
+
+impl Transferable for ArrayBuffer {}
+
+/// The list of objects to transfer rather than copy when cloning a value
+/// or posting a message.
+#[derive(Clone, Debug)]
+pub struct TransferList {
+    list: Array,
+}
+
+impl TransferList {
+    /// Creates an empty list.
+    pub fn new() -> TransferList {
+        TransferList { list: Array::new() }
+    }
+
+    /// Adds `value` to the objects to transfer.
+    pub fn push<T: Transferable>(&mut self, value: &T) -> &mut TransferList {
+        self.list.push(value.as_ref());
+        self
+    }
+
+    /// Adds `value` to the objects to transfer without checking that it's a
+    /// transferable type, for transferable objects like `MessagePort` which
+    /// aren't defined in this crate.
+    pub fn push_unchecked(&mut self, value: &JsValue) -> &mut TransferList {
+        self.list.push(value);
+        self
+    }
+
+    fn options(&self) -> Object {
+        let options = Object::new();
+        Reflect::set(&options, &JsValue::from_str("transfer"), &self.list).unwrap_throw();
+        options
+    }
+}
+
+impl Default for TransferList {
+    fn default() -> TransferList {
+        TransferList::new()
+    }
 }
 
 // Array
@@ -662,6 +771,258 @@ pub mod Atomics {
     }
 }
 
+// BigInt
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = Object, is_type_of = JsValue::is_bigint)]
+    #[derive(Clone, PartialEq, Eq)]
+    pub type BigInt;
+
+    #[wasm_bindgen(catch, js_name = BigInt)]
+    fn new_bigint(value: &JsValue) -> Result<BigInt, JsValue>;
+
+    #[wasm_bindgen(js_name = BigInt)]
+    fn new_bigint_i64(value: i64) -> BigInt;
+
+    #[wasm_bindgen(js_name = BigInt)]
+    fn new_bigint_u64(value: u64) -> BigInt;
+
+    /// The BigInt.asIntN() static method clamps a BigInt value to the given
+    /// number of bits, and returns that value as a signed integer.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/BigInt/asIntN)
+    #[wasm_bindgen(static_method_of = BigInt, js_name = asIntN)]
+    pub fn as_int_n(bits: f64, bigint: &BigInt) -> BigInt;
+
+    /// The BigInt.asUintN() static method clamps a BigInt value to the given
+    /// number of bits, and returns that value as an unsigned integer.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/BigInt/asUintN)
+    #[wasm_bindgen(static_method_of = BigInt, js_name = asUintN)]
+    pub fn as_uint_n(bits: f64, bigint: &BigInt) -> BigInt;
+
+    #[wasm_bindgen(static_method_of = BigInt, js_name = asIntN)]
+    fn as_int_n_i64(bits: f64, bigint: &BigInt) -> i64;
+
+    #[wasm_bindgen(static_method_of = BigInt, js_name = asUintN)]
+    fn as_uint_n_u64(bits: f64, bigint: &BigInt) -> u64;
+
+    /// The toLocaleString() method returns a string with a language-sensitive
+    /// representation of this BigInt.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/BigInt/toLocaleString)
+    #[wasm_bindgen(method, js_name = toLocaleString)]
+    pub fn to_locale_string(this: &BigInt, locales: &JsValue, options: &JsValue) -> JsString;
+
+    /// The toString() method returns a string representing the specified
+    /// BigInt in the given radix.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/BigInt/toString)
+    #[wasm_bindgen(catch, method, js_name = toString)]
+    pub fn to_string(this: &BigInt, radix: u8) -> Result<JsString, JsValue>;
+
+    /// The valueOf() method returns the wrapped primitive value of a BigInt
+    /// object.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/BigInt/valueOf)
+    #[wasm_bindgen(method, js_name = valueOf)]
+    pub fn value_of(this: &BigInt) -> BigInt;
+}
+
+impl BigInt {
+    /// The `BigInt()` function converts a number, string or boolean into a
+    /// BigInt, returning the thrown `SyntaxError` or `RangeError` if `value`
+    /// doesn't represent an integer.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/BigInt/BigInt)
+    pub fn new(value: &JsValue) -> Result<BigInt, JsValue> {
+        new_bigint(value)
+    }
+
+    /// Returns this value as an `i64`, or `None` if it doesn't fit.
+    pub fn to_i64(&self) -> Option<i64> {
+        let n = BigInt::as_int_n_i64(64.0, self);
+        if BigInt::from(n) == *self {
+            Some(n)
+        } else {
+            None
+        }
+    }
+
+    /// Returns this value as a `u64`, or `None` if it doesn't fit.
+    pub fn to_u64(&self) -> Option<u64> {
+        let n = BigInt::as_uint_n_u64(64.0, self);
+        if BigInt::from(n) == *self {
+            Some(n)
+        } else {
+            None
+        }
+    }
+
+    /// Raises this value to the power of `exponent`.
+    pub fn pow(&self, exponent: u32) -> BigInt {
+        let base: &JsValue = self.as_ref();
+        base.pow(&BigInt::from(exponent)).unchecked_into()
+    }
+
+    /// Divides this value by `rhs`, truncating towards zero, or returns
+    /// `None` if `rhs` is zero.
+    pub fn checked_div(&self, rhs: &BigInt) -> Option<BigInt> {
+        if rhs.is_zero() {
+            return None;
+        }
+        let lhs: &JsValue = self.as_ref();
+        Some((lhs / rhs.as_ref()).unchecked_into())
+    }
+
+    /// Computes the remainder of dividing this value by `rhs`, or returns
+    /// `None` if `rhs` is zero.
+    pub fn checked_rem(&self, rhs: &BigInt) -> Option<BigInt> {
+        if rhs.is_zero() {
+            return None;
+        }
+        let lhs: &JsValue = self.as_ref();
+        Some((lhs % rhs.as_ref()).unchecked_into())
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == BigInt::from(0i64)
+    }
+}
+
+macro_rules! bigint_from {
+    ($($x:ident)* => $via:ident $new:ident) => ($(
+        impl From<$x> for BigInt {
+            #[inline]
+            fn from(x: $x) -> BigInt {
+                $new($via::from(x))
+            }
+        }
+    )*)
+}
+bigint_from!(i8 i16 i32 i64 => i64 new_bigint_i64);
+bigint_from!(u8 u16 u32 u64 => u64 new_bigint_u64);
+
+macro_rules! bigint_ops {
+    ($($tr:ident $f:ident,)*) => ($(
+        impl<'a> ops::$tr for &'a BigInt {
+            type Output = BigInt;
+
+            #[inline]
+            fn $f(self, rhs: &'a BigInt) -> BigInt {
+                let lhs: &JsValue = self.as_ref();
+                ops::$tr::$f(lhs, rhs.as_ref()).unchecked_into()
+            }
+        }
+
+        impl ops::$tr for BigInt {
+            type Output = BigInt;
+
+            #[inline]
+            fn $f(self, rhs: BigInt) -> BigInt {
+                ops::$tr::$f(&self, &rhs)
+            }
+        }
+    )*)
+}
+bigint_ops! {
+    Add add,
+    Sub sub,
+    Mul mul,
+    BitAnd bitand,
+    BitOr bitor,
+    BitXor bitxor,
+    Shl shl,
+    Shr shr,
+}
+
+impl<'a> ops::Div for &'a BigInt {
+    type Output = BigInt;
+
+    fn div(self, rhs: &'a BigInt) -> BigInt {
+        match self.checked_div(rhs) {
+            Some(n) => n,
+            None => panic!("attempt to divide by zero"),
+        }
+    }
+}
+
+impl ops::Div for BigInt {
+    type Output = BigInt;
+
+    #[inline]
+    fn div(self, rhs: BigInt) -> BigInt {
+        &self / &rhs
+    }
+}
+
+impl<'a> ops::Rem for &'a BigInt {
+    type Output = BigInt;
+
+    fn rem(self, rhs: &'a BigInt) -> BigInt {
+        match self.checked_rem(rhs) {
+            Some(n) => n,
+            None => panic!("attempt to calculate the remainder with a divisor of zero"),
+        }
+    }
+}
+
+impl ops::Rem for BigInt {
+    type Output = BigInt;
+
+    #[inline]
+    fn rem(self, rhs: BigInt) -> BigInt {
+        &self % &rhs
+    }
+}
+
+impl<'a> ops::Neg for &'a BigInt {
+    type Output = BigInt;
+
+    #[inline]
+    fn neg(self) -> BigInt {
+        let val: &JsValue = self.as_ref();
+        (-val).unchecked_into()
+    }
+}
+
+impl ops::Neg for BigInt {
+    type Output = BigInt;
+
+    #[inline]
+    fn neg(self) -> BigInt {
+        -&self
+    }
+}
+
+impl<'a> ops::Not for &'a BigInt {
+    type Output = BigInt;
+
+    #[inline]
+    fn not(self) -> BigInt {
+        let val: &JsValue = self.as_ref();
+        (!val).unchecked_into()
+    }
+}
+
+impl ops::Not for BigInt {
+    type Output = BigInt;
+
+    #[inline]
+    fn not(self) -> BigInt {
+        !&self
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.to_string(10) {
+            Ok(s) => write!(f, "{}n", String::from(s)),
+            Err(_) => f.write_str("BigInt"),
+        }
+    }
+}
+
 // Boolean
 #[wasm_bindgen]
 extern "C" {
@@ -942,6 +1303,32 @@ extern "C" {
     pub fn set_float64_endian(this: &DataView, byte_offset: usize, value: f64, little_endian: bool);
 }
 
+// AggregateError
+#[wasm_bindgen]
+extern "C" {
+    /// The `AggregateError` object represents an error when several errors
+    /// need to be wrapped in a single error, such as the rejection reason of
+    /// `Promise.any()` when all of its promises reject.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/AggregateError)
+    #[wasm_bindgen(extends = Error, extends = Object)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub type AggregateError;
+
+    /// The `AggregateError` constructor creates an error for several errors
+    /// that need to be wrapped in a single error.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/AggregateError/AggregateError)
+    #[wasm_bindgen(constructor)]
+    pub fn new(errors: &JsValue, message: &str) -> AggregateError;
+
+    /// The `errors` property is an array of the errors that were aggregated.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/AggregateError/errors)
+    #[wasm_bindgen(method, getter, structural)]
+    pub fn errors(this: &AggregateError) -> Array;
+}
+
 // Error
 #[wasm_bindgen]
 extern "C" {
@@ -958,6 +1345,43 @@ extern "C" {
     #[wasm_bindgen(constructor)]
     pub fn new(message: &str) -> Error;
 
+    /// Like `Error::new`, with an `options` object whose `cause` property, if
+    /// present, becomes the `cause` of the created error.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Error/Error)
+    #[wasm_bindgen(constructor)]
+    pub fn new_with_options(message: &str, options: &Object) -> Error;
+
+    /// The `Error.captureStackTrace()` static method installs a `stack`
+    /// property on `target` describing the current call stack.
+    ///
+    /// This method is specific to V8, so an error is returned on engines
+    /// which don't support it.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Error/captureStackTrace)
+    #[wasm_bindgen(catch, static_method_of = Error, js_name = captureStackTrace)]
+    pub fn capture_stack_trace(target: &Object) -> Result<(), JsValue>;
+
+    /// Like `Error::capture_stack_trace`, except that the frames above the
+    /// last call to `constructor`, including that call, are left out of the
+    /// captured stack trace.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Error/captureStackTrace)
+    #[wasm_bindgen(catch, static_method_of = Error, js_name = captureStackTrace)]
+    pub fn capture_stack_trace_with_constructor(
+        target: &Object,
+        constructor: &Function,
+    ) -> Result<(), JsValue>;
+
+    /// The cause property indicates the specific original cause of the error,
+    /// and is `undefined` if the error wasn't created with a cause.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Error/cause)
+    #[wasm_bindgen(method, getter, structural)]
+    pub fn cause(this: &Error) -> JsValue;
+    #[wasm_bindgen(method, setter, structural)]
+    pub fn set_cause(this: &Error, cause: &JsValue);
+
     /// The message property is a human-readable description of the error.
     ///
     /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Error/message)
@@ -974,6 +1398,16 @@ extern "C" {
     #[wasm_bindgen(method, setter, structural)]
     pub fn set_name(this: &Error, name: &str);
 
+    /// The non-standard stack property is a description of the functions
+    /// which were called, in which order, when the error was created. It is
+    /// `None` on engines which don't support it.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Error/stack)
+    #[wasm_bindgen(method, getter, structural)]
+    pub fn stack(this: &Error) -> Option<String>;
+    #[wasm_bindgen(method, setter, structural)]
+    pub fn set_stack(this: &Error, stack: &str);
+
     /// The toString() method returns a string representing the specified Error object
     ///
     /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Error/toString)
@@ -981,6 +1415,18 @@ extern "C" {
     pub fn to_string(this: &Error) -> JsString;
 }
 
+impl Error {
+    /// Creates an error whose `cause` is `cause`, to wrap a lower-level error
+    /// with additional context.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Error/cause)
+    pub fn with_cause(message: &str, cause: &JsValue) -> Error {
+        let options = Object::new();
+        Reflect::set(&options, &JsValue::from_str("cause"), cause).unwrap_throw();
+        Error::new_with_options(message, &options)
+    }
+}
+
 // EvalError
 #[wasm_bindgen]
 extern "C" {
@@ -1146,6 +1592,181 @@ impl Function {
     }
 }
 
+/// A JS function with a known signature, like the callbacks taken by a lot of
+/// JS APIs.
+///
+/// `Args` is a tuple of the types of the arguments of the function and `Ret`
+/// the type of its return value. When a `Callback` is taken by an exported
+/// function they're used to declare the function's signature in the
+/// generated TypeScript, for example `(arg0: string, arg1: number) => void`
+/// for `Callback<(String, u32)>`, and they type check the arguments of
+/// `invoke`.
+///
+/// Note that JS functions aren't checked to actually have this signature.
+#[repr(transparent)]
+pub struct Callback<Args, Ret = ()> {
+    function: Function,
+    _marker: PhantomData<fn(Args) -> Ret>,
+}
+
+impl<Args, Ret> Callback<Args, Ret> {
+    /// Creates a callback from `function`, assuming it has this callback's
+    /// signature.
+    pub fn unchecked_from_function(function: Function) -> Callback<Args, Ret> {
+        Callback {
+            function,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the JS function of this callback.
+    pub fn as_function(&self) -> &Function {
+        &self.function
+    }
+
+    /// Returns the JS function of this callback.
+    pub fn into_function(self) -> Function {
+        self.function
+    }
+}
+
+impl<Args, Ret> Clone for Callback<Args, Ret> {
+    fn clone(&self) -> Callback<Args, Ret> {
+        Callback::unchecked_from_function(self.function.clone())
+    }
+}
+
+impl<Args, Ret> fmt::Debug for Callback<Args, Ret> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.function.fmt(f)
+    }
+}
+
+impl<Args, Ret> AsRef<JsValue> for Callback<Args, Ret> {
+    fn as_ref(&self) -> &JsValue {
+        self.function.as_ref()
+    }
+}
+
+impl<Args, Ret> AsRef<Function> for Callback<Args, Ret> {
+    fn as_ref(&self) -> &Function {
+        &self.function
+    }
+}
+
+impl<Args, Ret> From<Callback<Args, Ret>> for JsValue {
+    fn from(callback: Callback<Args, Ret>) -> JsValue {
+        callback.function.into()
+    }
+}
+
+/// The argument tuples of a `Callback`, which describe their types to
+/// `wasm-bindgen`.
+#[doc(hidden)]
+pub trait CallbackArguments {
+    fn describe();
+}
+
+macro_rules! callbacks {
+    ($(($n:tt $($arg:ident $var:ident)*))*) => ($(
+        impl<$($arg: WasmDescribe),*> CallbackArguments for ($($arg,)*) {
+            fn describe() {
+                inform($n);
+                $(<$arg as WasmDescribe>::describe();)*
+            }
+        }
+
+        impl<$($arg: Into<JsValue>,)* Ret> Callback<($($arg,)*), Ret> {
+            /// Calls the JS function with the given arguments and `undefined`
+            /// as `this`, returning what it returns or the exception it
+            /// throws.
+            pub fn invoke(&self, $($var: $arg),*) -> Result<JsValue, JsValue> {
+                let args = Array::new();
+                $(args.push(&$var.into());)*
+                self.function.apply(&JsValue::UNDEFINED, &args)
+            }
+        }
+    )*)
+}
+
+callbacks! {
+    (0)
+    (1 A a)
+    (2 A a B b)
+    (3 A a B b C c)
+    (4 A a B b C c D d)
+    (5 A a B b C c D d E e)
+    (6 A a B b C c D d E e F f)
+    (7 A a B b C c D d E e F f G g)
+}
+
+impl<Args: CallbackArguments, Ret: WasmDescribe> WasmDescribe for Callback<Args, Ret> {
+    fn describe() {
+        inform(CALLBACK);
+        Args::describe();
+        Ret::describe();
+    }
+}
+
+impl<Args: CallbackArguments, Ret: WasmDescribe> IntoWasmAbi for Callback<Args, Ret> {
+    type Abi = <Function as IntoWasmAbi>::Abi;
+
+    #[inline]
+    fn into_abi(self) -> Self::Abi {
+        self.function.into_abi()
+    }
+}
+
+impl<'a, Args: CallbackArguments, Ret: WasmDescribe> IntoWasmAbi for &'a Callback<Args, Ret> {
+    type Abi = <&'a Function as IntoWasmAbi>::Abi;
+
+    #[inline]
+    fn into_abi(self) -> Self::Abi {
+        (&self.function).into_abi()
+    }
+}
+
+impl<Args: CallbackArguments, Ret: WasmDescribe> OptionIntoWasmAbi for Callback<Args, Ret> {
+    #[inline]
+    fn none() -> Self::Abi {
+        <Function as OptionIntoWasmAbi>::none()
+    }
+}
+
+impl<'a, Args: CallbackArguments, Ret: WasmDescribe> OptionIntoWasmAbi for &'a Callback<Args, Ret> {
+    #[inline]
+    fn none() -> Self::Abi {
+        <&'a Function as OptionIntoWasmAbi>::none()
+    }
+}
+
+impl<Args: CallbackArguments, Ret: WasmDescribe> FromWasmAbi for Callback<Args, Ret> {
+    type Abi = <Function as FromWasmAbi>::Abi;
+
+    #[inline]
+    unsafe fn from_abi(js: Self::Abi) -> Self {
+        Callback::unchecked_from_function(Function::from_abi(js))
+    }
+}
+
+impl<Args: CallbackArguments, Ret: WasmDescribe> OptionFromWasmAbi for Callback<Args, Ret> {
+    #[inline]
+    fn is_none(abi: &Self::Abi) -> bool {
+        <Function as OptionFromWasmAbi>::is_none(abi)
+    }
+}
+
+impl<Args: CallbackArguments, Ret: WasmDescribe> RefFromWasmAbi for Callback<Args, Ret> {
+    type Abi = <Function as RefFromWasmAbi>::Abi;
+    type Anchor = mem::ManuallyDrop<Callback<Args, Ret>>;
+
+    #[inline]
+    unsafe fn ref_from_abi(js: Self::Abi) -> Self::Anchor {
+        let function = mem::ManuallyDrop::into_inner(Function::ref_from_abi(js));
+        mem::ManuallyDrop::new(Callback::unchecked_from_function(function))
+    }
+}
+
 // Generator
 #[wasm_bindgen]
 extern "C" {
@@ -1279,6 +1900,56 @@ extern "C" {
     /// non-object value") will be thrown.
     #[wasm_bindgen(catch, method, structural)]
     pub fn next(this: &Iterator) -> Result<IteratorNext, JsValue>;
+
+    /// The `Iterator.from()` static method creates an iterator with the
+    /// iterator helper methods from an iterable or an object implementing
+    /// the iterator protocol.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Iterator/from)
+    #[wasm_bindgen(catch, static_method_of = Iterator)]
+    pub fn from(object: &JsValue) -> Result<Iterator, JsValue>;
+
+    /// The `filter()` method returns a new iterator helper that yields only
+    /// the elements of this iterator for which `predicate` returns `true`.
+    ///
+    /// The predicate is called lazily, as the returned iterator is consumed,
+    /// so the closure must outlive it. This method is only available on
+    /// iterators inheriting from `Iterator.prototype`, see `Iterator::from`.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Iterator/filter)
+    #[wasm_bindgen(catch, method, structural)]
+    pub fn filter(
+        this: &Iterator,
+        predicate: &Closure<dyn FnMut(JsValue, u32) -> bool>,
+    ) -> Result<Iterator, JsValue>;
+
+    /// The `map()` method returns a new iterator helper that yields the
+    /// elements of this iterator, each transformed by `callback`.
+    ///
+    /// The callback is called lazily, as the returned iterator is consumed,
+    /// so the closure must outlive it. This method is only available on
+    /// iterators inheriting from `Iterator.prototype`, see `Iterator::from`.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Iterator/map)
+    #[wasm_bindgen(catch, method, structural)]
+    pub fn map(
+        this: &Iterator,
+        callback: &Closure<dyn FnMut(JsValue, u32) -> JsValue>,
+    ) -> Result<Iterator, JsValue>;
+
+    /// The `take()` method returns a new iterator helper that yields at most
+    /// `limit` elements of this iterator and then terminates.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Iterator/take)
+    #[wasm_bindgen(catch, method, structural)]
+    pub fn take(this: &Iterator, limit: f64) -> Result<Iterator, JsValue>;
+
+    /// The `toArray()` method consumes this iterator and returns a new array
+    /// with all of the elements it yielded.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Iterator/toArray)
+    #[wasm_bindgen(catch, method, structural, js_name = toArray)]
+    pub fn to_array(this: &Iterator) -> Result<Array, JsValue>;
 }
 
 impl Iterator {
@@ -1386,6 +2057,65 @@ impl IterState {
     }
 }
 
+/// Extension methods for Rust iterators over the items of a JS iterator,
+/// such as the ones returned by `try_iter`.
+pub trait IteratorExt: std::iter::Iterator<Item = Result<JsValue, JsValue>> + Sized {
+    /// Casts each item to the type `T`, yielding a `TypeError` for items
+    /// which aren't an instance of `T` according to `JsCast::dyn_into`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use js_sys::{try_iter, IteratorExt, JsString};
+    /// use wasm_bindgen::JsValue;
+    ///
+    /// fn concat(iterable: &JsValue) -> Result<String, JsValue> {
+    ///     let mut ret = String::new();
+    ///     if let Some(iter) = try_iter(iterable)? {
+    ///         for s in iter.cast::<JsString>() {
+    ///             ret.push_str(&String::from(s?));
+    ///         }
+    ///     }
+    ///     Ok(ret)
+    /// }
+    /// ```
+    fn cast<T: JsCast>(self) -> Cast<Self, T> {
+        Cast {
+            iter: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<I> IteratorExt for I where I: std::iter::Iterator<Item = Result<JsValue, JsValue>> {}
+
+/// An iterator which casts the items of a JS iterator to `T`.
+///
+/// Use `IteratorExt::cast` to create this.
+pub struct Cast<I, T> {
+    iter: I,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<I, T> std::iter::Iterator for Cast<I, T>
+where
+    I: std::iter::Iterator<Item = Result<JsValue, JsValue>>,
+    T: JsCast,
+{
+    type Item = Result<T, JsValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = match self.iter.next()? {
+            Ok(item) => item,
+            Err(e) => return Some(Err(e)),
+        };
+        Some(
+            item.dyn_into()
+                .map_err(|_| TypeError::new("iterator yielded an item of the wrong type").into()),
+        )
+    }
+}
+
 /// Create an iterator over `val` using the JS iteration protocol and
 /// `Symbol.iterator`.
 pub fn try_iter(val: &JsValue) -> Result<Option<IntoIter>, JsValue> {
@@ -2161,6 +2891,10 @@ extern "C" {
 // Object.
 #[wasm_bindgen]
 extern "C" {
+    // Every non-primitive value is an object, including ones without
+    // `Object.prototype` in their prototype chain, like `Object.create(null)`
+    // or objects from other realms, which `instanceof` doesn't recognize.
+    #[wasm_bindgen(is_type_of = |v| v.is_object() || v.is_function())]
     #[derive(Clone, Debug)]
     pub type Object;
 
@@ -2260,7 +2994,7 @@ extern "C" {
     ///
     /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/getOwnPropertyDescriptors)
     #[wasm_bindgen(static_method_of = Object, js_name = getOwnPropertyDescriptors)]
-    pub fn get_own_property_descriptors(obj: &Object) -> JsValue;
+    pub fn get_own_property_descriptors(obj: &Object) -> Object;
 
     /// The Object.getOwnPropertyNames() method returns an array of
     /// all properties (including non-enumerable properties except for
@@ -2285,6 +3019,28 @@ extern "C" {
     #[wasm_bindgen(static_method_of = Object, js_name = getPrototypeOf)]
     pub fn get_prototype_of(obj: &JsValue) -> Object;
 
+    /// The Object.groupBy() static method groups the elements of an iterable
+    /// according to the string values returned by `callback`, which is
+    /// called with each element and its index.
+    ///
+    /// The returned object has a `null` prototype and one property per
+    /// group, each holding an array of the elements in that group.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/groupBy)
+    #[wasm_bindgen(static_method_of = Object, catch, js_name = groupBy)]
+    pub fn group_by(
+        items: &JsValue,
+        callback: &mut dyn FnMut(JsValue, u32) -> JsValue,
+    ) -> Result<Object, JsValue>;
+
+    /// The Object.hasOwn() static method returns `true` if the specified
+    /// object has the indicated property as its own property, and `false`
+    /// if the property is inherited or does not exist.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/hasOwn)
+    #[wasm_bindgen(static_method_of = Object, js_name = hasOwn)]
+    pub fn has_own(obj: &Object, property: &JsValue) -> bool;
+
     /// The `hasOwnProperty()` method returns a boolean indicating whether the
     /// object has the specified property as its own property (as opposed to
     /// inheriting it).
@@ -2447,6 +3203,181 @@ extern "C" {
     pub fn revocable(target: &JsValue, handler: &Object) -> Object;
 }
 
+macro_rules! proxy_traps {
+    ($(
+        $(#[doc = $doc:literal])*
+        $name:ident = $js:literal: FnMut($($arg:ty),*) -> $ret:ty;
+    )*) => {
+        /// A builder for the handler object of a [`Proxy`], where each trap is
+        /// a Rust closure.
+        ///
+        /// The closures are owned by the `ProxyHandler`, so it has to be kept
+        /// alive for as long as any proxy created with it may still be used
+        /// from JS, or leaked with `forget`.
+        ///
+        /// # Example
+        ///
+        /// ```no_run
+        /// use js_sys::{Object, Proxy, ProxyHandler};
+        /// use wasm_bindgen::JsValue;
+        ///
+        /// let mut handler = ProxyHandler::new();
+        /// handler.get(|target, property, _receiver| {
+        ///     js_sys::Reflect::get(&target, &property)
+        ///         .ok()
+        ///         .filter(|value| !value.is_undefined())
+        ///         .unwrap_or(JsValue::from(37))
+        /// });
+        /// let proxy = Proxy::new(&Object::new(), handler.as_ref());
+        /// handler.forget();
+        /// ```
+        pub struct ProxyHandler {
+            handler: Object,
+            $($name: Option<Closure<dyn FnMut($($arg),*) -> $ret>>,)*
+        }
+
+        impl ProxyHandler {
+            /// Creates a handler without any traps, which forwards every
+            /// operation to the proxy's target.
+            pub fn new() -> ProxyHandler {
+                ProxyHandler {
+                    handler: Object::new(),
+                    $($name: None,)*
+                }
+            }
+
+            $(
+                $(#[doc = $doc])*
+                pub fn $name<F>(&mut self, trap: F) -> &mut ProxyHandler
+                where
+                    F: FnMut($($arg),*) -> $ret + 'static,
+                {
+                    let trap = Closure::wrap(Box::new(trap) as Box<dyn FnMut($($arg),*) -> $ret>);
+                    Reflect::set(&self.handler, &JsValue::from_str($js), trap.as_ref())
+                        .unwrap_throw();
+                    self.$name = Some(trap);
+                    self
+                }
+            )*
+
+            /// Leaks the closures of this handler, returning the handler
+            /// object which then stays valid for the rest of the program.
+            pub fn forget(self) -> Object {
+                $(
+                    if let Some(trap) = self.$name {
+                        trap.forget();
+                    }
+                )*
+                self.handler
+            }
+        }
+    };
+}
+
+proxy_traps! {
+    /// Sets the `handler.apply()` trap, called with the target function, the
+    /// `this` argument and the arguments of a function call.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy/handler/apply)
+    apply = "apply": FnMut(Function, JsValue, Array) -> JsValue;
+
+    /// Sets the `handler.construct()` trap, called with the target function,
+    /// the arguments and the `new.target` of a `new` expression.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy/handler/construct)
+    construct = "construct": FnMut(Function, Array, Function) -> Object;
+
+    /// Sets the `handler.defineProperty()` trap, called with the target, the
+    /// property key and the property descriptor.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy/handler/defineProperty)
+    define_property = "defineProperty": FnMut(Object, JsValue, Object) -> bool;
+
+    /// Sets the `handler.deleteProperty()` trap, called with the target and
+    /// the property key of a `delete` operation.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy/handler/deleteProperty)
+    delete_property = "deleteProperty": FnMut(Object, JsValue) -> bool;
+
+    /// Sets the `handler.get()` trap, called with the target, the property
+    /// key and the receiver of a property lookup.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy/handler/get)
+    get = "get": FnMut(Object, JsValue, JsValue) -> JsValue;
+
+    /// Sets the `handler.getOwnPropertyDescriptor()` trap, which returns a
+    /// property descriptor object or `undefined`.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy/handler/getOwnPropertyDescriptor)
+    get_own_property_descriptor = "getOwnPropertyDescriptor": FnMut(Object, JsValue) -> JsValue;
+
+    /// Sets the `handler.getPrototypeOf()` trap, which returns an object or
+    /// `null`.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy/handler/getPrototypeOf)
+    get_prototype_of = "getPrototypeOf": FnMut(Object) -> JsValue;
+
+    /// Sets the `handler.has()` trap, called with the target and the property
+    /// key of an `in` operation.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy/handler/has)
+    has = "has": FnMut(Object, JsValue) -> bool;
+
+    /// Sets the `handler.isExtensible()` trap.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy/handler/isExtensible)
+    is_extensible = "isExtensible": FnMut(Object) -> bool;
+
+    /// Sets the `handler.ownKeys()` trap, which returns the array of the
+    /// target's own property keys.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy/handler/ownKeys)
+    own_keys = "ownKeys": FnMut(Object) -> Array;
+
+    /// Sets the `handler.preventExtensions()` trap.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy/handler/preventExtensions)
+    prevent_extensions = "preventExtensions": FnMut(Object) -> bool;
+
+    /// Sets the `handler.set()` trap, called with the target, the property
+    /// key, the new value and the receiver of a property assignment.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy/handler/set)
+    set = "set": FnMut(Object, JsValue, JsValue, JsValue) -> bool;
+
+    /// Sets the `handler.setPrototypeOf()` trap, called with the target and
+    /// the new prototype, which is an object or `null`.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy/handler/setPrototypeOf)
+    set_prototype_of = "setPrototypeOf": FnMut(Object, JsValue) -> bool;
+}
+
+impl Default for ProxyHandler {
+    fn default() -> ProxyHandler {
+        ProxyHandler::new()
+    }
+}
+
+impl AsRef<Object> for ProxyHandler {
+    fn as_ref(&self) -> &Object {
+        &self.handler
+    }
+}
+
+impl AsRef<JsValue> for ProxyHandler {
+    fn as_ref(&self) -> &JsValue {
+        self.handler.as_ref()
+    }
+}
+
+impl fmt::Debug for ProxyHandler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ProxyHandler")
+            .field("handler", &self.handler)
+            .finish()
+    }
+}
+
 // RangeError
 #[wasm_bindgen]
 extern "C" {
@@ -2556,6 +3487,18 @@ pub mod Reflect {
         #[wasm_bindgen(js_namespace = Reflect, js_name = "get", catch)]
         pub fn get_u32(target: &JsValue, key: u32) -> Result<JsValue, JsValue>;
 
+        /// The static `Reflect.get()` method works like getting a property
+        /// from an object (`target[propertyKey]`) as a function, with `this`
+        /// set to `receiver` when a getter is encountered.
+        ///
+        /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Reflect/get)
+        #[wasm_bindgen(js_namespace = Reflect, js_name = get, catch)]
+        pub fn get_with_receiver(
+            target: &JsValue,
+            key: &JsValue,
+            receiver: &JsValue,
+        ) -> Result<JsValue, JsValue>;
+
         /// The static `Reflect.getOwnPropertyDescriptor()` method is similar to
         /// `Object.getOwnPropertyDescriptor()`. It returns a property descriptor
         /// of the given property if it exists on the object, `undefined` otherwise.
@@ -4371,6 +5314,24 @@ extern "C" {
     #[wasm_bindgen(static_method_of = Promise)]
     pub fn all(obj: &JsValue) -> Promise;
 
+    /// The `Promise.allSettled(iterable)` method returns a promise that
+    /// resolves after all of the given promises have either resolved or
+    /// rejected, with an array of objects that each describe the outcome of
+    /// each promise.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Promise/allSettled)
+    #[wasm_bindgen(static_method_of = Promise, js_name = allSettled)]
+    pub fn all_settled(obj: &JsValue) -> Promise;
+
+    /// The `Promise.any(iterable)` method returns a promise that resolves as
+    /// soon as one of the promises in the iterable resolves, with the value of
+    /// that promise. If all of the promises reject, it rejects with an
+    /// `AggregateError` holding all of the rejection reasons.
+    ///
+    /// [MDN documentation](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Promise/any)
+    #[wasm_bindgen(static_method_of = Promise)]
+    pub fn any(obj: &JsValue) -> Promise;
+
     /// The `Promise.race(iterable)` method returns a promise that resolves or
     /// rejects as soon as one of the promises in the iterable resolves or
     /// rejects, with the value or reason from that promise.
@@ -4668,6 +5629,34 @@ macro_rules! arrays {
                 let offset = dst.as_ptr() as usize / mem::size_of::<$ty>();
                 all_wasm_memory.set(self, offset as u32);
             }
+
+            /// Copy the contents of this JS typed array into a new Rust
+            /// `Vec`.
+            ///
+            /// This function will efficiently copy the memory from a typed
+            /// array into this wasm module's own linear memory, allocating a
+            /// `Vec` of the same length as this typed array.
+            pub fn to_vec(&self) -> Vec<$ty> {
+                let mut output = vec![$ty::default(); self.length() as usize];
+                self.copy_to(&mut output);
+                output
+            }
+
+            /// Copy the contents of the source Rust slice into this
+            /// JS typed array.
+            ///
+            /// This function will efficiently copy the memory from within
+            /// the wasm module's own linear memory to this typed array.
+            ///
+            /// # Panics
+            ///
+            /// This function will panic if this typed array's length is
+            /// different than the length of the provided `src` array.
+            pub fn copy_from(&self, src: &[$ty]) {
+                assert_eq!(self.length() as usize, src.len());
+                // This is safe because the `set` function copies from its TypedArray argument
+                unsafe { self.set(&$name::view(src), 0) }
+            }
         }
 
         impl<'a> From<&'a [$ty]> for $name {
@@ -4677,6 +5666,15 @@ macro_rules! arrays {
                 unsafe { $name::new(&$name::view(slice)) }
             }
         }
+
+        impl std::iter::FromIterator<$ty> for $name {
+            /// Collects the items into a Rust `Vec` first, which is then
+            /// copied into a new typed array all at once.
+            fn from_iter<I: IntoIterator<Item = $ty>>(iter: I) -> $name {
+                let items = iter.into_iter().collect::<Vec<_>>();
+                $name::from(&items[..])
+            }
+        }
     )*);
 }
 
@@ -4716,4 +5714,36 @@ arrays! {
     /// `Float64Array()`
     /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Float64Array
     Float64Array: f64,
+
+    /// `BigInt64Array()`
+    /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/BigInt64Array
+    BigInt64Array: i64,
+
+    /// `BigUint64Array()`
+    /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/BigUint64Array
+    BigUint64Array: u64,
+}
+
+impl Uint8Array {
+    /// Returns a `Uint8Array` viewing the bytes of `value`, without copying
+    /// them, or `None` if `value` isn't an `ArrayBuffer` view.
+    ///
+    /// Node.js `Buffer`s are `Uint8Array`s, so they are returned as is,
+    /// while for a `DataView` or a typed array of another type a new view of
+    /// the same bytes is created.
+    pub fn view_of(value: &JsValue) -> Option<Uint8Array> {
+        if value.is_instance_of::<Uint8Array>() {
+            return Some(value.clone().unchecked_into());
+        }
+        if !ArrayBuffer::is_view(value) {
+            return None;
+        }
+        // All views have the same accessors as `DataView`.
+        let view = value.unchecked_ref::<DataView>();
+        Some(Uint8Array::new_with_byte_offset_and_length(
+            &view.buffer(),
+            view.byte_offset() as u32,
+            view.byte_length() as u32,
+        ))
+    }
 }
@@ -0,0 +1,23 @@
+use js_sys::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+fn new() {
+    let errors = Array::of2(&Error::new("a").into(), &"b".into());
+    let error = AggregateError::new(&errors, "some message");
+    let base_error: &Error = error.dyn_ref().unwrap();
+    assert_eq!(JsValue::from(base_error.message()), "some message");
+    assert_eq!(JsValue::from(base_error.name()), "AggregateError");
+    assert_eq!(error.errors().length(), 2);
+}
+
+#[wasm_bindgen_test]
+fn aggregate_error_inheritance() {
+    let error = AggregateError::new(&Array::new(), "test");
+    assert!(error.is_instance_of::<AggregateError>());
+    assert!(error.is_instance_of::<Error>());
+    assert!(error.is_instance_of::<Object>());
+    let _: &Error = error.as_ref();
+}
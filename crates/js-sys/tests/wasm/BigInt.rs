@@ -0,0 +1,69 @@
+use js_sys::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+fn new() {
+    assert_eq!(BigInt::new(&42.into()).unwrap(), BigInt::from(42));
+    assert_eq!(BigInt::new(&"-17".into()).unwrap(), BigInt::from(-17));
+    assert!(BigInt::new(&1.5.into()).is_err());
+    assert!(BigInt::new(&"one".into()).is_err());
+}
+
+#[wasm_bindgen_test]
+fn is_type_of() {
+    let value = JsValue::from(BigInt::from(1));
+    assert!(value.is_bigint());
+    assert!(value.dyn_ref::<BigInt>().is_some());
+    assert!(JsValue::from(1).dyn_ref::<BigInt>().is_none());
+}
+
+#[wasm_bindgen_test]
+fn as_int_n() {
+    assert_eq!(BigInt::as_int_n(8.0, &BigInt::from(255)), BigInt::from(-1));
+    assert_eq!(BigInt::as_uint_n(8.0, &BigInt::from(-1)), BigInt::from(255));
+}
+
+#[wasm_bindgen_test]
+fn to_i64() {
+    assert_eq!(BigInt::from(std::i64::MIN).to_i64(), Some(std::i64::MIN));
+    assert_eq!(BigInt::from(std::u64::MAX).to_i64(), None);
+    assert_eq!(BigInt::from(std::u64::MAX).to_u64(), Some(std::u64::MAX));
+    assert_eq!(BigInt::from(-1).to_u64(), None);
+}
+
+#[wasm_bindgen_test]
+fn to_string() {
+    let n = BigInt::from(255);
+    assert_eq!(n.to_string(16).unwrap(), "ff");
+    assert_eq!(n.to_string(10).unwrap(), "255");
+    assert!(n.to_string(100).is_err());
+    assert_eq!(format!("{:?}", n), "255n");
+}
+
+#[wasm_bindgen_test]
+fn arithmetic() {
+    let a = BigInt::from(std::u64::MAX);
+    let b = BigInt::from(2);
+    assert_eq!(&(&a + &b) - &b, a);
+    assert_eq!((&a * &b).to_string(10).unwrap(), "36893488147419103230");
+    assert_eq!(&a / &b, BigInt::from(std::u64::MAX / 2));
+    assert_eq!(&a % &b, BigInt::from(1));
+    assert_eq!(-BigInt::from(3), BigInt::from(-3));
+    assert_eq!(b.pow(64) - BigInt::from(1), a);
+    assert_eq!(b.checked_div(&BigInt::from(0)), None);
+    assert_eq!(b.checked_rem(&BigInt::from(0)), None);
+}
+
+#[wasm_bindgen_test]
+fn bitwise() {
+    let a = BigInt::from(0b1100);
+    let b = BigInt::from(0b1010);
+    assert_eq!(&a & &b, BigInt::from(0b1000));
+    assert_eq!(&a | &b, BigInt::from(0b1110));
+    assert_eq!(&a ^ &b, BigInt::from(0b0110));
+    assert_eq!(!&a, BigInt::from(!0b1100));
+    assert_eq!(&a << &BigInt::from(64), a.clone() * BigInt::from(2).pow(64));
+    assert_eq!(a >> BigInt::from(2), BigInt::from(0b11));
+}
@@ -47,3 +47,37 @@ fn error_inheritance() {
     assert!(error.is_instance_of::<Object>());
     let _: &Object = error.as_ref();
 }
+
+#[wasm_bindgen_test]
+fn cause() {
+    let error = Error::new("test");
+    assert!(error.cause().is_undefined());
+
+    let inner = Error::new("inner");
+    let error = Error::with_cause("outer", &inner);
+    assert_eq!(error.cause(), JsValue::from(&inner));
+    assert_eq!(JsValue::from(error.message()), "outer");
+
+    let options = Object::new();
+    Reflect::set(&options, &"cause".into(), &42.into()).unwrap();
+    let error = Error::new_with_options("test", &options);
+    assert_eq!(error.cause(), 42);
+    error.set_cause(&"changed".into());
+    assert_eq!(error.cause(), "changed");
+}
+
+#[wasm_bindgen_test]
+fn stack() {
+    let error = Error::new("test");
+    error.set_stack("custom stack");
+    assert_eq!(error.stack().unwrap(), "custom stack");
+}
+
+#[wasm_bindgen_test]
+fn capture_stack_trace() {
+    let target = Object::new();
+    match Error::capture_stack_trace(&target) {
+        Ok(()) => assert!(Reflect::get(&target, &"stack".into()).unwrap().is_string()),
+        Err(e) => assert!(e.is_instance_of::<TypeError>()),
+    }
+}
@@ -16,4 +16,10 @@ exports.call_function = function(f) {
 };
 exports.call_function_arg =  function(f, arg1) {
   return f(arg1);
-};
\ No newline at end of file
+};
+exports.repeat_callback = function() {
+  return function(s, n) { return s.repeat(n); };
+};
+exports.throwing_callback = function() {
+  return function() { throw new Error('oops'); };
+};
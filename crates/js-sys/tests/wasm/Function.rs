@@ -38,6 +38,8 @@ extern "C" {
     fn add_arguments() -> Function;
     fn call_function(f: &Function) -> JsValue;
     fn call_function_arg(f: &Function, arg0: JsValue) -> JsValue;
+    fn repeat_callback() -> Callback<(String, u32)>;
+    fn throwing_callback() -> Callback<()>;
 
 }
 
@@ -132,3 +134,15 @@ fn function_inheritance() {
     assert!(MAX.is_instance_of::<Object>());
     let _: &Object = MAX.as_ref();
 }
+
+#[wasm_bindgen_test]
+fn callback_invoke() {
+    let repeat = repeat_callback();
+    assert_eq!(repeat.invoke("ab".to_string(), 3).unwrap(), "ababab");
+    assert_eq!(repeat.as_function().length(), 2);
+
+    let function: Function = throwing_callback().into_function();
+    let throwing = Callback::<(), u32>::unchecked_from_function(function);
+    let err = throwing.invoke().unwrap_err();
+    assert!(err.is_instance_of::<Error>());
+}
@@ -17,3 +17,5 @@ exports.get_symbol_iterator_returns_not_object = () => ({
 exports.get_symbol_iterator_returns_object_without_next = () => ({
   [Symbol.iterator]: () => new Object,
 });
+
+exports.get_mixed_iterable = () => ["one", 2, "three"];
@@ -1,5 +1,6 @@
 use js_sys::*;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_test::*;
 
 #[wasm_bindgen(module = "tests/wasm/Iterator.js")]
@@ -15,6 +16,8 @@ extern "C" {
     fn get_symbol_iterator_returns_not_object() -> JsValue;
 
     fn get_symbol_iterator_returns_object_without_next() -> JsValue;
+
+    fn get_mixed_iterable() -> JsValue;
 }
 
 #[wasm_bindgen_test]
@@ -40,3 +43,52 @@ fn try_iter_handles_iteration_protocol() {
         .unwrap()
         .is_none());
 }
+
+#[wasm_bindgen_test]
+fn cast() {
+    let strings = try_iter(&get_iterable())
+        .unwrap()
+        .unwrap()
+        .cast::<JsString>()
+        .map(|s| String::from(s.unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(strings, vec!["one", "two", "three"]);
+
+    let mut iter = try_iter(&get_mixed_iterable())
+        .unwrap()
+        .unwrap()
+        .cast::<JsString>();
+    assert_eq!(iter.next().unwrap().unwrap(), "one");
+    assert!(iter
+        .next()
+        .unwrap()
+        .unwrap_err()
+        .is_instance_of::<TypeError>());
+    assert_eq!(iter.next().unwrap().unwrap(), "three");
+    assert!(iter.next().is_none());
+}
+
+#[wasm_bindgen_test]
+fn helpers() {
+    let iter = Iterator::from(&get_iterable()).unwrap();
+    let map = Closure::wrap(Box::new(|x: JsValue, i: u32| {
+        format!("{}{}", x.as_string().unwrap(), i).into()
+    }) as Box<dyn FnMut(JsValue, u32) -> JsValue>);
+    let filter = Closure::wrap(
+        Box::new(|x: JsValue, _: u32| x != "two1") as Box<dyn FnMut(JsValue, u32) -> bool>
+    );
+    let array = iter
+        .map(&map)
+        .unwrap()
+        .filter(&filter)
+        .unwrap()
+        .take(5.0)
+        .unwrap()
+        .to_array()
+        .unwrap();
+    assert_eq!(array.join(","), "one0,three2");
+
+    assert!(Iterator::from(&get_not_iterable()).is_err());
+    let iter = Iterator::from(&get_iterable()).unwrap();
+    assert!(iter.take(-1.0).is_err());
+}
@@ -161,6 +161,38 @@ fn get_own_property_descriptors() {
     assert_eq!(PropertyDescriptor::from(foo_desc).value(), 42);
 }
 
+#[wasm_bindgen_test]
+fn group_by() {
+    let items = Array::of3(&1.into(), &2.into(), &3.into());
+    let mut indices = Vec::new();
+    let groups = Object::group_by(&items, &mut |item, i| {
+        indices.push(i);
+        if item.as_f64().unwrap() % 2.0 == 0.0 {
+            "even".into()
+        } else {
+            "odd".into()
+        }
+    })
+    .unwrap();
+    assert_eq!(indices, [0, 1, 2]);
+
+    let odd: Array = Reflect::get(&groups, &"odd".into()).unwrap().into();
+    assert_eq!(odd.join(","), "1,3");
+    let even: Array = Reflect::get(&groups, &"even".into()).unwrap().into();
+    assert_eq!(even.join(","), "2");
+
+    let error = Object::group_by(&Object::new(), &mut |item, _| item).unwrap_err();
+    assert!(error.is_instance_of::<TypeError>());
+}
+
+#[wasm_bindgen_test]
+fn has_own() {
+    let foo = foo_42();
+    assert!(Object::has_own(&foo, &"foo".into()));
+    assert!(!Object::has_own(&foo, &"bar".into()));
+    assert!(!Object::has_own(&foo, &"toString".into()));
+}
+
 #[wasm_bindgen_test]
 fn get_own_property_names() {
     let names = Object::get_own_property_names(&foo_42());
@@ -1,5 +1,8 @@
+use futures::Future;
 use js_sys::*;
 use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
 use wasm_bindgen_test::*;
 
 #[wasm_bindgen_test]
@@ -9,3 +12,50 @@ fn promise_inheritance() {
     assert!(promise.is_instance_of::<Object>());
     let _: &Object = promise.as_ref();
 }
+
+#[wasm_bindgen_test(async)]
+fn all_settled() -> impl Future<Item = (), Error = JsValue> {
+    let promises = Array::of2(
+        &Promise::resolve(&1.into()),
+        &Promise::reject(&"nope".into()),
+    );
+    JsFuture::from(Promise::all_settled(&promises)).map(|results| {
+        let results: Array = results.into();
+        assert_eq!(results.length(), 2);
+        let fulfilled = results.shift();
+        assert_eq!(
+            Reflect::get(&fulfilled, &"status".into()).unwrap(),
+            "fulfilled"
+        );
+        assert_eq!(Reflect::get(&fulfilled, &"value".into()).unwrap(), 1);
+        let rejected = results.shift();
+        assert_eq!(
+            Reflect::get(&rejected, &"status".into()).unwrap(),
+            "rejected"
+        );
+        assert_eq!(Reflect::get(&rejected, &"reason".into()).unwrap(), "nope");
+    })
+}
+
+#[wasm_bindgen_test(async)]
+fn any() -> impl Future<Item = (), Error = JsValue> {
+    let promises = Array::of2(
+        &Promise::reject(&"nope".into()),
+        &Promise::resolve(&2.into()),
+    );
+    JsFuture::from(Promise::any(&promises)).map(|value| {
+        assert_eq!(value, 2);
+    })
+}
+
+#[wasm_bindgen_test(async)]
+fn any_rejected() -> impl Future<Item = (), Error = JsValue> {
+    let promises = Array::of2(&Promise::reject(&"a".into()), &Promise::reject(&"b".into()));
+    JsFuture::from(Promise::any(&promises))
+        .map(|_| unreachable!())
+        .or_else(|e| {
+            let error: AggregateError = e.dyn_into().unwrap();
+            assert_eq!(error.errors().join(","), "a,b");
+            Ok(())
+        })
+}
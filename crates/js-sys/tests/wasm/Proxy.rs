@@ -1,5 +1,6 @@
 use js_sys::*;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_test::*;
 
 #[wasm_bindgen(module = "tests/wasm/Proxy.js")]
@@ -43,3 +44,48 @@ fn revocable() {
     assert!(obj.b().is_err());
     assert!(JsValue::from(obj).is_object());
 }
+
+#[wasm_bindgen_test]
+fn handler() {
+    let mut handler = ProxyHandler::new();
+    handler
+        .get(|target, property, _receiver| {
+            if Reflect::has(&target, &property).unwrap() {
+                Reflect::get(&target, &property).unwrap()
+            } else {
+                37.into()
+            }
+        })
+        .has(|_target, property| property != "hidden")
+        .set(|target, property, value, _receiver| {
+            let doubled = value.as_f64().unwrap() * 2.0;
+            Reflect::set(&target, &property, &doubled.into()).unwrap()
+        });
+    let proxy = Proxy::new(&proxy_target(), handler.as_ref());
+    let obj = Custom::from(JsValue::from(proxy.clone()));
+    assert_eq!(obj.a().unwrap(), 100);
+    assert_eq!(obj.b().unwrap(), 37);
+    assert!(Reflect::has(&proxy, &"anything".into()).unwrap());
+    assert!(!Reflect::has(&proxy, &"hidden".into()).unwrap());
+    assert!(Reflect::set(&proxy, &"b".into(), &4.into()).unwrap());
+    assert_eq!(obj.b().unwrap(), 8);
+}
+
+#[wasm_bindgen_test]
+fn handler_apply() {
+    let mut handler = ProxyHandler::new();
+    handler.apply(|target, this, args| {
+        let ret = target.apply(&this, &args).unwrap();
+        (ret.as_f64().unwrap() + 1.0).into()
+    });
+    let target = Function::new_with_args("a, b", "return a + b");
+    let proxy = Proxy::new(&target, handler.as_ref());
+    let proxy = proxy.unchecked_into::<Function>();
+    assert_eq!(
+        proxy.call2(&JsValue::NULL, &1.into(), &2.into()).unwrap(),
+        4
+    );
+
+    let handler = handler.forget();
+    assert!(Reflect::has(&handler, &"apply".into()).unwrap());
+}
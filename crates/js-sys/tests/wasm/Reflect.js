@@ -39,3 +39,11 @@ exports.throw_all_the_time = () => new Proxy({}, {
   apply() { throw new Error("nope"); },
   construct() { throw new Error("nope"); },
 });
+
+exports.receiver_getter = function() {
+  return {
+    get value() {
+      return this.x;
+    }
+  };
+};
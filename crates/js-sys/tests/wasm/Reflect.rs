@@ -23,6 +23,7 @@ extern "C" {
     fn set_x(this: &Rectangle, x: u32);
 
     fn throw_all_the_time() -> Object;
+    fn receiver_getter() -> Object;
 }
 
 #[wasm_bindgen]
@@ -105,6 +106,20 @@ fn get() {
     assert_eq!(Reflect::get(&obj, &"x".into()).unwrap(), 10);
 }
 
+#[wasm_bindgen_test]
+fn get_with_receiver() {
+    let r = Rectangle::new();
+    r.set_x(10);
+
+    let obj = receiver_getter();
+    let value = Reflect::get_with_receiver(&obj, &"value".into(), &r).unwrap();
+    assert_eq!(value, 10);
+    assert_eq!(
+        Reflect::get(&obj, &"value".into()).unwrap(),
+        JsValue::UNDEFINED
+    );
+}
+
 #[wasm_bindgen_test]
 fn get_f64() {
     let a = Array::new();
@@ -14,6 +14,8 @@ macro_rules! each {
         $m!(Int32Array);
         $m!(Float32Array);
         $m!(Float64Array);
+        $m!(BigInt64Array);
+        $m!(BigUint64Array);
     };
 }
 
@@ -131,3 +133,62 @@ fn copy_to() {
         assert_eq!(*i, 5);
     }
 }
+
+#[wasm_bindgen_test]
+fn to_vec() {
+    let array = Int32Array::new(&10.into());
+    array.fill(5, 0, 10);
+    assert_eq!(array.to_vec(), vec![5; 10]);
+}
+
+#[wasm_bindgen_test]
+fn bigint_arrays() {
+    let x = [std::i64::MIN, -1, std::i64::MAX];
+    let array = BigInt64Array::from(&x[..]);
+    assert_eq!(array.length(), 3);
+    assert_eq!(array.to_vec(), x);
+
+    let x = [0, 1, std::u64::MAX];
+    let array = unsafe { BigUint64Array::view(&x) };
+    let mut y = [0; 3];
+    array.copy_to(&mut y);
+    assert_eq!(y, x);
+}
+
+#[wasm_bindgen_test]
+fn copy_from() {
+    let x = [1.5, 2.5, 3.5];
+    let array = Float32Array::new(&3.into());
+    array.copy_from(&x);
+    assert_eq!(array.to_vec(), x);
+}
+
+#[wasm_bindgen_test]
+fn from_iter() {
+    let array = (1..=3).map(|x| x * 2).collect::<Uint16Array>();
+    assert_eq!(array.length(), 3);
+    assert_eq!(array.to_vec(), [2, 4, 6]);
+
+    let array = std::iter::empty().collect::<Float64Array>();
+    assert_eq!(array.length(), 0);
+}
+
+#[wasm_bindgen_test]
+fn view_of() {
+    let bytes = Uint8Array::from(&[1, 2, 3][..]);
+    let view = Uint8Array::view_of(&bytes).unwrap();
+    assert!(JsValue::from(view) == JsValue::from(bytes));
+
+    let shorts = Uint16Array::from(&[0x0101, 0x0202][..]);
+    let view = Uint8Array::view_of(&shorts).unwrap();
+    assert_eq!(view.to_vec(), [1, 1, 2, 2]);
+    view.fill(3, 0, 2);
+    assert_eq!(shorts.to_vec(), [0x0303, 0x0202]);
+
+    let buffer = ArrayBuffer::new(4);
+    let data_view = DataView::new(&buffer, 1, 2);
+    assert_eq!(Uint8Array::view_of(&data_view).unwrap().length(), 2);
+
+    assert!(Uint8Array::view_of(&buffer).is_none());
+    assert!(Uint8Array::view_of(&JsValue::NULL).is_none());
+}
@@ -0,0 +1,6 @@
+exports.message_target = () => ({
+  posted: null,
+  postMessage(message, options) {
+    this.posted = { message, transfer: options.transfer };
+  },
+});
@@ -1,8 +1,14 @@
 use std::f64::{INFINITY, NAN};
 
 use js_sys::*;
+use wasm_bindgen::prelude::*;
 use wasm_bindgen_test::*;
 
+#[wasm_bindgen(module = "tests/wasm/global_fns.js")]
+extern "C" {
+    fn message_target() -> Object;
+}
+
 #[wasm_bindgen_test]
 fn test_decode_uri() {
     let x = decode_uri("https://mozilla.org/?x=%D1%88%D0%B5%D0%BB%D0%BB%D1%8B")
@@ -88,3 +94,41 @@ fn test_unescape() {
     assert_eq!(String::from(unescape("%u0107")), "ć");
     assert_eq!(String::from(unescape("@*_+-./")), "@*_+-./");
 }
+
+#[wasm_bindgen_test]
+fn test_structured_clone() {
+    let obj = Object::new();
+    Reflect::set(&obj, &"a".into(), &Array::of1(&1.into())).unwrap();
+    let clone = structured_clone(&obj).unwrap();
+    assert!(clone != JsValue::from(&obj));
+    let a = Reflect::get(&clone, &"a".into()).unwrap();
+    assert!(Array::is_array(&a));
+    assert_eq!(Reflect::get_u32(&a, 0).unwrap(), 1);
+
+    let function = Function::new_no_args("");
+    assert!(structured_clone(&function).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_structured_clone_with_transfer() {
+    let buffer = ArrayBuffer::new(8);
+    let mut transfer = TransferList::new();
+    transfer.push(&buffer);
+    let clone = structured_clone_with_transfer(&buffer, &transfer).unwrap();
+    assert_eq!(buffer.byte_length(), 0);
+    assert_eq!(ArrayBuffer::from(clone).byte_length(), 8);
+}
+
+#[wasm_bindgen_test]
+fn test_post_message_with_transfer() {
+    let target = message_target();
+    let buffer = ArrayBuffer::new(8);
+    let mut transfer = TransferList::new();
+    transfer.push(&buffer);
+    post_message_with_transfer(&target, &"hello".into(), &transfer).unwrap();
+
+    let posted = Reflect::get(&target, &"posted".into()).unwrap();
+    assert_eq!(Reflect::get(&posted, &"message".into()).unwrap(), "hello");
+    let list = Reflect::get(&posted, &"transfer".into()).unwrap();
+    assert_eq!(Reflect::get_u32(&list, 0).unwrap(), JsValue::from(&buffer));
+}
@@ -7,9 +7,11 @@ extern crate wasm_bindgen;
 extern crate wasm_bindgen_futures;
 extern crate wasm_bindgen_test;
 
+pub mod AggregateError;
 pub mod Array;
 pub mod ArrayBuffer;
 pub mod ArrayIterator;
+pub mod BigInt;
 pub mod Boolean;
 pub mod DataView;
 pub mod Date;
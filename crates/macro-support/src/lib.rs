@@ -46,42 +46,80 @@ pub fn expand_class_marker(
     input: TokenStream,
 ) -> Result<TokenStream, Diagnostic> {
     parser::reset_attrs_used();
-    let mut item = syn::parse2::<syn::ImplItemMethod>(input)?;
+    let item = syn::parse2::<syn::ImplItem>(input)?;
     let opts: ClassMarker = syn::parse2(attr)?;
 
     let mut program = backend::ast::Program::default();
-    item.macro_parse(&mut program, (&opts.class, &opts.js_class))?;
-    parser::assert_all_attrs_checked(); // same as above
-
-    // This is where things are slightly different, we are being expanded in the
-    // context of an impl so we can't inject arbitrary item-like tokens into the
-    // output stream. If we were to do that then it wouldn't parse!
-    //
-    // Instead what we want to do is to generate the tokens for `program` into
-    // the header of the function. This'll inject some no_mangle functions and
-    // statics and such, and they should all be valid in the context of the
-    // start of a function.
-    //
-    // We manually implement `ToTokens for ImplItemMethod` here, injecting our
-    // program's tokens before the actual method's inner body tokens.
     let mut tokens = proc_macro2::TokenStream::new();
-    tokens.append_all(item.attrs.iter().filter(|attr| match attr.style {
-        syn::AttrStyle::Outer => true,
-        _ => false,
-    }));
-    item.vis.to_tokens(&mut tokens);
-    item.sig.to_tokens(&mut tokens);
     let mut err = None;
-    item.block.brace_token.surround(&mut tokens, |tokens| {
-        if let Err(e) = program.try_to_tokens(tokens) {
-            err = Some(e);
+    match item {
+        syn::ImplItem::Method(mut item) => {
+            let trait_ = opts.trait_.as_ref();
+            item.macro_parse(&mut program, (&opts.class, &opts.js_class, trait_))?;
+            parser::assert_all_attrs_checked(); // same as above
+
+            // This is where things are slightly different, we are being
+            // expanded in the context of an impl so we can't inject arbitrary
+            // item-like tokens into the output stream. If we were to do that
+            // then it wouldn't parse!
+            //
+            // Instead what we want to do is to generate the tokens for
+            // `program` into the header of the function. This'll inject some
+            // no_mangle functions and statics and such, and they should all be
+            // valid in the context of the start of a function.
+            //
+            // We manually implement `ToTokens for ImplItemMethod` here,
+            // injecting our program's tokens before the actual method's inner
+            // body tokens.
+            tokens.append_all(item.attrs.iter().filter(|attr| match attr.style {
+                syn::AttrStyle::Outer => true,
+                _ => false,
+            }));
+            item.vis.to_tokens(&mut tokens);
+            item.sig.to_tokens(&mut tokens);
+            item.block.brace_token.surround(&mut tokens, |tokens| {
+                if let Err(e) = program.try_to_tokens(tokens) {
+                    err = Some(e);
+                }
+                tokens.append_all(item.attrs.iter().filter(|attr| match attr.style {
+                    syn::AttrStyle::Inner(_) => true,
+                    _ => false,
+                }));
+                tokens.append_all(&item.block.stmts);
+            });
+        }
+        syn::ImplItem::Const(mut item) => {
+            item.macro_parse(&mut program, (&opts.class, &opts.js_class))?;
+            parser::assert_all_attrs_checked(); // same as above
+
+            // Same as for methods above, except that the program's tokens are
+            // injected into the block of the constant's initializer, which
+            // `macro_parse` has wrapped in a block.
+            let block = match &item.expr {
+                syn::Expr::Block(block) => &block.block,
+                _ => {
+                    item.to_tokens(&mut tokens);
+                    return Ok(tokens);
+                }
+            };
+            tokens.append_all(&item.attrs);
+            item.vis.to_tokens(&mut tokens);
+            item.defaultness.to_tokens(&mut tokens);
+            item.const_token.to_tokens(&mut tokens);
+            item.ident.to_tokens(&mut tokens);
+            item.colon_token.to_tokens(&mut tokens);
+            item.ty.to_tokens(&mut tokens);
+            item.eq_token.to_tokens(&mut tokens);
+            block.brace_token.surround(&mut tokens, |tokens| {
+                if let Err(e) = program.try_to_tokens(tokens) {
+                    err = Some(e);
+                }
+                tokens.append_all(&block.stmts);
+            });
+            item.semi_token.to_tokens(&mut tokens);
         }
-        tokens.append_all(item.attrs.iter().filter(|attr| match attr.style {
-            syn::AttrStyle::Inner(_) => true,
-            _ => false,
-        }));
-        tokens.append_all(&item.block.stmts);
-    });
+        item => bail_span!(item, "unexpected class marker on impl item"),
+    }
 
     if let Some(err) = err {
         return Err(err);
@@ -93,6 +131,7 @@ pub fn expand_class_marker(
 struct ClassMarker {
     class: syn::Ident,
     js_class: String,
+    trait_: Option<syn::Path>,
 }
 
 impl Parse for ClassMarker {
@@ -100,6 +139,16 @@ impl Parse for ClassMarker {
         let class = input.parse::<syn::Ident>()?;
         input.parse::<Token![=]>()?;
         let js_class = input.parse::<syn::LitStr>()?.value();
-        Ok(ClassMarker { class, js_class })
+        let trait_ = if input.is_empty() {
+            None
+        } else {
+            input.parse::<Token![,]>()?;
+            Some(input.parse::<syn::Path>()?)
+        };
+        Ok(ClassMarker {
+            class,
+            js_class,
+            trait_,
+        })
     }
 }
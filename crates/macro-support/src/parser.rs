@@ -1,9 +1,10 @@
 use std::cell::Cell;
+use std::collections::HashMap;
 
 use backend::ast;
 use backend::util::{ident_ty, ShortHash};
 use backend::Diagnostic;
-use proc_macro2::{Delimiter, Ident, Span, TokenStream, TokenTree};
+use proc_macro2::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
 use quote::ToTokens;
 use shared;
 use syn;
@@ -15,6 +16,9 @@ thread_local!(static ATTRS: AttributeParseState = Default::default());
 struct AttributeParseState {
     parsed: Cell<usize>,
     checks: Cell<usize>,
+    /// Number of `link_to` imports seen so far, to give each one a distinct
+    /// shim even when linking to the same file twice.
+    link_tos: Cell<usize>,
 }
 
 /// Parsed attributes from a `#[wasm_bindgen(..)]`.
@@ -31,7 +35,7 @@ macro_rules! attrgen {
             (constructor, Constructor(Span)),
             (method, Method(Span)),
             (static_method_of, StaticMethodOf(Span, Ident)),
-            (js_namespace, JsNamespace(Span, Ident)),
+            (js_namespace, JsNamespace(Span, Vec<Ident>)),
             (module, Module(Span, String, Span)),
             (raw_module, RawModule(Span, String, Span)),
             (inline_js, InlineJs(Span, String, Span)),
@@ -43,6 +47,7 @@ macro_rules! attrgen {
             (structural, Structural(Span)),
             (r#final, Final(Span)),
             (readonly, Readonly(Span)),
+            (thread_local, ThreadLocal(Span)),
             (js_name, JsName(Span, String, Span)),
             (js_class, JsClass(Span, String, Span)),
             (is_type_of, IsTypeOf(Span, syn::Expr)),
@@ -50,8 +55,21 @@ macro_rules! attrgen {
             (vendor_prefix, VendorPrefix(Span, Ident)),
             (variadic, Variadic(Span)),
             (typescript_custom_section, TypescriptCustomSection(Span)),
-            (start, Start(Span)),
+            (start, Start(Span, Option<i32>)),
             (skip, Skip(Span)),
+            (object, Object(Span)),
+            (implements, Implements(Span, syn::Path)),
+            (instantiate, Instantiate(Span, Vec<(Ident, syn::Type)>)),
+            (unsafe_view, UnsafeView(Span)),
+            (chain, Chain(Span)),
+            (no_free, NoFree(Span)),
+            (on_free, OnFree(Span, Ident)),
+            (serde, Serde(Span, Option<Vec<Ident>>)),
+            (plain_object, PlainObject(Span, Option<Vec<Ident>>)),
+            (typescript_type, TypescriptType(Span, Vec<(Ident, String)>)),
+            (defaults, Defaults(Span, Vec<(Ident, syn::Expr)>)),
+            (deprecated, Deprecated(Span, Option<String>)),
+            (link_to, LinkTo(Span, String, Span)),
         }
     };
 }
@@ -241,6 +259,16 @@ impl Parse for BindgenAttr {
                 return Ok(BindgenAttr::$variant(attr_span, ident))
             });
 
+            // A path of identifiers separated by dots, like `a` or `a.b.c`.
+            (@parser $variant:ident(Span, Vec<Ident>)) => ({
+                input.parse::<Token![=]>()?;
+                let mut idents = vec![input.parse::<AnyIdent>()?.0];
+                while input.parse::<Option<Token![.]>>()?.is_some() {
+                    idents.push(input.parse::<AnyIdent>()?.0);
+                }
+                return Ok(BindgenAttr::$variant(attr_span, idents))
+            });
+
             (@parser $variant:ident(Span, Option<Ident>)) => ({
                 if input.parse::<Token![=]>().is_ok() {
                     let ident = input.parse::<AnyIdent>()?.0;
@@ -250,11 +278,90 @@ impl Parse for BindgenAttr {
                 }
             });
 
+            (@parser $variant:ident(Span, Option<String>)) => ({
+                if input.parse::<Token![=]>().is_ok() {
+                    let val = input.parse::<syn::LitStr>()?.value();
+                    return Ok(BindgenAttr::$variant(attr_span, Some(val)))
+                } else {
+                    return Ok(BindgenAttr::$variant(attr_span, None));
+                }
+            });
+
+            (@parser $variant:ident(Span, Option<i32>)) => ({
+                if input.parse::<Token![=]>().is_ok() {
+                    let negative = input.parse::<Option<Token![-]>>()?.is_some();
+                    let lit = input.parse::<syn::LitInt>()?;
+                    let val = if negative {
+                        -(lit.value() as i64)
+                    } else {
+                        lit.value() as i64
+                    };
+                    if val < i32::min_value() as i64 || val > i32::max_value() as i64 {
+                        return Err(syn::Error::new(lit.span(), "integer out of range for `i32`"));
+                    }
+                    return Ok(BindgenAttr::$variant(attr_span, Some(val as i32)))
+                } else {
+                    return Ok(BindgenAttr::$variant(attr_span, None));
+                }
+            });
+
             (@parser $variant:ident(Span, syn::Path)) => ({
                 input.parse::<Token![=]>()?;
                 return Ok(BindgenAttr::$variant(attr_span, input.parse()?));
             });
 
+            (@parser $variant:ident(Span, Vec<(Ident, syn::Type)>)) => ({
+                let content;
+                parenthesized!(content in input);
+                let substitutions = syn::punctuated::Punctuated::<_, Token![,]>
+                    ::parse_terminated_with(&content, |input| {
+                        let ident = input.parse::<AnyIdent>()?.0;
+                        input.parse::<Token![=]>()?;
+                        let ty = input.parse::<syn::Type>()?;
+                        Ok((ident, ty))
+                    })?;
+                return Ok(BindgenAttr::$variant(attr_span, substitutions.into_iter().collect()));
+            });
+
+            (@parser $variant:ident(Span, Vec<(Ident, syn::Expr)>)) => ({
+                let content;
+                parenthesized!(content in input);
+                let pairs = syn::punctuated::Punctuated::<_, Token![,]>
+                    ::parse_terminated_with(&content, |input| {
+                        let ident = input.parse::<AnyIdent>()?.0;
+                        input.parse::<Token![=]>()?;
+                        let expr = input.parse::<syn::Expr>()?;
+                        Ok((ident, expr))
+                    })?;
+                return Ok(BindgenAttr::$variant(attr_span, pairs.into_iter().collect()));
+            });
+
+            (@parser $variant:ident(Span, Option<Vec<Ident>>)) => ({
+                if !input.peek(syn::token::Paren) {
+                    return Ok(BindgenAttr::$variant(attr_span, None));
+                }
+                let content;
+                parenthesized!(content in input);
+                let idents = syn::punctuated::Punctuated::<_, Token![,]>
+                    ::parse_terminated_with(&content, |input| {
+                        Ok(input.parse::<AnyIdent>()?.0)
+                    })?;
+                return Ok(BindgenAttr::$variant(attr_span, Some(idents.into_iter().collect())));
+            });
+
+            (@parser $variant:ident(Span, Vec<(Ident, String)>)) => ({
+                let content;
+                parenthesized!(content in input);
+                let pairs = syn::punctuated::Punctuated::<_, Token![,]>
+                    ::parse_terminated_with(&content, |input| {
+                        let ident = input.parse::<AnyIdent>()?.0;
+                        input.parse::<Token![=]>()?;
+                        let val = input.parse::<syn::LitStr>()?.value();
+                        Ok((ident, val))
+                    })?;
+                return Ok(BindgenAttr::$variant(attr_span, pairs.into_iter().collect()));
+            });
+
             (@parser $variant:ident(Span, syn::Expr)) => ({
                 input.parse::<Token![=]>()?;
                 return Ok(BindgenAttr::$variant(attr_span, input.parse()?));
@@ -336,14 +443,22 @@ impl<'a> ConvertToAst<BindgenAttrs> for &'a mut syn::ItemStruct {
                 continue;
             }
 
+            // The JS property can be renamed with either `js_name = foo` or
+            // `getter = foo`, and a field with only a `getter` gets no setter.
+            let js_field_name = match (attrs.js_name(), attrs.getter()) {
+                (Some((name, _)), _) => name.to_string(),
+                (None, Some(Some(name))) => name.to_string(),
+                (None, _) => name_str,
+            };
             let comments = extract_doc_comments(&field.attrs);
-            let getter = shared::struct_field_get(&js_name, &name_str);
-            let setter = shared::struct_field_set(&js_name, &name_str);
+            let getter = shared::struct_field_get(&js_name, &js_field_name);
+            let setter = shared::struct_field_set(&js_name, &js_field_name);
 
             fields.push(ast::StructField {
                 name: member,
+                js_name: js_field_name,
                 struct_name: self.ident.clone(),
-                readonly: attrs.readonly().is_some(),
+                readonly: attrs.readonly().is_some() || attrs.getter().is_some(),
                 ty: field.ty.clone(),
                 getter: Ident::new(&getter, Span::call_site()),
                 setter: Ident::new(&setter, Span::call_site()),
@@ -352,24 +467,44 @@ impl<'a> ConvertToAst<BindgenAttrs> for &'a mut syn::ItemStruct {
             attrs.check_used()?;
         }
         let comments: Vec<String> = extract_doc_comments(&self.attrs);
+        let no_free = attrs.no_free().is_some();
+        let on_free = attrs.on_free().map(|s| s.to_string());
+        if let (Some(span), Some(_)) = (attrs.no_free(), &on_free) {
+            let msg = "the `no_free` and `on_free` attributes can't be used together";
+            return Err(Diagnostic::span_error(*span, msg));
+        }
+        let js_namespace = export_namespace(&attrs);
         attrs.check_used()?;
         Ok(ast::Struct {
             rust_name: self.ident.clone(),
             js_name,
+            js_namespace,
             fields,
             comments,
+            no_free,
+            on_free,
         })
     }
 }
 
-impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignItemFn {
+impl<'a>
+    ConvertToAst<(
+        BindgenAttrs,
+        &'a ast::ImportModule,
+        &'a HashMap<Ident, String>,
+    )> for syn::ForeignItemFn
+{
     type Target = ast::ImportKind;
 
     fn convert(
         self,
-        (opts, module): (BindgenAttrs, &'a ast::ImportModule),
+        (opts, module, js_class_names): (
+            BindgenAttrs,
+            &'a ast::ImportModule,
+            &'a HashMap<Ident, String>,
+        ),
     ) -> Result<Self::Target, Diagnostic> {
-        let wasm = function_from_decl(
+        let mut wasm = function_from_decl(
             &self.ident,
             &opts,
             self.decl.clone(),
@@ -379,6 +514,12 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
             None,
         )?
         .0;
+        if !wasm.arg_defaults.is_empty() {
+            bail_span!(
+                self,
+                "default values for arguments are only supported on exports"
+            );
+        }
         let catch = opts.catch().is_some();
         let variadic = opts.variadic().is_some();
         let js_ret = if catch {
@@ -422,13 +563,16 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
             let class_name = opts
                 .js_class()
                 .map(|p| p.0.into())
-                .unwrap_or_else(|| class_name.to_string());
+                .unwrap_or_else(|| js_class_name(js_class_names, &class_name));
 
             let kind = ast::MethodKind::Operation(ast::Operation {
                 is_static: false,
                 kind: operation_kind,
             });
 
+            // The receiver is always passed as-is
+            wasm.wrapped_arguments.retain(|&(i, _)| i != 0);
+
             ast::ImportFunctionKind::Method {
                 class: class_name,
                 ty: class.clone(),
@@ -438,7 +582,7 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
             let class = opts
                 .js_class()
                 .map(|p| p.0.into())
-                .unwrap_or_else(|| cls.to_string());
+                .unwrap_or_else(|| js_class_name(js_class_names, cls));
             let ty = ident_ty(cls.clone());
 
             let kind = ast::MethodKind::Operation(ast::Operation {
@@ -463,7 +607,7 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
             let class_name = opts
                 .js_class()
                 .map(|p| p.0.into())
-                .unwrap_or_else(|| class_name.to_string());
+                .unwrap_or_else(|| js_class_name(js_class_names, &class_name));
 
             ast::ImportFunctionKind::Method {
                 class: class_name.to_string(),
@@ -474,12 +618,28 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
             ast::ImportFunctionKind::Normal
         };
 
+        let link_to = opts.link_to().map(|(path, span)| (path.to_string(), span));
+        if let Some((_, span)) = &link_to {
+            if let ast::ImportFunctionKind::Method { .. } = kind {
+                let msg = "`link_to` imports must be free functions";
+                return Err(Diagnostic::span_error(*span, msg));
+            }
+            if !wasm.arguments.is_empty() {
+                let msg = "`link_to` imports can't take arguments";
+                return Err(Diagnostic::span_error(*span, msg));
+            }
+        }
+
         let shim = {
             let ns = match kind {
                 ast::ImportFunctionKind::Normal => (0, "n"),
                 ast::ImportFunctionKind::Method { ref class, .. } => (1, &class[..]),
             };
-            let data = (ns, &self.ident, module);
+            let link_to = link_to.as_ref().map(|(path, _)| {
+                let n = ATTRS.with(|state| state.link_tos.replace(state.link_tos.get() + 1));
+                (path, n)
+            });
+            let data = (ns, &self.ident, module, link_to);
             format!(
                 "__wbg_{}_{}",
                 wasm.name
@@ -495,6 +655,18 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
                 return Err(Diagnostic::span_error(*span, msg));
             }
         }
+        let mut vendor_prefixes = Vec::new();
+        for (used, attr) in opts.attrs.iter() {
+            if let BindgenAttr::VendorPrefix(span, prefix) = attr {
+                if let ast::ImportFunctionKind::Method { .. } = kind {
+                    let msg = "vendor prefixes are only supported on free functions, \
+                               list them on the imported type instead";
+                    return Err(Diagnostic::span_error(*span, msg));
+                }
+                vendor_prefixes.push(prefix.clone());
+                used.set(true);
+            }
+        }
         let ret = ast::ImportKind::Function(ast::ImportFunction {
             function: wasm,
             kind,
@@ -505,6 +677,8 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
             rust_name: self.ident.clone(),
             shim: Ident::new(&shim, Span::call_site()),
             doc_comment: None,
+            vendor_prefixes,
+            link_to,
         });
         opts.check_used()?;
 
@@ -512,11 +686,18 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
     }
 }
 
-impl ConvertToAst<BindgenAttrs> for syn::ForeignItemType {
+impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignItemType {
     type Target = ast::ImportKind;
 
-    fn convert(self, attrs: BindgenAttrs) -> Result<Self::Target, Diagnostic> {
+    fn convert(
+        self,
+        (attrs, module): (BindgenAttrs, &'a ast::ImportModule),
+    ) -> Result<Self::Target, Diagnostic> {
         assert_not_variadic(&attrs)?;
+        let global = match module {
+            ast::ImportModule::None => attrs.js_namespace().is_none(),
+            _ => false,
+        };
         let js_name = attrs
             .js_name()
             .map(|s| s.0)
@@ -549,6 +730,7 @@ impl ConvertToAst<BindgenAttrs> for syn::ForeignItemType {
             js_name,
             extends,
             vendor_prefixes,
+            global,
         }))
     }
 }
@@ -560,9 +742,6 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
         self,
         (opts, module): (BindgenAttrs, &'a ast::ImportModule),
     ) -> Result<Self::Target, Diagnostic> {
-        if self.mutability.is_some() {
-            bail_span!(self.mutability, "cannot import mutable globals yet")
-        }
         assert_not_variadic(&opts)?;
         let default_name = self.ident.to_string();
         let js_name = opts
@@ -575,6 +754,17 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
             self.ident,
             ShortHash((&js_name, module, &self.ident)),
         );
+        let setter = if self.mutability.is_some() {
+            let setter = format!(
+                "__wbg_static_setter_{}_{}",
+                self.ident,
+                ShortHash((&js_name, module, &self.ident)),
+            );
+            Some(Ident::new(&setter, Span::call_site()))
+        } else {
+            None
+        };
+        let thread_local = opts.thread_local().is_some();
         opts.check_used()?;
         Ok(ast::ImportKind::Static(ast::ImportStatic {
             ty: *self.ty,
@@ -582,6 +772,8 @@ impl<'a> ConvertToAst<(BindgenAttrs, &'a ast::ImportModule)> for syn::ForeignIte
             rust_name: self.ident.clone(),
             js_name,
             shim: Ident::new(&shim, Span::call_site()),
+            setter,
+            thread_local,
         }))
     }
 }
@@ -694,6 +886,83 @@ fn function_from_decl(
         syn::ReturnType::Type(_, ty) => Some(replace_self(*ty)),
     };
 
+    let argument_index = |name: &Ident| {
+        arguments.iter().position(|arg| match &arg.pat {
+            syn::Pat::Ident(pat) => pat.ident == *name,
+            _ => false,
+        })
+    };
+
+    let mut wrapped_arguments = Vec::new();
+    let mut wrapped_ret = None;
+    let wrappers = [
+        (ast::Wrapper::Serde, opts.serde()),
+        (ast::Wrapper::PlainObject, opts.plain_object()),
+    ];
+    for (wrapper, names) in wrappers.iter() {
+        let wrapper = *wrapper;
+        let mut wrap_ret = false;
+        let mut wrap_arguments = Vec::new();
+        match names {
+            None => continue,
+            Some(None) => {
+                wrap_arguments.extend(0..arguments.len());
+                wrap_ret = ret.is_some();
+            }
+            Some(Some(names)) => {
+                for name in names.iter() {
+                    if name == "return" {
+                        if ret.is_none() {
+                            bail_span!(name, "function doesn't have a return value");
+                        }
+                        wrap_ret = true;
+                    } else {
+                        match argument_index(name) {
+                            Some(i) => wrap_arguments.push(i),
+                            None => {
+                                bail_span!(name, "function doesn't have an argument `{}`", name)
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let conflict = wrap_arguments
+            .iter()
+            .any(|i| wrapped_arguments.iter().any(|(j, _)| i == j))
+            || (wrap_ret && wrapped_ret.is_some());
+        if conflict {
+            bail_span!(
+                decl_name,
+                "`serde` and `plain_object` can't be used on the same argument or return value",
+            );
+        }
+        wrapped_arguments.extend(wrap_arguments.into_iter().map(|i| (i, wrapper)));
+        if wrap_ret {
+            wrapped_ret = Some(wrapper);
+        }
+    }
+
+    let mut typescript_types = Vec::new();
+    for (name, ty) in opts.typescript_type().map(|v| &v[..]).unwrap_or(&[]) {
+        if name == "return" {
+            if ret.is_none() {
+                bail_span!(name, "function doesn't have a return value");
+            }
+        } else if argument_index(name).is_none() {
+            bail_span!(name, "function doesn't have an argument `{}`", name);
+        }
+        typescript_types.push((name.to_string(), ty.clone()));
+    }
+
+    let mut arg_defaults = Vec::new();
+    for (name, value) in opts.defaults().map(|v| &v[..]).unwrap_or(&[]) {
+        if argument_index(name).is_none() {
+            bail_span!(name, "function doesn't have an argument `{}`", name);
+        }
+        arg_defaults.push((name.to_string(), js_literal(value)?));
+    }
+
     let (name, name_span, renamed_via_js_name) =
         if let Some((js_name, js_name_span)) = opts.js_name() {
             (js_name.to_string(), js_name_span, true)
@@ -709,6 +978,10 @@ fn function_from_decl(
             ret,
             rust_attrs: attrs,
             rust_vis: vis,
+            wrapped_arguments,
+            wrapped_ret,
+            typescript_types,
+            arg_defaults,
         },
         method_self,
     ))
@@ -752,26 +1025,49 @@ impl<'a> MacroParse<(Option<BindgenAttrs>, &'a mut TokenStream)> for syn::Item {
                     if f.decl.inputs.len() > 0 {
                         bail_span!(&f.decl.inputs, "the start function cannot have arguments",);
                     }
+                } else if let Some(asyncness) = &f.asyncness {
+                    bail_span!(asyncness, "only the start function can be `async`",);
+                }
+                if opts.instantiate().is_some() {
+                    return instantiate_fn(f, opts, program, tokens);
                 }
                 let method_kind = ast::MethodKind::Operation(ast::Operation {
                     is_static: true,
                     kind: operation_kind(&opts),
                 });
                 let rust_name = f.ident.clone();
-                let start = opts.start().is_some();
+                let start = opts.start().map(|priority| ast::Start {
+                    priority: priority.unwrap_or(0),
+                    asyncness: f.asyncness.is_some(),
+                });
+                let unsafe_view = opts.unsafe_view().is_some();
+                let js_namespace = export_namespace(&opts);
                 program.exports.push(ast::Export {
                     comments,
                     function: f.convert(opts)?,
                     js_class: None,
+                    js_namespace,
                     method_kind,
                     method_self: None,
                     rust_class: None,
+                    rust_trait: None,
                     rust_name,
                     start,
+                    unsafe_view,
+                    chain: false,
                 });
             }
             syn::Item::Struct(mut s) => {
                 let opts = opts.unwrap_or_default();
+                if opts.object().is_some() {
+                    return object_struct(s, opts, program, tokens);
+                }
+                if let Some(path) = opts.implements() {
+                    implements_struct(&s, path).to_tokens(tokens);
+                    opts.check_used()?;
+                    s.to_tokens(tokens);
+                    return Ok(());
+                }
                 program.structs.push((&mut s).convert(opts)?);
                 s.to_tokens(tokens);
             }
@@ -787,6 +1083,20 @@ impl<'a> MacroParse<(Option<BindgenAttrs>, &'a mut TokenStream)> for syn::Item {
                 };
                 f.macro_parse(program, opts)?;
             }
+            syn::Item::Trait(mut t) => {
+                let opts = opts.unwrap_or_default();
+                (&mut t).macro_parse(program, opts)?;
+                t.to_tokens(tokens);
+                let class = trait_object_class(&t.ident);
+                let vis = &t.vis;
+                let ident = &t.ident;
+                (quote::quote! {
+                    #[doc(hidden)]
+                    #[allow(bad_style)]
+                    #vis type #class = wasm_bindgen::__rt::std::boxed::Box<dyn #ident>;
+                })
+                .to_tokens(tokens);
+            }
             syn::Item::Enum(e) => {
                 if let Some(opts) = opts {
                     opts.check_used()?;
@@ -805,7 +1115,7 @@ impl<'a> MacroParse<(Option<BindgenAttrs>, &'a mut TokenStream)> for syn::Item {
                 bail_span!(
                     self,
                     "#[wasm_bindgen] can only be applied to a function, \
-                     struct, enum, impl, or extern block",
+                     struct, enum, impl, trait, or extern block",
                 );
             }
         }
@@ -814,6 +1124,399 @@ impl<'a> MacroParse<(Option<BindgenAttrs>, &'a mut TokenStream)> for syn::Item {
     }
 }
 
+/// Expands a generic function with `#[wasm_bindgen(instantiate(T = ...))]`
+/// attributes into one non-generic export per instantiation. Each export is
+/// named after the function with the substituted types appended, for example
+/// `scale_f32` for `scale` with `instantiate(T = f32)`.
+fn instantiate_fn(
+    f: syn::ItemFn,
+    opts: BindgenAttrs,
+    program: &mut ast::Program,
+    tokens: &mut TokenStream,
+) -> Result<(), Diagnostic> {
+    let mut instantiations = Vec::new();
+    for (used, attr) in opts.attrs.iter() {
+        if let BindgenAttr::Instantiate(span, substitutions) = attr {
+            instantiations.push((*span, substitutions.clone()));
+            used.set(true);
+        }
+    }
+    let base_name = opts
+        .js_name()
+        .map(|s| s.0.to_string())
+        .unwrap_or(f.ident.to_string());
+    let js_namespace = export_namespace(&opts);
+    opts.check_used()?;
+
+    let generics = &f.decl.generics;
+    if generics.lifetimes().next().is_some() {
+        bail_span!(
+            generics,
+            "can't #[wasm_bindgen] functions with lifetime parameters"
+        );
+    }
+    let params = generics
+        .type_params()
+        .map(|p| p.ident.clone())
+        .collect::<Vec<_>>();
+
+    let mut args = Vec::new();
+    for arg in f.decl.inputs.iter() {
+        match arg {
+            syn::FnArg::Captured(syn::ArgCaptured {
+                pat: syn::Pat::Ident(p),
+                ..
+            }) => args.push(p.ident.clone()),
+            _ => bail_span!(
+                arg,
+                "arguments of instantiated functions must be plain identifiers"
+            ),
+        }
+    }
+
+    let comments = extract_doc_comments(&f.attrs);
+    let name = &f.ident;
+    for (span, substitutions) in instantiations {
+        for (param, _) in substitutions.iter() {
+            if !params.contains(param) {
+                bail_span!(
+                    param,
+                    "`{}` is not a type parameter of this function",
+                    param
+                );
+            }
+        }
+        let tys = params
+            .iter()
+            .map(|param| {
+                substitutions
+                    .iter()
+                    .find(|(p, _)| p == param)
+                    .map(|(_, ty)| ty.clone())
+                    .ok_or_else(|| {
+                        Diagnostic::span_error(
+                            span,
+                            format!("missing instantiation for `{}`", param),
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let suffix = tys
+            .iter()
+            .map(|ty| {
+                ty.into_token_stream()
+                    .to_string()
+                    .chars()
+                    .filter(|c| c.is_alphanumeric() || *c == '_')
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("_");
+        let js_name = format!("{}_{}", base_name, suffix);
+        let wrapper = Ident::new(
+            &format!("__wbg_instantiate_{}_{}", name, suffix),
+            Span::call_site(),
+        );
+
+        let mut inputs = f.decl.inputs.clone();
+        for arg in inputs.iter_mut() {
+            if let syn::FnArg::Captured(c) = arg {
+                c.ty = substitute_type_params(&c.ty, &params, &tys)?;
+                if let syn::Pat::Ident(p) = &mut c.pat {
+                    p.mutability = None;
+                }
+            }
+        }
+        let output = match &f.decl.output {
+            syn::ReturnType::Default => syn::ReturnType::Default,
+            syn::ReturnType::Type(arrow, ty) => {
+                syn::ReturnType::Type(*arrow, Box::new(substitute_type_params(ty, &params, &tys)?))
+            }
+        };
+
+        let call_args = args.iter();
+        let wrapper_fn: syn::ItemFn = syn::parse_quote! {
+            #[doc(hidden)]
+            #[allow(bad_style)]
+            pub fn #wrapper(#inputs) #output {
+                #name::<#(#tys),*>(#(#call_args),*)
+            }
+        };
+        wrapper_fn.to_tokens(tokens);
+        let opts: BindgenAttrs = syn::parse_quote!(js_name = #js_name);
+        program.exports.push(ast::Export {
+            comments: comments.clone(),
+            function: wrapper_fn.convert(opts)?,
+            js_class: None,
+            js_namespace: js_namespace.clone(),
+            method_kind: ast::MethodKind::Operation(ast::Operation {
+                is_static: true,
+                kind: ast::OperationKind::Regular,
+            }),
+            method_self: None,
+            rust_class: None,
+            rust_trait: None,
+            rust_name: wrapper,
+            start: None,
+            unsafe_view: false,
+            chain: false,
+        });
+    }
+    Ok(())
+}
+
+/// Replaces the type parameters `params` in `ty` with the types `tys`.
+fn substitute_type_params(
+    ty: &syn::Type,
+    params: &[Ident],
+    tys: &[syn::Type],
+) -> Result<syn::Type, Diagnostic> {
+    fn substitute(tokens: TokenStream, params: &[Ident], tys: &[syn::Type]) -> TokenStream {
+        let mut ret = TokenStream::new();
+        for tt in tokens {
+            match tt {
+                TokenTree::Ident(ref i) if params.contains(i) => {
+                    let idx = params.iter().position(|p| p == i).unwrap();
+                    tys[idx].to_tokens(&mut ret);
+                }
+                TokenTree::Group(g) => {
+                    let mut group = Group::new(g.delimiter(), substitute(g.stream(), params, tys));
+                    group.set_span(g.span());
+                    ret.extend(Some(TokenTree::Group(group)));
+                }
+                other => ret.extend(Some(other)),
+            }
+        }
+        ret
+    }
+    Ok(syn::parse2(substitute(
+        ty.into_token_stream(),
+        params,
+        tys,
+    ))?)
+}
+
+/// Expands a `#[wasm_bindgen(object)]` struct into an imported type which is a
+/// plain JS object. Each field is accessed with structural getters and setters
+/// and the object can be built from Rust with `new` and one builder method per
+/// field.
+fn object_struct(
+    mut s: syn::ItemStruct,
+    opts: BindgenAttrs,
+    program: &mut ast::Program,
+    tokens: &mut TokenStream,
+) -> Result<(), Diagnostic> {
+    if s.generics.params.len() > 0 {
+        bail_span!(
+            s.generics,
+            "structs with #[wasm_bindgen(object)] cannot have lifetime or \
+             type parameters currently"
+        );
+    }
+    match s.fields {
+        syn::Fields::Named(_) => {}
+        _ => bail_span!(
+            s.fields,
+            "#[wasm_bindgen(object)] structs must have named fields"
+        ),
+    }
+    opts.check_used()?;
+
+    let vis = &s.vis;
+    let name = &s.ident;
+    let attrs = &s.attrs;
+    let create = Ident::new(&format!("__wbg_object_create_{}", name), Span::call_site());
+    let mut imports = Vec::new();
+    let mut builders = Vec::new();
+    for field in s.fields.iter_mut() {
+        let field_opts = BindgenAttrs::find(&mut field.attrs)?;
+        field_opts.check_used()?;
+        let field_vis = &field.vis;
+        let field_attrs = &field.attrs;
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let getter = Ident::new(&format!("get_{}", ident), Span::call_site());
+        let setter = Ident::new(&format!("set_{}", ident), Span::call_site());
+        imports.push(quote::quote! {
+            #[wasm_bindgen(method, structural, getter = #ident)]
+            #field_vis fn #getter(this: &#name) -> #ty;
+            #[wasm_bindgen(method, structural, setter = #ident)]
+            #field_vis fn #setter(this: &#name, val: #ty);
+        });
+        builders.push(quote::quote! {
+            #(#field_attrs)*
+            #field_vis fn #ident(self, val: #ty) -> #name {
+                self.#setter(val);
+                self
+            }
+        });
+    }
+
+    let foreign: syn::ItemForeignMod = syn::parse_quote! {
+        extern "C" {
+            #(#attrs)*
+            #[wasm_bindgen(is_type_of = wasm_bindgen::JsValue::is_object)]
+            #vis type #name;
+            #[wasm_bindgen(js_namespace = Object, js_name = create)]
+            fn #create(proto: &wasm_bindgen::JsValue) -> #name;
+            #(#imports)*
+        }
+    };
+    (quote::quote! {
+        #[allow(clippy::all)]
+        impl #name {
+            /// Creates a new empty object.
+            #vis fn new() -> #name {
+                #create(&wasm_bindgen::JsValue::NULL)
+            }
+
+            #(#builders)*
+        }
+    })
+    .to_tokens(tokens);
+    foreign.macro_parse(program, BindgenAttrs::default())
+}
+
+/// Generates the conversions for a `#[wasm_bindgen(implements = Trait)]`
+/// struct, which is passed to JS as a boxed trait object rather than as a class
+/// of its own.
+fn implements_struct(s: &syn::ItemStruct, path: &syn::Path) -> TokenStream {
+    let name = &s.ident;
+    let (impl_generics, ty_generics, where_clause) = s.generics.split_for_impl();
+    quote::quote! {
+        #[allow(clippy::all)]
+        impl #impl_generics wasm_bindgen::describe::WasmDescribe for #name #ty_generics
+            #where_clause
+        {
+            fn describe() {
+                <wasm_bindgen::__rt::std::boxed::Box<dyn #path>
+                    as wasm_bindgen::describe::WasmDescribe>::describe();
+            }
+        }
+
+        #[allow(clippy::all)]
+        impl #impl_generics wasm_bindgen::convert::IntoWasmAbi for #name #ty_generics
+            #where_clause
+        {
+            type Abi = u32;
+
+            fn into_abi(self) -> u32 {
+                let me: wasm_bindgen::__rt::std::boxed::Box<dyn #path> =
+                    wasm_bindgen::__rt::std::boxed::Box::new(self);
+                wasm_bindgen::convert::IntoWasmAbi::into_abi(me)
+            }
+        }
+
+        #[allow(clippy::all)]
+        impl #impl_generics wasm_bindgen::__rt::core::convert::From<#name #ty_generics>
+            for wasm_bindgen::JsValue
+            #where_clause
+        {
+            fn from(value: #name #ty_generics) -> Self {
+                let me: wasm_bindgen::__rt::std::boxed::Box<dyn #path> =
+                    wasm_bindgen::__rt::std::boxed::Box::new(value);
+                me.into()
+            }
+        }
+    }
+}
+
+impl<'a> MacroParse<BindgenAttrs> for &'a mut syn::ItemTrait {
+    /// Registers a JS class named after the trait which wraps a boxed trait
+    /// object. Every method taking `&self` or `&mut self` is exported as a
+    /// method of that class, dispatching through the trait object's vtable.
+    fn macro_parse(self, program: &mut ast::Program, opts: BindgenAttrs) -> Result<(), Diagnostic> {
+        if self.generics.params.len() > 0 {
+            bail_span!(
+                self.generics,
+                "traits with #[wasm_bindgen] cannot have lifetime or \
+                 type parameters currently"
+            );
+        }
+        if self.unsafety.is_some() {
+            bail_span!(
+                self.unsafety,
+                "#[wasm_bindgen] unsafe traits are not supported"
+            );
+        }
+        let js_name = opts
+            .js_name()
+            .map(|s| s.0.to_string())
+            .unwrap_or(self.ident.to_string());
+        let class = trait_object_class(&self.ident);
+        let mut errors = Vec::new();
+        for item in self.items.iter_mut() {
+            let method = match item {
+                syn::TraitItem::Method(m) => m,
+                _ => continue,
+            };
+            let method_opts = BindgenAttrs::find(&mut method.attrs)?;
+            let comments = extract_doc_comments(&method.attrs);
+            let (function, method_self) = function_from_decl(
+                &method.sig.ident,
+                &method_opts,
+                Box::new(method.sig.decl.clone()),
+                method.attrs.clone(),
+                syn::Visibility::Inherited,
+                true,
+                None,
+            )?;
+            match method_self {
+                Some(ast::MethodSelf::RefShared) | Some(ast::MethodSelf::RefMutable) => {}
+                // Methods without `&self` can't be called on a trait object
+                None => {
+                    method_opts.check_used()?;
+                    continue;
+                }
+                Some(ast::MethodSelf::ByValue) => {
+                    errors.push(err_span!(
+                        method.sig.decl.inputs,
+                        "methods of #[wasm_bindgen] traits must take `&self` or `&mut self`"
+                    ));
+                    method_opts.check_used()?;
+                    continue;
+                }
+            }
+            program.exports.push(ast::Export {
+                comments,
+                function,
+                js_class: Some(js_name.clone()),
+                js_namespace: Vec::new(),
+                method_kind: ast::MethodKind::Operation(ast::Operation {
+                    is_static: false,
+                    kind: operation_kind(&method_opts),
+                }),
+                method_self,
+                rust_class: Some(class.clone()),
+                rust_trait: None,
+                rust_name: method.sig.ident.clone(),
+                start: None,
+                unsafe_view: method_opts.unsafe_view().is_some(),
+                chain: false,
+            });
+            method_opts.check_used()?;
+        }
+        Diagnostic::from_vec(errors)?;
+        program.structs.push(ast::Struct {
+            rust_name: class.clone(),
+            js_name,
+            js_namespace: export_namespace(&opts),
+            fields: Vec::new(),
+            comments: extract_doc_comments(&self.attrs),
+            no_free: false,
+            on_free: None,
+        });
+        opts.check_used()?;
+        Ok(())
+    }
+}
+
+/// The name of the type alias for `Box<dyn Trait>` which is exported to JS as
+/// a class for a `#[wasm_bindgen]` trait.
+fn trait_object_class(trait_name: &Ident) -> Ident {
+    Ident::new(&format!("__wbg_dyn_{}", trait_name), Span::call_site())
+}
+
 impl<'a> MacroParse<BindgenAttrs> for &'a mut syn::ItemImpl {
     fn macro_parse(
         self,
@@ -832,8 +1535,8 @@ impl<'a> MacroParse<BindgenAttrs> for &'a mut syn::ItemImpl {
                 "#[wasm_bindgen] unsafe impls are not supported"
             );
         }
-        if let Some((_, path, _)) = &self.trait_ {
-            bail_span!(path, "#[wasm_bindgen] trait impls are not supported");
+        if let Some((Some(bang), _, _)) = &self.trait_ {
+            bail_span!(bang, "#[wasm_bindgen] negative impls are not supported");
         }
         if self.generics.params.len() > 0 {
             bail_span!(
@@ -851,9 +1554,10 @@ impl<'a> MacroParse<BindgenAttrs> for &'a mut syn::ItemImpl {
                 "unsupported self type in #[wasm_bindgen] impl"
             ),
         };
+        let trait_ = self.trait_.as_ref().map(|(_, path, _)| path);
         let mut errors = Vec::new();
         for item in self.items.iter_mut() {
-            if let Err(e) = prepare_for_impl_recursion(item, &name, &opts) {
+            if let Err(e) = prepare_for_impl_recursion(item, &name, trait_, &opts) {
                 errors.push(e);
             }
         }
@@ -871,19 +1575,21 @@ impl<'a> MacroParse<BindgenAttrs> for &'a mut syn::ItemImpl {
 // Note that we currently do this because inner items may have things like cfgs
 // on them, so we want to expand the impl first, let the insides get cfg'd, and
 // then go for the rest.
+//
+// For trait impls the trait is passed along too, which lets crates other than
+// the one defining a class add methods to it.
 fn prepare_for_impl_recursion(
     item: &mut syn::ImplItem,
     class: &Ident,
+    trait_: Option<&syn::Path>,
     impl_opts: &BindgenAttrs,
 ) -> Result<(), Diagnostic> {
-    let method = match item {
-        syn::ImplItem::Method(m) => m,
-        syn::ImplItem::Const(_) => {
-            bail_span!(
-                &*item,
-                "const definitions aren't supported with #[wasm_bindgen]"
-            );
-        }
+    let attrs = match item {
+        // Associated consts and types of trait impls belong to the trait
+        // rather than to the class, so they're left alone.
+        syn::ImplItem::Const(_) | syn::ImplItem::Type(_) if trait_.is_some() => return Ok(()),
+        syn::ImplItem::Method(m) => &mut m.attrs,
+        syn::ImplItem::Const(c) => &mut c.attrs,
         syn::ImplItem::Type(_) => bail_span!(
             &*item,
             "type definitions in impls aren't supported with #[wasm_bindgen]"
@@ -907,30 +1613,44 @@ fn prepare_for_impl_recursion(
         .map(|s| s.0.to_string())
         .unwrap_or(class.to_string());
 
-    method.attrs.insert(
+    attrs.insert(
         0,
         syn::Attribute {
             pound_token: Default::default(),
             style: syn::AttrStyle::Outer,
             bracket_token: Default::default(),
             path: syn::parse_quote! { wasm_bindgen::prelude::__wasm_bindgen_class_marker },
-            tts: quote::quote! { (#class = #js_class) }.into(),
+            tts: match trait_ {
+                Some(path) => quote::quote! { (#class = #js_class, #path) },
+                None => quote::quote! { (#class = #js_class) },
+            }
+            .into(),
         },
     );
 
     Ok(())
 }
 
-impl<'a, 'b> MacroParse<(&'a Ident, &'a str)> for &'b mut syn::ImplItemMethod {
+impl<'a, 'b> MacroParse<(&'a Ident, &'a str, Option<&'a syn::Path>)>
+    for &'b mut syn::ImplItemMethod
+{
     fn macro_parse(
         self,
         program: &mut ast::Program,
-        (class, js_class): (&'a Ident, &'a str),
+        (class, js_class, trait_): (&'a Ident, &'a str, Option<&'a syn::Path>),
     ) -> Result<(), Diagnostic> {
+        // Methods of trait impls are as public as the trait itself, so they're
+        // all exported unless they're skipped.
         match self.vis {
             syn::Visibility::Public(_) => {}
+            _ if trait_.is_some() => {}
             _ => return Ok(()),
         }
+        let opts = BindgenAttrs::find(&mut self.attrs)?;
+        if opts.skip().is_some() {
+            opts.check_used()?;
+            return Ok(());
+        }
         if self.defaultness.is_some() {
             panic!("default methods are not supported");
         }
@@ -944,7 +1664,12 @@ impl<'a, 'b> MacroParse<(&'a Ident, &'a str)> for &'b mut syn::ImplItemMethod {
             bail_span!(self.sig.unsafety, "can only bindgen safe functions",);
         }
 
-        let opts = BindgenAttrs::find(&mut self.attrs)?;
+        if let Some(path) = opts.js_namespace() {
+            bail_span!(
+                path[0],
+                "methods can't have a `js_namespace`, it's set on the struct instead"
+            );
+        }
         let comments = extract_doc_comments(&self.attrs);
         let (function, method_self) = function_from_decl(
             &self.sig.ident,
@@ -962,15 +1687,122 @@ impl<'a, 'b> MacroParse<(&'a Ident, &'a str)> for &'b mut syn::ImplItemMethod {
             let kind = operation_kind(&opts);
             ast::MethodKind::Operation(ast::Operation { is_static, kind })
         };
+        let chain = match opts.chain() {
+            Some(span) => {
+                match method_self {
+                    Some(ast::MethodSelf::ByValue) => {}
+                    _ => {
+                        let msg =
+                            "the `chain` attribute requires the method to take `self` by value";
+                        return Err(Diagnostic::span_error(*span, msg));
+                    }
+                }
+                match &function.ret {
+                    Some(ty) if is_class_type(ty, class) => {}
+                    _ => bail_span!(
+                        self.sig.decl.output,
+                        "the `chain` attribute requires the method to return `Self`"
+                    ),
+                }
+                true
+            }
+            None => false,
+        };
         program.exports.push(ast::Export {
             comments,
             function,
             js_class: Some(js_class.to_string()),
+            js_namespace: Vec::new(),
             method_kind,
             method_self,
             rust_class: Some(class.clone()),
+            rust_trait: trait_.cloned(),
             rust_name: self.sig.ident.clone(),
-            start: false,
+            start: None,
+            unsafe_view: opts.unsafe_view().is_some(),
+            chain,
+        });
+        opts.check_used()?;
+        Ok(())
+    }
+}
+
+impl<'a, 'b> MacroParse<(&'a Ident, &'a str)> for &'b mut syn::ImplItemConst {
+    fn macro_parse(
+        self,
+        program: &mut ast::Program,
+        (class, js_class): (&'a Ident, &'a str),
+    ) -> Result<(), Diagnostic> {
+        match self.vis {
+            syn::Visibility::Public(_) => {}
+            _ => return Ok(()),
+        }
+
+        let opts = BindgenAttrs::find(&mut self.attrs)?;
+        let comments = extract_doc_comments(&self.attrs);
+        let (js_name, js_name_span) = match opts.js_name() {
+            Some((name, span)) => (name.to_string(), span),
+            None => (self.ident.to_string(), self.ident.span()),
+        };
+
+        // Associated constants are exported to JS as static getters on the
+        // class, which call a free function defined in the constant's own
+        // initializer since there's no way to add items to the `impl` block
+        // itself. Strings are returned as owned values since exports can't
+        // return borrowed references.
+        let ident = &self.ident;
+        let (ret, body): (syn::Type, _) = match &self.ty {
+            syn::Type::Reference(syn::TypeReference {
+                mutability: None,
+                elem,
+                ..
+            }) if is_str(elem) => (
+                syn::parse_quote! { String },
+                quote::quote! { String::from(#class::#ident) },
+            ),
+            ty => (ty.clone(), quote::quote! { #class::#ident }),
+        };
+        let accessor = Ident::new(
+            &format!("__wbindgen_const_{}", self.ident),
+            Span::call_site(),
+        );
+        let expr = &self.expr;
+        self.expr = syn::parse_quote! {{
+            #[allow(non_snake_case)]
+            fn #accessor() -> #ret {
+                #body
+            }
+            #expr
+        }};
+
+        program.exports.push(ast::Export {
+            comments,
+            function: ast::Function {
+                name: js_name,
+                name_span: js_name_span,
+                renamed_via_js_name: opts.js_name().is_some(),
+                arguments: Vec::new(),
+                ret: Some(ret),
+                rust_attrs: Vec::new(),
+                rust_vis: self.vis.clone(),
+                wrapped_arguments: Vec::new(),
+                wrapped_ret: None,
+                typescript_types: Vec::new(),
+                arg_defaults: Vec::new(),
+            },
+            js_class: Some(js_class.to_string()),
+            js_namespace: Vec::new(),
+            method_kind: ast::MethodKind::Operation(ast::Operation {
+                is_static: true,
+                kind: ast::OperationKind::Getter(None),
+            }),
+            method_self: None,
+            rust_class: None,
+            rust_trait: None,
+            rust_name: accessor,
+            start: None,
+            unsafe_view: false,
+            chain: false,
         });
         opts.check_used()?;
         Ok(())
@@ -1131,8 +1963,52 @@ impl MacroParse<BindgenAttrs> for syn::ItemForeignMod {
         } else {
             ast::ImportModule::None
         };
-        for item in self.items.into_iter() {
-            if let Err(e) = item.macro_parse(program, module.clone()) {
+
+        // Parse the attributes of all items up front so the JS names of the
+        // imported types are known when processing their methods, which
+        // otherwise would all need a `js_class` of their own.
+        let mut items = Vec::new();
+        let mut js_class_names = HashMap::new();
+        for mut item in self.items.into_iter() {
+            let attrs = match item {
+                syn::ForeignItem::Fn(ref mut f) => &mut f.attrs,
+                syn::ForeignItem::Type(ref mut t) => &mut t.attrs,
+                syn::ForeignItem::Static(ref mut s) => &mut s.attrs,
+                _ => panic!("only foreign functions/types allowed for now"),
+            };
+            let item_opts = match BindgenAttrs::find(attrs) {
+                Ok(opts) => opts,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+            // Deprecated imports are marked as such in Rust, except for
+            // types which are used by the trait impls generated for them.
+            if let Some(note) = item_opts.deprecated() {
+                match &mut item {
+                    syn::ForeignItem::Type(t) => {
+                        let msg = "imported types can't be `deprecated`";
+                        errors.push(Diagnostic::spanned_error(&t.ident, msg));
+                        continue;
+                    }
+                    syn::ForeignItem::Fn(syn::ForeignItemFn { attrs, .. })
+                    | syn::ForeignItem::Static(syn::ForeignItemStatic { attrs, .. }) => {
+                        attrs.push(deprecated_attribute(note.as_ref().map(|s| &s[..])));
+                    }
+                    _ => {}
+                }
+            }
+            if let syn::ForeignItem::Type(t) = &item {
+                if let Some((js_name, _)) = item_opts.js_name() {
+                    js_class_names.insert(t.ident.clone(), js_name.to_string());
+                }
+            }
+            items.push((item, item_opts));
+        }
+        for (item, item_opts) in items {
+            let ctx = (module.clone(), item_opts, &js_class_names);
+            if let Err(e) = item.macro_parse(program, ctx) {
                 errors.push(e);
             }
         }
@@ -1142,25 +2018,30 @@ impl MacroParse<BindgenAttrs> for syn::ItemForeignMod {
     }
 }
 
-impl MacroParse<ast::ImportModule> for syn::ForeignItem {
+impl<'a> MacroParse<(ast::ImportModule, BindgenAttrs, &'a HashMap<Ident, String>)>
+    for syn::ForeignItem
+{
     fn macro_parse(
-        mut self,
+        self,
         program: &mut ast::Program,
-        module: ast::ImportModule,
+        (module, item_opts, js_class_names): (
+            ast::ImportModule,
+            BindgenAttrs,
+            &'a HashMap<Ident, String>,
+        ),
     ) -> Result<(), Diagnostic> {
-        let item_opts = {
-            let attrs = match self {
-                syn::ForeignItem::Fn(ref mut f) => &mut f.attrs,
-                syn::ForeignItem::Type(ref mut t) => &mut t.attrs,
-                syn::ForeignItem::Static(ref mut s) => &mut s.attrs,
-                _ => panic!("only foreign functions/types allowed for now"),
-            };
-            BindgenAttrs::find(attrs)?
+        let js_namespace = match item_opts.js_namespace() {
+            Some(path) if path.len() > 1 => bail_span!(
+                path[1],
+                "nested namespaces are only supported for exports, imports \
+                 must use a single `js_namespace`"
+            ),
+            Some(path) => Some(path[0].clone()),
+            None => None,
         };
-        let js_namespace = item_opts.js_namespace().cloned();
         let kind = match self {
-            syn::ForeignItem::Fn(f) => f.convert((item_opts, &module))?,
-            syn::ForeignItem::Type(t) => t.convert(item_opts)?,
+            syn::ForeignItem::Fn(f) => f.convert((item_opts, &module, js_class_names))?,
+            syn::ForeignItem::Type(t) => t.convert((item_opts, &module))?,
             syn::ForeignItem::Static(s) => s.convert((item_opts, &module))?,
             _ => panic!("only foreign functions/types allowed for now"),
         };
@@ -1175,6 +2056,48 @@ impl MacroParse<ast::ImportModule> for syn::ForeignItem {
     }
 }
 
+/// Returns the path of the JS namespace an export is placed in, given with
+/// `js_namespace`, which is empty by default.
+fn export_namespace(opts: &BindgenAttrs) -> Vec<String> {
+    opts.js_namespace()
+        .map(|path| path.iter().map(|i| i.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Converts a Rust literal used as a default value of an argument into the
+/// equivalent JS expression.
+fn js_literal(expr: &syn::Expr) -> Result<String, Diagnostic> {
+    let (neg, lit) = match expr {
+        syn::Expr::Lit(syn::ExprLit { lit, .. }) => (false, lit),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => match &**expr {
+            syn::Expr::Lit(syn::ExprLit { lit, .. }) => (true, lit),
+            _ => bail_span!(expr, "default values must be literals"),
+        },
+        _ => bail_span!(expr, "default values must be literals"),
+    };
+    let js = match lit {
+        syn::Lit::Int(i) => i.value().to_string(),
+        syn::Lit::Float(f) => f.value().to_string(),
+        syn::Lit::Str(s) if !neg => format!("{:?}", s.value()),
+        syn::Lit::Bool(b) if !neg => b.value.to_string(),
+        _ => bail_span!(expr, "unsupported literal for a default value"),
+    };
+    Ok(if neg { format!("-{}", js) } else { js })
+}
+
+/// Returns the JS name of the imported type `ty`, which is its `js_name` if it
+/// was declared with one in the same `extern` block.
+fn js_class_name(js_class_names: &HashMap<Ident, String>, ty: &Ident) -> String {
+    js_class_names
+        .get(ty)
+        .cloned()
+        .unwrap_or_else(|| ty.to_string())
+}
+
 /// Get the first type parameter of a generic type, errors on incorrect input.
 fn extract_first_ty_param(ty: Option<&syn::Type>) -> Result<Option<syn::Type>, Diagnostic> {
     let t = match ty {
@@ -1213,9 +2136,27 @@ fn extract_first_ty_param(ty: Option<&syn::Type>) -> Result<Option<syn::Type>, D
     Ok(Some(ty.clone()))
 }
 
-/// Extract the documentation comments from a Vec of attributes
+/// Returns whether `ty` is the `str` type.
+fn is_str(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.is_ident("str"),
+        _ => false,
+    }
+}
+
+/// Returns whether `ty` is the type `class` itself, which is what `Self` is
+/// replaced with in method signatures.
+fn is_class_type(ty: &syn::Type, class: &Ident) -> bool {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.is_ident(class.clone()),
+        _ => false,
+    }
+}
+
+/// Extract the documentation comments from a Vec of attributes, followed by a
+/// JSDoc `@deprecated` tag if they include `#[deprecated]`.
 fn extract_doc_comments(attrs: &[syn::Attribute]) -> Vec<String> {
-    attrs
+    let mut comments = attrs
         .iter()
         .filter_map(|a| {
             // if the path segments include an ident of "doc" we know this
@@ -1240,7 +2181,55 @@ fn extract_doc_comments(attrs: &[syn::Attribute]) -> Vec<String> {
         .fold(vec![], |mut acc, a| {
             acc.extend(a);
             acc
-        })
+        });
+    if let Some(note) = attrs.iter().filter_map(deprecation_note).next() {
+        // Comments are quoted like the literals of doc comments.
+        let tag = match note {
+            Some(note) => format!(" @deprecated {}", note),
+            None => " @deprecated".to_string(),
+        };
+        comments.push(format!("{:?}", tag));
+    }
+    comments
+}
+
+/// Returns the note of a `#[deprecated]` attribute, or `None` if `attr` isn't
+/// one.
+fn deprecation_note(attr: &syn::Attribute) -> Option<Option<String>> {
+    if !attr.path.is_ident("deprecated") {
+        return None;
+    }
+    let note = match attr.parse_meta().ok()? {
+        syn::Meta::Word(_) => None,
+        syn::Meta::NameValue(syn::MetaNameValue {
+            lit: syn::Lit::Str(note),
+            ..
+        }) => Some(note.value()),
+        syn::Meta::NameValue(_) => None,
+        syn::Meta::List(list) => list.nested.iter().find_map(|nested| match nested {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                ident,
+                lit: syn::Lit::Str(note),
+                ..
+            })) if ident == "note" => Some(note.value()),
+            _ => None,
+        }),
+    };
+    Some(note)
+}
+
+/// Creates a `#[deprecated]` attribute with the given note.
+fn deprecated_attribute(note: Option<&str>) -> syn::Attribute {
+    syn::Attribute {
+        pound_token: Default::default(),
+        style: syn::AttrStyle::Outer,
+        bracket_token: Default::default(),
+        path: syn::parse_quote! { deprecated },
+        tts: match note {
+            Some(note) => quote::quote! { (note = #note) },
+            None => TokenStream::new(),
+        },
+    }
 }
 
 /// Check there are no lifetimes on the function.
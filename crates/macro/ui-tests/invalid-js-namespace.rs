@@ -0,0 +1,18 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct A;
+
+#[wasm_bindgen]
+impl A {
+    #[wasm_bindgen(js_namespace = debug)]
+    pub fn dump(&self) {}
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = a.b)]
+    fn f();
+}
+
+fn main() {}
@@ -12,7 +12,7 @@ unsafe impl A {
 }
 
 #[wasm_bindgen]
-impl Clone for A {
+impl !Send for A {
 }
 
 #[wasm_bindgen]
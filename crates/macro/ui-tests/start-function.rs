@@ -9,4 +9,7 @@ pub fn foo2(x: u32) {}
 #[wasm_bindgen(start)]
 pub fn foo3<T>() {}
 
+#[wasm_bindgen(start = 2147483648)]
+pub fn foo4() {}
+
 fn main() {}
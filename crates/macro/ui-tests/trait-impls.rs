@@ -0,0 +1,34 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct A;
+
+pub struct NotWasm;
+
+pub trait Ext {
+    type Item;
+    const N: u32;
+    fn ok(&self) -> u32;
+    fn skipped(&self) -> NotWasm;
+    fn invalid(&self) {}
+}
+
+#[wasm_bindgen]
+impl Ext for A {
+    type Item = NotWasm;
+    const N: u32 = 3;
+
+    fn ok(&self) -> u32 {
+        Self::N
+    }
+
+    #[wasm_bindgen(skip)]
+    fn skipped(&self) -> NotWasm {
+        NotWasm
+    }
+
+    #[wasm_bindgen(js_namespace = foo)]
+    fn invalid(&self) {}
+}
+
+fn main() {}
@@ -15,6 +15,7 @@ macro_rules! shared_api {
             structs: Vec<Struct<'a>>,
             typescript_custom_sections: Vec<&'a str>,
             local_modules: Vec<LocalModule<'a>>,
+            linked_files: Vec<LinkedFile<'a>>,
             inline_js: Vec<&'a str>,
             unique_crate_identifier: &'a str,
             package_json: Option<&'a str>,
@@ -47,6 +48,8 @@ macro_rules! shared_api {
             method: Option<MethodData<'a>>,
             structural: bool,
             function: Function<'a>,
+            vendor_prefixes: Vec<&'a str>,
+            link_to: Option<&'a str>,
         }
 
         struct MethodData<'a> {
@@ -76,6 +79,8 @@ macro_rules! shared_api {
         struct ImportStatic<'a> {
             name: &'a str,
             shim: &'a str,
+            setter: Option<&'a str>,
+            thread_local: bool,
         }
 
         struct ImportType<'a> {
@@ -88,11 +93,19 @@ macro_rules! shared_api {
 
         struct Export<'a> {
             class: Option<&'a str>,
+            js_namespace: Vec<&'a str>,
             comments: Vec<&'a str>,
             consumed: bool,
+            chain: bool,
             function: Function<'a>,
             method_kind: MethodKind<'a>,
-            start: bool,
+            cfg: Option<&'a str>,
+            start: Option<Start>,
+        }
+
+        struct Start {
+            priority: i32,
+            asyncness: bool,
         }
 
         struct Enum<'a> {
@@ -109,12 +122,27 @@ macro_rules! shared_api {
         struct Function<'a> {
             arg_names: Vec<String>,
             name: &'a str,
+            typescript_types: Vec<TypescriptType<'a>>,
+            arg_defaults: Vec<ArgDefault<'a>>,
+        }
+
+        struct TypescriptType<'a> {
+            name: &'a str,
+            ty: &'a str,
+        }
+
+        struct ArgDefault<'a> {
+            name: &'a str,
+            value: &'a str,
         }
 
         struct Struct<'a> {
             name: &'a str,
+            js_namespace: Vec<&'a str>,
             fields: Vec<StructField<'a>>,
             comments: Vec<&'a str>,
+            no_free: bool,
+            on_free: Option<&'a str>,
         }
 
         struct StructField<'a> {
@@ -127,6 +155,11 @@ macro_rules! shared_api {
             identifier: &'a str,
             contents: &'a str,
         }
+
+        struct LinkedFile<'a> {
+            path: &'a str,
+            contents: &'a [u8],
+        }
         }
     }; // end of mac case
 } // end of mac definition
@@ -184,3 +217,23 @@ pub fn version() -> String {
     }
     return v;
 }
+
+/// The definition of the programs encoded in the custom section, see
+/// `schema_hash`.
+const SCHEMA: &str = shared_api!(stringify);
+
+/// Returns a hash of the encoding of the programs in the custom section.
+///
+/// Unlike `SCHEMA_VERSION` this only changes when the encoding does, which
+/// lets the CLI tell whether it can still decode the output of another
+/// version of the macro. Whitespace is ignored as it depends on how the
+/// compiler pretty-prints the definition.
+pub fn schema_hash() -> String {
+    // 64-bit FNV-1a, which is stable across platforms and compilers.
+    let mut hash = 0xcbf29ce484222325u64;
+    for byte in SCHEMA.bytes().filter(|b| !b.is_ascii_whitespace()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
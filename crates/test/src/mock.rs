@@ -0,0 +1,230 @@
+//! Mocks of imported JS functions.
+//!
+//! Imports of `#[wasm_bindgen] extern` blocks which aren't imported from an
+//! ES module, like `Math.random`, `console.log` or methods of web APIs, are
+//! looked up on the global object every time they're called. A `Mock`
+//! replaces such a function with one recording its calls, so that tests can
+//! assert which JS APIs were called and with which arguments, without having
+//! to run in a browser providing them:
+//!
+//! ```ignore
+//! use wasm_bindgen_test::mock::Mock;
+//!
+//! #[wasm_bindgen_test]
+//! fn logs_greeting() {
+//!     let log = Mock::new("console.log");
+//!     greet("world");
+//!     assert!(log.was_called_with(&["Hello, world!".into()]));
+//! }
+//! ```
+//!
+//! The original function is restored when the `Mock` is dropped.
+//!
+//! Functions imported with `#[wasm_bindgen(module = "...")]` are bound when
+//! the wasm module is instantiated, so they can't be replaced afterwards.
+//! Those imported with a `js_namespace` (or as static methods of an imported
+//! class) are looked up on that object when they're called though, so they
+//! can be mocked with `Mock::on` given the namespace object:
+//!
+//! ```ignore
+//! #[wasm_bindgen(module = "/js/api.js")]
+//! extern "C" {
+//!     #[wasm_bindgen(js_name = api)]
+//!     static API: Object;
+//!     #[wasm_bindgen(js_namespace = api)]
+//!     fn fetch_user(id: u32) -> JsValue;
+//! }
+//!
+//! #[wasm_bindgen_test]
+//! fn fetches_user() {
+//!     let fetch_user = Mock::on(&API, "fetch_user");
+//!     // ...
+//! }
+//! ```
+
+use js_sys::{Array, Function, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// A JS function replaced with one recording its calls, restored on drop.
+///
+/// By default the mock returns `undefined`, see `returns`, `implementation`
+/// and `call_through` to change that.
+pub struct Mock {
+    path: String,
+    target: Object,
+    key: JsValue,
+    descriptor: JsValue,
+    previous: JsValue,
+    state: Object,
+    function: Function,
+}
+
+impl Mock {
+    /// Replaces the function at `path` with a mock.
+    ///
+    /// The `path` is a dot-separated list of properties, looked up starting
+    /// from the global object, like `"alert"`, `"console.log"` or
+    /// `"Document.prototype.createElement"`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the objects along `path` doesn't exist, or if the
+    /// property can't be redefined.
+    pub fn new(path: &str) -> Mock {
+        Mock::on(&js_sys::global(), path)
+    }
+
+    /// Replaces the function at `path` with a mock, like `new` but looking up
+    /// `path` starting from `target` instead of the global object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the objects along `path` doesn't exist, or if the
+    /// property can't be redefined.
+    pub fn on(target: &Object, path: &str) -> Mock {
+        let mut target = target.clone();
+        let mut keys = path.split('.');
+        let last = keys.next_back().unwrap();
+        for key in keys {
+            target = match Reflect::get(&target, &key.into()) {
+                Ok(value) if value.is_object() || value.is_function() => value.unchecked_into(),
+                _ => panic!("cannot mock `{}`, `{}` isn't an object", path, key),
+            };
+        }
+        let key = JsValue::from(last);
+        let descriptor =
+            Reflect::get_own_property_descriptor(&target, &key).unwrap_or(JsValue::UNDEFINED);
+        // The property may be inherited, in which case there's no descriptor
+        // to restore but the mock can still call through.
+        let previous = Reflect::get(&target, &key).unwrap_or(JsValue::UNDEFINED);
+
+        let state = Object::new();
+        set(&state, "calls", &Array::new());
+        set(&state, "receivers", &Array::new());
+        let function = Function::new_with_args(
+            "state",
+            "
+            return function() {
+                const args = Array.prototype.slice.call(arguments);
+                state.calls.push(args);
+                state.receivers.push(this);
+                if (state.implementation !== undefined)
+                    return state.implementation.apply(this, args);
+                return state.result;
+            };
+            ",
+        )
+        .call1(&JsValue::UNDEFINED, &state)
+        .unwrap_throw()
+        .unchecked_into::<Function>();
+
+        let attributes = Object::new();
+        set(&attributes, "value", &function);
+        set(&attributes, "writable", &JsValue::TRUE);
+        set(&attributes, "configurable", &JsValue::TRUE);
+        if Reflect::define_property(&target, &key, &attributes) != Ok(true) {
+            panic!("cannot mock `{}`, the property can't be redefined", path);
+        }
+
+        Mock {
+            path: path.to_string(),
+            target,
+            key,
+            descriptor,
+            previous,
+            state,
+            function,
+        }
+    }
+
+    /// Makes the mock return `value` from now on.
+    pub fn returns(&self, value: &JsValue) -> &Mock {
+        set(&self.state, "implementation", &JsValue::UNDEFINED);
+        set(&self.state, "result", value);
+        self
+    }
+
+    /// Makes the mock call `f` from now on, with the same `this` and
+    /// arguments, and return its result.
+    pub fn implementation(&self, f: &Function) -> &Mock {
+        set(&self.state, "implementation", f);
+        self
+    }
+
+    /// Makes the mock call the original function from now on, which turns it
+    /// into a spy only recording calls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mocked property wasn't a function.
+    pub fn call_through(&self) -> &Mock {
+        match self.previous.clone().dyn_into::<Function>() {
+            Ok(f) => self.implementation(&f),
+            Err(_) => panic!("cannot call through `{}`, it wasn't a function", self.path),
+        }
+    }
+
+    /// Returns the arguments of each call made to the mock so far.
+    pub fn calls(&self) -> Vec<Vec<JsValue>> {
+        to_vec(&get(&self.state, "calls"))
+            .into_iter()
+            .map(|args| to_vec(&args))
+            .collect()
+    }
+
+    /// Returns the `this` value of each call made to the mock so far.
+    pub fn receivers(&self) -> Vec<JsValue> {
+        to_vec(&get(&self.state, "receivers"))
+    }
+
+    /// Returns the number of calls made to the mock so far.
+    pub fn call_count(&self) -> usize {
+        get(&self.state, "calls").unchecked_into::<Array>().length() as usize
+    }
+
+    /// Returns whether one of the calls so far had exactly `args` as
+    /// arguments, compared with the JS `===` operator.
+    pub fn was_called_with(&self, args: &[JsValue]) -> bool {
+        self.calls().iter().any(|call| call.as_slice() == args)
+    }
+
+    /// Forgets the calls made to the mock so far.
+    pub fn reset(&self) {
+        set(&self.state, "calls", &Array::new());
+        set(&self.state, "receivers", &Array::new());
+    }
+
+    /// Returns the JS function which replaced the original one.
+    pub fn function(&self) -> &Function {
+        &self.function
+    }
+}
+
+impl Drop for Mock {
+    fn drop(&mut self) {
+        let restored = if self.descriptor.is_undefined() {
+            Reflect::delete_property(&self.target, &self.key)
+        } else {
+            Reflect::define_property(&self.target, &self.key, self.descriptor.unchecked_ref())
+        };
+        if restored != Ok(true) {
+            console_log!("failed to restore `{}` after mocking it", self.path);
+        }
+    }
+}
+
+fn get(obj: &Object, key: &str) -> JsValue {
+    Reflect::get(obj, &key.into()).unwrap_throw()
+}
+
+fn set(obj: &Object, key: &str, value: &JsValue) {
+    Reflect::set(obj, &key.into(), value).unwrap_throw();
+}
+
+fn to_vec(array: &JsValue) -> Vec<JsValue> {
+    let length = array.unchecked_ref::<Array>().length();
+    (0..length)
+        .map(|i| Reflect::get_u32(array, i).unwrap_throw())
+        .collect()
+}
@@ -0,0 +1,10 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct WithConsts {}
+
+#[wasm_bindgen]
+impl WithConsts {
+    pub const LIMIT: u32 = 10;
+    pub const LABEL: &'static str = "label";
+}
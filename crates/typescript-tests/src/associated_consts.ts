@@ -0,0 +1,4 @@
+import * as wbg from '../pkg/typescript_tests';
+
+const limit: number = wbg.WithConsts.LIMIT;
+const label: string = wbg.WithConsts.LABEL;
@@ -0,0 +1,21 @@
+use js_sys::Callback;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub fn read_file(path: &str, done: Callback<(Option<js_sys::Error>, String)>) {
+    done.invoke(None, format!("contents of {}", path)).unwrap();
+}
+
+#[wasm_bindgen]
+pub fn count_matches(filter: &Callback<(u32,), bool>, progress: Option<Callback<(f64,)>>) -> u32 {
+    let mut count = 0;
+    for i in 0..10 {
+        if filter.invoke(i).unwrap().is_truthy() {
+            count += 1;
+        }
+        if let Some(progress) = &progress {
+            progress.invoke(f64::from(i) / 10.0).unwrap();
+        }
+    }
+    count
+}
@@ -0,0 +1,10 @@
+import * as wbg from '../pkg/typescript_tests';
+
+wbg.read_file("a.txt", (err: any, data: string) => {
+  const _data: string = data;
+});
+
+const filter: (arg0: number) => boolean = n => n % 2 == 0;
+const _count: number = wbg.count_matches(filter);
+wbg.count_matches(filter, (progress: number) => {});
+wbg.count_matches(filter, undefined);
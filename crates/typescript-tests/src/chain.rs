@@ -0,0 +1,24 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct RequestBuilder {
+    retries: u32,
+}
+
+#[wasm_bindgen]
+impl RequestBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> RequestBuilder {
+        RequestBuilder { retries: 0 }
+    }
+
+    #[wasm_bindgen(chain)]
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.retries + 1
+    }
+}
@@ -0,0 +1,5 @@
+import * as wbg from '../pkg/typescript_tests';
+
+const builder: wbg.RequestBuilder = new wbg.RequestBuilder().retries(3).retries(4);
+const _a: number = builder.attempts();
+builder.free();
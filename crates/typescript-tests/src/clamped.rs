@@ -0,0 +1,20 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::Clamped;
+
+#[wasm_bindgen]
+pub fn clamped_vec(a: Clamped<Vec<u8>>) -> Clamped<Vec<u8>> {
+    a
+}
+
+#[wasm_bindgen]
+pub fn clamped_boxed(a: Clamped<Box<[u8]>>) -> Clamped<Box<[u8]>> {
+    a
+}
+
+#[wasm_bindgen]
+pub fn clamped_mut(_: Clamped<&mut [u8]>) {}
+
+#[wasm_bindgen]
+pub fn clamped_option(a: Option<Clamped<Vec<u8>>>) -> Option<Clamped<Vec<u8>>> {
+    a
+}
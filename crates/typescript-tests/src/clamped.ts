@@ -0,0 +1,6 @@
+import * as wbg from '../pkg/typescript_tests';
+
+const clamped_vec: (a: Uint8ClampedArray) => Uint8ClampedArray = wbg.clamped_vec;
+const clamped_boxed: (a: Uint8ClampedArray) => Uint8ClampedArray = wbg.clamped_boxed;
+const clamped_mut: (a: Uint8ClampedArray) => void = wbg.clamped_mut;
+const clamped_option: (a?: Uint8ClampedArray) => Uint8ClampedArray | undefined = wbg.clamped_option;
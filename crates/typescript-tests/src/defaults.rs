@@ -0,0 +1,11 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(defaults(limit = 10, name = "none"))]
+pub fn defaults_fn(a: u32, limit: Option<u32>, name: Option<String>) -> u32 {
+    a + limit.unwrap() + name.unwrap().len() as u32
+}
+
+#[wasm_bindgen(defaults(b = 1))]
+pub fn defaults_fn_required(b: u32) -> u32 {
+    b
+}
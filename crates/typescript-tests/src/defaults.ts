@@ -0,0 +1,4 @@
+import * as wbg from '../pkg/typescript_tests';
+
+const defaults_fn: (a: number, limit?: number, name?: string) => number = wbg.defaults_fn;
+const defaults_fn_required: (b?: number) => number = wbg.defaults_fn_required;
@@ -0,0 +1,15 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub enum Direction {
+    Up = 1,
+    Down = 3,
+}
+
+#[wasm_bindgen]
+pub fn flip(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Down,
+        Direction::Down => Direction::Up,
+    }
+}
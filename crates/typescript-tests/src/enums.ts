@@ -0,0 +1,15 @@
+import * as wbg from '../pkg/typescript_tests';
+
+function describe(direction: wbg.Direction): string {
+  switch (direction) {
+    case wbg.Direction.Up:
+      return 'up';
+    case wbg.Direction.Down:
+      return 'down';
+  }
+}
+
+const up: wbg.Direction = wbg.Direction.Up;
+const down: 3 = wbg.Direction.Down;
+const _a: string = describe(up);
+const _b: number = wbg.flip(down);
@@ -0,0 +1,21 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct Fields {
+    #[wasm_bindgen(readonly)]
+    pub id: u32,
+    pub weight: f64,
+    pub limit: Option<u32>,
+}
+
+#[wasm_bindgen]
+impl Fields {
+    #[wasm_bindgen(constructor)]
+    pub fn new(id: u32) -> Fields {
+        Fields {
+            id,
+            weight: 0.0,
+            limit: None,
+        }
+    }
+}
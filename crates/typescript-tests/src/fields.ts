@@ -0,0 +1,9 @@
+import * as wbg from '../pkg/typescript_tests';
+
+const fields: wbg.Fields = new wbg.Fields(1);
+const _id: number = fields.id;
+fields.weight = 2.5;
+const _weight: number = fields.weight;
+fields.limit = 10;
+fields.limit = undefined;
+const _limit: number | undefined = fields.limit;
@@ -0,0 +1,26 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(no_free)]
+pub struct PooledItem {}
+
+#[wasm_bindgen]
+impl PooledItem {
+    pub fn acquire() -> PooledItem {
+        PooledItem {}
+    }
+
+    pub fn release(self) {}
+}
+
+#[wasm_bindgen(on_free = close)]
+pub struct Resource {}
+
+#[wasm_bindgen]
+impl Resource {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Resource {
+        Resource {}
+    }
+
+    pub fn close(&mut self) {}
+}
@@ -0,0 +1,7 @@
+import * as wbg from '../pkg/typescript_tests';
+
+const item: wbg.PooledItem = wbg.PooledItem.acquire();
+item.release();
+
+const resource: wbg.Resource = new wbg.Resource();
+resource.free();
@@ -66,3 +66,22 @@ impl ColorWithGetterAndSetter {
         };
     }
 }
+
+#[wasm_bindgen]
+pub struct ColorDefaults {}
+
+#[wasm_bindgen]
+impl ColorDefaults {
+    #[wasm_bindgen(getter)]
+    pub fn alpha() -> u8 {
+        255
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn red() -> f64 {
+        0.0
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_red(_red: f64) {}
+}
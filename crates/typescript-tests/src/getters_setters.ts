@@ -9,3 +9,7 @@ colorWithSetter.r = 1;
 const colorWithGetterAndSetter: wbg.ColorWithGetterAndSetter = new wbg.ColorWithGetterAndSetter;
 colorWithGetterAndSetter.r = 1;
 const _b = colorWithGetterAndSetter.r;
+
+const _c: number = wbg.ColorDefaults.alpha;
+wbg.ColorDefaults.red = 0.5;
+const _d: number = wbg.ColorDefaults.red;
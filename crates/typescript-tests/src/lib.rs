@@ -1,5 +1,15 @@
+pub mod associated_consts;
+pub mod callbacks;
+pub mod chain;
+pub mod clamped;
 pub mod custom_section;
+pub mod defaults;
+pub mod enums;
+pub mod fields;
+pub mod free;
 pub mod getters_setters;
+pub mod maps;
 pub mod opt_args_and_ret;
 pub mod simple_fn;
 pub mod simple_struct;
+pub mod typescript_type;
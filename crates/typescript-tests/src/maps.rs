@@ -0,0 +1,12 @@
+use std::collections::{BTreeMap, HashMap};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub fn map_to_map(a: HashMap<String, u32>) -> HashMap<String, u32> {
+    a
+}
+
+#[wasm_bindgen(plain_object(return))]
+pub fn map_to_object(a: BTreeMap<String, String>) -> BTreeMap<String, String> {
+    a
+}
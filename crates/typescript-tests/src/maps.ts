@@ -0,0 +1,4 @@
+import * as wbg from '../pkg/typescript_tests';
+
+const map_to_map: (a: Map<string, any> | { [key: string]: any }) => Map<string, any> = wbg.map_to_map;
+const map_to_object: (a: Map<string, any> | { [key: string]: any }) => { [key: string]: any } = wbg.map_to_object;
@@ -0,0 +1,6 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_type(a = "string[]", return = "{ b: number }"))]
+pub fn typescript_type(a: JsValue, _b: u32) -> JsValue {
+    a
+}
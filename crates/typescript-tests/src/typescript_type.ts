@@ -0,0 +1,3 @@
+import * as wbg from '../pkg/typescript_tests';
+
+const typescript_type: (a: string[], b: number) => { b: number } = wbg.typescript_type;
@@ -1,3 +1,12 @@
-import initialize from "../../pkg/web/typescript_tests";
+import initialize, { InitInput, InitOutput, initSync } from "../../pkg/web/typescript_tests";
 
 const init: Promise<any> = initialize(".");
+
+const input: InitInput = new URL("typescript_tests_bg.wasm", "https://example.com/");
+const output: Promise<InitOutput> = initialize(input);
+output.then(exports => {
+  const memory: WebAssembly.Memory = exports.memory;
+});
+
+const fromBytes: InitOutput = initSync(new Uint8Array());
+const _memory: WebAssembly.Memory = fromBytes.memory;
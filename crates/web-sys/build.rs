@@ -22,21 +22,12 @@ fn main() {
 
 fn try_main() -> Result<(), failure::Error> {
     println!("cargo:rerun-if-changed=build.rs");
-    println!("cargo:rerun-if-changed=webidls/enabled");
 
-    let entries = fs::read_dir("webidls/enabled").context("reading webidls/enabled directory")?;
-    let mut source = SourceFile::default();
-    for entry in entries {
-        let entry = entry.context("getting webidls/enabled/*.webidl entry")?;
-        let path = entry.path();
-        if path.extension() != Some(OsStr::new("webidl")) {
-            continue;
-        }
-        println!("cargo:rerun-if-changed={}", path.display());
-        source = source
-            .add_file(&path)
-            .with_context(|_| format!("reading contents of file \"{}\"", path.display()))?;
-    }
+    // The unstable WebIDL is read after the stable one, into the same source
+    // file so that errors can be located in either of them.
+    let source = read_webidls("webidls/enabled", SourceFile::default())?;
+    let stable_len = source.contents.len();
+    let source = read_webidls("webidls/unstable", source)?;
 
     // Read our manifest, learn all `[feature]` directives with "toml parsing".
     // Use all these names to match against environment variables set by Cargo
@@ -74,7 +65,8 @@ fn try_main() -> Result<(), failure::Error> {
         Some(&allowed[..])
     };
 
-    let bindings = match wasm_bindgen_webidl::compile(&source.contents, allowed) {
+    let (stable, unstable) = source.contents.split_at(stable_len);
+    let bindings = match wasm_bindgen_webidl::compile(stable, unstable, allowed) {
         Ok(bindings) => bindings,
         Err(e) => match e.kind() {
             wasm_bindgen_webidl::ErrorKind::ParsingWebIDLSourcePos(pos) => {
@@ -116,3 +108,20 @@ fn try_main() -> Result<(), failure::Error> {
 
     Ok(())
 }
+
+fn read_webidls(dir: &str, mut source: SourceFile) -> Result<SourceFile, failure::Error> {
+    println!("cargo:rerun-if-changed={}", dir);
+    let entries = fs::read_dir(dir).with_context(|_| format!("reading {} directory", dir))?;
+    for entry in entries {
+        let entry = entry.with_context(|_| format!("getting {}/*.webidl entry", dir))?;
+        let path = entry.path();
+        if path.extension() != Some(OsStr::new("webidl")) {
+            continue;
+        }
+        println!("cargo:rerun-if-changed={}", path.display());
+        source = source
+            .add_file(&path)
+            .with_context(|_| format!("reading contents of file \"{}\"", path.display()))?;
+    }
+    Ok(source)
+}
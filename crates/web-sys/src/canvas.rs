@@ -0,0 +1,106 @@
+use crate::*;
+#[allow(unused_imports)]
+use wasm_bindgen::{JsCast, JsValue};
+
+// Generates typed wrappers around a canvas' `getContext`, which in WebIDL
+// returns an untyped object whose interface depends on the context id.
+macro_rules! context_getters {
+    ($canvas:ident: $(
+        $(#[$attr:meta])*
+        $name:ident, $name_with_options:ident => $id:tt as $context:ident;
+    )*) => ($(
+        impl $canvas {
+            $(#[$attr])*
+            pub fn $name(&self) -> Result<Option<$context>, JsValue> {
+                Ok(self.get_context($id)?.map(JsCast::unchecked_into))
+            }
+
+            $(#[$attr])*
+            ///
+            /// The `options` are passed along as `getContext`'s second
+            /// argument.
+            pub fn $name_with_options(
+                &self,
+                options: &JsValue,
+            ) -> Result<Option<$context>, JsValue> {
+                Ok(self
+                    .get_context_with_context_options($id, options)?
+                    .map(JsCast::unchecked_into))
+            }
+        }
+    )*)
+}
+
+#[cfg(feature = "HtmlCanvasElement")]
+context_getters! {
+    HtmlCanvasElement:
+
+    /// Returns the `"2d"` context of this canvas, or `None` if it already
+    /// has a context of another type.
+    ///
+    /// *This API requires the following crate features to be activated: `HtmlCanvasElement`, `CanvasRenderingContext2d`*
+    #[cfg(feature = "CanvasRenderingContext2d")]
+    get_context_2d, get_context_2d_with_options
+        => "2d" as CanvasRenderingContext2d;
+
+    /// Returns the `"webgl"` context of this canvas, or `None` if it already
+    /// has a context of another type.
+    ///
+    /// *This API requires the following crate features to be activated: `HtmlCanvasElement`, `WebGlRenderingContext`*
+    #[cfg(feature = "WebGlRenderingContext")]
+    get_context_webgl, get_context_webgl_with_options
+        => "webgl" as WebGlRenderingContext;
+
+    /// Returns the `"webgl2"` context of this canvas, or `None` if it already
+    /// has a context of another type.
+    ///
+    /// *This API requires the following crate features to be activated: `HtmlCanvasElement`, `WebGl2RenderingContext`*
+    #[cfg(feature = "WebGl2RenderingContext")]
+    get_context_webgl2, get_context_webgl2_with_options
+        => "webgl2" as WebGl2RenderingContext;
+
+    /// Returns the `"bitmaprenderer"` context of this canvas, or `None` if it
+    /// already has a context of another type.
+    ///
+    /// *This API requires the following crate features to be activated: `HtmlCanvasElement`, `ImageBitmapRenderingContext`*
+    #[cfg(feature = "ImageBitmapRenderingContext")]
+    get_context_bitmaprenderer, get_context_bitmaprenderer_with_options
+        => "bitmaprenderer" as ImageBitmapRenderingContext;
+}
+
+#[cfg(feature = "OffscreenCanvas")]
+context_getters! {
+    OffscreenCanvas:
+
+    /// Returns the `"2d"` context of this canvas, or `None` if it already
+    /// has a context of another type.
+    ///
+    /// *This API requires the following crate features to be activated: `OffscreenCanvas`, `OffscreenCanvasRenderingContext2d`*
+    #[cfg(feature = "OffscreenCanvasRenderingContext2d")]
+    get_context_2d, get_context_2d_with_options
+        => "2d" as OffscreenCanvasRenderingContext2d;
+
+    /// Returns the `"webgl"` context of this canvas, or `None` if it already
+    /// has a context of another type.
+    ///
+    /// *This API requires the following crate features to be activated: `OffscreenCanvas`, `WebGlRenderingContext`*
+    #[cfg(feature = "WebGlRenderingContext")]
+    get_context_webgl, get_context_webgl_with_options
+        => "webgl" as WebGlRenderingContext;
+
+    /// Returns the `"webgl2"` context of this canvas, or `None` if it already
+    /// has a context of another type.
+    ///
+    /// *This API requires the following crate features to be activated: `OffscreenCanvas`, `WebGl2RenderingContext`*
+    #[cfg(feature = "WebGl2RenderingContext")]
+    get_context_webgl2, get_context_webgl2_with_options
+        => "webgl2" as WebGl2RenderingContext;
+
+    /// Returns the `"bitmaprenderer"` context of this canvas, or `None` if it
+    /// already has a context of another type.
+    ///
+    /// *This API requires the following crate features to be activated: `OffscreenCanvas`, `ImageBitmapRenderingContext`*
+    #[cfg(feature = "ImageBitmapRenderingContext")]
+    get_context_bitmaprenderer, get_context_bitmaprenderer_with_options
+        => "bitmaprenderer" as ImageBitmapRenderingContext;
+}
@@ -0,0 +1,293 @@
+//! Helpers for sending requests with `fetch` and reading their responses.
+//!
+//! The raw `fetch` bindings take a `RequestInit` dictionary and return
+//! promises, which makes even simple requests fairly verbose. This module
+//! wraps them with a `RequestBuilder` taking Rust values and futures
+//! resolving to Rust values:
+//!
+//! ```no_run
+//! # use futures::Future;
+//! use web_sys::fetch::RequestBuilder;
+//!
+//! let body = RequestBuilder::new()
+//!     .method("POST")
+//!     .header("Content-Type", "text/plain")
+//!     .body_str("hello")
+//!     .send("/echo")
+//!     .and_then(|response| response.text());
+//! ```
+//!
+//! *This module requires the `fetch` feature to be activated*
+
+use crate::*;
+use futures::{try_ready, Async, Future, Poll};
+use js_sys::{Promise, Reflect, Uint8Array};
+use std::fmt;
+use std::ops::Deref;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+#[wasm_bindgen]
+extern "C" {
+    // `fetch` lives on both `Window` and `WorkerGlobalScope`, so import the
+    // global one to work in either.
+    #[wasm_bindgen(js_name = fetch)]
+    fn fetch_with_request(input: &Request) -> Promise;
+}
+
+/// A builder for the `RequestInit` dictionary passed to `fetch`.
+///
+/// Unlike `RequestInit` itself this accepts headers and bodies as Rust
+/// values, converting them to their JS counterparts in `build`.
+#[derive(Clone, Debug, Default)]
+pub struct RequestBuilder {
+    method: Option<String>,
+    headers: Vec<(String, String)>,
+    body: Option<JsValue>,
+    streaming: bool,
+    signal: Option<AbortSignal>,
+}
+
+impl RequestBuilder {
+    /// Creates a new builder for a `GET` request with no headers or body.
+    pub fn new() -> RequestBuilder {
+        RequestBuilder::default()
+    }
+
+    /// Sets the request method, like `"POST"`.
+    pub fn method(&mut self, method: &str) -> &mut RequestBuilder {
+        self.method = Some(method.to_string());
+        self
+    }
+
+    /// Appends a header to the request.
+    pub fn header(&mut self, name: &str, value: &str) -> &mut RequestBuilder {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Appends all headers of `headers`, for example a `HashMap<String,
+    /// String>` or a slice of `(&str, &str)` pairs.
+    pub fn headers<I, K, V>(&mut self, headers: I) -> &mut RequestBuilder
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        for (name, value) in headers {
+            self.header(name.as_ref(), value.as_ref());
+        }
+        self
+    }
+
+    /// Uses a copy of `body` as the request body.
+    pub fn body_bytes(&mut self, body: &[u8]) -> &mut RequestBuilder {
+        self.body(Uint8Array::from(body).into(), false)
+    }
+
+    /// Uses `body` as the request body.
+    pub fn body_str(&mut self, body: &str) -> &mut RequestBuilder {
+        self.body(body.into(), false)
+    }
+
+    /// Streams the request body from `body`.
+    ///
+    /// A Rust `Stream` can be passed here after converting it with
+    /// `wasm_bindgen_futures::stream::stream_to_readable_stream`.
+    ///
+    /// *This API requires the following crate features to be activated: `ReadableStream`*
+    #[cfg(feature = "ReadableStream")]
+    pub fn body_stream(&mut self, body: &ReadableStream) -> &mut RequestBuilder {
+        self.body(body.into(), true)
+    }
+
+    fn body(&mut self, body: JsValue, streaming: bool) -> &mut RequestBuilder {
+        self.body = Some(body);
+        self.streaming = streaming;
+        self
+    }
+
+    /// Aborts the request when `signal` is, see `AbortController`.
+    pub fn signal(&mut self, signal: &AbortSignal) -> &mut RequestBuilder {
+        self.signal = Some(signal.clone());
+        self
+    }
+
+    /// Builds the `RequestInit` dictionary, which fails if any of the headers
+    /// is invalid.
+    pub fn build(&self) -> Result<RequestInit, JsValue> {
+        let mut init = RequestInit::new();
+        if let Some(method) = &self.method {
+            init = init.method(method);
+        }
+        if !self.headers.is_empty() {
+            let headers = Headers::new()?;
+            for (name, value) in self.headers.iter() {
+                headers.append(name, value)?;
+            }
+            init = init.headers(&headers);
+        }
+        if let Some(body) = &self.body {
+            init = init.body(Some(body));
+        }
+        if self.streaming {
+            // Browsers reject streaming request bodies unless the request is
+            // explicitly half duplex.
+            Reflect::set(init.as_ref(), &"duplex".into(), &"half".into())?;
+        }
+        if let Some(signal) = &self.signal {
+            init = init.signal(Some(signal));
+        }
+        Ok(init)
+    }
+
+    /// Builds a `Request` for `url`.
+    pub fn request(&self, url: &str) -> Result<Request, JsValue> {
+        Request::new_with_str_and_init(url, &self.build()?)
+    }
+
+    /// Builds a `Request` for `url` and sends it with `fetch`.
+    pub fn send(&self, url: &str) -> ResponseFuture {
+        match self.request(url) {
+            Ok(request) => fetch(&request),
+            Err(e) => ResponseFuture {
+                inner: JsFuture::from(Promise::reject(&e)),
+            },
+        }
+    }
+}
+
+/// Sends `request` with the global `fetch` function, in either a window or a
+/// worker.
+pub fn fetch(request: &Request) -> ResponseFuture {
+    ResponseFuture {
+        inner: JsFuture::from(fetch_with_request(request)),
+    }
+}
+
+/// A future resolving to the response of a `fetch`.
+///
+/// This fails if the request couldn't be sent at all, HTTP error statuses
+/// still resolve to a `FetchResponse`.
+pub struct ResponseFuture {
+    inner: JsFuture,
+}
+
+impl Future for ResponseFuture {
+    type Item = FetchResponse;
+    type Error = JsValue;
+
+    fn poll(&mut self) -> Poll<FetchResponse, JsValue> {
+        let response = try_ready!(self.inner.poll());
+        Ok(Async::Ready(FetchResponse {
+            response: response.unchecked_into(),
+        }))
+    }
+}
+
+impl fmt::Debug for ResponseFuture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ResponseFuture {{ ... }}")
+    }
+}
+
+/// A `Response` with typed readers for its body.
+///
+/// The underlying `Response` is accessible through `Deref`, for example to
+/// check its `status`.
+#[derive(Clone, Debug)]
+pub struct FetchResponse {
+    response: Response,
+}
+
+impl FetchResponse {
+    /// Wraps a `Response` obtained elsewhere.
+    pub fn new(response: Response) -> FetchResponse {
+        FetchResponse { response }
+    }
+
+    /// Returns the underlying `Response`.
+    pub fn into_inner(self) -> Response {
+        self.response
+    }
+
+    /// Reads the whole body as bytes.
+    pub fn bytes(self) -> BodyFuture<Vec<u8>> {
+        BodyFuture::new(self.response.array_buffer(), |buf| {
+            Ok(Uint8Array::new(&buf).to_vec())
+        })
+    }
+
+    /// Reads the whole body as a string decoded from UTF-8.
+    pub fn text(self) -> BodyFuture<String> {
+        BodyFuture::new(self.response.text(), |text| {
+            text.as_string()
+                .ok_or_else(|| JsValue::from("response text isn't a string"))
+        })
+    }
+
+    /// Reads the whole body as JSON and deserializes it into a `T`.
+    ///
+    /// *This API requires the following crate features to be activated: `serde-serialize`*
+    #[cfg(feature = "serde-serialize")]
+    pub fn json_into_serde<T>(self) -> BodyFuture<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        BodyFuture::new(self.response.json(), |json| {
+            json.into_serde().map_err(|e| JsValue::from(e.to_string()))
+        })
+    }
+}
+
+impl Deref for FetchResponse {
+    type Target = Response;
+
+    fn deref(&self) -> &Response {
+        &self.response
+    }
+}
+
+impl From<Response> for FetchResponse {
+    fn from(response: Response) -> FetchResponse {
+        FetchResponse::new(response)
+    }
+}
+
+/// A future resolving to the converted body of a `FetchResponse`.
+pub struct BodyFuture<T> {
+    inner: JsFuture,
+    convert: fn(JsValue) -> Result<T, JsValue>,
+}
+
+impl<T> BodyFuture<T> {
+    fn new(
+        promise: Result<Promise, JsValue>,
+        convert: fn(JsValue) -> Result<T, JsValue>,
+    ) -> BodyFuture<T> {
+        // Reading a body which was already read throws, report that through
+        // the future like any other error.
+        let promise = promise.unwrap_or_else(|e| Promise::reject(&e));
+        BodyFuture {
+            inner: JsFuture::from(promise),
+            convert,
+        }
+    }
+}
+
+impl<T> Future for BodyFuture<T> {
+    type Item = T;
+    type Error = JsValue;
+
+    fn poll(&mut self) -> Poll<T, JsValue> {
+        let value = try_ready!(self.inner.poll());
+        (self.convert)(value).map(Async::Ready)
+    }
+}
+
+impl<T> fmt::Debug for BodyFuture<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BodyFuture {{ ... }}")
+    }
+}
@@ -0,0 +1,371 @@
+//! Futures and streams on top of the event based IndexedDB bindings.
+//!
+//! IndexedDB reports the outcome of each request through `success` and
+//! `error` events, which are awkward to chain from Rust. This module wraps
+//! requests and transactions in futures, and cursors in streams:
+//!
+//! ```no_run
+//! # use futures::Future;
+//! # use wasm_bindgen::JsValue;
+//! use web_sys::indexeddb::{open_database, RequestFuture};
+//!
+//! let factory = web_sys::window().unwrap().indexed_db().unwrap().unwrap();
+//! let value = open_database(&factory, "db", 1, |db, _event| {
+//!     db.create_object_store("store").unwrap();
+//! })
+//! .unwrap()
+//! .and_then(|db| {
+//!     let store = db.transaction_with_str("store")?.object_store("store")?;
+//!     Ok(RequestFuture::<JsValue>::new(&store.get(&"key".into())?))
+//! })
+//! .flatten();
+//! ```
+//!
+//! Note that transactions commit as soon as there's no pending request left
+//! once their callbacks have run, so any request continuing a transaction has
+//! to be made from within the futures of its previous requests.
+//!
+//! *This module requires the `indexeddb` feature to be activated*
+
+use crate::*;
+use futures::task::{self, Task};
+use futures::{try_ready, Async, Future, Poll, Stream};
+use std::cell::RefCell;
+use std::fmt;
+use std::marker;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+// The latest outcome of a request or transaction, filled in by its event
+// handlers and taken by the future or stream polling it.
+struct Shared {
+    outcome: Option<Result<JsValue, JsValue>>,
+    task: Option<Task>,
+}
+
+impl Shared {
+    fn new() -> Rc<RefCell<Shared>> {
+        Rc::new(RefCell::new(Shared {
+            outcome: None,
+            task: None,
+        }))
+    }
+
+    fn finish(this: &RefCell<Shared>, outcome: Result<JsValue, JsValue>) {
+        let mut this = this.borrow_mut();
+        this.outcome = Some(outcome);
+        if let Some(task) = this.task.take() {
+            task.notify();
+        }
+    }
+
+    fn poll(this: &RefCell<Shared>) -> Poll<JsValue, JsValue> {
+        let mut this = this.borrow_mut();
+        match this.outcome.take() {
+            Some(outcome) => outcome.map(Async::Ready),
+            None => {
+                this.task = Some(task::current());
+                Ok(Async::NotReady)
+            }
+        }
+    }
+}
+
+// Handlers for the `success` and `error` events of a request, which are
+// removed again once dropped.
+struct RequestListener {
+    request: IdbRequest,
+    shared: Rc<RefCell<Shared>>,
+    _success: Closure<dyn FnMut()>,
+    _error: Closure<dyn FnMut()>,
+}
+
+impl RequestListener {
+    fn new(request: &IdbRequest) -> RequestListener {
+        let shared = Shared::new();
+        let success = {
+            let request = request.clone();
+            let shared = shared.clone();
+            Closure::wrap(Box::new(move || {
+                Shared::finish(&shared, request.result());
+            }) as Box<dyn FnMut()>)
+        };
+        let error = {
+            let request = request.clone();
+            let shared = shared.clone();
+            Closure::wrap(Box::new(move || {
+                Shared::finish(&shared, Err(request_error(&request)));
+            }) as Box<dyn FnMut()>)
+        };
+        request.set_onsuccess(Some(success.as_ref().unchecked_ref()));
+        request.set_onerror(Some(error.as_ref().unchecked_ref()));
+
+        // Wrapping a request which already completed won't fire any event,
+        // so pick up its outcome right away.
+        if request.ready_state() == IdbRequestReadyState::Done {
+            let error = request_error(request);
+            let outcome = if error.is_null() {
+                request.result()
+            } else {
+                Err(error)
+            };
+            Shared::finish(&shared, outcome);
+        }
+
+        RequestListener {
+            request: request.clone(),
+            shared,
+            _success: success,
+            _error: error,
+        }
+    }
+}
+
+impl Drop for RequestListener {
+    fn drop(&mut self) {
+        self.request.set_onsuccess(None);
+        self.request.set_onerror(None);
+    }
+}
+
+fn request_error(request: &IdbRequest) -> JsValue {
+    match request.error() {
+        Ok(error) => error.map(JsValue::from).unwrap_or(JsValue::NULL),
+        Err(e) => e,
+    }
+}
+
+/// A future resolving to the result of an `IdbRequest`.
+///
+/// The result is cast to `T` without any checks, use `JsValue` to inspect it
+/// manually. A failed request resolves to its `DomException`.
+pub struct RequestFuture<T> {
+    listener: Option<RequestListener>,
+    _marker: marker::PhantomData<T>,
+}
+
+impl<T: JsCast> RequestFuture<T> {
+    /// Starts listening for the outcome of `request`.
+    pub fn new(request: &IdbRequest) -> RequestFuture<T> {
+        RequestFuture {
+            listener: Some(RequestListener::new(request)),
+            _marker: marker::PhantomData,
+        }
+    }
+}
+
+impl<T: JsCast> Future for RequestFuture<T> {
+    type Item = T;
+    type Error = JsValue;
+
+    fn poll(&mut self) -> Poll<T, JsValue> {
+        let result = match &self.listener {
+            Some(listener) => Shared::poll(&listener.shared),
+            None => panic!("cannot poll a finished RequestFuture"),
+        };
+        if let Ok(Async::NotReady) = result {
+            return Ok(Async::NotReady);
+        }
+        self.listener = None;
+        result.map(|value| value.map(JsCast::unchecked_into))
+    }
+}
+
+impl<T> fmt::Debug for RequestFuture<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RequestFuture {{ ... }}")
+    }
+}
+
+/// A future resolving to an `IdbDatabase` once it's opened.
+///
+/// Created by `open_database`.
+pub struct OpenDatabaseFuture {
+    request: IdbOpenDbRequest,
+    inner: RequestFuture<IdbDatabase>,
+    _upgrade: Closure<dyn FnMut(IdbVersionChangeEvent)>,
+}
+
+/// Opens the database `name` at `version`, calling `on_upgrade` with the
+/// database and the `upgradeneeded` event if it doesn't exist yet or has an
+/// older version.
+///
+/// `on_upgrade` runs within the `versionchange` transaction, so it's the only
+/// place where object stores and indexes can be created.
+pub fn open_database<F>(
+    factory: &IdbFactory,
+    name: &str,
+    version: u32,
+    mut on_upgrade: F,
+) -> Result<OpenDatabaseFuture, JsValue>
+where
+    F: FnMut(&IdbDatabase, &IdbVersionChangeEvent) + 'static,
+{
+    let request = factory.open_with_u32(name, version)?;
+    let upgrade = {
+        let request = request.clone();
+        Closure::wrap(Box::new(move |event: IdbVersionChangeEvent| {
+            if let Ok(db) = request.result() {
+                on_upgrade(db.unchecked_ref(), &event);
+            }
+        }) as Box<dyn FnMut(IdbVersionChangeEvent)>)
+    };
+    request.set_onupgradeneeded(Some(upgrade.as_ref().unchecked_ref()));
+    Ok(OpenDatabaseFuture {
+        inner: RequestFuture::new(&request),
+        request,
+        _upgrade: upgrade,
+    })
+}
+
+impl Future for OpenDatabaseFuture {
+    type Item = IdbDatabase;
+    type Error = JsValue;
+
+    fn poll(&mut self) -> Poll<IdbDatabase, JsValue> {
+        self.inner.poll()
+    }
+}
+
+impl Drop for OpenDatabaseFuture {
+    fn drop(&mut self) {
+        self.request.set_onupgradeneeded(None);
+    }
+}
+
+impl fmt::Debug for OpenDatabaseFuture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OpenDatabaseFuture {{ ... }}")
+    }
+}
+
+/// A future resolving once an `IdbTransaction` completes.
+///
+/// An aborted transaction resolves to the `DomException` which caused it, or
+/// `null` if it was aborted explicitly.
+pub struct TransactionFuture {
+    transaction: IdbTransaction,
+    shared: Rc<RefCell<Shared>>,
+    _complete: Closure<dyn FnMut()>,
+    _abort: Closure<dyn FnMut()>,
+}
+
+impl TransactionFuture {
+    /// Starts listening for the outcome of `transaction`.
+    pub fn new(transaction: &IdbTransaction) -> TransactionFuture {
+        let shared = Shared::new();
+        let complete = {
+            let shared = shared.clone();
+            Closure::wrap(Box::new(move || {
+                Shared::finish(&shared, Ok(JsValue::UNDEFINED));
+            }) as Box<dyn FnMut()>)
+        };
+        // Errors of requests within the transaction abort it unless they're
+        // handled, so only `abort` marks the transaction as failed.
+        let abort = {
+            let transaction = transaction.clone();
+            let shared = shared.clone();
+            Closure::wrap(Box::new(move || {
+                let error = transaction.error().map(JsValue::from);
+                Shared::finish(&shared, Err(error.unwrap_or(JsValue::NULL)));
+            }) as Box<dyn FnMut()>)
+        };
+        transaction.set_oncomplete(Some(complete.as_ref().unchecked_ref()));
+        transaction.set_onabort(Some(abort.as_ref().unchecked_ref()));
+        TransactionFuture {
+            transaction: transaction.clone(),
+            shared,
+            _complete: complete,
+            _abort: abort,
+        }
+    }
+}
+
+impl Future for TransactionFuture {
+    type Item = ();
+    type Error = JsValue;
+
+    fn poll(&mut self) -> Poll<(), JsValue> {
+        try_ready!(Shared::poll(&self.shared));
+        Ok(Async::Ready(()))
+    }
+}
+
+impl Drop for TransactionFuture {
+    fn drop(&mut self) {
+        self.transaction.set_oncomplete(None);
+        self.transaction.set_onabort(None);
+    }
+}
+
+impl fmt::Debug for TransactionFuture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TransactionFuture {{ ... }}")
+    }
+}
+
+/// A stream of the positions of a cursor.
+///
+/// This wraps the request returned by methods like
+/// `IdbObjectStore::open_cursor`, yielding the cursor once for each record
+/// it visits. `T` is `IdbCursorWithValue` for `open_cursor` and `IdbCursor`
+/// for `open_key_cursor`.
+///
+/// The cursor only moves to the next record when the next item is requested,
+/// so the yielded cursor can be used to read, update or delete the current
+/// record until then.
+pub struct CursorStream<T> {
+    listener: Option<RequestListener>,
+    cursor: Option<IdbCursor>,
+    _marker: marker::PhantomData<T>,
+}
+
+impl<T: JsCast> CursorStream<T> {
+    /// Starts iterating over the records of the cursor `request`.
+    pub fn new(request: &IdbRequest) -> CursorStream<T> {
+        CursorStream {
+            listener: Some(RequestListener::new(request)),
+            cursor: None,
+            _marker: marker::PhantomData,
+        }
+    }
+}
+
+impl<T: JsCast> Stream for CursorStream<T> {
+    type Item = T;
+    type Error = JsValue;
+
+    fn poll(&mut self) -> Poll<Option<T>, JsValue> {
+        let result = match &self.listener {
+            Some(listener) => {
+                if let Some(cursor) = self.cursor.take() {
+                    cursor.continue_()?;
+                }
+                Shared::poll(&listener.shared)
+            }
+            None => return Ok(Async::Ready(None)),
+        };
+        match result {
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Ok(Async::Ready(ref value)) if value.is_null() => {
+                self.listener = None;
+                Ok(Async::Ready(None))
+            }
+            Ok(Async::Ready(value)) => {
+                self.cursor = Some(value.clone().unchecked_into());
+                Ok(Async::Ready(Some(value.unchecked_into())))
+            }
+            Err(e) => {
+                self.listener = None;
+                Err(e)
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for CursorStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CursorStream {{ ... }}")
+    }
+}
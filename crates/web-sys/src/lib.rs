@@ -10,6 +10,9 @@
 //! type). Using an API requires enabling the features for all types used in the
 //! API, and APIs should mention in the documentation what features they
 //! require.
+//!
+//! A few lowercase features instead enable small helper modules on top of the
+//! raw bindings, like `fetch` for the `fetch` module.
 
 #![doc(html_root_url = "https://docs.rs/web-sys/0.2")]
 #![allow(deprecated)]
@@ -31,4 +34,37 @@ pub fn window() -> Option<Window> {
     js_sys::global().dyn_into::<Window>().ok()
 }
 
+#[cfg(any(feature = "HtmlCanvasElement", feature = "OffscreenCanvas"))]
+mod canvas;
+
+#[cfg(feature = "fetch")]
+pub mod fetch;
+
+#[cfg(feature = "indexeddb")]
+pub mod indexeddb;
+
+#[cfg(feature = "EventTarget")]
+mod listener;
+#[cfg(feature = "EventTarget")]
+pub use crate::listener::{EventTargetExt, ListenerHandle};
+
+#[cfg(any(
+    all(
+        feature = "IntersectionObserver",
+        feature = "IntersectionObserverEntry"
+    ),
+    all(feature = "MutationObserver", feature = "MutationRecord"),
+    all(feature = "ResizeObserver", feature = "ResizeObserverEntry"),
+))]
+mod observer;
+#[cfg(any(
+    all(
+        feature = "IntersectionObserver",
+        feature = "IntersectionObserverEntry"
+    ),
+    all(feature = "MutationObserver", feature = "MutationRecord"),
+    all(feature = "ResizeObserver", feature = "ResizeObserverEntry"),
+))]
+pub use crate::observer::ObserverHandle;
+
 include!(env!("BINDINGS"));
@@ -0,0 +1,142 @@
+use crate::*;
+use std::fmt;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// Registration of event listeners backed by Rust closures.
+///
+/// This is implemented for `EventTarget` and all of its subclasses.
+pub trait EventTargetExt {
+    /// Calls `callback` with each event of type `type_` dispatched to this
+    /// target, until the returned handle is dropped.
+    ///
+    /// Events are cast to `E` without any checks, so `E` should be the event
+    /// interface used for `type_`, like `MouseEvent` for `"click"`.
+    fn add_listener<E, F>(&self, type_: &str, callback: F) -> ListenerHandle
+    where
+        E: JsCast,
+        F: FnMut(E) + 'static;
+
+    /// Like `add_listener`, but configures the listener with `options`, for
+    /// example to make it passive.
+    ///
+    /// *This API requires the following crate features to be activated: `EventTarget`, `AddEventListenerOptions`*
+    #[cfg(feature = "AddEventListenerOptions")]
+    fn add_listener_with_options<E, F>(
+        &self,
+        type_: &str,
+        options: &AddEventListenerOptions,
+        callback: F,
+    ) -> ListenerHandle
+    where
+        E: JsCast,
+        F: FnMut(E) + 'static;
+}
+
+impl<T: AsRef<EventTarget>> EventTargetExt for T {
+    fn add_listener<E, F>(&self, type_: &str, callback: F) -> ListenerHandle
+    where
+        E: JsCast,
+        F: FnMut(E) + 'static,
+    {
+        let target = self.as_ref();
+        ListenerHandle::new(target, type_, false, callback, |f| {
+            target.add_event_listener_with_callback(type_, f)
+        })
+    }
+
+    #[cfg(feature = "AddEventListenerOptions")]
+    fn add_listener_with_options<E, F>(
+        &self,
+        type_: &str,
+        options: &AddEventListenerOptions,
+        callback: F,
+    ) -> ListenerHandle
+    where
+        E: JsCast,
+        F: FnMut(E) + 'static,
+    {
+        // Listeners are removed by type, callback and capture flag, so keep
+        // track of the latter for `Drop`.
+        let capture = options.get_capture().unwrap_or(false);
+        let target = self.as_ref();
+        ListenerHandle::new(target, type_, capture, callback, |f| {
+            target
+                .add_event_listener_with_callback_and_add_event_listener_options(type_, f, options)
+        })
+    }
+}
+
+/// An event listener registered with `EventTargetExt`.
+///
+/// This owns the `Closure` handed to JavaScript, and dropping it removes the
+/// listener before freeing the closure.
+pub struct ListenerHandle {
+    target: EventTarget,
+    type_: String,
+    capture: bool,
+    callback: Option<Closure<dyn FnMut(JsValue)>>,
+}
+
+impl ListenerHandle {
+    fn new<E, F, A>(
+        target: &EventTarget,
+        type_: &str,
+        capture: bool,
+        mut callback: F,
+        add: A,
+    ) -> ListenerHandle
+    where
+        E: JsCast,
+        F: FnMut(E) + 'static,
+        A: FnOnce(&js_sys::Function) -> Result<(), JsValue>,
+    {
+        let callback = Closure::wrap(Box::new(move |event: JsValue| {
+            callback(event.unchecked_into());
+        }) as Box<dyn FnMut(JsValue)>);
+        // `addEventListener` only throws when called on something which isn't
+        // an `EventTarget`, which the type of `target` rules out.
+        add(callback.as_ref().unchecked_ref()).unwrap_throw();
+        ListenerHandle {
+            target: target.clone(),
+            type_: type_.to_string(),
+            capture,
+            callback: Some(callback),
+        }
+    }
+
+    /// Returns the target this listener is registered on.
+    pub fn target(&self) -> &EventTarget {
+        &self.target
+    }
+
+    /// Keeps the listener registered for the rest of the program, leaking its
+    /// closure.
+    pub fn forget(mut self) {
+        if let Some(callback) = self.callback.take() {
+            callback.forget();
+        }
+    }
+}
+
+impl Drop for ListenerHandle {
+    fn drop(&mut self) {
+        if let Some(callback) = self.callback.take() {
+            drop(self.target.remove_event_listener_with_callback_and_bool(
+                &self.type_,
+                callback.as_ref().unchecked_ref(),
+                self.capture,
+            ));
+        }
+    }
+}
+
+impl fmt::Debug for ListenerHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ListenerHandle")
+            .field("target", &self.target)
+            .field("type_", &self.type_)
+            .field("capture", &self.capture)
+            .finish()
+    }
+}
@@ -0,0 +1,137 @@
+use crate::*;
+use js_sys::{Array, Function};
+use std::fmt;
+use std::ops::Deref;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+#[wasm_bindgen]
+extern "C" {
+    type Observer;
+
+    #[wasm_bindgen(method)]
+    fn disconnect(this: &Observer);
+}
+
+/// An observer along with the Rust closure backing its callback.
+///
+/// This is returned by constructors like `ResizeObserver::with_callback` and
+/// keeps the `Closure` handed to JavaScript alive for exactly as long as the
+/// observer is in use, so there's no need to leak it with `Closure::forget`.
+/// Dropping an `ObserverHandle` disconnects the observer and frees the
+/// closure.
+///
+/// The observer itself is accessible through `Deref`.
+pub struct ObserverHandle<T: JsCast> {
+    observer: T,
+    _callback: Closure<dyn FnMut(Array)>,
+}
+
+impl<T: JsCast> ObserverHandle<T> {
+    fn new<E, F, C>(mut callback: F, construct: C) -> Result<ObserverHandle<T>, JsValue>
+    where
+        E: JsCast,
+        F: FnMut(Vec<E>) + 'static,
+        C: FnOnce(&Function) -> Result<T, JsValue>,
+    {
+        // Observer callbacks also receive the observer itself as a second
+        // argument, which we don't need since the caller already has it.
+        let callback = Closure::wrap(Box::new(move |entries: Array| {
+            let mut list = Vec::with_capacity(entries.length() as usize);
+            entries.for_each(&mut |entry, _, _| list.push(entry.unchecked_into()));
+            callback(list);
+        }) as Box<dyn FnMut(Array)>);
+        let observer = construct(callback.as_ref().unchecked_ref())?;
+        Ok(ObserverHandle {
+            observer,
+            _callback: callback,
+        })
+    }
+}
+
+impl<T: JsCast> Deref for ObserverHandle<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.observer
+    }
+}
+
+impl<T: JsCast + fmt::Debug> fmt::Debug for ObserverHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ObserverHandle")
+            .field("observer", &self.observer)
+            .finish()
+    }
+}
+
+impl<T: JsCast> Drop for ObserverHandle<T> {
+    fn drop(&mut self) {
+        self.observer.unchecked_ref::<Observer>().disconnect();
+    }
+}
+
+#[cfg(all(
+    feature = "IntersectionObserver",
+    feature = "IntersectionObserverEntry"
+))]
+impl IntersectionObserver {
+    /// Creates a new `IntersectionObserver` which calls `callback` with the
+    /// observed entries, keeping `callback` alive for as long as the returned
+    /// handle.
+    ///
+    /// *This API requires the following crate features to be activated: `IntersectionObserver`, `IntersectionObserverEntry`*
+    pub fn with_callback<F>(callback: F) -> Result<ObserverHandle<IntersectionObserver>, JsValue>
+    where
+        F: FnMut(Vec<IntersectionObserverEntry>) + 'static,
+    {
+        ObserverHandle::new(callback, IntersectionObserver::new)
+    }
+
+    /// Creates a new `IntersectionObserver` configured with `options` which
+    /// calls `callback` with the observed entries, keeping `callback` alive for
+    /// as long as the returned handle.
+    ///
+    /// *This API requires the following crate features to be activated: `IntersectionObserver`, `IntersectionObserverEntry`, `IntersectionObserverInit`*
+    #[cfg(feature = "IntersectionObserverInit")]
+    pub fn with_callback_and_options<F>(
+        callback: F,
+        options: &IntersectionObserverInit,
+    ) -> Result<ObserverHandle<IntersectionObserver>, JsValue>
+    where
+        F: FnMut(Vec<IntersectionObserverEntry>) + 'static,
+    {
+        ObserverHandle::new(callback, |f| {
+            IntersectionObserver::new_with_options(f, options)
+        })
+    }
+}
+
+#[cfg(all(feature = "MutationObserver", feature = "MutationRecord"))]
+impl MutationObserver {
+    /// Creates a new `MutationObserver` which calls `callback` with the
+    /// observed mutation records, keeping `callback` alive for as long as the
+    /// returned handle.
+    ///
+    /// *This API requires the following crate features to be activated: `MutationObserver`, `MutationRecord`*
+    pub fn with_callback<F>(callback: F) -> Result<ObserverHandle<MutationObserver>, JsValue>
+    where
+        F: FnMut(Vec<MutationRecord>) + 'static,
+    {
+        ObserverHandle::new(callback, MutationObserver::new)
+    }
+}
+
+#[cfg(all(feature = "ResizeObserver", feature = "ResizeObserverEntry"))]
+impl ResizeObserver {
+    /// Creates a new `ResizeObserver` which calls `callback` with the observed
+    /// entries, keeping `callback` alive for as long as the returned handle.
+    ///
+    /// *This API requires the following crate features to be activated: `ResizeObserver`, `ResizeObserverEntry`*
+    pub fn with_callback<F>(callback: F) -> Result<ObserverHandle<ResizeObserver>, JsValue>
+    where
+        F: FnMut(Vec<ResizeObserverEntry>) + 'static,
+    {
+        ObserverHandle::new(callback, ResizeObserver::new)
+    }
+}
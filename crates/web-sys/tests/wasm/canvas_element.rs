@@ -0,0 +1,33 @@
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+use web_sys::HtmlCanvasElement;
+
+fn new_canvas() -> HtmlCanvasElement {
+    let document = web_sys::window().unwrap().document().unwrap();
+    document
+        .create_element("canvas")
+        .unwrap()
+        .dyn_into::<HtmlCanvasElement>()
+        .unwrap()
+}
+
+#[wasm_bindgen_test]
+fn get_context_2d() {
+    let canvas = new_canvas();
+    let context = canvas.get_context_2d().unwrap().unwrap();
+    assert_eq!(
+        JsValue::from(context.canvas().unwrap()),
+        JsValue::from(canvas.clone())
+    );
+
+    // A canvas only ever has one type of context.
+    assert!(canvas.get_context_webgl().unwrap().is_none());
+}
+
+#[wasm_bindgen_test]
+fn get_context_bitmaprenderer() {
+    let canvas = new_canvas();
+    let context = canvas.get_context_bitmaprenderer().unwrap();
+    assert!(context.is_some());
+    assert!(canvas.get_context_2d().unwrap().is_none());
+}
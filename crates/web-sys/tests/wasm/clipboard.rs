@@ -0,0 +1,38 @@
+use js_sys::{Object, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+use web_sys::{Clipboard, ClipboardItem, ClipboardItemOptions, PresentationStyle};
+
+#[wasm_bindgen_test]
+fn clipboard() {
+    // Only exposed in secure contexts, which the test server may not be.
+    let navigator = web_sys::window().unwrap().navigator();
+    if !Reflect::has(&navigator, &JsValue::from_str("clipboard")).unwrap() {
+        return;
+    }
+    assert!(navigator.clipboard().is_instance_of::<Clipboard>());
+}
+
+#[wasm_bindgen_test]
+fn clipboard_item() {
+    let options = ClipboardItemOptions::new().presentation_style(PresentationStyle::Inline);
+    assert_eq!(
+        options.get_presentation_style(),
+        Some(PresentationStyle::Inline)
+    );
+
+    if !Reflect::has(&js_sys::global(), &JsValue::from_str("ClipboardItem")).unwrap() {
+        return;
+    }
+    let items = Object::new();
+    Reflect::set(
+        &items,
+        &JsValue::from_str("text/plain"),
+        &JsValue::from_str("hi"),
+    )
+    .unwrap();
+    let item = ClipboardItem::new_with_options(&items, &options).unwrap();
+    let types = item.types();
+    assert_eq!(types.length(), 1);
+    assert_eq!(types.pop(), "text/plain");
+}
@@ -0,0 +1,78 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+use web_sys::{
+    AddEventListenerOptions, CustomEvent, CustomEventInit, Event, EventTarget, EventTargetExt,
+};
+
+#[wasm_bindgen_test]
+fn add_listener() {
+    let target = EventTarget::new().unwrap();
+    let count = Rc::new(Cell::new(0));
+    let handle = {
+        let count = count.clone();
+        target.add_listener("foo", move |event: Event| {
+            assert_eq!(event.type_(), "foo");
+            count.set(count.get() + 1);
+        })
+    };
+
+    target.dispatch_event(&Event::new("foo").unwrap()).unwrap();
+    target.dispatch_event(&Event::new("bar").unwrap()).unwrap();
+    assert_eq!(count.get(), 1);
+
+    drop(handle);
+    target.dispatch_event(&Event::new("foo").unwrap()).unwrap();
+    assert_eq!(count.get(), 1);
+}
+
+#[wasm_bindgen_test]
+fn typed_event() {
+    let target = EventTarget::new().unwrap();
+    let detail = Rc::new(Cell::new(0.0));
+    let _handle = {
+        let detail = detail.clone();
+        target.add_listener("custom", move |event: CustomEvent| {
+            detail.set(event.detail().as_f64().unwrap());
+        })
+    };
+
+    let init = CustomEventInit::new().detail(&JsValue::from(3));
+    let event = CustomEvent::new_with_event_init_dict("custom", &init).unwrap();
+    target.dispatch_event(&event).unwrap();
+    assert_eq!(detail.get(), 3.0);
+}
+
+#[wasm_bindgen_test]
+fn listener_with_options() {
+    let target = EventTarget::new().unwrap();
+    let count = Rc::new(Cell::new(0));
+    let options = AddEventListenerOptions::new().capture(true).once(true);
+    let handle = {
+        let count = count.clone();
+        target.add_listener_with_options("foo", &options, move |_: Event| {
+            count.set(count.get() + 1);
+        })
+    };
+
+    target.dispatch_event(&Event::new("foo").unwrap()).unwrap();
+    target.dispatch_event(&Event::new("foo").unwrap()).unwrap();
+    assert_eq!(count.get(), 1);
+    drop(handle);
+}
+
+#[wasm_bindgen_test]
+fn forget() {
+    let target = EventTarget::new().unwrap();
+    let count = Rc::new(Cell::new(0));
+    {
+        let count = count.clone();
+        target
+            .add_listener("foo", move |_: Event| count.set(count.get() + 1))
+            .forget();
+    }
+
+    target.dispatch_event(&Event::new("foo").unwrap()).unwrap();
+    assert_eq!(count.get(), 1);
+}
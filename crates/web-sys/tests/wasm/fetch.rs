@@ -0,0 +1,92 @@
+use futures::Future;
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+use web_sys::fetch::{FetchResponse, RequestBuilder};
+use web_sys::{AbortController, Response};
+
+#[wasm_bindgen_test]
+fn request_builder() {
+    let mut headers = BTreeMap::new();
+    headers.insert("x-a", "1");
+    headers.insert("x-b", "2");
+    let request = RequestBuilder::new()
+        .method("PUT")
+        .header("content-type", "text/plain")
+        .headers(headers)
+        .header("x-a", "3")
+        .body_str("hello")
+        .request("https://example.com/")
+        .unwrap();
+    assert_eq!(request.method(), "PUT");
+    assert_eq!(request.url(), "https://example.com/");
+    let headers = request.headers();
+    assert_eq!(
+        headers.get("Content-Type").unwrap(),
+        Some("text/plain".to_string()),
+    );
+    assert_eq!(headers.get("x-a").unwrap(), Some("1, 3".to_string()));
+    assert_eq!(headers.get("x-b").unwrap(), Some("2".to_string()));
+}
+
+#[wasm_bindgen_test]
+fn request_builder_invalid_header() {
+    assert!(RequestBuilder::new().header("", "x").build().is_err());
+}
+
+#[wasm_bindgen_test]
+fn request_builder_signal() {
+    let controller = AbortController::new().unwrap();
+    let request = RequestBuilder::new()
+        .signal(&controller.signal())
+        .request("https://example.com/")
+        .unwrap();
+    assert!(!request.signal().aborted());
+    controller.abort();
+    assert!(request.signal().aborted());
+}
+
+#[wasm_bindgen_test(async)]
+fn send() -> impl Future<Item = (), Error = JsValue> {
+    RequestBuilder::new()
+        .send("data:text/plain,hello")
+        .and_then(|response| {
+            assert!(response.ok());
+            response.text()
+        })
+        .map(|text| assert_eq!(text, "hello"))
+}
+
+#[wasm_bindgen_test(async)]
+fn response_bytes() -> impl Future<Item = (), Error = JsValue> {
+    let mut bytes = [1, 3, 5];
+    let response = Response::new_with_opt_u8_array(Some(&mut bytes)).unwrap();
+    FetchResponse::new(response)
+        .bytes()
+        .map(|bytes| assert_eq!(bytes, [1, 3, 5]))
+}
+
+#[wasm_bindgen_test(async)]
+fn response_json() -> impl Future<Item = (), Error = JsValue> {
+    let response = Response::new_with_opt_str(Some(r#"{"a": 1, "b": 2}"#)).unwrap();
+    FetchResponse::new(response)
+        .json_into_serde::<BTreeMap<String, u32>>()
+        .map(|map| {
+            assert_eq!(map.len(), 2);
+            assert_eq!(map["a"], 1);
+            assert_eq!(map["b"], 2);
+        })
+}
+
+#[wasm_bindgen_test(async)]
+fn body_read_twice() -> impl Future<Item = (), Error = JsValue> {
+    let response = Response::new_with_opt_str(Some("hello")).unwrap();
+    let second = FetchResponse::new(Clone::clone(&response));
+    FetchResponse::new(response).text().then(move |text| {
+        assert_eq!(text.unwrap(), "hello");
+        second.text().then(|text| {
+            assert!(text.is_err());
+            Ok(())
+        })
+    })
+}
@@ -1,8 +1,85 @@
+use futures::{Future, Stream};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_test::*;
 use web_sys;
+use web_sys::indexeddb::{
+    open_database, CursorStream, OpenDatabaseFuture, RequestFuture, TransactionFuture,
+};
+use web_sys::{DomException, IdbCursorWithValue, IdbTransactionMode};
 
 #[wasm_bindgen_test]
 fn accessor_works() {
     let window = web_sys::window().unwrap();
     assert!(window.indexed_db().unwrap().is_some());
 }
+
+fn open(name: &str) -> OpenDatabaseFuture {
+    let factory = web_sys::window().unwrap().indexed_db().unwrap().unwrap();
+    open_database(&factory, name, 1, |db, event| {
+        assert_eq!(event.old_version(), 0.0);
+        db.create_object_store("store").unwrap();
+    })
+    .unwrap()
+}
+
+#[wasm_bindgen_test(async)]
+fn request_future() -> impl Future<Item = (), Error = JsValue> {
+    open("wasm-bindgen-request-future").and_then(|db| {
+        let transaction = db
+            .transaction_with_str_and_mode("store", IdbTransactionMode::Readwrite)
+            .unwrap();
+        let store = transaction.object_store("store").unwrap();
+        let put = store.put_with_key(&"value".into(), &"key".into()).unwrap();
+        RequestFuture::<JsValue>::new(&put).and_then(move |key| {
+            assert_eq!(key, "key");
+            let get = store.get(&"key".into()).unwrap();
+            RequestFuture::<JsValue>::new(&get)
+                .map(|value| assert_eq!(value, "value"))
+                .join(TransactionFuture::new(&transaction))
+                .map(|_| ())
+        })
+    })
+}
+
+#[wasm_bindgen_test(async)]
+fn request_error() -> impl Future<Item = (), Error = JsValue> {
+    open("wasm-bindgen-request-error").and_then(|db| {
+        let transaction = db
+            .transaction_with_str_and_mode("store", IdbTransactionMode::Readwrite)
+            .unwrap();
+        let store = transaction.object_store("store").unwrap();
+        store.put_with_key(&1.into(), &"key".into()).unwrap();
+        let add = store.add_with_key(&2.into(), &"key".into()).unwrap();
+        RequestFuture::<JsValue>::new(&add).then(move |result| {
+            let error: DomException = result.unwrap_err().dyn_into().unwrap();
+            assert_eq!(error.name(), "ConstraintError");
+            TransactionFuture::new(&transaction).then(|result| {
+                assert!(result.is_err());
+                Ok(())
+            })
+        })
+    })
+}
+
+#[wasm_bindgen_test(async)]
+fn cursor_stream() -> impl Future<Item = (), Error = JsValue> {
+    open("wasm-bindgen-cursor-stream").and_then(|db| {
+        let transaction = db
+            .transaction_with_str_and_mode("store", IdbTransactionMode::Readwrite)
+            .unwrap();
+        let store = transaction.object_store("store").unwrap();
+        for i in 0..3 {
+            store.put_with_key(&(i * 10).into(), &i.into()).unwrap();
+        }
+        TransactionFuture::new(&transaction).and_then(move |()| {
+            let transaction = db.transaction_with_str("store").unwrap();
+            let store = transaction.object_store("store").unwrap();
+            let request = store.open_cursor().unwrap();
+            CursorStream::<IdbCursorWithValue>::new(&request)
+                .map(|cursor| cursor.value().unwrap().as_f64().unwrap())
+                .collect()
+                .map(|values| assert_eq!(values, [0.0, 10.0, 20.0]))
+        })
+    })
+}
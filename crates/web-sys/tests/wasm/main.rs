@@ -15,10 +15,14 @@ pub mod anchor_element;
 pub mod body_element;
 pub mod br_element;
 pub mod button_element;
+pub mod canvas_element;
+pub mod clipboard;
 pub mod console;
 pub mod div_element;
 pub mod element;
 pub mod event;
+pub mod event_target;
+pub mod fetch;
 pub mod head_element;
 pub mod headers;
 pub mod heading_element;
@@ -36,6 +40,7 @@ pub mod location;
 pub mod meta_element;
 pub mod meter_element;
 pub mod mod_elements;
+pub mod mutation_observer;
 pub mod olist_element;
 pub mod optgroup_element;
 pub mod option_element;
@@ -44,6 +49,7 @@ pub mod output_element;
 pub mod paragraph_element;
 pub mod param_element;
 pub mod performance;
+pub mod permissions;
 pub mod pre_element;
 pub mod progress_element;
 pub mod quote_element;
@@ -51,11 +57,14 @@ pub mod response;
 pub mod rtc_rtp_transceiver_direction;
 pub mod script_element;
 pub mod select_element;
+pub mod share;
 pub mod slot_element;
 pub mod span_element;
 pub mod style_element;
 pub mod table_element;
 pub mod title_element;
+pub mod web_codecs;
+pub mod web_transport;
 pub mod whitelisted_immutable_slices;
 pub mod xpath_result;
 
@@ -0,0 +1,46 @@
+use futures::sync::oneshot;
+use futures::Future;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+use web_sys::{MutationObserver, MutationObserverInit, MutationRecord};
+
+#[wasm_bindgen_test(async)]
+fn with_callback() -> impl Future<Item = (), Error = JsValue> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+
+    let (tx, rx) = oneshot::channel();
+    let mut tx = Some(tx);
+    let observer = MutationObserver::with_callback(move |records: Vec<MutationRecord>| {
+        if let Some(tx) = tx.take() {
+            drop(tx.send(records));
+        }
+    })
+    .unwrap();
+    let options = MutationObserverInit::new().attributes(true);
+    observer.observe_with_options(&element, &options).unwrap();
+    element.set_attribute("title", "hello").unwrap();
+
+    rx.map(move |records| {
+        drop(observer);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].type_(), "attributes");
+        assert_eq!(records[0].attribute_name(), Some("title".to_string()));
+    })
+    .map_err(|_| unreachable!())
+}
+
+#[wasm_bindgen_test]
+fn dropping_disconnects() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+
+    let observer = MutationObserver::with_callback(|_: Vec<MutationRecord>| {}).unwrap();
+    let raw: MutationObserver = (*observer).clone();
+    let options = MutationObserverInit::new().attributes(true);
+    observer.observe_with_options(&element, &options).unwrap();
+    drop(observer);
+
+    element.set_attribute("title", "hello").unwrap();
+    assert_eq!(raw.take_records().length(), 0);
+}
@@ -0,0 +1,45 @@
+use futures::Future;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+use web_sys::{
+    CameraDevicePermissionDescriptor, MidiPermissionDescriptor, PermissionDescriptor,
+    PermissionName, PermissionState, PermissionStatus, PushPermissionDescriptor,
+};
+
+#[wasm_bindgen_test]
+fn descriptors() {
+    let push = PushPermissionDescriptor::new(PermissionName::Push).user_visible_only(true);
+    assert_eq!(push.get_name(), PermissionName::Push);
+    assert_eq!(push.get_user_visible_only(), Some(true));
+
+    let midi = MidiPermissionDescriptor::new(PermissionName::Midi);
+    assert_eq!(midi.get_name(), PermissionName::Midi);
+    assert_eq!(midi.get_sysex(), None);
+    assert_eq!(midi.sysex(true).get_sysex(), Some(true));
+
+    let camera = CameraDevicePermissionDescriptor::new(PermissionName::Camera)
+        .device_id("default")
+        .pan_tilt_zoom(true);
+    assert_eq!(camera.get_name(), PermissionName::Camera);
+    assert_eq!(camera.get_device_id(), Some("default".to_string()));
+    assert_eq!(camera.get_pan_tilt_zoom(), Some(true));
+}
+
+#[wasm_bindgen_test(async)]
+fn query() -> impl Future<Item = (), Error = JsValue> {
+    let permissions = web_sys::window()
+        .unwrap()
+        .navigator()
+        .permissions()
+        .unwrap();
+    let descriptor = PermissionDescriptor::new(PermissionName::Notifications);
+    let promise = permissions.query(descriptor.unchecked_ref()).unwrap();
+    JsFuture::from(promise).map(|status| {
+        let status = status.unchecked_into::<PermissionStatus>();
+        match status.state() {
+            PermissionState::Granted | PermissionState::Denied | PermissionState::Prompt => {}
+            state => panic!("unexpected permission state {:?}", state),
+        }
+    })
+}
@@ -26,14 +26,12 @@ fn rtc_rtp_transceiver_direction() -> Box<dyn Future<Item = (), Error = JsValue>
         return Box::new(Ok(()).into_future());
     }
 
-    let mut tr_init: RtcRtpTransceiverInit = RtcRtpTransceiverInit::new();
+    let tr_init: RtcRtpTransceiverInit =
+        RtcRtpTransceiverInit::new().direction(RtcRtpTransceiverDirection::Sendonly);
 
     let pc1: RtcPeerConnection = RtcPeerConnection::new().unwrap();
 
-    let tr1: RtcRtpTransceiver = pc1.add_transceiver_with_str_and_init(
-        "audio",
-        tr_init.direction(RtcRtpTransceiverDirection::Sendonly),
-    );
+    let tr1: RtcRtpTransceiver = pc1.add_transceiver_with_str_and_init("audio", &tr_init);
     assert_eq!(tr1.direction(), RtcRtpTransceiverDirection::Sendonly);
     assert_eq!(tr1.current_direction(), None);
 
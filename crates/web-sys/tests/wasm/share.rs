@@ -0,0 +1,28 @@
+use js_sys::{Array, Reflect};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+use web_sys::ShareData;
+
+#[wasm_bindgen_test]
+fn share_data() {
+    let data = ShareData::new()
+        .title("wasm-bindgen")
+        .text("hello")
+        .url("https://example.com/")
+        .files(&Array::new());
+    assert_eq!(data.get_title(), Some("wasm-bindgen".to_string()));
+    assert_eq!(data.get_text(), Some("hello".to_string()));
+    assert_eq!(data.get_url(), Some("https://example.com/".to_string()));
+    assert_eq!(data.get_files().unwrap().length(), 0);
+    assert_eq!(ShareData::new().get_title(), None);
+}
+
+#[wasm_bindgen_test]
+fn can_share() {
+    // Only some platforms implement the Web Share API.
+    let navigator = web_sys::window().unwrap().navigator();
+    if !Reflect::has(&navigator, &JsValue::from_str("canShare")).unwrap() {
+        return;
+    }
+    assert!(!navigator.can_share_with_data(&ShareData::new()));
+}
@@ -0,0 +1,67 @@
+use js_sys::{Function, Reflect, Uint8Array};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+use web_sys::{
+    CodecState, EncodedVideoChunk, EncodedVideoChunkInit, EncodedVideoChunkType,
+    HardwareAcceleration, LatencyMode, VideoEncoder, VideoEncoderConfig, VideoEncoderInit,
+};
+
+// Not every browser the tests run in implements WebCodecs yet, in which case
+// only the dictionaries can be checked.
+fn supported(name: &str) -> bool {
+    Reflect::has(&js_sys::global(), &JsValue::from_str(name)).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn video_encoder_config() {
+    let config = VideoEncoderConfig::new("vp8", 480, 640)
+        .bitrate(1_000_000.0)
+        .framerate(30.0)
+        .hardware_acceleration(HardwareAcceleration::PreferSoftware)
+        .latency_mode(LatencyMode::Realtime);
+    assert_eq!(config.get_codec(), "vp8");
+    assert_eq!(config.get_width(), 640);
+    assert_eq!(config.get_height(), 480);
+    assert_eq!(config.get_display_width(), None);
+    assert_eq!(config.get_bitrate(), Some(1_000_000.0));
+    assert_eq!(config.get_framerate(), Some(30.0));
+    assert_eq!(
+        config.get_hardware_acceleration(),
+        Some(HardwareAcceleration::PreferSoftware)
+    );
+    assert_eq!(config.get_latency_mode(), Some(LatencyMode::Realtime));
+}
+
+#[wasm_bindgen_test]
+fn encoded_video_chunk() {
+    if !supported("EncodedVideoChunk") {
+        return;
+    }
+    let data = Uint8Array::from(&[1u8, 2, 3, 4][..]);
+    let init = EncodedVideoChunkInit::new(&data, 10.0, EncodedVideoChunkType::Key).duration(5.0);
+    let chunk = EncodedVideoChunk::new(&init).unwrap();
+    assert_eq!(chunk.type_(), EncodedVideoChunkType::Key);
+    assert_eq!(chunk.timestamp(), 10.0);
+    assert_eq!(chunk.duration(), Some(5.0));
+    assert_eq!(chunk.byte_length(), 4);
+
+    let mut copy = [0u8; 4];
+    chunk.copy_to_with_u8_array(&mut copy).unwrap();
+    assert_eq!(copy, [1, 2, 3, 4]);
+}
+
+#[wasm_bindgen_test]
+fn video_encoder() {
+    if !supported("VideoEncoder") {
+        return;
+    }
+    let noop = Function::new_no_args("");
+    let encoder = VideoEncoder::new(&VideoEncoderInit::new(&noop, &noop)).unwrap();
+    assert_eq!(encoder.state(), CodecState::Unconfigured);
+    assert_eq!(encoder.encode_queue_size(), 0);
+    encoder.close().unwrap();
+    assert_eq!(encoder.state(), CodecState::Closed);
+    assert!(encoder
+        .configure(&VideoEncoderConfig::new("vp8", 480, 640))
+        .is_err());
+}
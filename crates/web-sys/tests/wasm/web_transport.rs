@@ -0,0 +1,67 @@
+use js_sys::{Array, Reflect, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+use web_sys::{
+    WebTransport, WebTransportCloseInfo, WebTransportError, WebTransportErrorInit,
+    WebTransportErrorSource, WebTransportHash, WebTransportOptions,
+};
+
+// Not every browser the tests run in implements WebTransport yet, in which
+// case only the dictionaries can be checked.
+fn supported(name: &str) -> bool {
+    Reflect::has(&js_sys::global(), &JsValue::from_str(name)).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn options() {
+    let hash = WebTransportHash::new()
+        .algorithm("sha-256")
+        .value(&Uint8Array::new_with_length(32));
+    let options = WebTransportOptions::new()
+        .allow_pooling(true)
+        .server_certificate_hashes(&Array::of1(hash.as_ref()));
+    assert_eq!(options.get_allow_pooling(), Some(true));
+
+    let hashes = options.get_server_certificate_hashes().unwrap();
+    assert_eq!(hashes.length(), 1);
+    let hash = hashes.pop().unchecked_into::<WebTransportHash>();
+    assert_eq!(hash.get_algorithm(), Some("sha-256".to_string()));
+    let value = hash.get_value().unwrap().unchecked_into::<Uint8Array>();
+    assert_eq!(value.length(), 32);
+
+    assert_eq!(WebTransportOptions::new().get_allow_pooling(), None);
+}
+
+#[wasm_bindgen_test]
+fn close_info() {
+    let info = WebTransportCloseInfo::new().close_code(42).reason("done");
+    assert_eq!(info.get_close_code(), Some(42));
+    assert_eq!(info.get_reason(), Some("done".to_string()));
+}
+
+#[wasm_bindgen_test]
+fn rejects_insecure_urls() {
+    if !supported("WebTransport") {
+        return;
+    }
+    assert!(WebTransport::new("http://localhost:4433/").is_err());
+    let options = WebTransportOptions::new().allow_pooling(false);
+    assert!(WebTransport::new_with_options("http://localhost:4433/", &options).is_err());
+}
+
+#[wasm_bindgen_test]
+fn error() {
+    if !supported("WebTransportError") {
+        return;
+    }
+    let init = WebTransportErrorInit::new()
+        .stream_error_code(Some(7))
+        .message("reset");
+    let error = WebTransportError::new_with_init(&init).unwrap();
+    assert_eq!(error.source(), WebTransportErrorSource::Stream);
+    assert_eq!(error.stream_error_code(), Some(7));
+    assert_eq!(error.message(), "reset");
+
+    let error = WebTransportError::new().unwrap();
+    assert_eq!(error.stream_error_code(), None);
+}
@@ -24,8 +24,7 @@ fn smoke() {
     A::new().c(1).g(2).h(3).d(4);
     B::new().c(1).g(2).h(3).d(4).a(5).b(6);
 
-    let mut c = C::new();
-    c.a(1).b(2).c(3).d(4).e(5).f(6).g(7).h(8);
+    let c = C::new().a(1).b(2).c(3).d(4).e(5).f(6).g(7).h(8);
     assert_dict_c(&c);
     assert_dict_c2(c.clone());
     assert_dict_c3(Some(&c));
@@ -50,10 +49,31 @@ fn many_types() {
 
 #[wasm_bindgen_test]
 fn required() {
-    assert_dict_required(Required::new(3, "a").c(4));
+    assert_dict_required(&Required::new(3, "a").c(4));
 }
 
 #[wasm_bindgen_test]
 fn correct_casing_in_js() {
-    assert_camel_case(PreserveNames::new().wierd_field_name(1));
+    assert_camel_case(&PreserveNames::new().wierd_field_name(1));
+}
+
+#[wasm_bindgen_test]
+fn getters() {
+    let c = C::new().a(1).c(3).e(5).h(8);
+    assert_eq!(c.get_a(), Some(1));
+    assert_eq!(c.get_b(), None);
+    assert_eq!(c.get_c(), Some(3));
+    assert_eq!(c.get_e(), Some(5));
+    assert_eq!(c.get_h(), Some(8));
+    assert_eq!(mk_dict_a().get_c(), None);
+
+    let r = Required::new(3, "a");
+    assert_eq!(r.get_a(), 3);
+    assert_eq!(r.get_b(), "a");
+    assert_eq!(r.get_c(), None);
+    let r = r.c(4);
+    assert_eq!(r.get_c(), Some(4));
+
+    let p = PreserveNames::new().wierd_field_name(1);
+    assert_eq!(p.get_wierd_field_name(), Some(1));
 }
@@ -9,7 +9,9 @@ pub mod consts;
 pub mod dictionary;
 pub mod enums;
 pub mod global;
+pub mod maplike;
 pub mod namespace;
 pub mod no_interface;
 pub mod simple;
 pub mod throws;
+pub mod unions;
@@ -0,0 +1,22 @@
+const strictEqual = require('assert').strictEqual;
+
+global.TestReadonlyMap = class extends Map {
+  constructor() {
+    super([['a', 1], ['b', 2]]);
+  }
+};
+
+global.TestMap = class extends Map {};
+
+global.TestSet = class extends Set {
+  delete(value, verbose) {
+    strictEqual(verbose, true);
+    return super.delete(value);
+  }
+};
+
+global.TestObservableArray = class {
+  constructor() {
+    this.items = ['a'];
+  }
+};
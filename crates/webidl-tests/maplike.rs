@@ -0,0 +1,75 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+include!(concat!(env!("OUT_DIR"), "/maplike.rs"));
+
+fn strings(iter: js_sys::Iterator) -> Vec<String> {
+    iter.into_iter()
+        .map(|s| s.unwrap().as_string().unwrap())
+        .collect()
+}
+
+#[wasm_bindgen_test]
+fn readonly_maplike() {
+    let map = TestReadonlyMap::new().unwrap();
+    assert_eq!(map.size(), 2);
+    assert_eq!(map.get("a"), Some(1));
+    assert_eq!(map.get("c"), None);
+    assert!(map.has("b"));
+    assert!(!map.has("c"));
+    assert_eq!(strings(map.keys()), ["a", "b"]);
+    let values = map
+        .values()
+        .into_iter()
+        .map(|v| v.unwrap().as_f64().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(values, [1.0, 2.0]);
+    assert_eq!(map.entries().into_iter().count(), 2);
+}
+
+#[wasm_bindgen_test]
+fn maplike() {
+    let map = TestMap::new().unwrap();
+    map.set("a", 1).set("b", 2);
+    assert_eq!(map.size(), 2);
+    assert_eq!(map.get("b"), Some(2));
+    assert!(map.delete("a"));
+    assert!(!map.delete("a"));
+    assert_eq!(strings(map.keys()), ["b"]);
+    map.clear();
+    assert_eq!(map.size(), 0);
+}
+
+#[wasm_bindgen_test]
+fn setlike() {
+    let set = TestSet::new().unwrap();
+    set.add("a").add("b").add("a");
+    assert_eq!(set.size(), 2);
+    assert!(set.has("a"));
+    assert_eq!(strings(set.values()), ["a", "b"]);
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen2 = seen.clone();
+    let callback = Closure::wrap(Box::new(move |value: JsValue| {
+        seen2.borrow_mut().push(value.as_string().unwrap());
+    }) as Box<dyn FnMut(JsValue)>);
+    set.for_each(callback.as_ref().unchecked_ref());
+    assert_eq!(*seen.borrow(), ["a", "b"]);
+
+    // The interface declares its own `delete`, which takes precedence.
+    assert!(set.delete("a", true));
+    assert_eq!(strings(set.keys()), ["b"]);
+}
+
+#[wasm_bindgen_test]
+fn observable_array() {
+    let x = TestObservableArray::new().unwrap();
+    let items = x.items();
+    assert_eq!(items.length(), 1);
+    assert_eq!(items.pop(), "a");
+    x.set_items(&js_sys::Array::of2(&"b".into(), &"c".into()));
+    assert_eq!(x.items().length(), 2);
+}
@@ -0,0 +1,26 @@
+global.UnionA = class {};
+global.UnionB = class {};
+
+const name = x => {
+  if (x === null)
+    return 'null';
+  if (x instanceof UnionA)
+    return 'a';
+  if (x instanceof UnionB)
+    return 'b';
+  if (x instanceof UnionTaker)
+    return 'taker';
+  return 'unknown';
+};
+
+global.UnionTaker = class {
+  take(x) {
+    return name(x);
+  }
+  takeNullable(x) {
+    return name(x);
+  }
+  takeNested(x) {
+    return name(x);
+  }
+};
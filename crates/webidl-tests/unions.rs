@@ -0,0 +1,27 @@
+use wasm_bindgen_test::*;
+
+include!(concat!(env!("OUT_DIR"), "/unions.rs"));
+
+#[wasm_bindgen_test]
+fn take() {
+    let taker = UnionTaker::new().unwrap();
+    let a = UnionA::new().unwrap();
+    let b = UnionB::new().unwrap();
+    assert_eq!(taker.take(UnionAOrB::UnionA(&a)), "a");
+    assert_eq!(taker.take(UnionAOrB::UnionB(&b)), "b");
+    assert_eq!(taker.take((&a).into()), "a");
+}
+
+#[wasm_bindgen_test]
+fn nullable() {
+    let taker = UnionTaker::new().unwrap();
+    let b = UnionB::new().unwrap();
+    assert_eq!(taker.take_nullable(Some((&b).into())), "b");
+    assert_eq!(taker.take_nullable(None), "null");
+}
+
+#[wasm_bindgen_test]
+fn nested() {
+    let taker = UnionTaker::new().unwrap();
+    assert_eq!(taker.take_nested((&taker).into()), "taker");
+}
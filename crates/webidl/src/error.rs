@@ -19,6 +19,9 @@ pub enum ErrorKind {
     /// Failed to parse a WebIDL file.
     #[fail(display = "parsing WebIDL source text")]
     ParsingWebIDLSource,
+    /// Some of the allowed types extend interfaces which aren't allowed.
+    #[fail(display = "types are missing their parent interfaces")]
+    MissingParentTypes,
 }
 
 /// The error type for this crate.
@@ -7,6 +7,7 @@
 //! Only `interface`s, `dictionary`s, `enum`s and `mixin`s can
 //! be partial.
 
+use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -38,6 +39,10 @@ pub(crate) struct FirstPassRecord<'src> {
     pub(crate) callbacks: BTreeSet<&'src str>,
     pub(crate) callback_interfaces: BTreeMap<&'src str, CallbackInterfaceData<'src>>,
     pub(crate) immutable_slice_whitelist: BTreeSet<&'static str>,
+    /// The unions of interfaces taken as arguments, mapping the name of the
+    /// enum generated for each of them to the names of its members. This is
+    /// filled in while generating bindings rather than during the first pass.
+    pub(crate) unions: RefCell<BTreeMap<String, Vec<String>>>,
 }
 
 /// We need to collect interface data during the first pass, to be used later.
@@ -52,6 +57,10 @@ pub(crate) struct InterfaceData<'src> {
     pub(crate) operations: BTreeMap<OperationId<'src>, OperationData<'src>>,
     pub(crate) superclass: Option<&'src str>,
     pub(crate) definition_attributes: Option<&'src ExtendedAttributeList<'src>>,
+    /// The `maplike<K, V>` declaration of this interface, if any
+    pub(crate) maplike: Option<&'src MaplikeInterfaceMember<'src>>,
+    /// The `setlike<T>` declaration of this interface, if any
+    pub(crate) setlike: Option<&'src SetlikeInterfaceMember<'src>>,
 }
 
 /// We need to collect mixin data during the first pass, to be used later.
@@ -426,9 +435,11 @@ impl<'src> FirstPass<'src, &'src str> for weedle::interface::InterfaceMember<'sr
                 log::warn!("Unsupported WebIDL iterable interface member: {:?}", self);
                 Ok(())
             }
-            // TODO
-            InterfaceMember::Maplike(_) => {
-                log::warn!("Unsupported WebIDL Maplike interface member: {:?}", self);
+            InterfaceMember::Maplike(maplike) => {
+                if util::is_chrome_only(&maplike.attributes) {
+                    return Ok(());
+                }
+                record.interfaces.get_mut(self_name).unwrap().maplike = Some(maplike);
                 Ok(())
             }
             InterfaceMember::Stringifier(_) => {
@@ -438,8 +449,11 @@ impl<'src> FirstPass<'src, &'src str> for weedle::interface::InterfaceMember<'sr
                 );
                 Ok(())
             }
-            InterfaceMember::Setlike(_) => {
-                log::warn!("Unsupported WebIDL Setlike interface member: {:?}", self);
+            InterfaceMember::Setlike(setlike) => {
+                if util::is_chrome_only(&setlike.attributes) {
+                    return Ok(());
+                }
+                record.interfaces.get_mut(self_name).unwrap().setlike = Some(setlike);
                 Ok(())
             }
         }
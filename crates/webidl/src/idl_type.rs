@@ -636,6 +636,26 @@ impl<'a> IdlType<'a> {
         }
     }
 
+    /// Returns the member interfaces of a (possibly nullable) union which only
+    /// consists of interfaces, possibly nested.
+    ///
+    /// These unions aren't flattened in arguments, but rather passed as an
+    /// enum over all their members.
+    pub(crate) fn interface_union_members(&self) -> Option<Vec<&'a str>> {
+        let idl_types = match self {
+            IdlType::Union(_) => self.flatten(),
+            IdlType::Nullable(idl_type) => return idl_type.interface_union_members(),
+            _ => return None,
+        };
+        idl_types
+            .iter()
+            .map(|idl_type| match idl_type {
+                IdlType::Interface(name) => Some(*name),
+                _ => None,
+            })
+            .collect()
+    }
+
     /// Flattens unions recursively.
     ///
     /// Works similarly to [flattened union member types],
@@ -754,6 +774,30 @@ fn idl_type_flatten_test() {
     );
 }
 
+#[test]
+fn idl_type_interface_union_members_test() {
+    use self::IdlType::*;
+
+    assert_eq!(
+        Union(vec![
+            Interface("HTMLImageElement"),
+            Union(vec![Interface("ImageBitmap"), Interface("OffscreenCanvas")]),
+        ])
+        .interface_union_members(),
+        Some(vec!["HTMLImageElement", "ImageBitmap", "OffscreenCanvas"]),
+    );
+    assert_eq!(
+        Union(vec![Interface("Blob"), DomString]).interface_union_members(),
+        None,
+    );
+    assert_eq!(
+        Nullable(Box::new(Union(vec![Interface("Blob"), Interface("File")])))
+            .interface_union_members(),
+        Some(vec!["Blob", "File"]),
+    );
+    assert_eq!(Interface("Blob").interface_union_members(), None);
+}
+
 /// From `T` create `::wasm_bindgen::Clamped<T>`
 fn clamped(t: syn::Type) -> syn::Type {
     let arguments = syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments {
@@ -16,7 +16,7 @@ mod util;
 
 use crate::first_pass::{CallbackInterfaceData, OperationData};
 use crate::first_pass::{FirstPass, FirstPassRecord, InterfaceData, OperationId};
-use crate::idl_type::ToIdlType;
+use crate::idl_type::{IdlType, ToIdlType};
 use crate::util::{
     camel_case_ident, mdn_doc, public, shouty_snake_case_ident, snake_case_ident,
     webidl_const_v_to_backend_const_v, TypePosition,
@@ -24,15 +24,18 @@ use crate::util::{
 use failure::format_err;
 use proc_macro2::{Ident, Span};
 use quote::{quote, ToTokens};
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::env;
+use std::ffi::OsStr;
 use std::fmt::Display;
 use std::fs;
 use std::iter::FromIterator;
 use wasm_bindgen_backend::ast;
 use wasm_bindgen_backend::defined::ImportedTypeReferences;
 use wasm_bindgen_backend::defined::{ImportedTypeDefinitions, RemoveUndefinedImports};
-use wasm_bindgen_backend::util::{ident_ty, raw_ident, rust_ident, wrap_import_function};
+use wasm_bindgen_backend::util::{
+    ident_ty, leading_colon_path_ty, raw_ident, rust_ident, wrap_import_function,
+};
 use wasm_bindgen_backend::TryToTokens;
 use weedle::attribute::ExtendedAttributeList;
 use weedle::dictionary::DictionaryMember;
@@ -40,13 +43,117 @@ use weedle::interface::InterfaceMember;
 
 pub use crate::error::{Error, ErrorKind, Result};
 
+#[derive(Default)]
 struct Program {
     main: ast::Program,
     submodules: Vec<(String, ast::Program)>,
 }
 
+impl Program {
+    /// Removes everything which is also in `stable`, leaving only the bindings
+    /// added by unstable WebIDL.
+    ///
+    /// Items are matched by name, so unstable additions to a stable dictionary
+    /// or enum are dropped rather than changing the stable definition.
+    fn retain_unstable(&mut self, stable: &Program) {
+        retain_unstable(&mut self.main, &stable.main);
+        for (name, m) in self.submodules.iter_mut() {
+            if let Some((_, s)) = stable.submodules.iter().find(|(n, _)| n == name) {
+                retain_unstable(m, s);
+            }
+        }
+        self.submodules.retain(|(_, m)| !is_empty(m));
+    }
+}
+
+fn retain_unstable(program: &mut ast::Program, stable: &ast::Program) {
+    let imports = stable
+        .imports
+        .iter()
+        .map(import_key)
+        .collect::<HashSet<_>>();
+    program
+        .imports
+        .retain(|i| !imports.contains(&import_key(i)));
+    let dictionaries = stable
+        .dictionaries
+        .iter()
+        .map(|d| &d.name)
+        .collect::<HashSet<_>>();
+    program
+        .dictionaries
+        .retain(|d| !dictionaries.contains(&d.name));
+    let consts = stable
+        .consts
+        .iter()
+        .map(|c| (&c.class, &c.name))
+        .collect::<HashSet<_>>();
+    program
+        .consts
+        .retain(|c| !consts.contains(&(&c.class, &c.name)));
+    let unions = stable
+        .unions
+        .iter()
+        .map(|u| &u.name)
+        .collect::<HashSet<_>>();
+    program.unions.retain(|u| !unions.contains(&u.name));
+}
+
+fn import_key(import: &ast::Import) -> String {
+    match &import.kind {
+        ast::ImportKind::Function(f) => format!("fn {}", f.shim),
+        ast::ImportKind::Static(s) => format!("static {}", s.rust_name),
+        ast::ImportKind::Type(t) => format!("type {}", t.rust_name),
+        ast::ImportKind::Enum(e) => format!("enum {}", e.name),
+    }
+}
+
+fn is_empty(program: &ast::Program) -> bool {
+    program.imports.is_empty()
+        && program.dictionaries.is_empty()
+        && program.consts.is_empty()
+        && program.unions.is_empty()
+}
+
+const UNSTABLE_DOC: &str = "\n\n*This API is unstable and requires `--cfg=web_sys_unstable_apis` \
+                            to be activated, as [described in the `wasm-bindgen` guide]\
+                            (https://rustwasm.github.io/wasm-bindgen/web-sys/unstable-apis.html)*";
+
+/// Notes in the documentation of everything in `program` that it's unstable.
+fn document_unstable(program: &mut ast::Program) {
+    let push = |doc: &mut Option<String>| {
+        doc.get_or_insert_with(String::new).push_str(UNSTABLE_DOC);
+    };
+    for import in program.imports.iter_mut() {
+        match &mut import.kind {
+            ast::ImportKind::Function(f) => push(&mut f.doc_comment),
+            ast::ImportKind::Type(t) => push(&mut t.doc_comment),
+            ast::ImportKind::Enum(e) => {
+                let doc = UNSTABLE_DOC.trim_start();
+                e.rust_attrs.push(syn::parse_quote!(#[doc = #doc]));
+            }
+            ast::ImportKind::Static(_) => {}
+        }
+    }
+    for dict in program.dictionaries.iter_mut() {
+        push(&mut dict.doc_comment);
+        push(&mut dict.ctor_doc_comment);
+        for field in dict.fields.iter_mut() {
+            push(&mut field.doc_comment);
+        }
+    }
+    for union in program.unions.iter_mut() {
+        push(&mut union.doc_comment);
+    }
+}
+
 /// Parse a string of WebIDL source text into a wasm-bindgen AST.
 fn parse(webidl_source: &str, allowed_types: Option<&[&str]>) -> Result<Program> {
+    // `ObservableArray<T>` attributes behave like `sequence<T>` ones as far as
+    // the bindings are concerned: reading them yields an array and they can be
+    // assigned any iterable. `weedle` doesn't know about them though, so
+    // rewrite them beforehand, padding to keep error positions intact.
+    let webidl_source = &webidl_source.replace("ObservableArray<", "sequence       <");
     let definitions = match weedle::parse(webidl_source) {
         Ok(def) => def,
         Err(e) => {
@@ -114,18 +221,32 @@ fn parse(webidl_source: &str, allowed_types: Option<&[&str]>) -> Result<Program>
             first_pass_record.append_callback_interface(&mut program, d);
         }
     }
+    first_pass_record.append_unions(&mut program);
 
-    // Prune out `extends` annotations that aren't defined as these shouldn't
-    // prevent the type from being usable entirely. They're just there for
-    // `AsRef` and such implementations.
-    for import in program.imports.iter_mut() {
-        if let ast::ImportKind::Type(t) = &mut import.kind {
-            t.extends.retain(|n| {
-                let ident = &n.segments.last().unwrap().value().ident;
-                first_pass_record.builtin_idents.contains(ident) || filter(&ident.to_string())
-            });
+    // Types are only usable through their parent interfaces, for `Deref` and
+    // inherited methods, so those need to be allowed as well. Name them here
+    // rather than leaving this to a cryptic "method not found" error later on.
+    let mut missing = Vec::new();
+    for import in program.imports.iter() {
+        if let ast::ImportKind::Type(t) = &import.kind {
+            let parents = t
+                .extends
+                .iter()
+                .map(|n| &n.segments.last().unwrap().value().ident)
+                .filter(|i| !first_pass_record.builtin_idents.contains(i))
+                .filter(|i| !filter(&i.to_string()))
+                .map(|i| format!("`{}`", i))
+                .collect::<Vec<_>>();
+            if parents.len() > 0 {
+                missing.push(format!("`{}` requires {}", t.rust_name, parents.join(", ")));
+            }
         }
     }
+    if missing.len() > 0 {
+        return Err(format_err!("{}", missing.join("; "))
+            .context(ErrorKind::MissingParentTypes)
+            .into());
+    }
 
     Ok(Program {
         main: program,
@@ -135,9 +256,39 @@ fn parse(webidl_source: &str, allowed_types: Option<&[&str]>) -> Result<Program>
 
 /// Compile the given WebIDL source text into Rust source text containing
 /// `wasm-bindgen` bindings to the things described in the WebIDL.
-pub fn compile(webidl_source: &str, allowed_types: Option<&[&str]>) -> Result<String> {
-    let ast = parse(webidl_source, allowed_types)?;
-    Ok(compile_ast(ast))
+///
+/// The definitions in `unstable_source` are compiled along with those of
+/// `webidl_source`, but the bindings only they introduce are emitted behind
+/// `#[cfg(web_sys_unstable_apis)]`. This includes new members of stable
+/// interfaces, like `Navigator.gpu` from `Navigator includes NavigatorGPU`.
+pub fn compile(
+    webidl_source: &str,
+    unstable_source: &str,
+    allowed_types: Option<&[&str]>,
+) -> Result<String> {
+    let mut ast = parse(webidl_source, allowed_types)?;
+    let definitions = remove_undefined_imports(&mut ast);
+    if unstable_source.len() == 0 {
+        if let Some(path) = env::var_os("__WASM_BINDGEN_DUMP_FEATURES") {
+            dump_features(&ast, definitions, &path);
+        }
+        return Ok(compile_ast(ast, Program::default()));
+    }
+
+    // Parse the unstable WebIDL together with the stable one as it builds on
+    // its definitions, and then only keep what's new.
+    let source = format!("{}{}", webidl_source, unstable_source);
+    let mut unstable = parse(&source, allowed_types)?;
+    let definitions = remove_undefined_imports(&mut unstable);
+    if let Some(path) = env::var_os("__WASM_BINDGEN_DUMP_FEATURES") {
+        dump_features(&unstable, definitions, &path);
+    }
+    unstable.retain_unstable(&ast);
+    document_unstable(&mut unstable.main);
+    for (_, m) in unstable.submodules.iter_mut() {
+        document_unstable(m);
+    }
+    Ok(compile_ast(ast, unstable))
 }
 
 fn builtin_idents() -> BTreeSet<Ident> {
@@ -168,6 +319,7 @@ fn builtin_idents() -> BTreeSet<Ident> {
             "Object",
             "Promise",
             "Function",
+            "Iterator",
             "Clamped",
         ]
         .into_iter()
@@ -213,23 +365,18 @@ fn immutable_slice_whitelist() -> BTreeSet<&'static str> {
     ])
 }
 
-/// Run codegen on the AST to generate rust code.
-fn compile_ast(mut ast: Program) -> String {
-    // Iteratively prune all entries from the AST which reference undefined
-    // fields. Each pass may remove definitions of types and so we need to
-    // reexecute this pass to see if we need to keep removing types until we
-    // reach a steady state.
+/// Iteratively prune all entries from the AST which reference undefined
+/// types, returning the names of all types which remain defined.
+fn remove_undefined_imports(ast: &mut Program) -> BTreeSet<Ident> {
+    // Each pass may remove definitions of types and so we need to reexecute
+    // this pass to see if we need to keep removing types until we reach a
+    // steady state.
     let builtin = builtin_idents();
-    let mut all_definitions = BTreeSet::new();
-    let track = env::var_os("__WASM_BINDGEN_DUMP_FEATURES");
     loop {
         let mut defined = builtin.clone();
         {
             let mut cb = |id: &Ident| {
                 defined.insert(id.clone());
-                if track.is_some() {
-                    all_definitions.insert(id.clone());
-                }
             };
             ast.main.imported_type_definitions(&mut cb);
             for (name, m) in ast.submodules.iter() {
@@ -245,32 +392,84 @@ fn compile_ast(mut ast: Program) -> String {
                 .iter_mut()
                 .any(|(_, m)| m.remove_undefined_imports(&|id| defined.contains(id)));
         if !changed {
-            break;
+            return defined.difference(&builtin).cloned().collect();
         }
     }
-    if let Some(path) = track {
-        let contents = all_definitions
-            .into_iter()
-            .filter(|def| !builtin.contains(def))
-            .map(|s| format!("{} = []", s))
-            .collect::<Vec<_>>()
-            .join("\n");
-        fs::write(path, contents).unwrap();
+}
+
+/// Writes the list of features for `definitions` to `path`, in the format of
+/// web-sys's manifest.
+fn dump_features(ast: &Program, definitions: BTreeSet<Ident>, path: &OsStr) {
+    // Each type's feature activates the features of its parents, see `parse`
+    // above.
+    let builtin = builtin_idents();
+    let mut parents = BTreeMap::new();
+    for import in ast.main.imports.iter() {
+        if let ast::ImportKind::Type(t) = &import.kind {
+            let list = t
+                .extends
+                .iter()
+                .map(|n| n.segments.last().unwrap().value().ident.clone())
+                .filter(|i| !builtin.contains(i))
+                .map(|i| format!("\"{}\"", i))
+                .collect::<BTreeSet<_>>();
+            parents.insert(t.rust_name.clone(), list);
+        }
     }
+    // Unions aren't features of their own, they're available along with all
+    // of their members.
+    let unions = ast
+        .main
+        .unions
+        .iter()
+        .map(|u| &u.name)
+        .collect::<HashSet<_>>();
+    let contents = definitions
+        .into_iter()
+        .filter(|s| !unions.contains(s))
+        .map(|s| {
+            let list = parents.remove(&s).unwrap_or_default();
+            let list = list.into_iter().collect::<Vec<_>>();
+            format!("{} = [{}]", s, list.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    fs::write(path, contents).unwrap();
+}
 
+/// Run codegen on the AST to generate rust code.
+fn compile_ast(ast: Program, mut unstable: Program) -> String {
     let mut tokens = proc_macro2::TokenStream::new();
     if let Err(e) = ast.main.try_to_tokens(&mut tokens) {
         e.panic();
     }
+    unstable_to_tokens(&unstable.main, &mut tokens);
     for (name, m) in ast.submodules.iter() {
         let mut m_tokens = proc_macro2::TokenStream::new();
         if let Err(e) = m.try_to_tokens(&mut m_tokens) {
             e.panic();
         }
+        if let Some(i) = unstable.submodules.iter().position(|(n, _)| n == name) {
+            unstable_to_tokens(&unstable.submodules.remove(i).1, &mut m_tokens);
+        }
+
+        let name = Ident::new(name, Span::call_site());
+
+        (quote! {
+            pub mod #name { #m_tokens }
+        })
+        .to_tokens(&mut tokens);
+    }
+    for (name, m) in unstable.submodules.iter() {
+        let mut m_tokens = proc_macro2::TokenStream::new();
+        if let Err(e) = m.try_to_tokens(&mut m_tokens) {
+            e.panic();
+        }
 
         let name = Ident::new(name, Span::call_site());
 
         (quote! {
+            #[cfg(web_sys_unstable_apis)]
             pub mod #name { #m_tokens }
         })
         .to_tokens(&mut tokens);
@@ -278,6 +477,31 @@ fn compile_ast(mut ast: Program) -> String {
     tokens.to_string()
 }
 
+/// Emits the bindings of `program` in a module which only exists with
+/// `--cfg=web_sys_unstable_apis`. The types and functions it defines are
+/// re-exported from there, while methods of stable types are inherent `impl`
+/// blocks which apply wherever they're defined.
+fn unstable_to_tokens(program: &ast::Program, tokens: &mut proc_macro2::TokenStream) {
+    if is_empty(program) {
+        return;
+    }
+    let mut unstable_tokens = proc_macro2::TokenStream::new();
+    if let Err(e) = program.try_to_tokens(&mut unstable_tokens) {
+        e.panic();
+    }
+    (quote! {
+        #[cfg(web_sys_unstable_apis)]
+        #[allow(unused_imports)]
+        mod __unstable {
+            use super::*;
+            #unstable_tokens
+        }
+        #[cfg(web_sys_unstable_apis)]
+        pub use self::__unstable::*;
+    })
+    .to_tokens(tokens);
+}
+
 impl<'src> FirstPassRecord<'src> {
     fn append_enum(&self, program: &mut ast::Program, enum_: &'src weedle::EnumDefinition<'src>) {
         let variants = &enum_.values.body.list;
@@ -345,6 +569,62 @@ impl<'src> FirstPassRecord<'src> {
         dict.ctor_doc_comment = ctor_doc_comment;
 
         program.dictionaries.push(dict);
+        self.append_dictionary_getters(program, def.identifier.0, def.identifier.0);
+    }
+
+    // Dictionaries are plain JS objects, so their fields can be read back
+    // through structural getters, for example on dictionaries handed to us by
+    // JS like the ones in an `RTCStatsReport`.
+    fn append_dictionary_getters(
+        &self,
+        program: &mut ast::Program,
+        self_name: &'src str,
+        dict: &'src str,
+    ) {
+        let dict_data = &self.dictionaries[&dict];
+        let definition = dict_data.definition.unwrap();
+        if let Some(parent) = &definition.inheritance {
+            self.append_dictionary_getters(program, self_name, parent.identifier.0);
+        }
+
+        let members = definition.members.body.iter();
+        let partials = dict_data.partials.iter().flat_map(|d| &d.members.body);
+        for member in members.chain(partials) {
+            // Fields which aren't required may not be present, in which case
+            // the getter returns `None`.
+            let ty = match member.type_.to_idl_type(self) {
+                ty @ IdlType::Nullable(_) => ty,
+                ty if member.required.is_some() => ty,
+                ty => IdlType::Nullable(Box::new(ty)),
+            };
+            // Getters are emitted as `obj.name`, so skip fields like
+            // `report-only` which aren't valid JS identifiers.
+            let name = member.identifier.0;
+            if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                continue;
+            }
+            let kind = ast::OperationKind::Getter(Some(raw_ident(name)));
+            let kind = self.import_function_kind(self_name, false, kind);
+            let mut import_function = match self.create_one_function(
+                name,
+                &format!("get_{}", snake_case_ident(name)),
+                None.into_iter(),
+                &ty,
+                kind,
+                true,
+                false,
+                false,
+                Some(format!("Get the `{}` field of this object\n", name)),
+            ) {
+                Some(f) => f,
+                None => continue,
+            };
+            let mut doc = import_function.doc_comment.take();
+            let extra_feature = camel_case_ident(self_name);
+            self.append_required_features_doc(&import_function, &mut doc, &[&extra_feature]);
+            import_function.doc_comment = doc;
+            program.imports.push(wrap_import_function(import_function));
+        }
     }
 
     fn append_dictionary_members(
@@ -557,6 +837,7 @@ impl<'src> FirstPassRecord<'src> {
             },
             extends: Vec::new(),
             vendor_prefixes: Vec::new(),
+            global: true,
         };
 
         // whitelist a few names that have known polyfills
@@ -603,6 +884,7 @@ impl<'src> FirstPassRecord<'src> {
                 data.definition_attributes,
             );
         }
+        self.append_maplike_setlike(program, name, data);
 
         for mixin_data in self.all_mixins(name) {
             for (id, op_data) in mixin_data.operations.iter() {
@@ -737,6 +1019,123 @@ impl<'src> FirstPassRecord<'src> {
         }
     }
 
+    /// Generates the methods implied by a `maplike` or `setlike` declaration,
+    /// skipping those which the interface already declares itself.
+    fn append_maplike_setlike(
+        &self,
+        program: &mut ast::Program,
+        self_name: &'src str,
+        data: &InterfaceData<'src>,
+    ) {
+        let (key, value, readonly) = match (data.maplike, data.setlike) {
+            (Some(maplike), _) => {
+                let (key, _, value) = &maplike.generics.body;
+                (
+                    key.type_.to_idl_type(self),
+                    value.type_.to_idl_type(self),
+                    maplike.readonly.is_some(),
+                )
+            }
+            (None, Some(setlike)) => {
+                let value = setlike.generics.body.type_.to_idl_type(self);
+                (value.clone(), value, setlike.readonly.is_some())
+            }
+            (None, None) => return,
+        };
+        let is_map = data.maplike.is_some();
+        let key_name = if is_map { "key" } else { "value" };
+        let self_ty = IdlType::Interface(self_name);
+
+        let mut methods = Vec::new();
+        methods.push(("size", vec![], IdlType::UnsignedLong, true));
+        if is_map {
+            methods.push((
+                "get",
+                vec![("key", &key)],
+                IdlType::Nullable(Box::new(value.clone())),
+                false,
+            ));
+        }
+        methods.push(("has", vec![(key_name, &key)], IdlType::Boolean, false));
+        methods.push(("entries", vec![], IdlType::Any, false));
+        methods.push(("keys", vec![], IdlType::Any, false));
+        methods.push(("values", vec![], IdlType::Any, false));
+        methods.push((
+            "forEach",
+            vec![("callback", &IdlType::Callback)],
+            IdlType::Void,
+            false,
+        ));
+        if !readonly {
+            if is_map {
+                methods.push((
+                    "set",
+                    vec![("key", &key), ("value", &value)],
+                    self_ty.clone(),
+                    false,
+                ));
+            } else {
+                methods.push(("add", vec![("value", &value)], self_ty.clone(), false));
+            }
+            methods.push(("delete", vec![(key_name, &key)], IdlType::Boolean, false));
+            methods.push(("clear", vec![], IdlType::Void, false));
+        }
+
+        for (js_name, args, ret, is_getter) in methods {
+            let declared = data.operations.keys().any(|id| match id {
+                OperationId::Operation(Some(name)) => *name == js_name,
+                _ => false,
+            });
+            let declared = declared
+                || data
+                    .attributes
+                    .iter()
+                    .any(|attr| attr.identifier.0 == js_name);
+            if declared {
+                continue;
+            }
+            let (kind, doc) = if is_getter {
+                (
+                    ast::OperationKind::Getter(Some(raw_ident(js_name))),
+                    format!("The `{}` getter\n\n", js_name),
+                )
+            } else {
+                (
+                    ast::OperationKind::Regular,
+                    format!("The `{}()` method\n\n", js_name),
+                )
+            };
+            let doc = doc + &mdn_doc(self_name, Some(js_name));
+            let kind = self.import_function_kind(self_name, false, kind);
+            let mut function = match self.create_one_function(
+                js_name,
+                &snake_case_ident(js_name),
+                args.into_iter(),
+                &ret,
+                kind,
+                true,
+                false,
+                false,
+                None,
+            ) {
+                Some(f) => f,
+                None => continue,
+            };
+            // Iteration methods return a JS iterator, which `js_sys::Iterator`
+            // turns into a Rust one.
+            if let "entries" | "keys" | "values" = js_name {
+                let ty = leading_colon_path_ty(vec![rust_ident("js_sys"), rust_ident("Iterator")]);
+                function.function.ret = Some(ty.clone());
+                function.js_ret = Some(ty);
+            }
+            let mut doc = Some(doc);
+            self.append_required_features_doc(&function, &mut doc, &[]);
+            function.doc_comment = doc;
+            self.add_deprecated(data, &mut function.function.rust_attrs);
+            program.imports.push(wrap_import_function(function));
+        }
+    }
+
     fn add_deprecated(&self, data: &InterfaceData<'src>, dst: &mut Vec<syn::Attribute>) {
         let msg = match &data.deprecated {
             Some(s) => s,
@@ -759,8 +1158,10 @@ impl<'src> FirstPassRecord<'src> {
             .iter()
             .map(|s| Ident::new(s, Span::call_site()))
             .collect::<BTreeSet<_>>();
+        // Unions aren't features, and only need one of their members'.
+        let unions = self.unions.borrow();
         item.imported_type_references(&mut |f| {
-            if !self.builtin_idents.contains(f) {
+            if !self.builtin_idents.contains(f) && !unions.contains_key(&f.to_string()) {
                 required.insert(f.clone());
             }
         });
@@ -792,6 +1193,28 @@ impl<'src> FirstPassRecord<'src> {
         ))
     }
 
+    fn append_unions(&self, program: &mut ast::Program) {
+        for (name, members) in self.unions.borrow().iter() {
+            let list = members
+                .iter()
+                .map(|member| format!("`{}`", member))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let doc_comment = format!(
+                "A reference to one of {}, for arguments accepting any of them\n\n\
+                 *Each variant requires the crate feature of its type to be activated*",
+                list
+            );
+            program.unions.push(ast::Union {
+                vis: public(),
+                name: rust_ident(name),
+                variants: members.iter().map(|m| rust_ident(m)).collect(),
+                variant_tys: members.iter().map(|m| ident_ty(rust_ident(m))).collect(),
+                doc_comment: Some(doc_comment),
+            });
+        }
+    }
+
     fn append_callback_interface(
         &self,
         program: &mut ast::Program,
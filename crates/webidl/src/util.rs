@@ -259,16 +259,19 @@ impl<'src> FirstPassRecord<'src> {
         let idl_arguments: Vec<_> = idl_arguments.collect();
         let arguments_count = idl_arguments.len();
         for (i, (argument_name, idl_type)) in idl_arguments.into_iter().enumerate() {
-            let syn_type = match idl_type.to_syn_type(TypePosition::Argument) {
-                Some(t) => t,
-                None => {
-                    log::warn!(
-                        "Unsupported argument type: {:?} on {:?}",
-                        idl_type,
-                        rust_name
-                    );
-                    return None;
-                }
+            let syn_type = match idl_type.interface_union_members() {
+                Some(members) => self.union_argument(idl_type, members),
+                None => match idl_type.to_syn_type(TypePosition::Argument) {
+                    Some(t) => t,
+                    None => {
+                        log::warn!(
+                            "Unsupported argument type: {:?} on {:?}",
+                            idl_type,
+                            rust_name
+                        );
+                        return None;
+                    }
+                },
             };
             let syn_type = if variadic && i == arguments_count - 1 {
                 let path = vec![rust_ident("js_sys"), rust_ident("Array")];
@@ -308,6 +311,10 @@ impl<'src> FirstPassRecord<'src> {
                 ret: ret.clone(),
                 rust_attrs: vec![],
                 rust_vis: public(),
+                wrapped_arguments: Vec::new(),
+                wrapped_ret: None,
+                typescript_types: Vec::new(),
+                arg_defaults: Vec::new(),
             },
             rust_name: rust_ident(rust_name),
             js_ret: js_ret.clone(),
@@ -323,9 +330,36 @@ impl<'src> FirstPassRecord<'src> {
             },
             kind,
             doc_comment,
+            vendor_prefixes: Vec::new(),
+            link_to: None,
         })
     }
 
+    /// Returns the type of the enum generated for an argument which is a union
+    /// of interfaces, recording the union so that the enum gets defined.
+    ///
+    /// The enum is named after the typedef of the union if there's one, like
+    /// `CanvasImageSource`, and after its members otherwise.
+    fn union_argument(&self, idl_type: &IdlType, members: Vec<&str>) -> syn::Type {
+        let typedef = self.typedefs.iter().find(|(_, ty)| {
+            ty.to_idl_type(self).interface_union_members().as_ref() == Some(&members)
+        });
+        let members = members
+            .iter()
+            .map(|member| camel_case_ident(member))
+            .collect::<Vec<_>>();
+        let name = match typedef {
+            Some((name, _)) => camel_case_ident(name),
+            None => members.join("Or"),
+        };
+        let ty = ident_ty(rust_ident(&name));
+        self.unions.borrow_mut().insert(name, members);
+        match idl_type {
+            IdlType::Nullable(_) => option_ty(ty),
+            _ => ty,
+        }
+    }
+
     /// Create a wasm-bindgen getter method, if possible.
     pub fn create_getter(
         &self,
@@ -470,9 +504,14 @@ impl<'src> FirstPassRecord<'src> {
 
                 // The first element of the flattened type gets pushed directly
                 // in-place, but all other flattened types will cause new
-                // signatures to be created.
+                // signatures to be created. Unions of interfaces are kept as
+                // is though, as they're passed as an enum instead.
                 let cur = actual_signatures.len();
-                for (j, idl_type) in idl_type.flatten().into_iter().enumerate() {
+                let idl_types = match idl_type.interface_union_members() {
+                    Some(_) => vec![idl_type.clone()],
+                    None => idl_type.flatten(),
+                };
+                for (j, idl_type) in idl_types.into_iter().enumerate() {
                     for k in start..cur {
                         if j == 0 {
                             actual_signatures[k].args.push(idl_type.clone());
@@ -37,9 +37,7 @@ pub struct Signature {
 
 #[wasm_bindgen]
 pub fn run() -> Promise {
-    let mut opts = RequestInit::new();
-    opts.method("GET");
-    opts.mode(RequestMode::Cors);
+    let opts = RequestInit::new().method("GET").mode(RequestMode::Cors);
 
     let request = Request::new_with_str_and_init(
         "https://api.github.com/repos/rustwasm/wasm-bindgen/branches/master",
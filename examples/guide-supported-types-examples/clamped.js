@@ -0,0 +1,17 @@
+import {
+  take_clamped_by_value,
+  take_clamped_by_mut,
+  return_clamped,
+  take_option_clamped,
+} from './guide_supported_types_examples';
+
+take_clamped_by_value(new Uint8ClampedArray(100));
+
+let pixels = new Uint8ClampedArray(4);
+take_clamped_by_mut(pixels);
+
+let x = return_clamped();
+console.log(x instanceof Uint8ClampedArray); // true
+
+take_option_clamped(null);
+take_option_clamped(new Uint8ClampedArray(16));
@@ -0,0 +1,15 @@
+import {
+  take_map_by_value,
+  return_map,
+  return_object,
+} from './guide_supported_types_examples';
+
+take_map_by_value(new Map([['a', 1], ['b', 2]]));
+take_map_by_value({ a: 1, b: 2 });
+
+let map = return_map();
+console.log(map instanceof Map); // true
+console.log(map.get('answer')); // 42
+
+let object = return_object();
+console.log(object.name); // "wasm"
@@ -0,0 +1,16 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::Clamped;
+
+#[wasm_bindgen]
+pub fn take_clamped_by_value(x: Clamped<Vec<u8>>) {}
+
+#[wasm_bindgen]
+pub fn take_clamped_by_mut(x: Clamped<&mut [u8]>) {}
+
+#[wasm_bindgen]
+pub fn return_clamped() -> Clamped<Box<[u8]>> {
+    Clamped(vec![0; 4].into_boxed_slice())
+}
+
+#[wasm_bindgen]
+pub fn take_option_clamped(x: Option<Clamped<Vec<u8>>>) {}
@@ -4,9 +4,11 @@ pub mod bool;
 pub mod boxed_js_value_slice;
 pub mod boxed_number_slices;
 pub mod char;
+pub mod clamped;
 pub mod exported_types;
 pub mod imported_types;
 pub mod js_value;
+pub mod maps;
 pub mod number_slices;
 pub mod numbers;
 pub mod pointers;
@@ -0,0 +1,19 @@
+use std::collections::{BTreeMap, HashMap};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub fn take_map_by_value(x: HashMap<String, u32>) {}
+
+#[wasm_bindgen]
+pub fn return_map() -> HashMap<String, u32> {
+    let mut map = HashMap::new();
+    map.insert("answer".to_string(), 42);
+    map
+}
+
+#[wasm_bindgen(plain_object(return))]
+pub fn return_object() -> BTreeMap<String, JsValue> {
+    let mut map = BTreeMap::new();
+    map.insert("name".to_string(), JsValue::from_str("wasm"));
+    map
+}
@@ -177,6 +177,20 @@ pub unsafe extern "C" fn __wbindgen_anyref_heap_live_count_impl() -> u32 {
         .unwrap_or_else(|_| internal_error("tls access failure"))
 }
 
+// Implementation of `__wbindgen_heap_capacity` for when we are using `anyref`
+// instead of the JS `heap`.
+#[no_mangle]
+pub unsafe extern "C" fn __wbindgen_anyref_heap_capacity_impl() -> u32 {
+    HEAP_SLAB
+        .try_with(|slot| {
+            let slab = slot.replace(Slab::new());
+            let capacity = slab.data.len() as u32;
+            slot.replace(slab);
+            capacity
+        })
+        .unwrap_or_else(|_| internal_error("tls access failure"))
+}
+
 // see comment in module above this in `link_mem_intrinsics`
 #[inline(never)]
 pub fn link_intrinsics() {}
@@ -129,7 +129,10 @@ where
     ///
     /// Unlike `instanceof`, this can be specialised to use a custom check by
     /// adding a `#[wasm_bindgen(is_type_of = callback)]` attribute to the
-    /// type import declaration.
+    /// type import declaration. Without one, imported types also accept
+    /// values from other realms whose prototype chain has a constructor with
+    /// the type's name, as long as they're values of all the types the import
+    /// `extends` as well.
     ///
     /// Other than that, this is intended to be an internal implementation
     /// detail of `has_type` and you likely won't need to call this.
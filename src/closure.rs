@@ -10,6 +10,7 @@ use std::marker::Unsize;
 use std::mem::{self, ManuallyDrop};
 use std::prelude::v1::*;
 
+use crate::convert::closures::{ByRef, ClosureArg};
 use crate::convert::*;
 use crate::describe::*;
 use crate::throw_str;
@@ -30,10 +31,11 @@ use crate::UnwrapThrowExt;
 /// location in your program.
 ///
 /// The type parameter on `Closure` is the type of closure that this represents.
-/// Currently this can only be the `Fn` and `FnMut` traits with up to 7
+/// Currently this can only be the `Fn` and `FnMut` traits with up to 8
 /// arguments (and an optional return value). The arguments/return value of the
-/// trait must be numbers like `u32` for now, although this restriction may be
-/// lifted in the future!
+/// trait must be types that can be shared with JS, and up to two of the
+/// arguments may also be references to such types, like
+/// `FnMut(&web_sys::Event, &JsValue)`.
 ///
 /// # Examples
 ///
@@ -252,7 +254,7 @@ union FatPtr<T: ?Sized> {
 
 impl<T> Closure<T>
 where
-    T: ?Sized + WasmClosure,
+    T: ?Sized + WasmClosure + 'static,
 {
     /// A more ergonomic version of `Closure::wrap` that does the boxing and
     /// cast to trait object for you.
@@ -280,19 +282,90 @@ where
     /// * It must be `'static`, aka no stack references (use the `move`
     ///   keyword).
     ///
-    /// * It can have at most 7 arguments.
+    /// * It can have at most 8 arguments, at most 2 of which may be
+    ///   references.
     ///
     /// * Its arguments and return values are all types that can be shared with
     ///   JS (i.e. have `#[wasm_bindgen]` annotations or are simple numbers,
     ///   etc.)
-    pub fn wrap(mut data: Box<T>) -> Closure<T> {
+    pub fn wrap(data: Box<T>) -> Closure<T> {
+        // `T` is `'static` so there's nothing which could be invalidated while
+        // JS still has a handle to the closure.
+        unsafe { Closure::wrap_unchecked(data) }
+    }
+
+    /// Leaks this `Closure` to ensure it remains valid for the duration of the
+    /// entire program.
+    ///
+    /// > **Note**: this function will leak memory. It should be used sparingly
+    /// > to ensure the memory leak doesn't affect the program too much.
+    ///
+    /// When a `Closure` is dropped it will invalidate the associated JS
+    /// closure, but this isn't always desired. Some callbacks are alive for
+    /// the entire duration of the program, so this can be used to conveniently
+    /// leak this instance of `Closure` while performing as much internal
+    /// cleanup as it can.
+    pub fn forget(self) {
+        unsafe {
+            super::__wbindgen_cb_forget(self.js.idx);
+            mem::forget(self);
+        }
+    }
+}
+
+impl<T> Closure<T>
+where
+    T: ?Sized + WasmClosure,
+{
+    /// Creates a `Closure` from a Rust function which doesn't need to be
+    /// `'static`, and passes it to `f` for the duration of the call.
+    ///
+    /// This is useful for JS APIs which only call a callback before they
+    /// return, like `Array.prototype.forEach`, as the closure can then borrow
+    /// from the stack instead of having to share state through
+    /// `Rc<RefCell<_>>`. Once `f` returns the `Closure` is dropped, so any
+    /// later attempt to call the JS function, for example if JS held on to
+    /// it after all, will raise an exception.
+    ///
+    /// Note that the default lifetime of a boxed trait object is `'static`, so
+    /// the box has to be cast to something like `Box<dyn FnMut(u32) + '_>`.
+    ///
+    /// ```rust,ignore
+    /// use wasm_bindgen::prelude::*;
+    ///
+    /// #[wasm_bindgen]
+    /// extern "C" {
+    ///     type Array;
+    ///     #[wasm_bindgen(method, js_name = forEach)]
+    ///     fn for_each(this: &Array, callback: &JsValue);
+    /// }
+    ///
+    /// fn sum(array: &Array) -> f64 {
+    ///     let mut sum = 0.0;
+    ///     let add = Box::new(|x: f64| sum += x) as Box<dyn FnMut(f64) + '_>;
+    ///     Closure::scope(add, |add| array.for_each(add.as_ref()));
+    ///     sum
+    /// }
+    /// ```
+    pub fn scope<F, U>(data: Box<T>, f: F) -> U
+    where
+        F: FnOnce(&Closure<T>) -> U,
+    {
+        // Only lending out the `Closure` means it can't be leaked with
+        // `forget` or `mem::forget`, so it's dropped, and the JS function
+        // invalidated, before `T` goes out of scope.
+        let closure = unsafe { Closure::wrap_unchecked(data) };
+        f(&closure)
+    }
+
+    /// Like `Closure::wrap`, except that the caller must guarantee that the
+    /// returned `Closure` is dropped before anything borrowed by `T`.
+    unsafe fn wrap_unchecked(mut data: Box<T>) -> Closure<T> {
         assert_eq!(mem::size_of::<*const T>(), mem::size_of::<FatPtr<T>>());
-        let (a, b) = unsafe {
-            FatPtr {
-                ptr: &mut *data as *mut T,
-            }
-            .fields
-        };
+        let (a, b) = FatPtr {
+            ptr: &mut *data as *mut T,
+        }
+        .fields;
 
         // Here we need to create a `JsValue` with the data and `T::invoke()`
         // function pointer. To do that we... take a few unconventional turns.
@@ -348,31 +421,13 @@ where
             super::__wbindgen_describe_closure(a as u32, b as u32, describe::<T> as u32)
         }
 
-        let idx = unsafe { breaks_if_inlined::<T>(a, b) };
+        let idx = breaks_if_inlined::<T>(a, b);
 
         Closure {
             js: ManuallyDrop::new(JsValue::_new(idx)),
             data: ManuallyDrop::new(data),
         }
     }
-
-    /// Leaks this `Closure` to ensure it remains valid for the duration of the
-    /// entire program.
-    ///
-    /// > **Note**: this function will leak memory. It should be used sparingly
-    /// > to ensure the memory leak doesn't affect the program too much.
-    ///
-    /// When a `Closure` is dropped it will invalidate the associated JS
-    /// closure, but this isn't always desired. Some callbacks are alive for
-    /// the entire duration of the program, so this can be used to conveniently
-    /// leak this instance of `Closure` while performing as much internal
-    /// cleanup as it can.
-    pub fn forget(self) {
-        unsafe {
-            super::__wbindgen_cb_forget(self.js.idx);
-            mem::forget(self);
-        }
-    }
 }
 
 // NB: we use a specific `T` for this `Closure<T>` impl block to avoid every
@@ -541,17 +596,98 @@ macro_rules! doit {
     ($(
         ($($var:ident)*)
     )*) => ($(
-        unsafe impl<$($var,)* R> WasmClosure for dyn Fn($($var),*) -> R + 'static
-            where $($var: FromWasmAbi + 'static,)*
+        doit!(@munch [$($var)*] [& &] [] [] [] [] []);
+    )*);
+
+    // Each argument is either taken by value or by reference, so recurse once
+    // for each of those, accumulating for that argument:
+    //
+    // * the type parameter
+    // * the type in the `Fn` signature
+    // * the `ClosureArg` converting it, which is `ByRef<_>` for references
+    // * the expression passed to the closure
+    // * the bound on the type parameter
+    //
+    // Every impl of these traits for `dyn Fn*` is checked for overlap against
+    // every other one, so allowing each argument to be a reference would make
+    // compile times explode. Instead references are taken out of a budget of
+    // two, in any position, which covers the common case of callbacks taking
+    // an event and maybe some other `JsValue`.
+    (@munch
+        [$next:ident $($rest:ident)*]
+        [$budget:tt $($budgets:tt)*]
+        [$($vars:ident)*]
+        [$($tys:ty,)*]
+        [$($args:ty,)*]
+        [$($calls:expr,)*]
+        [$($bounds:tt)*]
+    ) => {
+        doit!(@munch
+            [$($rest)*]
+            [$budget $($budgets)*]
+            [$($vars)* $next]
+            [$($tys,)* $next,]
+            [$($args,)* $next,]
+            [$($calls,)* $next,]
+            [$($bounds)* $next: FromWasmAbi + 'static,]
+        );
+        doit!(@munch
+            [$($rest)*]
+            [$($budgets)*]
+            [$($vars)* $next]
+            [$($tys,)* &$next,]
+            [$($args,)* ByRef<$next>,]
+            [$($calls,)* &*$next,]
+            [$($bounds)* $next: RefFromWasmAbi + 'static,]
+        );
+    };
+
+    (@munch
+        [$next:ident $($rest:ident)*]
+        []
+        [$($vars:ident)*]
+        [$($tys:ty,)*]
+        [$($args:ty,)*]
+        [$($calls:expr,)*]
+        [$($bounds:tt)*]
+    ) => {
+        doit!(@munch
+            [$($rest)*]
+            []
+            [$($vars)* $next]
+            [$($tys,)* $next,]
+            [$($args,)* $next,]
+            [$($calls,)* $next,]
+            [$($bounds)* $next: FromWasmAbi + 'static,]
+        );
+    };
+
+    (@munch
+        []
+        $budget:tt
+        [$($var:ident)*]
+        [$($ty:ty,)*]
+        [$($arg:ty,)*]
+        [$($call:expr,)*]
+        [$($bound:tt)*]
+    ) => {
+        // Impls taking references only differ from the ones taking values in
+        // the lifetimes of the references, which rustc warns about.
+        #[allow(coherence_leak_check)]
+        unsafe impl<'a, $($var,)* R> WasmClosure for dyn Fn($($ty),*) -> R + 'a
+            where $($bound)*
                   R: ReturnWasmAbi + 'static,
         {
             fn describe() {
                 #[allow(non_snake_case)]
-                unsafe extern "C" fn invoke<$($var: FromWasmAbi,)* R: ReturnWasmAbi>(
+                unsafe extern "C" fn invoke<$($var,)* R>(
                     a: usize,
                     b: usize,
-                    $($var: <$var as FromWasmAbi>::Abi),*
-                ) -> <R as ReturnWasmAbi>::Abi {
+                    $($var: <$arg as ClosureArg>::Abi,)*
+                ) -> <R as ReturnWasmAbi>::Abi
+                    where $($bound)*
+                          R: ReturnWasmAbi + 'static,
+                {
                     if a == 0 {
                         throw_str("closure invoked recursively or destroyed already");
                     }
@@ -559,22 +695,23 @@ macro_rules! doit {
                     // convert `ret` as it may throw (for `Result`, for
                     // example)
                     let ret = {
-                        let f: *const dyn Fn($($var),*) -> R =
+                        let f: *const dyn Fn($($ty),*) -> R =
                             FatPtr { fields: (a, b) }.ptr;
-                        $(
-                            let $var = <$var as FromWasmAbi>::from_abi($var);
-                        )*
-                        (*f)($($var),*)
+                        $(let $var = <$arg as ClosureArg>::from_abi($var);)*
+                        (*f)($($call),*)
                     };
                     ret.return_abi()
                 }
 
-                inform(invoke::<$($var,)* R> as u32);
+                inform(invoke::<$($var,)* R> as *const () as u32);
 
-                unsafe extern fn destroy<$($var: FromWasmAbi,)* R: ReturnWasmAbi>(
+                unsafe extern "C" fn destroy<$($var,)* R>(
                     a: usize,
                     b: usize,
-                ) {
+                )
+                    where $($bound)*
+                          R: ReturnWasmAbi + 'static,
+                {
                     // This can be called by the JS glue in erroneous situations
                     // such as when the closure has already been destroyed. If
                     // that's the case let's not make things worse by
@@ -583,27 +720,31 @@ macro_rules! doit {
                     if a == 0 {
                         return;
                     }
-                    drop(Box::from_raw(FatPtr::<dyn Fn($($var,)*) -> R> {
+                    drop(Box::from_raw(FatPtr::<dyn Fn($($ty),*) -> R> {
                         fields: (a, b)
                     }.ptr));
                 }
-                inform(destroy::<$($var,)* R> as u32);
+                inform(destroy::<$($var,)* R> as *const () as u32);
 
                 <&Self>::describe();
             }
         }
 
-        unsafe impl<$($var,)* R> WasmClosure for dyn FnMut($($var),*) -> R + 'static
-            where $($var: FromWasmAbi + 'static,)*
+        #[allow(coherence_leak_check)]
+        unsafe impl<'a, $($var,)* R> WasmClosure for dyn FnMut($($ty),*) -> R + 'a
+            where $($bound)*
                   R: ReturnWasmAbi + 'static,
         {
             fn describe() {
                 #[allow(non_snake_case)]
-                unsafe extern "C" fn invoke<$($var: FromWasmAbi,)* R: ReturnWasmAbi>(
+                unsafe extern "C" fn invoke<$($var,)* R>(
                     a: usize,
                     b: usize,
-                    $($var: <$var as FromWasmAbi>::Abi),*
-                ) -> <R as ReturnWasmAbi>::Abi {
+                    $($var: <$arg as ClosureArg>::Abi,)*
+                ) -> <R as ReturnWasmAbi>::Abi
+                    where $($bound)*
+                          R: ReturnWasmAbi + 'static,
+                {
                     if a == 0 {
                         throw_str("closure invoked recursively or destroyed already");
                     }
@@ -611,48 +752,51 @@ macro_rules! doit {
                     // convert `ret` as it may throw (for `Result`, for
                     // example)
                     let ret = {
-                        let f: *const dyn FnMut($($var),*) -> R =
+                        let f: *const dyn FnMut($($ty),*) -> R =
                             FatPtr { fields: (a, b) }.ptr;
-                        let f = f as *mut dyn FnMut($($var),*) -> R;
-                        $(
-                            let $var = <$var as FromWasmAbi>::from_abi($var);
-                        )*
-                        (*f)($($var),*)
+                        let f = f as *mut dyn FnMut($($ty),*) -> R;
+                        $(let $var = <$arg as ClosureArg>::from_abi($var);)*
+                        (*f)($($call),*)
                     };
                     ret.return_abi()
                 }
 
-                inform(invoke::<$($var,)* R> as u32);
+                inform(invoke::<$($var,)* R> as *const () as u32);
 
-                unsafe extern fn destroy<$($var: FromWasmAbi,)* R: ReturnWasmAbi>(
+                unsafe extern "C" fn destroy<$($var,)* R>(
                     a: usize,
                     b: usize,
-                ) {
+                )
+                    where $($bound)*
+                          R: ReturnWasmAbi + 'static,
+                {
                     // See `Fn()` above for why we simply return
                     if a == 0 {
                         return;
                     }
-                    drop(Box::from_raw(FatPtr::<dyn FnMut($($var,)*) -> R> {
+                    drop(Box::from_raw(FatPtr::<dyn FnMut($($ty),*) -> R> {
                         fields: (a, b)
                     }.ptr));
                 }
-                inform(destroy::<$($var,)* R> as u32);
+                inform(destroy::<$($var,)* R> as *const () as u32);
 
                 <&mut Self>::describe();
             }
         }
 
+        // The `ClosureArg`s of the arguments are what tell apart impls for
+        // closures taking references from the ones taking values here.
         #[allow(non_snake_case)]
-        impl<T, $($var,)* R> WasmClosureFnOnce<($($var),*), R> for T
-            where T: 'static + FnOnce($($var),*) -> R,
-                  $($var: FromWasmAbi + 'static,)*
+        impl<T, $($var,)* R> WasmClosureFnOnce<($($arg,)*), R> for T
+            where T: 'static + FnOnce($($ty),*) -> R,
+                  $($bound)*
                   R: ReturnWasmAbi + 'static
         {
-            type FnMut = dyn FnMut($($var),*) -> R;
+            type FnMut = dyn FnMut($($ty),*) -> R;
 
             fn into_fn_mut(self) -> Box<Self::FnMut> {
                 let mut me = Some(self);
-                Box::new(move |$($var),*| {
+                Box::new(move |$($var: $ty),*| {
                     let me = me.take().expect_throw("FnOnce called more than once");
                     me($($var),*)
                 })
@@ -667,7 +811,7 @@ macro_rules! doit {
                 let rc1 = Rc::new(WasmRefCell::new(None));
                 let rc2 = rc1.clone();
 
-                let closure = Closure::wrap(Box::new(move |$($var),*| {
+                let closure = Closure::wrap(Box::new(move |$($var: $ty),*| {
                     // Invoke ourself and get the result.
                     let me = me.take().expect_throw("FnOnce called more than once");
                     let result = me($($var),*);
@@ -680,7 +824,7 @@ macro_rules! doit {
                     drop(option_closure);
 
                     result
-                }) as Box<dyn FnMut($($var),*) -> R>);
+                }) as Box<dyn FnMut($($ty),*) -> R>);
 
                 let js_val = closure.as_ref().clone();
 
@@ -691,9 +835,11 @@ macro_rules! doit {
                 js_val
             }
         }
-    )*)
+    };
 }
 
+// Expands to impls for every way of taking up to two of the arguments by
+// reference.
 doit! {
     ()
     (A)
@@ -705,152 +851,3 @@ doit! {
     (A B C D E F G)
     (A B C D E F G H)
 }
-
-// Copy the above impls down here for where there's only one argument and it's a
-// reference. We could add more impls for more kinds of references, but it
-// becomes a combinatorial explosion quickly. Let's see how far we can get with
-// just this one! Maybe someone else can figure out voodoo so we don't have to
-// duplicate.
-
-unsafe impl<A, R> WasmClosure for dyn Fn(&A) -> R
-    where A: RefFromWasmAbi,
-          R: ReturnWasmAbi + 'static,
-{
-    fn describe() {
-        #[allow(non_snake_case)]
-        unsafe extern "C" fn invoke<A: RefFromWasmAbi, R: ReturnWasmAbi>(
-            a: usize,
-            b: usize,
-            arg: <A as RefFromWasmAbi>::Abi,
-        ) -> <R as ReturnWasmAbi>::Abi {
-            if a == 0 {
-                throw_str("closure invoked recursively or destroyed already");
-            }
-            // Make sure all stack variables are converted before we
-            // convert `ret` as it may throw (for `Result`, for
-            // example)
-            let ret = {
-                let f: *const dyn Fn(&A) -> R =
-                    FatPtr { fields: (a, b) }.ptr;
-                let arg = <A as RefFromWasmAbi>::ref_from_abi(arg);
-                (*f)(&*arg)
-            };
-            ret.return_abi()
-        }
-
-        inform(invoke::<A, R> as u32);
-
-        unsafe extern fn destroy<A: RefFromWasmAbi, R: ReturnWasmAbi>(
-            a: usize,
-            b: usize,
-        ) {
-            // See `Fn()` above for why we simply return
-            if a == 0 {
-                return;
-            }
-            drop(Box::from_raw(FatPtr::<dyn Fn(&A) -> R> {
-                fields: (a, b)
-            }.ptr));
-        }
-        inform(destroy::<A, R> as u32);
-
-        <&Self>::describe();
-    }
-}
-
-unsafe impl<A, R> WasmClosure for dyn FnMut(&A) -> R
-    where A: RefFromWasmAbi,
-          R: ReturnWasmAbi + 'static,
-{
-    fn describe() {
-        #[allow(non_snake_case)]
-        unsafe extern "C" fn invoke<A: RefFromWasmAbi, R: ReturnWasmAbi>(
-            a: usize,
-            b: usize,
-            arg: <A as RefFromWasmAbi>::Abi,
-        ) -> <R as ReturnWasmAbi>::Abi {
-            if a == 0 {
-                throw_str("closure invoked recursively or destroyed already");
-            }
-            // Make sure all stack variables are converted before we
-            // convert `ret` as it may throw (for `Result`, for
-            // example)
-            let ret = {
-                let f: *const dyn FnMut(&A) -> R =
-                    FatPtr { fields: (a, b) }.ptr;
-                let f = f as *mut dyn FnMut(&A) -> R;
-                let arg = <A as RefFromWasmAbi>::ref_from_abi(arg);
-                (*f)(&*arg)
-            };
-            ret.return_abi()
-        }
-
-        inform(invoke::<A, R> as u32);
-
-        unsafe extern fn destroy<A: RefFromWasmAbi, R: ReturnWasmAbi>(
-            a: usize,
-            b: usize,
-        ) {
-            // See `Fn()` above for why we simply return
-            if a == 0 {
-                return;
-            }
-            drop(Box::from_raw(FatPtr::<dyn FnMut(&A) -> R> {
-                fields: (a, b)
-            }.ptr));
-        }
-        inform(destroy::<A, R> as u32);
-
-        <&mut Self>::describe();
-    }
-}
-
-#[allow(non_snake_case)]
-impl<T, A, R> WasmClosureFnOnce<(&A,), R> for T
-    where T: 'static + FnOnce(&A) -> R,
-          A: RefFromWasmAbi + 'static,
-          R: ReturnWasmAbi + 'static
-{
-    type FnMut = dyn FnMut(&A) -> R;
-
-    fn into_fn_mut(self) -> Box<Self::FnMut> {
-        let mut me = Some(self);
-        Box::new(move |arg| {
-            let me = me.take().expect_throw("FnOnce called more than once");
-            me(arg)
-        })
-    }
-
-    fn into_js_function(self) -> JsValue {
-        use std::rc::Rc;
-        use crate::__rt::WasmRefCell;
-
-        let mut me = Some(self);
-
-        let rc1 = Rc::new(WasmRefCell::new(None));
-        let rc2 = rc1.clone();
-
-        let closure = Closure::wrap(Box::new(move |arg: &A| {
-            // Invoke ourself and get the result.
-            let me = me.take().expect_throw("FnOnce called more than once");
-            let result = me(arg);
-
-            // And then drop the `Rc` holding this function's `Closure`
-            // alive.
-            debug_assert_eq!(Rc::strong_count(&rc2), 1);
-            let option_closure = rc2.borrow_mut().take();
-            debug_assert!(option_closure.is_some());
-            drop(option_closure);
-
-            result
-        }) as Box<dyn FnMut(&A) -> R>);
-
-        let js_val = closure.as_ref().clone();
-
-        *rc1.borrow_mut() = Some(closure);
-        debug_assert_eq!(Rc::strong_count(&rc1), 2);
-        drop(rc1);
-
-        js_val
-    }
-}
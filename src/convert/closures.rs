@@ -1,15 +1,137 @@
+use core::marker::PhantomData;
 use core::mem;
 
 use crate::convert::slices::WasmSlice;
 use crate::convert::RefFromWasmAbi;
-use crate::convert::{FromWasmAbi, IntoWasmAbi, ReturnWasmAbi};
+use crate::convert::{FromWasmAbi, IntoWasmAbi, ReturnWasmAbi, WasmAbi};
 use crate::describe::{inform, WasmDescribe, FUNCTION};
 use crate::throw_str;
 
+/// Conversion of an argument of a closure invoked from JS.
+///
+/// This is implemented for `T` arguments, taken by value, and for
+/// `ByRef<T>`, standing for `&T` arguments, so that closure impls handle
+/// both kinds of arguments the same way. `&T` itself can't be used here as
+/// other crates may implement `FromWasmAbi` for references to their types.
+pub trait ClosureArg {
+    /// The ABI type the argument is received as.
+    type Abi: WasmAbi;
+    /// The argument itself for `T`, or what a `&T` argument borrows from.
+    type Anchor;
+
+    /// Recovers the argument from its ABI type.
+    ///
+    /// # Safety
+    ///
+    /// Same as `FromWasmAbi::from_abi`.
+    unsafe fn from_abi(js: Self::Abi) -> Self::Anchor;
+
+    /// Describes the argument, like `WasmDescribe::describe`.
+    fn describe();
+}
+
+/// Stands for a `&T` closure argument, see `ClosureArg`.
+pub struct ByRef<T: ?Sized>(PhantomData<T>);
+
+impl<T: FromWasmAbi> ClosureArg for T {
+    type Abi = T::Abi;
+    type Anchor = T;
+
+    unsafe fn from_abi(js: T::Abi) -> T {
+        T::from_abi(js)
+    }
+
+    fn describe() {
+        T::describe();
+    }
+}
+
+impl<T: RefFromWasmAbi + ?Sized> ClosureArg for ByRef<T> {
+    type Abi = T::Abi;
+    type Anchor = T::Anchor;
+
+    unsafe fn from_abi(js: T::Abi) -> T::Anchor {
+        T::ref_from_abi(js)
+    }
+
+    fn describe() {
+        <&T>::describe();
+    }
+}
+
+macro_rules! count_idents {
+    () => (0);
+    ($first:ident $($rest:ident)*) => (1 + count_idents!($($rest)*));
+}
+
 macro_rules! stack_closures {
-    ($( ($cnt:tt $invoke:ident $invoke_mut:ident $($var:ident)*) )*) => ($(
-        impl<'a, 'b, $($var,)* R> IntoWasmAbi for &'a (dyn Fn($($var),*) -> R + 'b)
-            where $($var: FromWasmAbi,)*
+    ($( ($($var:ident)*) )*) => ($(
+        stack_closures!(@munch [$($var)*] [& &] [] [] [] [] []);
+    )*);
+
+    // See `doit!` in `closure.rs` for what's accumulated below and why only
+    // up to two references are taken.
+    (@munch
+        [$next:ident $($rest:ident)*]
+        [$budget:tt $($budgets:tt)*]
+        [$($vars:ident)*]
+        [$($tys:ty,)*]
+        [$($args:ty,)*]
+        [$($calls:expr,)*]
+        [$($bounds:tt)*]
+    ) => {
+        stack_closures!(@munch
+            [$($rest)*]
+            [$budget $($budgets)*]
+            [$($vars)* $next]
+            [$($tys,)* $next,]
+            [$($args,)* $next,]
+            [$($calls,)* $next,]
+            [$($bounds)* $next: FromWasmAbi,]
+        );
+        stack_closures!(@munch
+            [$($rest)*]
+            [$($budgets)*]
+            [$($vars)* $next]
+            [$($tys,)* &$next,]
+            [$($args,)* ByRef<$next>,]
+            [$($calls,)* &*$next,]
+            [$($bounds)* $next: RefFromWasmAbi,]
+        );
+    };
+
+    (@munch
+        [$next:ident $($rest:ident)*]
+        []
+        [$($vars:ident)*]
+        [$($tys:ty,)*]
+        [$($args:ty,)*]
+        [$($calls:expr,)*]
+        [$($bounds:tt)*]
+    ) => {
+        stack_closures!(@munch
+            [$($rest)*]
+            []
+            [$($vars)* $next]
+            [$($tys,)* $next,]
+            [$($args,)* $next,]
+            [$($calls,)* $next,]
+            [$($bounds)* $next: FromWasmAbi,]
+        );
+    };
+
+    (@munch
+        []
+        $budget:tt
+        [$($var:ident)*]
+        [$($ty:ty,)*]
+        [$($arg:ty,)*]
+        [$($call:expr,)*]
+        [$($bound:tt)*]
+    ) => {
+        #[allow(coherence_leak_check)]
+        impl<'a, 'b, $($var,)* R> IntoWasmAbi for &'a (dyn Fn($($ty),*) -> R + 'b)
+            where $($bound)*
                   R: ReturnWasmAbi
         {
             type Abi = WasmSlice;
@@ -22,42 +144,45 @@ macro_rules! stack_closures {
             }
         }
 
-        #[allow(non_snake_case)]
-        unsafe extern "C" fn $invoke<$($var: FromWasmAbi,)* R: ReturnWasmAbi>(
-            a: usize,
-            b: usize,
-            $($var: <$var as FromWasmAbi>::Abi),*
-        ) -> <R as ReturnWasmAbi>::Abi {
-            if a == 0 {
-                throw_str("closure invoked recursively or destroyed already");
-            }
-            // Scope all local variables before we call `return_abi` to
-            // ensure they're all destroyed as `return_abi` may throw
-            let ret = {
-                let f: &dyn Fn($($var),*) -> R = mem::transmute((a, b));
-                $(
-                    let $var = <$var as FromWasmAbi>::from_abi($var);
-                )*
-                f($($var),*)
-            };
-            ret.return_abi()
-        }
-
-        impl<'a, $($var,)* R> WasmDescribe for dyn Fn($($var),*) -> R + 'a
-            where $($var: FromWasmAbi,)*
+        #[allow(coherence_leak_check)]
+        impl<'a, $($var,)* R> WasmDescribe for dyn Fn($($ty),*) -> R + 'a
+            where $($bound)*
                   R: ReturnWasmAbi
         {
             fn describe() {
+                #[allow(non_snake_case)]
+                unsafe extern "C" fn invoke<$($var,)* R>(
+                    a: usize,
+                    b: usize,
+                    $($var: <$arg as ClosureArg>::Abi,)*
+                ) -> <R as ReturnWasmAbi>::Abi
+                    where $($bound)*
+                          R: ReturnWasmAbi
+                {
+                    if a == 0 {
+                        throw_str("closure invoked recursively or destroyed already");
+                    }
+                    // Scope all local variables before we call `return_abi` to
+                    // ensure they're all destroyed as `return_abi` may throw
+                    let ret = {
+                        let f: &dyn Fn($($ty),*) -> R = mem::transmute((a, b));
+                        $(let $var = <$arg as ClosureArg>::from_abi($var);)*
+                        f($($call),*)
+                    };
+                    ret.return_abi()
+                }
+
                 inform(FUNCTION);
-                inform($invoke::<$($var,)* R> as u32);
-                inform($cnt);
-                $(<$var as WasmDescribe>::describe();)*
+                inform(invoke::<$($var,)* R> as *const () as u32);
+                inform(count_idents!($($var)*));
+                $(<$arg as ClosureArg>::describe();)*
                 <R as WasmDescribe>::describe();
             }
         }
 
-        impl<'a, 'b, $($var,)* R> IntoWasmAbi for &'a mut (dyn FnMut($($var),*) -> R + 'b)
-            where $($var: FromWasmAbi,)*
+        #[allow(coherence_leak_check)]
+        impl<'a, 'b, $($var,)* R> IntoWasmAbi for &'a mut (dyn FnMut($($ty),*) -> R + 'b)
+            where $($bound)*
                   R: ReturnWasmAbi
         {
             type Abi = WasmSlice;
@@ -70,152 +195,54 @@ macro_rules! stack_closures {
             }
         }
 
-        #[allow(non_snake_case)]
-        unsafe extern "C" fn $invoke_mut<$($var: FromWasmAbi,)* R: ReturnWasmAbi>(
-            a: usize,
-            b: usize,
-            $($var: <$var as FromWasmAbi>::Abi),*
-        ) -> <R as ReturnWasmAbi>::Abi {
-            if a == 0 {
-                throw_str("closure invoked recursively or destroyed already");
-            }
-            // Scope all local variables before we call `return_abi` to
-            // ensure they're all destroyed as `return_abi` may throw
-            let ret = {
-                let f: &mut dyn FnMut($($var),*) -> R = mem::transmute((a, b));
-                $(
-                    let $var = <$var as FromWasmAbi>::from_abi($var);
-                )*
-                f($($var),*)
-            };
-            ret.return_abi()
-        }
-
-        impl<'a, $($var,)* R> WasmDescribe for dyn FnMut($($var),*) -> R + 'a
-            where $($var: FromWasmAbi,)*
+        #[allow(coherence_leak_check)]
+        impl<'a, $($var,)* R> WasmDescribe for dyn FnMut($($ty),*) -> R + 'a
+            where $($bound)*
                   R: ReturnWasmAbi
         {
             fn describe() {
+                #[allow(non_snake_case)]
+                unsafe extern "C" fn invoke<$($var,)* R>(
+                    a: usize,
+                    b: usize,
+                    $($var: <$arg as ClosureArg>::Abi,)*
+                ) -> <R as ReturnWasmAbi>::Abi
+                    where $($bound)*
+                          R: ReturnWasmAbi
+                {
+                    if a == 0 {
+                        throw_str("closure invoked recursively or destroyed already");
+                    }
+                    // Scope all local variables before we call `return_abi` to
+                    // ensure they're all destroyed as `return_abi` may throw
+                    let ret = {
+                        let f: &mut dyn FnMut($($ty),*) -> R = mem::transmute((a, b));
+                        $(let $var = <$arg as ClosureArg>::from_abi($var);)*
+                        f($($call),*)
+                    };
+                    ret.return_abi()
+                }
+
                 inform(FUNCTION);
-                inform($invoke_mut::<$($var,)* R> as u32);
-                inform($cnt);
-                $(<$var as WasmDescribe>::describe();)*
+                inform(invoke::<$($var,)* R> as *const () as u32);
+                inform(count_idents!($($var)*));
+                $(<$arg as ClosureArg>::describe();)*
                 <R as WasmDescribe>::describe();
             }
         }
-    )*)
-}
-
-stack_closures! {
-    (0 invoke0 invoke0_mut)
-    (1 invoke1 invoke1_mut A)
-    (2 invoke2 invoke2_mut A B)
-    (3 invoke3 invoke3_mut A B C)
-    (4 invoke4 invoke4_mut A B C D)
-    (5 invoke5 invoke5_mut A B C D E)
-    (6 invoke6 invoke6_mut A B C D E F)
-    (7 invoke7 invoke7_mut A B C D E F G)
-    (8 invoke8 invoke8_mut A B C D E F G H)
-}
-
-impl<'a, 'b, A, R> IntoWasmAbi for &'a (dyn Fn(&A) -> R + 'b)
-where
-    A: RefFromWasmAbi,
-    R: ReturnWasmAbi,
-{
-    type Abi = WasmSlice;
-
-    fn into_abi(self) -> WasmSlice {
-        unsafe {
-            let (a, b): (usize, usize) = mem::transmute(self);
-            WasmSlice {
-                ptr: a as u32,
-                len: b as u32,
-            }
-        }
-    }
-}
-
-#[allow(non_snake_case)]
-unsafe extern "C" fn invoke1_ref<A: RefFromWasmAbi, R: ReturnWasmAbi>(
-    a: usize,
-    b: usize,
-    arg: <A as RefFromWasmAbi>::Abi,
-) -> <R as ReturnWasmAbi>::Abi {
-    if a == 0 {
-        throw_str("closure invoked recursively or destroyed already");
-    }
-    // Scope all local variables before we call `return_abi` to
-    // ensure they're all destroyed as `return_abi` may throw
-    let ret = {
-        let f: &dyn Fn(&A) -> R = mem::transmute((a, b));
-        let arg = <A as RefFromWasmAbi>::ref_from_abi(arg);
-        f(&*arg)
-    };
-    ret.return_abi()
-}
-
-impl<'a, A, R> WasmDescribe for dyn Fn(&A) -> R + 'a
-where
-    A: RefFromWasmAbi,
-    R: ReturnWasmAbi,
-{
-    fn describe() {
-        inform(FUNCTION);
-        inform(invoke1_ref::<A, R> as u32);
-        inform(1);
-        <&A as WasmDescribe>::describe();
-        <R as WasmDescribe>::describe();
-    }
-}
-
-impl<'a, 'b, A, R> IntoWasmAbi for &'a mut (dyn FnMut(&A) -> R + 'b)
-where
-    A: RefFromWasmAbi,
-    R: ReturnWasmAbi,
-{
-    type Abi = WasmSlice;
-
-    fn into_abi(self) -> WasmSlice {
-        unsafe {
-            let (a, b): (usize, usize) = mem::transmute(self);
-            WasmSlice {
-                ptr: a as u32,
-                len: b as u32,
-            }
-        }
-    }
-}
-
-#[allow(non_snake_case)]
-unsafe extern "C" fn invoke1_mut_ref<A: RefFromWasmAbi, R: ReturnWasmAbi>(
-    a: usize,
-    b: usize,
-    arg: <A as RefFromWasmAbi>::Abi,
-) -> <R as ReturnWasmAbi>::Abi {
-    if a == 0 {
-        throw_str("closure invoked recursively or destroyed already");
-    }
-    // Scope all local variables before we call `return_abi` to
-    // ensure they're all destroyed as `return_abi` may throw
-    let ret = {
-        let f: &mut dyn FnMut(&A) -> R = mem::transmute((a, b));
-        let arg = <A as RefFromWasmAbi>::ref_from_abi(arg);
-        f(&*arg)
     };
-    ret.return_abi()
 }
 
-impl<'a, A, R> WasmDescribe for dyn FnMut(&A) -> R + 'a
-where
-    A: RefFromWasmAbi,
-    R: ReturnWasmAbi,
-{
-    fn describe() {
-        inform(FUNCTION);
-        inform(invoke1_mut_ref::<A, R> as u32);
-        inform(1);
-        <&A as WasmDescribe>::describe();
-        <R as WasmDescribe>::describe();
-    }
+// Expands to impls for every way of taking up to two of the arguments by
+// reference.
+stack_closures! {
+    ()
+    (A)
+    (A B)
+    (A B C)
+    (A B C D)
+    (A B C D E)
+    (A B C D E F)
+    (A B C D E F G)
+    (A B C D E F G H)
 }
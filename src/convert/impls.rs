@@ -2,9 +2,9 @@ use core::char;
 use core::mem::{self, ManuallyDrop};
 
 use crate::convert::traits::WasmAbi;
-use crate::convert::{FromWasmAbi, IntoWasmAbi, RefFromWasmAbi};
+use crate::convert::{FromWasmAbi, IntoWasmAbi, RefFromWasmAbi, RefMutFromWasmAbi, WasmSlice};
 use crate::convert::{OptionFromWasmAbi, OptionIntoWasmAbi, ReturnWasmAbi};
-use crate::{Clamped, JsValue};
+use crate::{Clamped, JsValue, PlainObject};
 
 unsafe impl WasmAbi for () {}
 
@@ -382,6 +382,45 @@ impl<T: FromWasmAbi> FromWasmAbi for Clamped<T> {
     }
 }
 
+impl<T: OptionIntoWasmAbi> OptionIntoWasmAbi for Clamped<T> {
+    fn none() -> Self::Abi {
+        T::none()
+    }
+}
+
+impl<T: OptionFromWasmAbi> OptionFromWasmAbi for Clamped<T> {
+    fn is_none(abi: &Self::Abi) -> bool {
+        T::is_none(abi)
+    }
+}
+
+// Exported functions taking `Clamped<&mut [u8]>` receive it by value, so unlike
+// a plain `&mut [u8]` it has to be recoverable through `FromWasmAbi`. The data
+// is owned by the JS glue which copies it back out after the call.
+impl<'a> FromWasmAbi for Clamped<&'a mut [u8]> {
+    type Abi = WasmSlice;
+
+    unsafe fn from_abi(js: WasmSlice) -> Self {
+        Clamped(<[u8] as RefMutFromWasmAbi>::ref_mut_from_abi(js))
+    }
+}
+
+impl<T: IntoWasmAbi> IntoWasmAbi for PlainObject<T> {
+    type Abi = T::Abi;
+
+    fn into_abi(self) -> Self::Abi {
+        self.0.into_abi()
+    }
+}
+
+impl<T: FromWasmAbi> FromWasmAbi for PlainObject<T> {
+    type Abi = T::Abi;
+
+    unsafe fn from_abi(js: T::Abi) -> Self {
+        PlainObject(T::from_abi(js))
+    }
+}
+
 impl IntoWasmAbi for () {
     type Abi = ();
 
@@ -391,13 +430,13 @@ impl IntoWasmAbi for () {
     }
 }
 
-impl<T: IntoWasmAbi> ReturnWasmAbi for Result<T, JsValue> {
+impl<T: IntoWasmAbi, E: Into<JsValue>> ReturnWasmAbi for Result<T, E> {
     type Abi = T::Abi;
 
     fn return_abi(self) -> Self::Abi {
         match self {
             Ok(v) => v.into_abi(),
-            Err(e) => crate::throw_val(e),
+            Err(e) => crate::throw_val(e.into()),
         }
     }
 }
@@ -0,0 +1,138 @@
+//! Conversions for maps with string keys, which show up in JS as either a `Map`
+//! or a plain object.
+//!
+//! Maps cross the boundary as a flat list of `JsValue`s alternating between
+//! keys and values, and the JS glue takes care of turning that list into (or
+//! building it from) the actual JS object.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+use std::prelude::v1::*;
+
+use crate::convert::{FromWasmAbi, IntoWasmAbi, WasmSlice};
+use crate::{JsCast, JsValue};
+
+/// Types which can be used as the values of maps passed between Rust and JS.
+///
+/// This is implemented for strings, booleans, numbers and all types which
+/// implement `JsCast`, such as `JsValue` and imported JS types.
+pub trait MapValue: Sized {
+    /// Converts this value into a `JsValue` to store in the JS map.
+    fn into_js_value(self) -> JsValue;
+
+    /// Converts a value read out of a JS map, returning `None` if it isn't of
+    /// the expected type.
+    fn from_js_value(js: JsValue) -> Option<Self>;
+}
+
+impl<T: JsCast> MapValue for T {
+    fn into_js_value(self) -> JsValue {
+        self.into()
+    }
+
+    fn from_js_value(js: JsValue) -> Option<Self> {
+        Some(T::unchecked_from_js(js))
+    }
+}
+
+impl MapValue for String {
+    fn into_js_value(self) -> JsValue {
+        JsValue::from_str(&self)
+    }
+
+    fn from_js_value(js: JsValue) -> Option<Self> {
+        js.as_string()
+    }
+}
+
+impl MapValue for bool {
+    fn into_js_value(self) -> JsValue {
+        JsValue::from_bool(self)
+    }
+
+    fn from_js_value(js: JsValue) -> Option<Self> {
+        js.as_bool()
+    }
+}
+
+macro_rules! numbers {
+    ($($t:ident)*) => ($(
+        impl MapValue for $t {
+            fn into_js_value(self) -> JsValue {
+                JsValue::from_f64(self as f64)
+            }
+
+            fn from_js_value(js: JsValue) -> Option<Self> {
+                js.as_f64().map(|n| n as $t)
+            }
+        }
+    )*)
+}
+
+numbers! { i8 u8 i16 u16 i32 u32 isize usize f32 f64 }
+
+fn into_entries<V, I>(map: I) -> WasmSlice
+where
+    V: MapValue,
+    I: IntoIterator<Item = (String, V)>,
+{
+    let mut entries = Vec::new();
+    for (key, value) in map {
+        entries.push(JsValue::from_str(&key));
+        entries.push(value.into_js_value());
+    }
+    entries.into_boxed_slice().into_abi()
+}
+
+unsafe fn from_entries<V, M>(js: WasmSlice) -> M
+where
+    V: MapValue,
+    M: Default + Extend<(String, V)>,
+{
+    let entries = <Box<[JsValue]>>::from_abi(js).into_vec();
+    let mut map = M::default();
+    let mut entries = entries.into_iter();
+    while let (Some(key), Some(value)) = (entries.next(), entries.next()) {
+        let key = match key.as_string() {
+            Some(key) => key,
+            None => crate::throw_str("expected a string key in map"),
+        };
+        match V::from_js_value(value) {
+            Some(value) => map.extend(Some((key, value))),
+            None => crate::throw_str(&format!("unexpected value for key `{}` in map", key)),
+        }
+    }
+    map
+}
+
+impl<V: MapValue, S: BuildHasher> IntoWasmAbi for HashMap<String, V, S> {
+    type Abi = WasmSlice;
+
+    fn into_abi(self) -> WasmSlice {
+        into_entries(self)
+    }
+}
+
+impl<V: MapValue, S: BuildHasher + Default> FromWasmAbi for HashMap<String, V, S> {
+    type Abi = WasmSlice;
+
+    unsafe fn from_abi(js: WasmSlice) -> Self {
+        from_entries(js)
+    }
+}
+
+impl<V: MapValue> IntoWasmAbi for BTreeMap<String, V> {
+    type Abi = WasmSlice;
+
+    fn into_abi(self) -> WasmSlice {
+        into_entries(self)
+    }
+}
+
+impl<V: MapValue> FromWasmAbi for BTreeMap<String, V> {
+    type Abi = WasmSlice;
+
+    unsafe fn from_abi(js: WasmSlice) -> Self {
+        from_entries(js)
+    }
+}
@@ -1,10 +1,16 @@
 //! This is mostly an internal module, no stability guarantees are provided. Use
 //! at your own risk.
 
-mod closures;
+pub mod closures;
 mod impls;
+#[cfg(feature = "std")]
+mod maps;
+#[cfg(feature = "serde-serialize")]
+mod serde;
 mod slices;
 mod traits;
 
+#[cfg(feature = "std")]
+pub use self::maps::MapValue;
 pub use self::slices::WasmSlice;
 pub use self::traits::*;
@@ -0,0 +1,113 @@
+//! Conversions for `Serde<T>`, which pass values to JS as plain JS objects.
+//!
+//! Values are serialized into a `serde_json::Value` which is then walked to
+//! build up the equivalent JS value piece by piece, and vice versa for values
+//! coming from JS.
+
+use std::prelude::v1::*;
+
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+use serde_json::{Map, Number, Value};
+
+use crate::convert::{FromWasmAbi, IntoWasmAbi};
+use crate::{JsValue, Serde};
+
+impl<T: Serialize> IntoWasmAbi for Serde<T> {
+    type Abi = u32;
+
+    fn into_abi(self) -> u32 {
+        match serde_json::to_value(&self.0) {
+            Ok(value) => to_js(&value).into_abi(),
+            Err(e) => crate::throw_str(&e.to_string()),
+        }
+    }
+}
+
+impl<T: DeserializeOwned> FromWasmAbi for Serde<T> {
+    type Abi = u32;
+
+    unsafe fn from_abi(js: u32) -> Self {
+        let js = JsValue::from_abi(js);
+        let value =
+            from_js(&js).and_then(|value| serde_json::from_value(value).map_err(|e| e.to_string()));
+        match value {
+            Ok(value) => Serde(value),
+            Err(e) => crate::throw_str(&e),
+        }
+    }
+}
+
+fn to_js(value: &Value) -> JsValue {
+    match value {
+        Value::Null => JsValue::NULL,
+        Value::Bool(b) => JsValue::from_bool(*b),
+        Value::Number(n) => JsValue::from_f64(n.as_f64().unwrap_or(core::f64::NAN)),
+        Value::String(s) => JsValue::from_str(s),
+        Value::Array(elements) => unsafe {
+            let array = JsValue::_new(crate::__wbindgen_array_new());
+            for element in elements {
+                crate::__wbindgen_array_push(array.idx, to_js(element).into_abi());
+            }
+            array
+        },
+        Value::Object(map) => unsafe {
+            let object = JsValue::_new(crate::__wbindgen_object_new());
+            for (key, value) in map {
+                let value = to_js(value).into_abi();
+                crate::__wbindgen_object_set(object.idx, key.as_ptr(), key.len(), value);
+            }
+            object
+        },
+    }
+}
+
+fn from_js(js: &JsValue) -> Result<Value, String> {
+    if js.is_null() || js.is_undefined() {
+        return Ok(Value::Null);
+    }
+    if let Some(b) = js.as_bool() {
+        return Ok(Value::Bool(b));
+    }
+    if let Some(n) = js.as_f64() {
+        return Ok(number(n));
+    }
+    if let Some(s) = js.as_string() {
+        return Ok(Value::String(s));
+    }
+    unsafe {
+        if crate::__wbindgen_is_array(js.idx) == 1 {
+            let len = crate::__wbindgen_array_length(js.idx);
+            return (0..len)
+                .map(|i| from_js(&JsValue::_new(crate::__wbindgen_array_get(js.idx, i))))
+                .collect::<Result<_, _>>()
+                .map(Value::Array);
+        }
+        if js.is_object() {
+            let keys = JsValue::_new(crate::__wbindgen_object_keys(js.idx));
+            let mut map = Map::new();
+            for i in 0..crate::__wbindgen_array_length(keys.idx) {
+                let key = JsValue::_new(crate::__wbindgen_array_get(keys.idx, i));
+                let key = key.as_string().unwrap_or_default();
+                let value = crate::__wbindgen_object_get(js.idx, key.as_ptr(), key.len());
+                let value = from_js(&JsValue::_new(value))?;
+                map.insert(key, value);
+            }
+            return Ok(Value::Object(map));
+        }
+    }
+    Err(format!("cannot deserialize {:?} with serde", js))
+}
+
+fn number(n: f64) -> Value {
+    // JS doesn't distinguish integers from floats, so turn integral numbers
+    // back into integers to allow deserializing them into integer types.
+    const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+    if n.trunc() == n && n.abs() <= MAX_SAFE_INTEGER {
+        Value::from(n as i64)
+    } else {
+        Number::from_f64(n)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+}
@@ -0,0 +1,58 @@
+//! Introspection of the JS values owned by wasm, for tracking down leaks.
+//!
+//! Every owned `JsValue` occupies a slot in a heap maintained by the generated
+//! JS glue, or a slab of `anyref` table slots when the `anyref` feature of the
+//! CLI is enabled. A long-running program whose number of live values keeps
+//! growing is most likely leaking `JsValue`s, and the functions here help
+//! figuring out where from.
+
+use std::prelude::v1::*;
+
+use crate::JsValue;
+
+/// Statistics about the JS values owned by wasm, as returned by `heap_stats`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HeapStats {
+    /// The number of owned JS values currently alive, the same as returned by
+    /// `anyref_heap_live_count`.
+    pub live: u32,
+    /// The number of slots in the heap, both live and free. The heap never
+    /// shrinks, so this is also the largest number of values which were alive
+    /// at once.
+    pub capacity: u32,
+}
+
+/// Returns statistics about the JS values currently owned by wasm.
+///
+/// Like `anyref_heap_live_count`, this is relatively slow and is intended to be
+/// used for debugging, not in production code.
+pub fn heap_stats() -> HeapStats {
+    unsafe {
+        HeapStats {
+            live: super::__wbindgen_anyref_heap_live_count(),
+            capacity: super::__wbindgen_heap_capacity(),
+        }
+    }
+}
+
+/// Returns the JS stack trace of where each JS value currently owned by wasm
+/// was handed to wasm.
+///
+/// Recording these is costly, so they're only available when the bindings are
+/// generated with both the `--debug` and `--record-allocation-sites` flags of
+/// the CLI, and this returns `None` otherwise. Allocation sites also aren't
+/// recorded when the `anyref` feature of the CLI is enabled.
+pub fn allocation_sites() -> Option<Vec<String>> {
+    unsafe {
+        let sites = JsValue::_new(super::__wbindgen_heap_allocation_sites());
+        if sites.is_undefined() {
+            return None;
+        }
+        let len = super::__wbindgen_array_length(sites.idx);
+        let sites = (0..len)
+            .map(|i| JsValue::_new(super::__wbindgen_array_get(sites.idx, i)))
+            .map(|site| site.as_string().unwrap_or_default())
+            .collect();
+        Some(sites)
+    }
+}
@@ -3,7 +3,7 @@
 
 #![doc(hidden)]
 
-use crate::{Clamped, JsValue};
+use crate::{Clamped, JsValue, PlainObject};
 
 macro_rules! tys {
     ($($a:ident)*) => (tys! { @ ($($a)*) 0 });
@@ -41,6 +41,10 @@ tys! {
     OPTIONAL
     UNIT
     CLAMPED
+    NAMED_ANYREF
+    MAP
+    PLAIN_OBJECT
+    CALLBACK
 }
 
 #[inline(always)] // see `interpret.rs` in the the cli-support crate
@@ -131,6 +135,14 @@ if_std! {
             <Box<[T]>>::describe();
         }
     }
+
+    impl<V, S> WasmDescribe for std::collections::HashMap<String, V, S> {
+        fn describe() { inform(MAP) }
+    }
+
+    impl<V> WasmDescribe for std::collections::BTreeMap<String, V> {
+        fn describe() { inform(MAP) }
+    }
 }
 
 impl<T: WasmDescribe> WasmDescribe for Option<T> {
@@ -146,9 +158,9 @@ impl WasmDescribe for () {
     }
 }
 
-// Note that this is only for `ReturnWasmAbi for Result<T, JsValue>`, which
-// throws the result, so we only need to inform about the `T`.
-impl<T: WasmDescribe> WasmDescribe for Result<T, JsValue> {
+// Note that this is only for `ReturnWasmAbi for Result<T, E>`, which throws
+// the error, so we only need to inform about the `T`.
+impl<T: WasmDescribe, E: Into<JsValue>> WasmDescribe for Result<T, E> {
     fn describe() {
         T::describe()
     }
@@ -160,3 +172,17 @@ impl<T: WasmDescribe> WasmDescribe for Clamped<T> {
         T::describe();
     }
 }
+
+impl<T: WasmDescribe> WasmDescribe for PlainObject<T> {
+    fn describe() {
+        inform(PLAIN_OBJECT);
+        T::describe();
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl<T> WasmDescribe for crate::Serde<T> {
+    fn describe() {
+        inform(ANYREF)
+    }
+}
@@ -12,9 +12,9 @@
 use core::fmt;
 use core::marker;
 use core::mem;
-use core::ops::{Deref, DerefMut};
+use core::ops::{self, Deref, DerefMut};
 
-use crate::convert::FromWasmAbi;
+use crate::convert::{FromWasmAbi, IntoWasmAbi};
 
 macro_rules! if_std {
     ($($i:item)*) => ($(
@@ -54,6 +54,7 @@ pub mod prelude {
 
     if_std! {
         pub use crate::closure::Closure;
+        pub use crate::JsError;
     }
 }
 
@@ -67,7 +68,12 @@ if_std! {
     extern crate std;
     use std::prelude::v1::*;
     pub mod closure;
+    pub mod debug;
     mod anyref;
+    mod memory;
+    pub use crate::memory::memory_grown;
+    mod thread_bound;
+    pub use crate::thread_bound::ThreadBound;
 }
 
 /// Representation of an object owned by JS.
@@ -139,6 +145,19 @@ impl JsValue {
         unsafe { JsValue::_new(__wbindgen_number_new(n)) }
     }
 
+    /// Creates a new JS `BigInt` by parsing `s` with the JS `BigInt` function.
+    ///
+    /// This accepts decimal integers as well as the `0x`, `0o` and `0b`
+    /// prefixed forms, and returns `None` if `s` isn't a valid integer.
+    pub fn bigint_from_str(s: &str) -> Option<JsValue> {
+        let ret = unsafe { JsValue::_new(__wbindgen_bigint_from_str(s.as_ptr(), s.len())) };
+        if ret.is_bigint() {
+            Some(ret)
+        } else {
+            None
+        }
+    }
+
     /// Creates a new JS value which is a boolean.
     ///
     /// This function creates a JS object representing a boolean (a heap
@@ -248,6 +267,38 @@ impl JsValue {
         }
     }
 
+    /// Returns the value of this JS value as an `i64` if it's a `BigInt` in
+    /// range of `i64`.
+    ///
+    /// If this JS value is not a `BigInt`, or doesn't fit into an `i64`, then
+    /// this returns `None`.
+    pub fn as_bigint_i64(&self) -> Option<i64> {
+        let mut ret = [0; 2];
+        unsafe {
+            if __wbindgen_bigint_get_as_i64(self.idx, &mut ret) == 1 {
+                Some((u64::from(ret[1]) << 32 | u64::from(ret[0])) as i64)
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns the value of this JS value as a `u64` if it's a `BigInt` in
+    /// range of `u64`.
+    ///
+    /// If this JS value is not a `BigInt`, or doesn't fit into a `u64`, then
+    /// this returns `None`.
+    pub fn as_bigint_u64(&self) -> Option<u64> {
+        let mut ret = [0; 2];
+        unsafe {
+            if __wbindgen_bigint_get_as_u64(self.idx, &mut ret) == 1 {
+                Some(u64::from(ret[1]) << 32 | u64::from(ret[0]))
+            } else {
+                None
+            }
+        }
+    }
+
     /// Tests whether this JS value is a JS string.
     pub fn is_string(&self) -> bool {
         unsafe { __wbindgen_is_string(self.idx) == 1 }
@@ -320,6 +371,21 @@ impl JsValue {
         unsafe { __wbindgen_is_symbol(self.idx) == 1 }
     }
 
+    /// Tests whether the type of this JS value is `bigint`
+    #[inline]
+    pub fn is_bigint(&self) -> bool {
+        unsafe { __wbindgen_is_bigint(self.idx) == 1 }
+    }
+
+    /// Applies the JS `**` operator to this value and `rhs`.
+    ///
+    /// Both operands must be of the same kind, either both numbers or both
+    /// `BigInt`s, otherwise a JS `TypeError` is thrown.
+    #[inline]
+    pub fn pow(&self, rhs: &JsValue) -> JsValue {
+        unsafe { JsValue::_new(__wbindgen_pow(self.idx, rhs.idx)) }
+    }
+
     /// Tests whether `typeof self == "object" && self !== null`.
     #[inline]
     pub fn is_object(&self) -> bool {
@@ -332,6 +398,67 @@ impl JsValue {
         unsafe { __wbindgen_is_function(self.idx) == 1 }
     }
 
+    /// Returns the result of the JS `typeof` operator applied to this value.
+    #[inline]
+    pub fn js_typeof(&self) -> JsType {
+        match unsafe { __wbindgen_typeof(self.idx) } {
+            0 => JsType::Undefined,
+            2 => JsType::Boolean,
+            3 => JsType::Number,
+            4 => JsType::BigInt,
+            5 => JsType::String,
+            6 => JsType::Symbol,
+            7 => JsType::Function,
+            // Anything else is a host object, which acts like an object.
+            _ => JsType::Object,
+        }
+    }
+
+    /// Applies the JS `==` operator, which converts operands of different
+    /// types before comparing them.
+    ///
+    /// Note that `==` on `JsValue` itself applies the JS `===` operator
+    /// instead.
+    #[inline]
+    pub fn loose_eq(&self, other: &JsValue) -> bool {
+        unsafe { __wbindgen_jsval_loose_eq(self.idx, other.idx) != 0 }
+    }
+
+    /// Tests whether this value and `other` are the same value according to
+    /// JS `Object.is`.
+    ///
+    /// This is like `===`, except that `NaN` is equal to itself and `+0` isn't
+    /// equal to `-0`.
+    #[inline]
+    pub fn object_is(&self, other: &JsValue) -> bool {
+        unsafe { __wbindgen_object_is(self.idx, other.idx) != 0 }
+    }
+
+    /// Applies the JS `<` operator, which compares numbers, `BigInt`s and
+    /// strings, converting other values to numbers first.
+    #[inline]
+    pub fn lt(&self, other: &JsValue) -> bool {
+        unsafe { __wbindgen_lt(self.idx, other.idx) != 0 }
+    }
+
+    /// Applies the JS `<=` operator, see `JsValue::lt`.
+    #[inline]
+    pub fn le(&self, other: &JsValue) -> bool {
+        unsafe { __wbindgen_le(self.idx, other.idx) != 0 }
+    }
+
+    /// Applies the JS `>` operator, see `JsValue::lt`.
+    #[inline]
+    pub fn gt(&self, other: &JsValue) -> bool {
+        unsafe { __wbindgen_gt(self.idx, other.idx) != 0 }
+    }
+
+    /// Applies the JS `>=` operator, see `JsValue::lt`.
+    #[inline]
+    pub fn ge(&self, other: &JsValue) -> bool {
+        unsafe { __wbindgen_ge(self.idx, other.idx) != 0 }
+    }
+
     /// Get a string representation of the JavaScript object for debugging
     #[cfg(feature = "std")]
     fn as_debug_string(&self) -> String {
@@ -344,6 +471,29 @@ impl JsValue {
     }
 }
 
+/// The type of a JS value, as returned by the JS `typeof` operator.
+///
+/// Note that `typeof null` is `"object"`, so `null` is a `JsType::Object`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum JsType {
+    /// `"undefined"`
+    Undefined,
+    /// `"object"`
+    Object,
+    /// `"boolean"`
+    Boolean,
+    /// `"number"`
+    Number,
+    /// `"bigint"`
+    BigInt,
+    /// `"string"`
+    String,
+    /// `"symbol"`
+    Symbol,
+    /// `"function"`
+    Function,
+}
+
 impl PartialEq for JsValue {
     #[inline]
     fn eq(&self, other: &JsValue) -> bool {
@@ -351,6 +501,65 @@ impl PartialEq for JsValue {
     }
 }
 
+macro_rules! binary_ops {
+    ($($(#[$doc:meta])* $tr:ident $f:ident $intrinsic:ident,)*) => ($(
+        impl<'a> ops::$tr for &'a JsValue {
+            type Output = JsValue;
+
+            $(#[$doc])*
+            #[inline]
+            fn $f(self, rhs: &'a JsValue) -> JsValue {
+                unsafe { JsValue::_new($intrinsic(self.idx, rhs.idx)) }
+            }
+        }
+    )*)
+}
+
+binary_ops! {
+    /// Applies the JS `+` operator, which also concatenates strings.
+    Add add __wbindgen_add,
+    /// Applies the JS `-` operator.
+    Sub sub __wbindgen_sub,
+    /// Applies the JS `*` operator.
+    Mul mul __wbindgen_mul,
+    /// Applies the JS `/` operator, which throws a `RangeError` when dividing
+    /// a `BigInt` by zero.
+    Div div __wbindgen_div,
+    /// Applies the JS `%` operator, which throws a `RangeError` when dividing
+    /// a `BigInt` by zero.
+    Rem rem __wbindgen_rem,
+    /// Applies the JS `&` operator.
+    BitAnd bitand __wbindgen_bit_and,
+    /// Applies the JS `|` operator.
+    BitOr bitor __wbindgen_bit_or,
+    /// Applies the JS `^` operator.
+    BitXor bitxor __wbindgen_bit_xor,
+    /// Applies the JS `<<` operator.
+    Shl shl __wbindgen_shl,
+    /// Applies the JS `>>` operator.
+    Shr shr __wbindgen_shr,
+}
+
+impl<'a> ops::Neg for &'a JsValue {
+    type Output = JsValue;
+
+    /// Applies the JS unary `-` operator.
+    #[inline]
+    fn neg(self) -> JsValue {
+        unsafe { JsValue::_new(__wbindgen_neg(self.idx)) }
+    }
+}
+
+impl<'a> ops::Not for &'a JsValue {
+    type Output = JsValue;
+
+    /// Applies the JS `~` operator.
+    #[inline]
+    fn not(self) -> JsValue {
+        unsafe { JsValue::_new(__wbindgen_bit_not(self.idx)) }
+    }
+}
+
 impl PartialEq<bool> for JsValue {
     #[inline]
     fn eq(&self, other: &bool) -> bool {
@@ -483,6 +692,56 @@ macro_rules! numbers {
 
 numbers! { i8 u8 i16 u16 i32 u32 f32 f64 }
 
+// 64-bit integers are passed to JS as two 32-bit halves, and 128-bit integers
+// are then assembled from two 64-bit halves in JS.
+
+impl From<i64> for JsValue {
+    /// Creates a JS `BigInt` with the value of `n`.
+    #[inline]
+    fn from(n: i64) -> JsValue {
+        unsafe { JsValue::_new(__wbindgen_bigint_from_i64(n as u32, (n >> 32) as u32)) }
+    }
+}
+
+impl From<u64> for JsValue {
+    /// Creates a JS `BigInt` with the value of `n`.
+    #[inline]
+    fn from(n: u64) -> JsValue {
+        unsafe { JsValue::_new(__wbindgen_bigint_from_u64(n as u32, (n >> 32) as u32)) }
+    }
+}
+
+impl From<i128> for JsValue {
+    /// Creates a JS `BigInt` with the value of `n`.
+    fn from(n: i128) -> JsValue {
+        let hi = JsValue::from((n >> 64) as i64);
+        let lo = JsValue::from(n as u64);
+        &(&hi << &JsValue::from(64u64)) | &lo
+    }
+}
+
+impl From<u128> for JsValue {
+    /// Creates a JS `BigInt` with the value of `n`.
+    fn from(n: u128) -> JsValue {
+        let hi = JsValue::from((n >> 64) as u64);
+        let lo = JsValue::from(n as u64);
+        &(&hi << &JsValue::from(64u64)) | &lo
+    }
+}
+
+macro_rules! bigints {
+    ($($n:ident)*) => ($(
+        impl PartialEq<$n> for JsValue {
+            #[inline]
+            fn eq(&self, other: &$n) -> bool {
+                *self == JsValue::from(*other)
+            }
+        }
+    )*)
+}
+
+bigints! { i64 u64 i128 u128 }
+
 externs! {
     #[link(wasm_import_module = "__wbindgen_placeholder__")]
     extern "C" {
@@ -491,10 +750,15 @@ externs! {
 
         fn __wbindgen_string_new(ptr: *const u8, len: usize) -> u32;
         fn __wbindgen_number_new(f: f64) -> u32;
+        fn __wbindgen_bigint_from_i64(lo: u32, hi: u32) -> u32;
+        fn __wbindgen_bigint_from_u64(lo: u32, hi: u32) -> u32;
+        fn __wbindgen_bigint_from_str(ptr: *const u8, len: usize) -> u32;
         fn __wbindgen_symbol_named_new(ptr: *const u8, len: usize) -> u32;
         fn __wbindgen_symbol_anonymous_new() -> u32;
 
         fn __wbindgen_anyref_heap_live_count() -> u32;
+        fn __wbindgen_heap_capacity() -> u32;
+        fn __wbindgen_heap_allocation_sites() -> u32;
 
         fn __wbindgen_is_null(idx: u32) -> u32;
         fn __wbindgen_is_undefined(idx: u32) -> u32;
@@ -502,14 +766,21 @@ externs! {
         fn __wbindgen_is_object(idx: u32) -> u32;
         fn __wbindgen_is_function(idx: u32) -> u32;
         fn __wbindgen_is_string(idx: u32) -> u32;
+        fn __wbindgen_is_bigint(idx: u32) -> u32;
+        fn __wbindgen_typeof(idx: u32) -> u32;
+        fn __wbindgen_has_constructor_named(idx: u32, name: *const u8, name_len: usize) -> u32;
 
         fn __wbindgen_number_get(idx: u32, invalid: *mut u8) -> f64;
+        fn __wbindgen_bigint_get_as_i64(idx: u32, ret: *mut [u32; 2]) -> u32;
+        fn __wbindgen_bigint_get_as_u64(idx: u32, ret: *mut [u32; 2]) -> u32;
         fn __wbindgen_boolean_get(idx: u32) -> u32;
         fn __wbindgen_string_get(idx: u32, len: *mut usize) -> *mut u8;
 
         fn __wbindgen_debug_string(ret: *mut [usize; 2], idx: u32) -> ();
 
         fn __wbindgen_throw(a: *const u8, b: usize) -> !;
+        fn __wbindgen_error_new(a: *const u8, b: usize, c: *const u8, d: usize) -> u32;
+        fn __wbindgen_panic_error(a: *const u8, b: usize, c: *const u8, d: usize) -> ();
         fn __wbindgen_rethrow(a: u32) -> !;
 
         fn __wbindgen_cb_drop(idx: u32) -> u32;
@@ -520,9 +791,39 @@ externs! {
 
         fn __wbindgen_json_parse(ptr: *const u8, len: usize) -> u32;
         fn __wbindgen_json_serialize(ret: *mut [usize; 2], idx: u32) -> ();
+
+        fn __wbindgen_object_new() -> u32;
+        fn __wbindgen_object_keys(idx: u32) -> u32;
+        fn __wbindgen_object_get(idx: u32, key: *const u8, key_len: usize) -> u32;
+        fn __wbindgen_object_set(idx: u32, key: *const u8, key_len: usize, val: u32) -> ();
+        fn __wbindgen_array_new() -> u32;
+        fn __wbindgen_is_array(idx: u32) -> u32;
+        fn __wbindgen_array_length(idx: u32) -> u32;
+        fn __wbindgen_array_get(idx: u32, i: u32) -> u32;
+        fn __wbindgen_array_push(idx: u32, val: u32) -> ();
         fn __wbindgen_jsval_eq(a: u32, b: u32) -> u32;
+        fn __wbindgen_jsval_loose_eq(a: u32, b: u32) -> u32;
+        fn __wbindgen_object_is(a: u32, b: u32) -> u32;
+        fn __wbindgen_lt(a: u32, b: u32) -> u32;
+        fn __wbindgen_le(a: u32, b: u32) -> u32;
+        fn __wbindgen_gt(a: u32, b: u32) -> u32;
+        fn __wbindgen_ge(a: u32, b: u32) -> u32;
+        fn __wbindgen_add(a: u32, b: u32) -> u32;
+        fn __wbindgen_sub(a: u32, b: u32) -> u32;
+        fn __wbindgen_mul(a: u32, b: u32) -> u32;
+        fn __wbindgen_div(a: u32, b: u32) -> u32;
+        fn __wbindgen_rem(a: u32, b: u32) -> u32;
+        fn __wbindgen_pow(a: u32, b: u32) -> u32;
+        fn __wbindgen_bit_and(a: u32, b: u32) -> u32;
+        fn __wbindgen_bit_or(a: u32, b: u32) -> u32;
+        fn __wbindgen_bit_xor(a: u32, b: u32) -> u32;
+        fn __wbindgen_shl(a: u32, b: u32) -> u32;
+        fn __wbindgen_shr(a: u32, b: u32) -> u32;
+        fn __wbindgen_neg(a: u32) -> u32;
+        fn __wbindgen_bit_not(a: u32) -> u32;
 
         fn __wbindgen_memory() -> u32;
+        fn __wbindgen_memory_watch() -> ();
         fn __wbindgen_module() -> u32;
         fn __wbindgen_function_table() -> u32;
     }
@@ -611,6 +912,74 @@ impl<T: FromWasmAbi + 'static> Deref for JsStatic<T> {
     }
 }
 
+/// Wrapper type for imported statics declared with
+/// `#[wasm_bindgen(thread_local)]`.
+///
+/// Unlike `JsStatic`, the value isn't cached: the JS expression is evaluated
+/// again on every access, on whichever thread performs it. This is useful for
+/// values that may change over time or which don't exist everywhere, like
+/// `document` in a worker:
+///
+/// ```ignore
+/// #[wasm_bindgen]
+/// extern "C" {
+///     #[wasm_bindgen(thread_local)]
+///     static document: Option<Document>;
+/// }
+///
+/// let has_document = document.with(|d| d.is_some());
+/// ```
+pub struct JsThreadLocal<T: 'static> {
+    #[doc(hidden)]
+    pub __init: fn() -> T,
+}
+
+impl<T: FromWasmAbi + 'static> JsThreadLocal<T> {
+    /// Evaluates the imported JS value and passes a reference to it to `f`.
+    pub fn with<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        f(&(self.__init)())
+    }
+}
+
+/// Wrapper type for imported statics declared as `static mut`.
+///
+/// Reading the value works like `JsThreadLocal`, evaluating the JS expression
+/// on every access, and `set` assigns a new value to the JS binding or
+/// property:
+///
+/// ```ignore
+/// #[wasm_bindgen]
+/// extern "C" {
+///     static mut onmessage: JsValue;
+/// }
+///
+/// onmessage.set(handler.as_ref().clone());
+/// ```
+pub struct JsMutStatic<T: 'static> {
+    #[doc(hidden)]
+    pub __get: fn() -> T,
+    #[doc(hidden)]
+    pub __set: fn(T),
+}
+
+impl<T: FromWasmAbi + IntoWasmAbi + 'static> JsMutStatic<T> {
+    /// Evaluates the imported JS value and passes a reference to it to `f`.
+    pub fn with<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        f(&(self.__get)())
+    }
+
+    /// Assigns `val` to the imported JS binding or property.
+    pub fn set(&'static self, val: T) {
+        (self.__set)(val)
+    }
+}
+
 #[cold]
 #[inline(never)]
 #[deprecated(note = "renamed to `throw_str`")]
@@ -798,6 +1167,33 @@ pub fn function_table() -> JsValue {
     unsafe { JsValue::_new(__wbindgen_function_table()) }
 }
 
+/// Returns the URL of a file of this crate, like a stylesheet, an image or the
+/// script of a worker, as a `String`.
+///
+/// The path is relative to the root of the crate, the directory containing its
+/// `Cargo.toml`. The file is embedded in the wasm file when compiling, and the
+/// `wasm-bindgen` CLI then copies it to the output directory with a hash of
+/// its contents in its name. The returned URL is resolved relative to the
+/// generated JS, or is a `file:` URL in Node.js.
+///
+/// ```rust,ignore
+/// let link = document.create_element("link")?;
+/// link.set_attribute("rel", "stylesheet")?;
+/// link.set_attribute("href", &wasm_bindgen::link_to!("assets/style.css"))?;
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! link_to {
+    ($path:tt) => {{
+        #[$crate::prelude::wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(link_to = $path)]
+            fn __wbindgen_link_to() -> $crate::__rt::std::string::String;
+        }
+        __wbindgen_link_to()
+    }};
+}
+
 #[doc(hidden)]
 pub mod __rt {
     use core::cell::{Cell, UnsafeCell};
@@ -833,6 +1229,82 @@ pub mod __rt {
         super::throw_str("null pointer passed to rust");
     }
 
+    /// Wrapper around a reference to a public field of an exported struct,
+    /// used by the field's generated getter to read the value.
+    ///
+    /// The getter calls `(&FieldValue(&field)).field_value().get()`, and
+    /// method resolution picks one of the traits below: fields whose type is
+    /// another exported struct (or an `Option` of one) are cloned and handed to
+    /// JS as a new instance, while all other fields fall back to `CopyField`,
+    /// whose `get` requires them to be `Copy`.
+    pub struct FieldValue<'a, T>(pub &'a T);
+
+    pub struct ClassField<'a, T>(&'a T);
+
+    impl<'a, T> ClassField<'a, T> {
+        #[inline]
+        pub fn get(self) -> T
+        where
+            T: Clone,
+        {
+            self.0.clone()
+        }
+    }
+
+    pub struct CopyField<'a, T>(&'a T);
+
+    impl<'a, T> CopyField<'a, T> {
+        #[inline]
+        pub fn get(self) -> T
+        where
+            T: Copy,
+        {
+            *self.0
+        }
+    }
+
+    pub trait ClassFieldValue<'a, T> {
+        fn field_value(&self) -> ClassField<'a, T>;
+    }
+
+    impl<'a, T> ClassFieldValue<'a, T> for FieldValue<'a, T>
+    where
+        T: Clone + crate::convert::RefFromWasmAbi<Anchor = Ref<'static, T>> + 'static,
+    {
+        #[inline]
+        fn field_value(&self) -> ClassField<'a, T> {
+            ClassField(self.0)
+        }
+    }
+
+    pub trait OptionClassFieldValue<'a, T> {
+        fn field_value(&self) -> ClassField<'a, Option<T>>;
+    }
+
+    impl<'a, T> OptionClassFieldValue<'a, T> for FieldValue<'a, Option<T>>
+    where
+        T: Clone + crate::convert::RefFromWasmAbi<Anchor = Ref<'static, T>> + 'static,
+    {
+        #[inline]
+        fn field_value(&self) -> ClassField<'a, Option<T>> {
+            ClassField(self.0)
+        }
+    }
+
+    // Implemented for `&FieldValue` without any bounds so that it's only
+    // picked, after an autoref, when the traits above don't apply, and the
+    // `Copy` bound of `CopyField::get` is then reported against the field.
+    pub trait CopyFieldValue<'a, T> {
+        fn field_value(&self) -> CopyField<'a, T>;
+    }
+
+    impl<'a, 'b, T> CopyFieldValue<'a, T> for &'b FieldValue<'a, T> {
+        #[inline]
+        fn field_value(&self) -> CopyField<'a, T> {
+            CopyField(self.0)
+        }
+    }
+
     /// A vendored version of `RefCell` from the standard library.
     ///
     /// Now why, you may ask, would we do that? Surely `RefCell` in libstd is
@@ -1054,6 +1526,69 @@ pub mod __rt {
         crate::anyref::link_intrinsics();
     }
 
+    /// Returns whether the prototype chain of `val` has a constructor named
+    /// `name`, used by the default `JsCast::is_type_of` of imported types to
+    /// recognize objects from other realms.
+    pub fn has_constructor_named(val: &super::JsValue, name: &str) -> bool {
+        unsafe { super::__wbindgen_has_constructor_named(val.idx, name.as_ptr(), name.len()) == 1 }
+    }
+
+    /// Called at the start of every exported function, this installs a panic
+    /// hook which reports panics to the JS glue when the `panic-exceptions`
+    /// feature is enabled, and does nothing otherwise.
+    ///
+    /// The hook records the message and location of a panic so that, once
+    /// the panic has trapped the wasm instance, the JS glue can throw them as
+    /// a JS exception rather than an opaque `RuntimeError`. Any previously
+    /// installed hook is still called afterwards.
+    #[inline]
+    pub fn init_panic_hook() {
+        #[cfg(feature = "panic-exceptions")]
+        panic_hook::init();
+    }
+
+    #[cfg(feature = "panic-exceptions")]
+    mod panic_hook {
+        use std::format;
+        use std::panic::{self, PanicInfo};
+        use std::prelude::v1::*;
+        use std::sync::Once;
+
+        pub fn init() {
+            static INIT: Once = Once::new();
+            INIT.call_once(|| {
+                let prev = panic::take_hook();
+                panic::set_hook(Box::new(move |info| {
+                    report(info);
+                    prev(info);
+                }));
+            });
+        }
+
+        fn report(info: &PanicInfo) {
+            let payload = info.payload();
+            let message = match payload.downcast_ref::<&str>() {
+                Some(s) => s.to_string(),
+                None => match payload.downcast_ref::<String>() {
+                    Some(s) => s.clone(),
+                    None => "Box<Any>".to_string(),
+                },
+            };
+            let location = match info.location() {
+                Some(l) => format!("{}:{}:{}", l.file(), l.line(), l.column()),
+                None => String::new(),
+            };
+            unsafe {
+                crate::__wbindgen_panic_error(
+                    message.as_ptr(),
+                    message.len(),
+                    location.as_ptr(),
+                    location.len(),
+                );
+            }
+        }
+    }
+
     static mut GLOBAL_EXNDATA: [u32; 2] = [0; 2];
 
     #[no_mangle]
@@ -1086,9 +1621,15 @@ pub mod __rt {
 /// * `Clamped<&[u8]>`
 /// * `Clamped<&mut [u8]>`
 /// * `Clamped<Vec<u8>>`
+/// * `Clamped<Box<[u8]>>`
 ///
 /// All of these types will show up as `Uint8ClampedArray` in JS and will have
 /// different forms of ownership in Rust.
+///
+/// Exported Rust functions can likewise take `Clamped<&mut [u8]>`,
+/// `Clamped<Vec<u8>>` and `Clamped<Box<[u8]>>` as arguments, and return the
+/// latter two, all of which are `Uint8ClampedArray` on the JS side. Any of
+/// these can also be wrapped in an `Option`.
 #[derive(Copy, Clone, PartialEq, Debug, Eq)]
 pub struct Clamped<T>(pub T);
 
@@ -1105,3 +1646,134 @@ impl<T> DerefMut for Clamped<T> {
         &mut self.0
     }
 }
+
+/// A wrapper type around maps which are passed to and from JS as plain JS
+/// objects rather than as a JS `Map`.
+///
+/// Exported functions can take and return `HashMap<String, V>` and
+/// `BTreeMap<String, V>`, which show up as a JS `Map` by default. Wrapping one
+/// in a `PlainObject` instead hands it to JS as an object with one property per
+/// key, for example:
+///
+/// * `PlainObject<HashMap<String, u32>>`
+/// * `PlainObject<BTreeMap<String, JsValue>>`
+///
+/// This is what the `#[wasm_bindgen(plain_object)]` attribute uses for
+/// arguments and return values. Values coming from JS may be either a `Map` or
+/// a plain object regardless of this wrapper.
+#[derive(Copy, Clone, PartialEq, Debug, Eq)]
+pub struct PlainObject<T>(pub T);
+
+impl<T> Deref for PlainObject<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for PlainObject<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+/// A wrapper type for values passed between Rust and JS through `serde`.
+///
+/// A `Serde<T>` is handed to JS as a plain JS value built out of objects,
+/// arrays, strings, numbers, booleans and `null`, and is turned back into a
+/// `T` when received from JS. Unlike `JsValue::from_serde` and
+/// `JsValue::into_serde` no JSON string is involved, the JS value is built up
+/// (or walked) directly.
+///
+/// This is what the `#[wasm_bindgen(serde)]` attribute uses for arguments and
+/// return values, but it can also be used directly in signatures.
+///
+/// Usage of this type requires activating the `serde-serialize` feature of
+/// the `wasm-bindgen` crate.
+#[cfg(feature = "serde-serialize")]
+#[derive(Copy, Clone, PartialEq, Debug, Eq)]
+pub struct Serde<T>(pub T);
+
+#[cfg(feature = "serde-serialize")]
+impl<T> Deref for Serde<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl<T> DerefMut for Serde<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+/// A JS `Error` object, typically used as the error type of exported
+/// functions.
+///
+/// Exported functions returning `Result<T, JsValue>` throw whatever `JsValue`
+/// they fail with, which is often just a string. Returning `Result<T, JsError>`
+/// instead throws a real JS `Error`, complete with a stack trace. Any type
+/// implementing `std::error::Error` converts into a `JsError`, so the `?`
+/// operator works as usual:
+///
+/// ```ignore
+/// #[wasm_bindgen]
+/// pub fn parse_port(s: &str) -> Result<u16, JsError> {
+///     Ok(s.parse()?)
+/// }
+/// ```
+///
+/// The message of the JS `Error` is the `Display` representation of the Rust
+/// error. In debug builds the chain of `source` errors is additionally appended
+/// to the `stack` of the JS `Error`.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct JsError {
+    value: JsValue,
+}
+
+#[cfg(feature = "std")]
+impl JsError {
+    /// Creates a new JS `Error` with the given message.
+    pub fn new(message: &str) -> JsError {
+        JsError::with_trace(message, "")
+    }
+
+    fn with_trace(message: &str, trace: &str) -> JsError {
+        unsafe {
+            let idx =
+                __wbindgen_error_new(message.as_ptr(), message.len(), trace.as_ptr(), trace.len());
+            JsError {
+                value: JsValue::_new(idx),
+            }
+        }
+    }
+}
+
+// Note that `JsError` itself doesn't implement `std::error::Error`, otherwise
+// this would conflict with the blanket `impl<T> From<T> for T`.
+#[cfg(feature = "std")]
+impl<E: std::error::Error> From<E> for JsError {
+    fn from(error: E) -> JsError {
+        let mut trace = String::new();
+        if cfg!(debug_assertions) {
+            let mut source = error.source();
+            while let Some(cause) = source {
+                trace.push_str(&format!("\nCaused by: {}", cause));
+                source = cause.source();
+            }
+        }
+        JsError::with_trace(&error.to_string(), &trace)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<JsError> for JsValue {
+    fn from(error: JsError) -> JsValue {
+        error.value
+    }
+}
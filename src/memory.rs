@@ -0,0 +1,58 @@
+//! Notifications of the growth of the wasm memory, see `memory_grown`.
+
+use std::cell::{Cell, RefCell};
+use std::mem;
+use std::prelude::v1::*;
+
+thread_local! {
+    static HOOKS: RefCell<Vec<Box<dyn FnMut(u32)>>> = RefCell::new(Vec::new());
+    static PENDING: Cell<Option<u32>> = Cell::new(None);
+    static RUNNING: Cell<bool> = Cell::new(false);
+}
+
+/// Registers `f` to be called with the new size of the wasm memory, in 64KiB
+/// pages, whenever it grows.
+///
+/// Growing the memory detaches its `ArrayBuffer`, so any typed array view of
+/// it, like those created with `js_sys::Uint8Array::view` or handed to WebGL,
+/// becomes empty. This hook is the place to recreate such views.
+///
+/// The generated JS glue notices that the memory has grown the next time wasm
+/// calls into JS, or the next time the glue itself reads or writes memory, and
+/// always before any JS code gets to see the new memory. That means `f` may run
+/// from within any call to an imported JS function, so it shouldn't rely on
+/// state which may be borrowed by the code calling JS. If `f` itself makes the
+/// memory grow, the hooks run again once all of them are done.
+///
+/// Hooks can't be unregistered, and registering one from within a hook only
+/// takes effect for the next growth.
+pub fn memory_grown<F>(f: F)
+where
+    F: FnMut(u32) + 'static,
+{
+    HOOKS.with(|hooks| hooks.borrow_mut().push(Box::new(f)));
+    unsafe { crate::__wbindgen_memory_watch() }
+}
+
+// Called by the JS glue when it notices that the memory has grown.
+#[no_mangle]
+pub extern "C" fn __wbindgen_memory_grown_impl(pages: u32) {
+    PENDING.with(|p| p.set(Some(pages)));
+    if RUNNING.with(|r| r.replace(true)) {
+        return;
+    }
+    while let Some(pages) = PENDING.with(|p| p.take()) {
+        // Take the hooks out while they run, so that they can register new
+        // hooks without a `RefCell` borrow error.
+        let mut hooks = HOOKS.with(|h| mem::replace(&mut *h.borrow_mut(), Vec::new()));
+        for hook in hooks.iter_mut() {
+            hook(pages);
+        }
+        HOOKS.with(|h| {
+            let mut h = h.borrow_mut();
+            let added = mem::replace(&mut *h, hooks);
+            h.extend(added);
+        });
+    }
+    RUNNING.with(|r| r.set(false));
+}
@@ -0,0 +1,190 @@
+//! Sharing values which belong to one thread, like `JsValue`, across threads.
+
+use core::fmt;
+use core::mem::{self, ManuallyDrop};
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+
+/// A wrapper which makes any value `Send` and `Sync` by only ever giving access
+/// to it on the thread which created it.
+///
+/// JS objects, and so `JsValue` and imported types, belong to the JS realm of
+/// the thread they were created on, and their handles are only meaningful on
+/// that thread. In a multithreaded wasm module a structure which is shared
+/// across threads can still hold such values by wrapping them in a
+/// `ThreadBound`:
+///
+/// ```rust,ignore
+/// struct Shared {
+///     canvas: ThreadBound<web_sys::HtmlCanvasElement>,
+///     frames: AtomicUsize,
+/// }
+/// ```
+///
+/// Accessing the value, through `Deref`, `DerefMut`, `get` or `into_inner`,
+/// panics when done on any other thread than the one which created the
+/// `ThreadBound`, and so does dropping it. Use `is_valid` or `try_get` to check
+/// beforehand.
+///
+/// Threads are told apart with the thread ID assigned to each wasm instance by
+/// the threads transformation of the CLI, so in a module built without the
+/// `atomics` target feature everything runs on the same thread and the checks
+/// never fail.
+pub struct ThreadBound<T> {
+    value: ManuallyDrop<T>,
+    thread: u32,
+}
+
+unsafe impl<T> Send for ThreadBound<T> {}
+unsafe impl<T> Sync for ThreadBound<T> {}
+
+impl<T> ThreadBound<T> {
+    /// Binds `value` to the current thread.
+    pub fn new(value: T) -> ThreadBound<T> {
+        ThreadBound {
+            value: ManuallyDrop::new(value),
+            thread: current_thread(),
+        }
+    }
+
+    /// Returns whether the current thread is the one this value is bound to,
+    /// and so whether accessing it won't panic.
+    pub fn is_valid(&self) -> bool {
+        self.thread == current_thread()
+    }
+
+    /// Returns a reference to the value, or `None` on another thread than the
+    /// one it is bound to.
+    pub fn try_get(&self) -> Option<&T> {
+        if self.is_valid() {
+            Some(&self.value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the value, or `None` on another thread
+    /// than the one it is bound to.
+    pub fn try_get_mut(&mut self) -> Option<&mut T> {
+        if self.is_valid() {
+            Some(&mut self.value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics on another thread than the one the value is bound to.
+    pub fn get(&self) -> &T {
+        self.assert_valid();
+        &self.value
+    }
+
+    /// Returns a mutable reference to the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics on another thread than the one the value is bound to.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.assert_valid();
+        &mut self.value
+    }
+
+    /// Unwraps the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics on another thread than the one the value is bound to.
+    pub fn into_inner(self) -> T {
+        self.assert_valid();
+        let value = unsafe { ptr::read(&*self.value) };
+        mem::forget(self);
+        value
+    }
+
+    fn assert_valid(&self) {
+        if !self.is_valid() {
+            panic!(
+                "a `ThreadBound` value bound to thread {} was accessed from thread {}",
+                self.thread,
+                current_thread(),
+            );
+        }
+    }
+}
+
+impl<T> Drop for ThreadBound<T> {
+    fn drop(&mut self) {
+        // Dropping a value on the wrong thread is as bad as using it, but
+        // panicking while already panicking would abort without a message, so
+        // leak the value instead.
+        if self.is_valid() {
+            unsafe { ManuallyDrop::drop(&mut self.value) }
+        } else if !std::thread::panicking() {
+            self.assert_valid();
+        }
+    }
+}
+
+impl<T> Deref for ThreadBound<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T> DerefMut for ThreadBound<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+impl<T> From<T> for ThreadBound<T> {
+    fn from(value: T) -> ThreadBound<T> {
+        ThreadBound::new(value)
+    }
+}
+
+impl<T: Clone> Clone for ThreadBound<T> {
+    /// Clones the value, binding the clone to the same thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics on another thread than the one the value is bound to.
+    fn clone(&self) -> ThreadBound<T> {
+        ThreadBound::new(self.get().clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ThreadBound<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.try_get() {
+            Some(value) => f.debug_tuple("ThreadBound").field(value).finish(),
+            None => write!(f, "ThreadBound(<bound to thread {}>)", self.thread),
+        }
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
+fn current_thread() -> u32 {
+    // Rewritten by the threads transformation into a read of the global
+    // holding the ID of the current thread.
+    #[link(wasm_import_module = "__wbindgen_thread_xform__")]
+    extern "C" {
+        fn __wbindgen_current_id() -> u32;
+    }
+    unsafe { __wbindgen_current_id() }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_feature = "atomics")))]
+fn current_thread() -> u32 {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    std::thread_local!(static ID: u32 = NEXT_ID.fetch_add(1, Ordering::Relaxed) as u32);
+    ID.with(|id| *id)
+}
@@ -1,6 +1,8 @@
-use js_sys::{Uint8Array, WebAssembly};
+use js_sys::{ArrayBuffer, Uint8Array, WebAssembly};
+use std::cell::Cell;
+use std::rc::Rc;
 use wasm_bindgen::prelude::*;
-use wasm_bindgen::{self, JsCast};
+use wasm_bindgen::{self, JsCast, JsType, ThreadBound};
 use wasm_bindgen_test::*;
 
 #[wasm_bindgen(module = "tests/wasm/api.js")]
@@ -148,6 +150,28 @@ fn memory_accessor_appears_to_work() {
     assert_eq!(v, [3, 0, 0, 0]);
 }
 
+#[wasm_bindgen_test]
+fn memory_grown_hooks_fire() {
+    let seen = Rc::new(Cell::new(0));
+    let seen2 = seen.clone();
+    wasm_bindgen::memory_grown(move |pages| seen2.set(pages));
+
+    let pages = |mem: JsValue| {
+        let mem = mem.dyn_into::<WebAssembly::Memory>().unwrap();
+        mem.buffer().unchecked_into::<ArrayBuffer>().byte_length() / 65536
+    };
+    let before = pages(wasm_bindgen::memory());
+    let mut chunks = Vec::new();
+    let mut after = before;
+    while after == before && chunks.len() < 256 {
+        chunks.push(vec![1u8; 1 << 20]);
+        // Calling into JS notices the growth.
+        after = pages(wasm_bindgen::memory());
+    }
+    assert!(after > before);
+    assert_eq!(seen.get(), after);
+}
+
 #[wasm_bindgen_test]
 fn debug_output() {
     let test_iter = debug_values()
@@ -183,3 +207,106 @@ fn function_table_is() {
 
 #[no_mangle]
 pub extern "C" fn function_table_lookup() {}
+
+#[wasm_bindgen_test]
+fn operators() {
+    let a = JsValue::from(6);
+    let b = JsValue::from(4);
+    assert_eq!(&a + &b, 10);
+    assert_eq!(&a - &b, 2);
+    assert_eq!(&a * &b, 24);
+    assert_eq!(&a / &b, 1.5);
+    assert_eq!(&a % &b, 2);
+    assert_eq!(a.pow(&b), 1296);
+    assert_eq!(&a & &b, 4);
+    assert_eq!(&a | &b, 6);
+    assert_eq!(&a ^ &b, 2);
+    assert_eq!(&a << &b, 96);
+    assert_eq!(&a >> &JsValue::from(1), 3);
+    assert_eq!(-&a, -6);
+    assert_eq!(!&a, -7);
+    assert_eq!(&JsValue::from("a") + &b, "a4");
+    assert!(!a.is_bigint());
+}
+
+#[wasm_bindgen_test]
+fn bigints() {
+    let a = JsValue::from(-5i64);
+    assert!(a.is_bigint());
+    assert_eq!(a, -5i64);
+    assert_eq!(a.as_bigint_i64(), Some(-5));
+    assert_eq!(a.as_bigint_u64(), None);
+    assert_eq!(JsValue::from(-5).as_bigint_i64(), None);
+
+    let max = JsValue::from(u64::max_value());
+    assert_eq!(max.as_bigint_u64(), Some(u64::max_value()));
+    assert_eq!(max.as_bigint_i64(), None);
+    let min = JsValue::from(i64::min_value());
+    assert_eq!(min.as_bigint_i64(), Some(i64::min_value()));
+    assert_eq!(&JsValue::from(6u64) * &JsValue::from(7i64), 42u64);
+
+    let s = "-170141183460469231731687303715884105728";
+    let min = JsValue::bigint_from_str(s).unwrap();
+    assert_eq!(min, i128::min_value());
+    assert_eq!(min.as_bigint_i64(), None);
+    let s = "0xffffffffffffffffffffffffffffffff";
+    assert_eq!(JsValue::bigint_from_str(s).unwrap(), u128::max_value());
+    assert_eq!(
+        JsValue::from(1u128 << 64 | 3),
+        JsValue::bigint_from_str("18446744073709551619").unwrap()
+    );
+    assert!(JsValue::bigint_from_str("1.5").is_none());
+}
+
+#[wasm_bindgen_test]
+fn comparisons() {
+    let one = JsValue::from(1);
+    let one_str = JsValue::from("1");
+    assert!(one != one_str);
+    assert!(one.loose_eq(&one_str));
+    assert!(JsValue::NULL.loose_eq(&JsValue::UNDEFINED));
+    assert!(!JsValue::NULL.loose_eq(&JsValue::FALSE));
+
+    let nan = JsValue::from(std::f64::NAN);
+    assert!(nan != nan);
+    assert!(nan.object_is(&nan));
+    assert!(JsValue::from(0.0) == JsValue::from(-0.0));
+    assert!(!JsValue::from(0.0).object_is(&JsValue::from(-0.0)));
+
+    let two = JsValue::from(2);
+    assert!(one.lt(&two) && one.le(&two) && !one.gt(&two) && !one.ge(&two));
+    assert!(one.le(&one) && one.ge(&one));
+    assert!(JsValue::from("a").lt(&JsValue::from("b")));
+    assert!(JsValue::from(3i64).gt(&two));
+    assert!(!nan.lt(&one) && !nan.ge(&one));
+}
+
+#[wasm_bindgen_test]
+fn js_typeof() {
+    assert_eq!(JsValue::UNDEFINED.js_typeof(), JsType::Undefined);
+    assert_eq!(JsValue::NULL.js_typeof(), JsType::Object);
+    assert_eq!(JsValue::TRUE.js_typeof(), JsType::Boolean);
+    assert_eq!(JsValue::from(1).js_typeof(), JsType::Number);
+    assert_eq!(JsValue::from(1i64).js_typeof(), JsType::BigInt);
+    assert_eq!(JsValue::from("a").js_typeof(), JsType::String);
+    assert_eq!(JsValue::symbol(None).js_typeof(), JsType::Symbol);
+    assert_eq!(js_sys::Object::new().js_typeof(), JsType::Object);
+    assert_eq!(
+        js_sys::Function::new_no_args("").js_typeof(),
+        JsType::Function
+    );
+}
+
+#[wasm_bindgen_test]
+fn thread_bound() {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    let val = ThreadBound::new(JsValue::from("bound"));
+    assert_send_sync(&val);
+    assert!(val.is_valid());
+    assert_eq!(val.as_string(), Some("bound".to_string()));
+    assert_eq!(val.try_get(), Some(&JsValue::from("bound")));
+    assert_eq!(format!("{:?}", val), "ThreadBound(JsValue(\"bound\"))");
+    let val = val.clone().into_inner();
+    assert_eq!(val, "bound");
+}
@@ -124,6 +124,23 @@ exports.js_readonly_fields = () => {
     a.free();
 };
 
+exports.js_renamed_fields = () => {
+    const a = wasm.RenamedFields.new();
+    assert.strictEqual(a.displayName, 1);
+    assert.strictEqual(a.display_name, undefined);
+    a.displayName = 4;
+    assert.strictEqual(a.displayName, 4);
+
+    assert.strictEqual(a.id, 2);
+    a.id = 5;
+    assert.strictEqual(a.id, 2);
+
+    assert.strictEqual(a.itemCount, 3);
+    a.itemCount = 6;
+    assert.strictEqual(a.itemCount, 3);
+    a.free();
+};
+
 exports.js_double_consume = () => {
     const r = new wasm.DoubleConsume();
     assert.throws(() => r.consume(r));
@@ -170,3 +187,107 @@ exports.js_test_option_classes = () => {
   assert.ok(c instanceof wasm.OptionClass);
   wasm.option_class_assert_some(c);
 };
+
+exports.js_associated_consts = () => {
+  assert.strictEqual(wasm.AssociatedConsts.MAX, 16);
+  assert.strictEqual(wasm.AssociatedConsts.RATIO, 0.5);
+  assert.strictEqual(wasm.AssociatedConsts.ENABLED, true);
+  assert.strictEqual(wasm.AssociatedConsts.NAME, 'consts');
+  assert.strictEqual(wasm.AssociatedConsts.renamed, -3);
+  assert.strictEqual(wasm.AssociatedConsts.RENAMED, undefined);
+  assert.strictEqual(wasm.AssociatedConsts.PRIVATE, undefined);
+  assert.strictEqual(wasm.AssociatedConsts.max_plus_one(), 17);
+
+  // the properties are readonly
+  assert.throws(() => { 'use strict'; wasm.AssociatedConsts.MAX = 1; }, TypeError);
+  assert.strictEqual(wasm.AssociatedConsts.MAX, 16);
+};
+
+exports.js_static_getters_and_setters = () => {
+  assert.strictEqual(wasm.StaticAccessors.level, 1);
+  wasm.StaticAccessors.level = 5;
+  assert.strictEqual(wasm.StaticAccessors.level, 5);
+  assert.strictEqual(wasm.StaticAccessors.defaultName, 'static');
+  assert.strictEqual(wasm.StaticAccessors.default_name, undefined);
+};
+
+exports.js_struct_fields = () => {
+  const outer = new wasm.FieldOuter();
+
+  // getters hand out copies of the field
+  const inner = outer.inner;
+  assert.ok(inner instanceof wasm.FieldInner);
+  assert.strictEqual(inner.value, 1);
+  inner.value = 2;
+  assert.strictEqual(outer.inner_value(), 1);
+
+  // setters take ownership of the assigned object
+  outer.inner = inner;
+  assert.strictEqual(outer.inner_value(), 2);
+  assert.strictEqual(inner.ptr, 0);
+
+  assert.strictEqual(outer.maybe, undefined);
+  outer.maybe = new wasm.FieldInner(3);
+  assert.strictEqual(outer.maybe.value, 3);
+  assert.strictEqual(outer.maybe_value(), 3);
+  outer.maybe = undefined;
+  assert.strictEqual(outer.maybe_value(), undefined);
+  outer.free();
+};
+
+exports.js_chained_methods = () => {
+  const builder = new wasm.ChainedBuilder();
+  const ptr = builder.ptr;
+
+  // chained methods hand back the very same object
+  assert.strictEqual(builder.push('a').push('b'), builder);
+  assert.strictEqual(builder.ptr, ptr);
+  assert.strictEqual(builder.build(), 'a b');
+
+  assert.strictEqual(builder.reset().push('c'), builder);
+  assert.strictEqual(builder.ptr, ptr);
+  assert.strictEqual(builder.build(), 'c');
+  builder.free();
+};
+
+exports.js_no_free = () => {
+  const pooled = wasm.Pooled.acquire(7);
+  assert.strictEqual(pooled.free, undefined);
+  assert.strictEqual(pooled.id(), 7);
+  assert.strictEqual(wasm.Pooled.live(), 1);
+  pooled.release();
+  assert.strictEqual(wasm.Pooled.live(), 0);
+};
+
+exports.js_on_free = () => {
+  let closed = false;
+  const close = wasm.Connection.prototype.close;
+  wasm.Connection.prototype.close = function() {
+    // the Rust value is still alive while the hook runs
+    assert.strictEqual(this.is_open(), true);
+    close.call(this);
+    assert.strictEqual(this.is_open(), false);
+    closed = true;
+  };
+  try {
+    const conn = new wasm.Connection();
+    conn.free();
+    assert.strictEqual(closed, true);
+    assert.strictEqual(conn.ptr, 0);
+  } finally {
+    wasm.Connection.prototype.close = close;
+  }
+};
+
+exports.js_trait_impl_methods = () => {
+  const facade = new wasm.Facade(3);
+  assert.strictEqual(facade.doubled(), 6);
+  facade.increment();
+  assert.strictEqual(facade.value(), 4);
+  facade.free();
+
+  const zero = wasm.Facade.zeroed();
+  assert.ok(zero instanceof wasm.Facade);
+  assert.strictEqual(zero.value(), 0);
+  zero.free();
+};
@@ -1,5 +1,7 @@
 #[allow(unused_imports)] // test for #919
 use std::borrow::BorrowMut;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering::SeqCst;
 
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_test::*;
@@ -18,6 +20,7 @@ extern "C" {
     fn js_public_fields();
     fn js_using_self();
     fn js_readonly_fields();
+    fn js_renamed_fields();
     fn js_double_consume();
     fn js_js_rename();
     fn js_access_fields();
@@ -30,6 +33,13 @@ extern "C" {
     fn js_return_none2() -> Option<OptionClass>;
     fn js_return_some(a: OptionClass) -> Option<OptionClass>;
     fn js_test_option_classes();
+    fn js_associated_consts();
+    fn js_static_getters_and_setters();
+    fn js_struct_fields();
+    fn js_chained_methods();
+    fn js_no_free();
+    fn js_on_free();
+    fn js_trait_impl_methods();
 }
 
 #[wasm_bindgen_test]
@@ -319,6 +329,33 @@ impl Readonly {
     }
 }
 
+#[wasm_bindgen_test]
+fn renamed_fields() {
+    js_renamed_fields();
+}
+
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct RenamedFields {
+    #[wasm_bindgen(js_name = displayName)]
+    pub display_name: u32,
+    #[wasm_bindgen(getter)]
+    pub id: u32,
+    #[wasm_bindgen(getter = itemCount)]
+    pub item_count: u32,
+}
+
+#[wasm_bindgen]
+impl RenamedFields {
+    pub fn new() -> RenamedFields {
+        RenamedFields {
+            display_name: 1,
+            id: 2,
+            item_count: 3,
+        }
+    }
+}
+
 #[wasm_bindgen_test]
 fn double_consume() {
     js_double_consume();
@@ -473,6 +510,246 @@ pub fn option_class_assert_some(x: Option<OptionClass>) {
     assert_eq!(x.unwrap().0, 3);
 }
 
+#[wasm_bindgen]
+pub struct AssociatedConsts {}
+
+#[wasm_bindgen]
+impl AssociatedConsts {
+    pub const MAX: u32 = 1 << 4;
+    pub const RATIO: f64 = 0.5;
+    pub const ENABLED: bool = true;
+    pub const NAME: &'static str = "consts";
+    #[wasm_bindgen(js_name = renamed)]
+    pub const RENAMED: i32 = -3;
+    #[allow(dead_code)]
+    const PRIVATE: u32 = 7;
+
+    pub fn max_plus_one() -> u32 {
+        Self::MAX + 1
+    }
+}
+
+#[wasm_bindgen_test]
+fn associated_consts() {
+    js_associated_consts();
+}
+
+static STATIC_LEVEL: AtomicU32 = AtomicU32::new(1);
+
+#[wasm_bindgen]
+pub struct StaticAccessors {}
+
+#[wasm_bindgen]
+impl StaticAccessors {
+    #[wasm_bindgen(getter)]
+    pub fn level() -> u32 {
+        STATIC_LEVEL.load(SeqCst)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_level(level: u32) {
+        STATIC_LEVEL.store(level, SeqCst);
+    }
+
+    #[wasm_bindgen(getter = defaultName)]
+    pub fn default_name() -> String {
+        "static".to_string()
+    }
+}
+
+#[wasm_bindgen_test]
+fn static_getters_and_setters() {
+    js_static_getters_and_setters();
+    assert_eq!(STATIC_LEVEL.load(SeqCst), 5);
+}
+
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct FieldInner {
+    pub value: u32,
+}
+
+#[wasm_bindgen]
+impl FieldInner {
+    #[wasm_bindgen(constructor)]
+    pub fn new(value: u32) -> FieldInner {
+        FieldInner { value }
+    }
+}
+
+#[wasm_bindgen]
+pub struct FieldOuter {
+    pub inner: FieldInner,
+    pub maybe: Option<FieldInner>,
+}
+
+#[wasm_bindgen]
+impl FieldOuter {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> FieldOuter {
+        FieldOuter {
+            inner: FieldInner { value: 1 },
+            maybe: None,
+        }
+    }
+
+    pub fn inner_value(&self) -> u32 {
+        self.inner.value
+    }
+
+    pub fn maybe_value(&self) -> Option<u32> {
+        self.maybe.as_ref().map(|m| m.value)
+    }
+}
+
+#[wasm_bindgen_test]
+fn struct_fields() {
+    js_struct_fields();
+}
+
+#[wasm_bindgen]
+pub struct ChainedBuilder {
+    parts: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl ChainedBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ChainedBuilder {
+        ChainedBuilder { parts: Vec::new() }
+    }
+
+    #[wasm_bindgen(chain)]
+    pub fn push(mut self, part: &str) -> Self {
+        self.parts.push(part.to_string());
+        self
+    }
+
+    #[wasm_bindgen(chain)]
+    pub fn reset(self) -> ChainedBuilder {
+        ChainedBuilder::new()
+    }
+
+    pub fn build(&self) -> String {
+        self.parts.join(" ")
+    }
+}
+
+#[wasm_bindgen_test]
+fn chained_methods() {
+    js_chained_methods();
+}
+
+static LIVE_POOLED: AtomicU32 = AtomicU32::new(0);
+
+#[wasm_bindgen(no_free)]
+pub struct Pooled {
+    id: u32,
+}
+
+#[wasm_bindgen]
+impl Pooled {
+    pub fn acquire(id: u32) -> Pooled {
+        LIVE_POOLED.fetch_add(1, SeqCst);
+        Pooled { id }
+    }
+
+    pub fn release(self) {}
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn live() -> u32 {
+        LIVE_POOLED.load(SeqCst)
+    }
+}
+
+impl Drop for Pooled {
+    fn drop(&mut self) {
+        LIVE_POOLED.fetch_sub(1, SeqCst);
+    }
+}
+
+#[wasm_bindgen_test]
+fn no_free() {
+    js_no_free();
+}
+
+#[wasm_bindgen(on_free = close)]
+pub struct Connection {
+    open: bool,
+}
+
+#[wasm_bindgen]
+impl Connection {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Connection {
+        Connection { open: true }
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+}
+
+#[wasm_bindgen_test]
+fn on_free() {
+    js_on_free();
+}
+
+#[wasm_bindgen]
+pub struct Facade {
+    value: u32,
+}
+
+#[wasm_bindgen]
+impl Facade {
+    #[wasm_bindgen(constructor)]
+    pub fn new(value: u32) -> Facade {
+        Facade { value }
+    }
+
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+}
+
+// Stands in for a trait defined in another crate, which can add methods to
+// the `Facade` class through a trait impl.
+mod facade_ext {
+    pub trait FacadeExt {
+        fn doubled(&self) -> u32;
+        fn increment(&mut self);
+        fn zero() -> Self;
+    }
+}
+
+#[wasm_bindgen]
+impl facade_ext::FacadeExt for Facade {
+    fn doubled(&self) -> u32 {
+        self.value * 2
+    }
+
+    fn increment(&mut self) {
+        self.value += 1;
+    }
+
+    #[wasm_bindgen(js_name = zeroed)]
+    fn zero() -> Facade {
+        Facade { value: 0 }
+    }
+}
+
+#[wasm_bindgen_test]
+fn trait_impl_methods() {
+    js_trait_impl_methods();
+}
+
 mod works_in_module {
     use wasm_bindgen::prelude::wasm_bindgen;
 
@@ -136,3 +136,20 @@ exports.js_store_forgotten_closure = f => {
 exports.js_call_forgotten_closure = () => {
   FORGOTTEN_CLOSURE();
 };
+
+exports.call_mixed_refs = (a, f) => {
+  const ret = f(a, 2, 'z');
+  a.free();
+  return ret;
+};
+
+exports.call_eight_args = f => {
+  f('a', 1, 2, 3, 4, 5, 6, 'h');
+};
+
+exports.call_twice_with_index = f => {
+  f(0);
+  f(1);
+};
+
+exports.call_val_with_y = f => f('y');
@@ -584,3 +584,90 @@ fn forget_works() {
     a.forget();
     js_call_forgotten_closure();
 }
+
+#[wasm_bindgen_test]
+fn mixed_references_build_at_all() {
+    #[wasm_bindgen]
+    extern "C" {
+        fn mixed_ref1(a: &Fn(u32, &JsValue));
+        fn mixed_ref2(a: &mut FnMut(&JsValue, u32, &RefFirstArgument) -> u32);
+        fn mixed_ref3(a: &Closure<Fn(String, &RefFirstArgument)>);
+        fn mixed_ref4(a: &Closure<FnMut(&JsValue, &JsValue, u32)>);
+    }
+
+    Closure::wrap(Box::new(|_: u32, _: &JsValue| ()) as Box<Fn(u32, &JsValue)>);
+    Closure::wrap(Box::new(|_: &JsValue, _: u32| ()) as Box<FnMut(&JsValue, u32)>);
+    Closure::once(|_: u32, _: &JsValue, _: &RefFirstArgument| ());
+    Closure::once_into_js(|_: &JsValue, _: String| ());
+}
+
+type EightArgs = Fn(&JsValue, u32, u32, u32, u32, u32, u32, &JsValue);
+
+#[wasm_bindgen_test]
+fn mixed_references_work() {
+    #[wasm_bindgen(module = "tests/wasm/closures.js")]
+    extern "C" {
+        fn call_mixed_refs(
+            a: RefFirstArgument,
+            b: &Closure<FnMut(&RefFirstArgument, u32, &JsValue) -> u32>,
+        ) -> u32;
+        #[wasm_bindgen(js_name = call_mixed_refs)]
+        fn call_mixed_refs_stack(
+            a: RefFirstArgument,
+            b: &mut FnMut(&RefFirstArgument, u32, &JsValue) -> u32,
+        ) -> u32;
+        fn call_eight_args(a: &Closure<EightArgs>);
+    }
+
+    let a = Closure::wrap(Box::new(|x: &RefFirstArgument, y: u32, z: &JsValue| {
+        assert_eq!(z.as_string().unwrap(), "z");
+        x.contents + y
+    })
+        as Box<FnMut(&RefFirstArgument, u32, &JsValue) -> u32>);
+    assert_eq!(call_mixed_refs(RefFirstArgument { contents: 3 }, &a), 5);
+
+    let n = call_mixed_refs_stack(
+        RefFirstArgument { contents: 4 },
+        &mut |x: &RefFirstArgument, y: u32, z: &JsValue| {
+            assert_eq!(z.as_string().unwrap(), "z");
+            x.contents + y
+        },
+    );
+    assert_eq!(n, 6);
+
+    let a = Closure::wrap(Box::new(
+        |a: &JsValue, b: u32, c: u32, d: u32, e: u32, f: u32, g: u32, h: &JsValue| {
+            assert_eq!(a.as_string().unwrap(), "a");
+            assert_eq!((b, c, d, e, f, g), (1, 2, 3, 4, 5, 6));
+            assert_eq!(h.as_string().unwrap(), "h");
+        },
+    ) as Box<EightArgs>);
+    call_eight_args(&a);
+}
+
+#[wasm_bindgen_test]
+fn scoped_closures() {
+    #[wasm_bindgen(module = "tests/wasm/closures.js")]
+    extern "C" {
+        fn call_twice_with_index(f: &JsValue);
+        fn call_val_with_y(f: &JsValue) -> String;
+    }
+
+    let mut calls = Vec::new();
+    let f = Closure::scope(
+        Box::new(|i: u32| calls.push(i)) as Box<FnMut(u32) + '_>,
+        |f| {
+            call_twice_with_index(f.as_ref());
+            f.as_ref().clone()
+        },
+    );
+    assert_eq!(calls, [0, 1]);
+    call_destroyed(&f);
+
+    let prefix = String::from("x");
+    let ret = Closure::scope(
+        Box::new(|s: String| format!("{}{}", prefix, s)) as Box<Fn(String) -> String + '_>,
+        |f| call_val_with_y(f.as_ref()),
+    );
+    assert_eq!(ret, "xy");
+}
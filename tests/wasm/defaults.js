@@ -0,0 +1,15 @@
+const wasm = require('wasm-bindgen-test.js');
+const assert = require('assert');
+
+exports.js_defaults = () => {
+  assert.strictEqual(wasm.defaults_describe(1), '1, 10, -0.5');
+  assert.strictEqual(wasm.defaults_describe(2, 3), '2, 3, -0.5');
+  assert.strictEqual(wasm.defaults_describe(2, undefined, '-'), '2-10--0.5');
+  assert.strictEqual(wasm.defaults_describe(2, 3, 'x', 4, true), '2X3X4.0');
+
+  const counter = new wasm.DefaultsCounter();
+  assert.strictEqual(counter.bump(), 2);
+  assert.strictEqual(counter.bump(5), 7);
+  counter.free();
+  assert.strictEqual(new wasm.DefaultsCounter(10).bump(), 11);
+};
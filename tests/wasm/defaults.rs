@@ -0,0 +1,57 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/defaults.js")]
+extern "C" {
+    fn js_defaults();
+}
+
+#[wasm_bindgen(defaults(limit = 10, sep = ", ", scale = -0.5, upper = false))]
+pub fn defaults_describe(
+    items: u32,
+    limit: Option<u32>,
+    sep: Option<String>,
+    scale: Option<f64>,
+    upper: Option<bool>,
+) -> String {
+    let sep = sep.unwrap();
+    let ret = format!(
+        "{}{}{}{}{:?}",
+        items,
+        sep,
+        limit.unwrap(),
+        sep,
+        scale.unwrap()
+    );
+    if upper.unwrap() {
+        ret.to_uppercase()
+    } else {
+        ret
+    }
+}
+
+#[wasm_bindgen]
+pub struct DefaultsCounter {
+    count: u32,
+}
+
+#[wasm_bindgen]
+impl DefaultsCounter {
+    #[wasm_bindgen(constructor, defaults(start = 1))]
+    pub fn new(start: Option<u32>) -> DefaultsCounter {
+        DefaultsCounter {
+            count: start.unwrap(),
+        }
+    }
+
+    #[wasm_bindgen(defaults(step = 1))]
+    pub fn bump(&mut self, step: Option<u32>) -> u32 {
+        self.count += step.unwrap();
+        self.count
+    }
+}
+
+#[wasm_bindgen_test]
+fn works() {
+    js_defaults();
+}
@@ -140,3 +140,25 @@ exports.StaticStructural = class {
     return x + 3;
   }
 };
+
+let STATIC_COUNTER = 0;
+
+exports.StaticAccessors = class {
+  static get counter() {
+    return STATIC_COUNTER;
+  }
+
+  static set counter(val) {
+    STATIC_COUNTER = val;
+  }
+
+  static get final_counter() {
+    return STATIC_COUNTER;
+  }
+
+  static set final_counter(val) {
+    STATIC_COUNTER = val;
+  }
+};
+
+exports.StaticAccessors.LIMIT = 10;
@@ -36,6 +36,13 @@ extern "C" {
     #[wasm_bindgen(method, js_class = default)]
     fn get(this: &RenamedTypes) -> i32;
 
+    #[wasm_bindgen(js_name = default)]
+    type DefaultExport;
+    #[wasm_bindgen(constructor)]
+    fn new(arg: i32) -> DefaultExport;
+    #[wasm_bindgen(method)]
+    fn get(this: &DefaultExport) -> i32;
+
     fn switch_methods_a();
     fn switch_methods_b();
     type SwitchMethods;
@@ -96,6 +103,20 @@ extern "C" {
     type StaticStructural;
     #[wasm_bindgen(static_method_of = StaticStructural, structural)]
     fn static_structural(a: u32) -> u32;
+
+    type StaticAccessors;
+    #[wasm_bindgen(static_method_of = StaticAccessors, getter)]
+    fn counter() -> u32;
+    #[wasm_bindgen(static_method_of = StaticAccessors, setter)]
+    fn set_counter(val: u32);
+    #[wasm_bindgen(static_method_of = StaticAccessors, getter = LIMIT)]
+    fn limit() -> u32;
+    #[wasm_bindgen(static_method_of = StaticAccessors, getter, final)]
+    fn final_counter() -> u32;
+    #[wasm_bindgen(static_method_of = StaticAccessors, setter, final)]
+    fn set_final_counter(val: u32);
+    #[wasm_bindgen(static_method_of = StaticAccessors, getter = LIMIT, final)]
+    fn final_limit() -> u32;
 }
 
 #[wasm_bindgen]
@@ -104,6 +125,10 @@ extern "C" {
     fn random() -> f64;
     #[wasm_bindgen(js_namespace = Math)]
     fn log(a: f64) -> f64;
+
+    type Number;
+    #[wasm_bindgen(static_method_of = Number, getter = MAX_SAFE_INTEGER, final)]
+    fn max_safe_integer() -> f64;
 }
 
 #[wasm_bindgen_test]
@@ -138,6 +163,12 @@ fn rename_type() {
     assert_eq!(f.get(), 2);
 }
 
+#[wasm_bindgen_test]
+fn default_export_type() {
+    let f = DefaultExport::new(2);
+    assert_eq!(f.get(), 3);
+}
+
 #[wasm_bindgen_test]
 #[cfg(ignored)] // TODO: fix this before landing
 fn switch_methods() {
@@ -237,3 +268,16 @@ fn catch_constructors() {
 fn static_structural() {
     assert_eq!(StaticStructural::static_structural(30), 33);
 }
+
+#[wasm_bindgen_test]
+fn static_accessors() {
+    assert_eq!(StaticAccessors::counter(), 0);
+    StaticAccessors::set_counter(3);
+    assert_eq!(StaticAccessors::counter(), 3);
+    assert_eq!(StaticAccessors::final_counter(), 3);
+    StaticAccessors::set_final_counter(5);
+    assert_eq!(StaticAccessors::counter(), 5);
+    assert_eq!(StaticAccessors::limit(), 10);
+    assert_eq!(StaticAccessors::final_limit(), 10);
+    assert_eq!(Number::max_safe_integer(), 9007199254740991.0);
+}
@@ -0,0 +1,24 @@
+const vm = require('vm');
+
+class Map {
+  get size() {
+    return 42;
+  }
+}
+
+exports.new_module_map = () => new Map();
+
+exports.new_null_prototype_object = () => {
+  const obj = Object.create(null);
+  obj.key = 'value';
+  return obj;
+};
+
+exports.new_cross_realm_date = () => vm.runInNewContext('new Date(2019, 0, 1)');
+
+global.importTypeCheck = {
+  Map: class Map {},
+  newMap() {
+    return new this.Map();
+  },
+};
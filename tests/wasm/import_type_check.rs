@@ -0,0 +1,47 @@
+//! Values handed to Rust as imported types are checked in debug mode, which is
+//! how this test suite is run, so these make sure values of the right type
+//! aren't rejected.
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/import_type_check.js")]
+extern "C" {
+    // Shadows the global `Map`, which its instances aren't.
+    type Map;
+    fn new_module_map() -> Map;
+    #[wasm_bindgen(method, getter)]
+    fn size(this: &Map) -> u32;
+
+    fn new_null_prototype_object() -> js_sys::Object;
+    fn new_cross_realm_date() -> js_sys::Date;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = importTypeCheck, js_name = Map)]
+    type NamespacedMap;
+    #[wasm_bindgen(js_namespace = importTypeCheck, js_name = newMap)]
+    fn new_namespaced_map() -> NamespacedMap;
+}
+
+#[wasm_bindgen_test]
+fn module_types_are_not_global_types() {
+    assert_eq!(new_module_map().size(), 42);
+}
+
+#[wasm_bindgen_test]
+fn namespaced_types_are_not_global_types() {
+    new_namespaced_map();
+}
+
+#[wasm_bindgen_test]
+fn null_prototype_objects_are_objects() {
+    let obj = new_null_prototype_object();
+    assert_eq!(js_sys::Object::keys(&obj).length(), 1);
+}
+
+#[wasm_bindgen_test]
+fn cross_realm_values_are_accepted() {
+    assert_eq!(new_cross_realm_date().get_full_year(), 2019);
+}
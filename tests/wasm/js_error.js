@@ -0,0 +1,22 @@
+const wasm = require('wasm-bindgen-test.js');
+const assert = require('assert');
+
+exports.js_error_exports = () => {
+  assert.strictEqual(wasm.js_error_parse('80'), 80);
+
+  assert.throws(() => wasm.js_error_parse('x'), e => {
+    assert.ok(e instanceof Error);
+    assert.strictEqual(e.message, 'invalid digit found in string');
+    return true;
+  });
+
+  assert.throws(() => wasm.js_error_parse_port('70000'), e => {
+    assert.ok(e instanceof Error);
+    assert.strictEqual(e.message, 'invalid port');
+    const trace = 'Caused by: number too large to fit in target type';
+    assert.strictEqual(e.stack.includes(trace), wasm.js_error_debug_assertions());
+    return true;
+  });
+
+  assert.throws(() => wasm.js_error_new(), /^Error: custom message$/);
+};
@@ -0,0 +1,54 @@
+use std::fmt;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/js_error.js")]
+extern "C" {
+    fn js_error_exports();
+}
+
+#[derive(Debug)]
+struct Outer(std::num::ParseIntError);
+
+impl fmt::Display for Outer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid port")
+    }
+}
+
+impl std::error::Error for Outer {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[wasm_bindgen]
+pub fn js_error_parse(s: &str) -> Result<u16, JsError> {
+    Ok(s.parse()?)
+}
+
+#[wasm_bindgen]
+pub fn js_error_parse_port(s: &str) -> Result<u16, JsError> {
+    s.parse().map_err(|e| Outer(e).into())
+}
+
+#[wasm_bindgen]
+pub fn js_error_debug_assertions() -> bool {
+    cfg!(debug_assertions)
+}
+
+#[wasm_bindgen]
+pub fn js_error_new() -> Result<(), JsError> {
+    Err(JsError::new("custom message"))
+}
+
+#[wasm_bindgen_test]
+fn works() {
+    js_error_exports();
+}
+
+#[wasm_bindgen_test]
+fn into_js_value() {
+    let value: JsValue = JsError::new("oh no").into();
+    assert!(value.is_object());
+}
@@ -1,3 +1,5 @@
+const vm = require('vm');
+
 class JsCast1 {
   constructor() {
     this.val = 1;
@@ -26,3 +28,17 @@ exports.JsCast1 = JsCast1;
 exports.JsCast2 = JsCast2;
 exports.JsCast3 = JsCast3;
 exports.JsCast4 = JsCast4;
+
+// Classes with the same names defined in another realm, which fail
+// `instanceof` checks against the classes above.
+exports.other_realm_js_cast4 = () => vm.runInNewContext(`
+  class JsCast1 {}
+  class JsCast3 extends JsCast1 {}
+  class JsCast4 extends JsCast3 {}
+  new JsCast4();
+`);
+
+exports.other_realm_orphan_js_cast3 = () => vm.runInNewContext(`
+  class JsCast3 {}
+  new JsCast3();
+`);
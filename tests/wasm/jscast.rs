@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_test::*;
@@ -23,6 +24,9 @@ extern "C" {
     type JsCast4;
     #[wasm_bindgen(constructor)]
     fn new() -> JsCast4;
+
+    fn other_realm_js_cast4() -> JsValue;
+    fn other_realm_orphan_js_cast3() -> JsValue;
 }
 
 #[wasm_bindgen_test]
@@ -86,3 +90,36 @@ fn multiple_layers_of_inheritance() {
     let b: &JsCast1 = a.as_ref();
     assert_eq!(b.myval(), 4);
 }
+
+#[wasm_bindgen_test]
+fn try_from_superclass() {
+    let a: JsCast1 = JsCast3::new().into();
+    let b = JsCast3::try_from(a).unwrap();
+    assert_eq!(b.myval(), 3);
+
+    let a = JsCast1::new();
+    let a = JsCast3::try_from(a).unwrap_err();
+    assert_eq!(a.myval(), 1);
+
+    let c = JsCast4::new();
+    let d: &JsCast1 = c.as_ref();
+    assert!(<&JsCast4>::try_from(d).is_ok());
+    assert!(<&JsCast3>::try_from(&JsCast1::new()).is_err());
+}
+
+#[wasm_bindgen_test]
+fn other_realms() {
+    let a = other_realm_js_cast4();
+    assert!(!a.is_instance_of::<JsCast4>());
+    assert!(a.dyn_ref::<JsCast4>().is_some());
+    assert!(a.dyn_ref::<JsCast3>().is_some());
+    assert!(a.dyn_ref::<JsCast1>().is_some());
+    assert!(a.dyn_ref::<JsCast2>().is_none());
+
+    // The name matches but the superclass declared with `extends` doesn't.
+    let b = other_realm_orphan_js_cast3();
+    assert!(b.dyn_ref::<JsCast3>().is_none());
+
+    assert!(JsValue::from(3).dyn_ref::<JsCast1>().is_none());
+    assert!(JsValue::NULL.dyn_ref::<JsCast1>().is_none());
+}
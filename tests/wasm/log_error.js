@@ -0,0 +1,30 @@
+const wasm = require('wasm-bindgen-test.js');
+const assert = require('assert');
+
+const error = new Error('uncaught');
+
+exports.throw_uncaught = function() {
+  throw error;
+};
+
+exports.call_throw_from_import = function() {
+  const logged = [];
+  const consoleError = console.error;
+  console.error = (...args) => logged.push(args.join(' '));
+  try {
+    assert.throws(wasm.throw_from_import, e => e === error);
+  } finally {
+    console.error = consoleError;
+  }
+
+  // Errors are only logged in debug mode.
+  if (logged.length === 0) {
+    return;
+  }
+  assert.strictEqual(logged.length, 1);
+  const name = /\(in `([^`]*)`\)/.exec(logged[0]);
+  assert.ok(name !== null, logged[0]);
+  assert.ok(name[1].includes('throwuncaught'), name[1]);
+  // The name is demangled, without the `_ZN` prefix and hash of the symbol.
+  assert.ok(!/^_ZN|17h[0-9a-f]{16}E$/.test(name[1]), name[1]);
+};
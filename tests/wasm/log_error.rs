@@ -0,0 +1,18 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/log_error.js")]
+extern "C" {
+    fn throw_uncaught();
+    fn call_throw_from_import();
+}
+
+#[wasm_bindgen]
+pub fn throw_from_import() {
+    throw_uncaught();
+}
+
+#[wasm_bindgen_test]
+fn names_the_function() {
+    call_throw_from_import();
+}
@@ -18,6 +18,7 @@ pub mod char;
 pub mod classes;
 pub mod closures;
 pub mod comments;
+pub mod defaults;
 pub mod duplicate_deps;
 pub mod duplicates;
 pub mod enums;
@@ -25,17 +26,28 @@ pub mod enums;
 pub mod final_;
 pub mod getters_and_setters;
 pub mod import_class;
+pub mod import_type_check;
 pub mod imports;
+pub mod js_error;
 pub mod js_objects;
 pub mod jscast;
+pub mod log_error;
+pub mod maps;
 pub mod math;
+pub mod mock;
+pub mod mutable_statics;
 pub mod node;
+pub mod object;
 pub mod option;
 pub mod optional_primitives;
 pub mod rethrow;
+#[cfg(feature = "serde-serialize")]
+pub mod serde_bridge;
 pub mod simple;
 pub mod slice;
 pub mod structural;
+pub mod thread_local_statics;
+pub mod traits;
 pub mod u64;
 pub mod validate_prt;
 pub mod variadic;
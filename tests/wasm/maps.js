@@ -0,0 +1,37 @@
+const wasm = require('wasm-bindgen-test.js');
+const assert = require('assert');
+
+exports.js_map_exports = () => {
+  const lengths = wasm.map_lengths(new Map([['a', 'xyz'], ['b', '']]));
+  assert.ok(lengths instanceof Map);
+  assert.deepStrictEqual(Array.from(lengths.entries()).sort(), [['a', 3], ['b', 0]]);
+
+  // plain objects are accepted wherever a map is expected
+  assert.deepStrictEqual(wasm.map_lengths({ c: 'ab' }), new Map([['c', 2]]));
+
+  assert.deepStrictEqual(wasm.map_to_object(new Map([['t', true], ['f', false]])), {
+    f: false,
+    t: true,
+  });
+  assert.deepStrictEqual(wasm.object_sum({ a: 1.5, b: 2 }), { sum: 3.5 });
+  assert.deepStrictEqual(wasm.map_wrapper({ a: -1 }), { a: -1 });
+
+  assert.throws(() => wasm.map_lengths(new Map([[1, 'a']])), /expected a string key/);
+  assert.throws(() => wasm.map_lengths({ a: 1 }), /unexpected value for key `a`/);
+};
+
+exports.js_double_map = map => {
+  assert.ok(map instanceof Map);
+  const ret = new Map();
+  for (const [key, value] of map) {
+    ret.set(key, value * 2);
+  }
+  return ret;
+};
+
+exports.js_upper_object = object => {
+  assert.deepStrictEqual(object, { x: 'foo' });
+  return { x: object.x.toUpperCase(), y: 'BAR' };
+};
+
+exports.js_make_values = () => new Map([['num', 3], ['null', null]]);
@@ -0,0 +1,68 @@
+use std::collections::{BTreeMap, HashMap};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::PlainObject;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/maps.js")]
+extern "C" {
+    fn js_map_exports();
+
+    fn js_double_map(map: HashMap<String, u32>) -> HashMap<String, u32>;
+
+    #[wasm_bindgen(plain_object)]
+    fn js_upper_object(object: BTreeMap<String, String>) -> BTreeMap<String, String>;
+
+    fn js_make_values() -> BTreeMap<String, JsValue>;
+}
+
+#[wasm_bindgen]
+pub fn map_lengths(map: HashMap<String, String>) -> HashMap<String, u32> {
+    map.into_iter()
+        .map(|(key, value)| (key, value.len() as u32))
+        .collect()
+}
+
+#[wasm_bindgen(plain_object(return))]
+pub fn map_to_object(map: BTreeMap<String, bool>) -> BTreeMap<String, bool> {
+    map
+}
+
+#[wasm_bindgen(plain_object)]
+pub fn object_sum(object: &HashMap<String, f64>) -> BTreeMap<String, f64> {
+    let mut ret = BTreeMap::new();
+    ret.insert("sum".to_string(), object.values().sum());
+    ret
+}
+
+#[wasm_bindgen]
+pub fn map_wrapper(map: PlainObject<BTreeMap<String, i32>>) -> PlainObject<BTreeMap<String, i32>> {
+    map
+}
+
+#[wasm_bindgen_test]
+fn exports() {
+    js_map_exports();
+}
+
+#[wasm_bindgen_test]
+fn imports() {
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), 1);
+    map.insert("b".to_string(), 2);
+    let map = js_double_map(map);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["a"], 2);
+    assert_eq!(map["b"], 4);
+
+    let mut object = BTreeMap::new();
+    object.insert("x".to_string(), "foo".to_string());
+    let object = js_upper_object(object);
+    assert_eq!(object.len(), 2);
+    assert_eq!(object["x"], "FOO");
+    assert_eq!(object["y"], "BAR");
+
+    let values = js_make_values();
+    assert_eq!(values.keys().collect::<Vec<_>>(), ["null", "num"]);
+    assert!(values["null"].is_null());
+    assert_eq!(values["num"].as_f64(), Some(3.0));
+}
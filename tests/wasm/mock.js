@@ -0,0 +1,3 @@
+exports.api = {
+    double: x => x * 2,
+};
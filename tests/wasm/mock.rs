@@ -0,0 +1,73 @@
+use js_sys::Object;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::mock::Mock;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = Math)]
+    fn max(a: f64, b: f64) -> f64;
+
+    #[wasm_bindgen(js_namespace = JSON)]
+    fn stringify(value: &JsValue) -> String;
+}
+
+#[wasm_bindgen(module = "tests/wasm/mock.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = api)]
+    static API: Object;
+
+    #[wasm_bindgen(js_namespace = api)]
+    fn double(x: u32) -> u32;
+}
+
+#[wasm_bindgen_test]
+fn records_calls() {
+    let mock = Mock::new("Math.max");
+    mock.returns(&JsValue::from(42));
+    assert_eq!(max(1.0, 2.0), 42.0);
+    assert_eq!(max(3.0, 4.0), 42.0);
+    assert_eq!(mock.call_count(), 2);
+    assert!(mock.was_called_with(&[JsValue::from(1), JsValue::from(2)]));
+    assert!(!mock.was_called_with(&[JsValue::from(2), JsValue::from(1)]));
+    assert_eq!(
+        mock.calls(),
+        vec![
+            vec![JsValue::from(1), JsValue::from(2)],
+            vec![JsValue::from(3), JsValue::from(4)],
+        ]
+    );
+
+    mock.reset();
+    assert_eq!(mock.call_count(), 0);
+}
+
+#[wasm_bindgen_test]
+fn restores_on_drop() {
+    {
+        let mock = Mock::new("Math.max");
+        mock.returns(&JsValue::from(0));
+        assert_eq!(max(1.0, 2.0), 0.0);
+    }
+    assert_eq!(max(1.0, 2.0), 2.0);
+}
+
+#[wasm_bindgen_test]
+fn calls_through() {
+    let mock = Mock::new("JSON.stringify");
+    mock.call_through();
+    assert_eq!(stringify(&JsValue::from(true)), "true");
+    assert!(mock.was_called_with(&[JsValue::TRUE]));
+}
+
+#[wasm_bindgen_test]
+fn mocks_module_namespaces() {
+    assert_eq!(double(2), 4);
+    {
+        let mock = Mock::on(&API, "double");
+        mock.returns(&JsValue::from(0));
+        assert_eq!(double(2), 0);
+        assert!(mock.was_called_with(&[JsValue::from(2)]));
+    }
+    assert_eq!(double(2), 4);
+}
@@ -0,0 +1,9 @@
+const g = typeof globalThis === 'undefined' ? global : globalThis;
+
+g.wbgMutableGlobal = 0;
+
+const MUTABLE_STATE = { value: null };
+exports.MUTABLE_STATE = MUTABLE_STATE;
+
+exports.mutable_global_value = () => g.wbgMutableGlobal;
+exports.mutable_state_value = () => MUTABLE_STATE.value;
@@ -0,0 +1,35 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/mutable_statics.js")]
+extern "C" {
+    fn mutable_global_value() -> JsValue;
+    fn mutable_state_value() -> JsValue;
+
+    #[wasm_bindgen(js_namespace = MUTABLE_STATE, js_name = value)]
+    static mut MUTABLE_STATE_VALUE: JsValue;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = wbgMutableGlobal)]
+    static mut MUTABLE_GLOBAL: JsValue;
+}
+
+#[wasm_bindgen_test]
+fn assign_global() {
+    assert_eq!(MUTABLE_GLOBAL.with(|v| v.as_f64()), Some(0.0));
+    MUTABLE_GLOBAL.set(JsValue::from(1));
+    assert_eq!(mutable_global_value().as_f64(), Some(1.0));
+    assert_eq!(MUTABLE_GLOBAL.with(|v| v.as_f64()), Some(1.0));
+    MUTABLE_GLOBAL.set(JsValue::from("foo"));
+    assert_eq!(mutable_global_value().as_string(), Some("foo".to_string()));
+}
+
+#[wasm_bindgen_test]
+fn assign_namespaced_property() {
+    assert!(MUTABLE_STATE_VALUE.with(|v| v.is_null()));
+    MUTABLE_STATE_VALUE.set(JsValue::from(2));
+    assert_eq!(mutable_state_value().as_f64(), Some(2.0));
+    assert_eq!(MUTABLE_STATE_VALUE.with(|v| v.as_f64()), Some(2.0));
+}
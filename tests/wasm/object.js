@@ -0,0 +1,9 @@
+const assert = require('assert');
+
+exports.js_check_options = a => {
+    assert.strictEqual(a.count, 3);
+    assert.strictEqual(a.name, 'x');
+    assert.deepStrictEqual(Object.keys(a), ['count', 'name']);
+};
+
+exports.js_make_options = () => ({ count: 4, name: 'y' });
@@ -0,0 +1,31 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/object.js")]
+extern "C" {
+    fn js_check_options(a: &ObjectOptions);
+    fn js_make_options() -> ObjectOptions;
+}
+
+#[wasm_bindgen(object)]
+pub struct ObjectOptions {
+    pub count: u32,
+    pub name: String,
+}
+
+#[wasm_bindgen_test]
+fn builder_sets_properties() {
+    let a = ObjectOptions::new().count(3).name("x".to_string());
+    assert_eq!(a.get_count(), 3);
+    assert_eq!(a.get_name(), "x");
+    js_check_options(&a);
+}
+
+#[wasm_bindgen_test]
+fn properties_are_structural() {
+    let a = js_make_options();
+    assert_eq!(a.get_count(), 4);
+    a.set_count(5);
+    assert_eq!(a.get_count(), 5);
+    assert_eq!(a.get_name(), "y");
+}
@@ -12,3 +12,13 @@ exports.call_throw_one = function() {
 exports.call_ok = function() {
   wasm.nothrow();
 };
+
+exports.call_throw_custom = function() {
+  assert.throws(() => wasm.throw_struct(3), e => {
+    assert.ok(e instanceof wasm.RethrowError);
+    assert.strictEqual(e.code, 3);
+    e.free();
+    return true;
+  });
+  assert.throws(() => wasm.throw_message(), e => e === 'custom');
+};
@@ -5,6 +5,7 @@ use wasm_bindgen_test::*;
 extern "C" {
     fn call_throw_one();
     fn call_ok();
+    fn call_throw_custom();
 }
 
 #[wasm_bindgen_test]
@@ -26,3 +27,39 @@ fn ok_works() {
 pub fn nothrow() -> Result<u32, JsValue> {
     Ok(1)
 }
+
+#[wasm_bindgen]
+pub struct RethrowError {
+    code: u32,
+}
+
+#[wasm_bindgen]
+impl RethrowError {
+    #[wasm_bindgen(getter)]
+    pub fn code(&self) -> u32 {
+        self.code
+    }
+}
+
+pub struct RethrowMessage(&'static str);
+
+impl From<RethrowMessage> for JsValue {
+    fn from(msg: RethrowMessage) -> JsValue {
+        JsValue::from_str(msg.0)
+    }
+}
+
+#[wasm_bindgen_test]
+fn custom_err_works() {
+    call_throw_custom();
+}
+
+#[wasm_bindgen]
+pub fn throw_struct(code: u32) -> Result<u32, RethrowError> {
+    Err(RethrowError { code })
+}
+
+#[wasm_bindgen]
+pub fn throw_message() -> Result<(), RethrowMessage> {
+    Err(RethrowMessage("custom"))
+}
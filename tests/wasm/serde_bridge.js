@@ -0,0 +1,38 @@
+const wasm = require('wasm-bindgen-test.js');
+const assert = require('assert');
+
+exports.js_serde_exports = () => {
+  const point = { x: 1, y: 0.5, label: 'p', tags: ['a'] };
+  assert.deepStrictEqual(wasm.serde_move_point(point, 2), {
+    label: 'p',
+    tags: ['a'],
+    x: 3,
+    y: 0.5,
+  });
+  assert.strictEqual(wasm.serde_point_label(point), 'p');
+  assert.strictEqual(wasm.serde_point_label({ x: 1, y: 2, tags: [] }), undefined);
+
+  assert.throws(() => wasm.serde_move_point({ x: 'nope' }, 1), /invalid type/);
+
+  const holder = new wasm.SerdeHolder(point);
+  assert.deepStrictEqual(holder.point(), [1, 0]);
+  holder.free();
+};
+
+exports.js_mirror_point = point => {
+  assert.deepStrictEqual(point, { label: 'a', tags: ['b'], x: 1, y: 2.5 });
+  return { x: -point.x, y: -point.y, label: null, tags: point.tags.concat(['c']) };
+};
+
+exports.js_make_map = n => {
+  const list = [];
+  for (let i = 0; i < n; i++) {
+    list.push(i);
+  }
+  return { three: list };
+};
+
+exports.js_check_wrapper = val => {
+  assert.deepStrictEqual(val, [true, null]);
+  return [undefined, false];
+};
@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::Serde;
+use wasm_bindgen_test::*;
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub struct Point {
+    x: i32,
+    y: f64,
+    label: Option<String>,
+    tags: Vec<String>,
+}
+
+#[wasm_bindgen(module = "tests/wasm/serde_bridge.js")]
+extern "C" {
+    fn js_serde_exports();
+
+    #[wasm_bindgen(serde)]
+    fn js_mirror_point(point: &Point) -> Point;
+
+    #[wasm_bindgen(serde(return))]
+    fn js_make_map(n: u32) -> BTreeMap<String, Vec<u32>>;
+
+    fn js_check_wrapper(val: Serde<Vec<Option<bool>>>) -> Serde<Vec<Option<bool>>>;
+}
+
+#[wasm_bindgen(serde, typescript_type(point = "Point", return = "Point"))]
+pub fn serde_move_point(point: Point, dx: i32) -> Point {
+    Point {
+        x: point.x + dx,
+        ..point
+    }
+}
+
+#[wasm_bindgen(serde(point))]
+pub fn serde_point_label(point: &Point) -> Option<String> {
+    point.label.clone()
+}
+
+#[wasm_bindgen]
+pub struct SerdeHolder {
+    point: Point,
+}
+
+#[wasm_bindgen]
+impl SerdeHolder {
+    #[wasm_bindgen(constructor, serde(point))]
+    pub fn new(point: Point) -> SerdeHolder {
+        SerdeHolder { point }
+    }
+
+    #[wasm_bindgen(serde(return))]
+    pub fn point(&self) -> Vec<i32> {
+        vec![self.point.x, self.point.y as i32]
+    }
+}
+
+#[wasm_bindgen_test]
+fn exports() {
+    js_serde_exports();
+}
+
+#[wasm_bindgen_test]
+fn imports() {
+    let point = Point {
+        x: 1,
+        y: 2.5,
+        label: Some("a".to_string()),
+        tags: vec!["b".to_string()],
+    };
+    let mirrored = js_mirror_point(&point);
+    assert_eq!(
+        mirrored,
+        Point {
+            x: -1,
+            y: -2.5,
+            label: None,
+            tags: vec!["b".to_string(), "c".to_string()],
+        }
+    );
+
+    let map = js_make_map(3);
+    assert_eq!(map.len(), 1);
+    assert_eq!(map["three"], [0, 1, 2]);
+
+    let val = js_check_wrapper(Serde(vec![Some(true), None]));
+    assert_eq!(val.0, [None, Some(false)]);
+}
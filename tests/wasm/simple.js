@@ -105,3 +105,10 @@ exports.test_string_roundtrip = () => {
   test('a longer string');
   test('a longer 💖 string');
 };
+
+exports.test_instantiated_generics = function() {
+  assert.strictEqual(wasm.simple_double_f32(1.5), 3);
+  assert.strictEqual(wasm.simple_double_u8(100), 200);
+  assert.strictEqual(wasm.simple_pair_u32_bool(3, true), 3);
+  assert.strictEqual(wasm.simple_double, undefined);
+};
@@ -29,6 +29,7 @@ extern "C" {
     fn new_renamed() -> Renamed;
 
     fn test_string_roundtrip();
+    fn test_instantiated_generics();
 }
 
 #[wasm_bindgen_test]
@@ -222,3 +223,31 @@ fn anyref_heap_live_count() {
     drop(y);
     assert_eq!(x, wasm_bindgen::anyref_heap_live_count());
 }
+
+#[wasm_bindgen_test]
+fn debug_heap_stats() {
+    let x = wasm_bindgen::debug::heap_stats();
+    assert!(x.capacity >= x.live);
+    let y = JsValue::from("leak?");
+    let stats = wasm_bindgen::debug::heap_stats();
+    assert_eq!(stats.live, x.live + 1);
+    assert!(stats.capacity >= stats.live);
+    drop(y);
+    assert_eq!(wasm_bindgen::debug::heap_stats().live, x.live);
+    assert_eq!(wasm_bindgen::debug::allocation_sites(), None);
+}
+
+#[wasm_bindgen_test]
+fn instantiated_generics() {
+    test_instantiated_generics();
+}
+
+#[wasm_bindgen(instantiate(T = f32), instantiate(T = u8))]
+pub fn simple_double<T: Copy + std::ops::Add<Output = T>>(a: T) -> T {
+    a + a
+}
+
+#[wasm_bindgen(js_name = simple_pair, instantiate(A = u32, B = bool))]
+pub fn simple_first<A, B>(a: A, _b: B) -> A {
+    a
+}
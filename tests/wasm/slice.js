@@ -224,3 +224,46 @@ exports.js_clamped = (a, offset) => {
   assert.equal(a[1], offset + 1);
   assert.equal(a[2], offset + 2);
 };
+
+exports.js_return_view = () => {
+    const buf = new wasm.ViewBuffer();
+    const bytes = buf.bytes();
+    assert.ok(bytes instanceof Uint8Array);
+    assert.deepStrictEqual(Array.from(bytes), [1, 2, 3]);
+
+    // The view aliases wasm memory, so changes made in Rust show through.
+    buf.set_byte(1, 42);
+    assert.strictEqual(bytes[1], 42);
+
+    const floats = buf.floats();
+    assert.ok(floats instanceof Float32Array);
+    assert.deepStrictEqual(Array.from(floats), [1.5, 2.5]);
+    buf.free();
+
+    const s = wasm.static_view();
+    assert.ok(s instanceof Uint8Array);
+    assert.strictEqual(String.fromCharCode(...s), 'hello');
+};
+
+exports.js_return_clamped = () => {
+  return new Uint8ClampedArray([-1, 128, 300]);
+};
+
+exports.js_export_clamped = () => {
+  const a = wasm.clamped_vec(new Uint8ClampedArray([1, 2, 3]));
+  assert.ok(a instanceof Uint8ClampedArray);
+  assert.deepStrictEqual(Array.from(a), [3, 2, 1]);
+
+  const b = wasm.clamped_boxed(new Uint8ClampedArray([4, 5]));
+  assert.ok(b instanceof Uint8ClampedArray);
+  assert.deepStrictEqual(Array.from(b), [4, 5, 0]);
+
+  const c = new Uint8ClampedArray([1, 200]);
+  wasm.clamped_mut(c);
+  assert.deepStrictEqual(Array.from(c), [101, 255]);
+
+  assert.strictEqual(wasm.clamped_option(undefined), undefined);
+  const d = wasm.clamped_option(new Uint8ClampedArray([7]));
+  assert.ok(d instanceof Uint8ClampedArray);
+  assert.deepStrictEqual(Array.from(d), [7]);
+};
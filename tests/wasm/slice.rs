@@ -14,11 +14,18 @@ extern "C" {
 
     fn js_return_vec();
 
+    fn js_return_view();
+
     fn js_clamped(val: Clamped<&[u8]>, offset: u8);
     #[wasm_bindgen(js_name = js_clamped)]
     fn js_clamped2(val: Clamped<Vec<u8>>, offset: u8);
     #[wasm_bindgen(js_name = js_clamped)]
     fn js_clamped3(val: Clamped<&mut [u8]>, offset: u8);
+    #[wasm_bindgen(js_name = js_clamped)]
+    fn js_clamped4(val: Clamped<Box<[u8]>>, offset: u8);
+    fn js_return_clamped() -> Clamped<Vec<u8>>;
+
+    fn js_export_clamped();
 }
 
 macro_rules! export_macro {
@@ -224,9 +231,82 @@ fn return_vec() {
     js_return_vec();
 }
 
+#[wasm_bindgen]
+pub fn clamped_vec(mut a: Clamped<Vec<u8>>) -> Clamped<Vec<u8>> {
+    a.reverse();
+    a
+}
+
+#[wasm_bindgen]
+pub fn clamped_boxed(a: Clamped<Box<[u8]>>) -> Clamped<Box<[u8]>> {
+    let mut v = a.0.into_vec();
+    v.push(0);
+    Clamped(v.into_boxed_slice())
+}
+
+#[wasm_bindgen]
+pub fn clamped_mut(a: Clamped<&mut [u8]>) {
+    for x in a.0.iter_mut() {
+        *x = x.saturating_add(100);
+    }
+}
+
+#[wasm_bindgen]
+pub fn clamped_option(a: Option<Clamped<Vec<u8>>>) -> Option<Clamped<Vec<u8>>> {
+    a
+}
+
+#[wasm_bindgen_test]
+fn export_clamped() {
+    js_export_clamped();
+}
+
 #[wasm_bindgen_test]
 fn take_clamped() {
     js_clamped(Clamped(&[1, 2, 3]), 1);
     js_clamped2(Clamped(vec![4, 5, 6]), 4);
     js_clamped3(Clamped(&mut [7, 8, 9]), 7);
+    js_clamped4(Clamped(vec![10, 11, 12].into_boxed_slice()), 10);
+    assert_eq!(js_return_clamped().0, [0, 128, 255]);
+}
+
+#[wasm_bindgen]
+pub struct ViewBuffer {
+    bytes: Vec<u8>,
+    floats: Vec<f32>,
+}
+
+#[wasm_bindgen]
+impl ViewBuffer {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ViewBuffer {
+        ViewBuffer {
+            bytes: vec![1, 2, 3],
+            floats: vec![1.5, 2.5],
+        }
+    }
+
+    #[wasm_bindgen(unsafe_view)]
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    #[wasm_bindgen(unsafe_view)]
+    pub fn floats(&self) -> &[f32] {
+        &self.floats
+    }
+
+    pub fn set_byte(&mut self, idx: usize, val: u8) {
+        self.bytes[idx] = val;
+    }
+}
+
+#[wasm_bindgen(unsafe_view)]
+pub fn static_view() -> &'static [u8] {
+    b"hello"
+}
+
+#[wasm_bindgen_test]
+fn return_view() {
+    js_return_view();
 }
@@ -0,0 +1,11 @@
+const g = typeof globalThis === 'undefined' ? global : globalThis;
+
+exports.set_thread_local_global = val => {
+  g.wbgThreadLocalGlobal = val;
+};
+
+exports.delete_thread_local_global = () => {
+  delete g.wbgThreadLocalGlobal;
+};
+
+exports.MODULE_VALUE = 'foo';
@@ -0,0 +1,41 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/thread_local_statics.js")]
+extern "C" {
+    fn set_thread_local_global(val: u32);
+    fn delete_thread_local_global();
+
+    #[wasm_bindgen(thread_local)]
+    static MODULE_VALUE: JsValue;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(thread_local, js_name = wbgThreadLocalGlobal)]
+    static THREAD_LOCAL_GLOBAL: JsValue;
+}
+
+#[wasm_bindgen_test]
+fn missing_global_is_undefined() {
+    delete_thread_local_global();
+    assert!(THREAD_LOCAL_GLOBAL.with(|v| v.is_undefined()));
+}
+
+#[wasm_bindgen_test]
+fn reevaluated_on_every_access() {
+    set_thread_local_global(1);
+    assert_eq!(THREAD_LOCAL_GLOBAL.with(|v| v.as_f64()), Some(1.0));
+    set_thread_local_global(2);
+    assert_eq!(THREAD_LOCAL_GLOBAL.with(|v| v.as_f64()), Some(2.0));
+    delete_thread_local_global();
+    assert!(THREAD_LOCAL_GLOBAL.with(|v| v.is_undefined()));
+}
+
+#[wasm_bindgen_test]
+fn module_statics() {
+    assert_eq!(
+        MODULE_VALUE.with(|v| v.as_string()),
+        Some("foo".to_string())
+    );
+}
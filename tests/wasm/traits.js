@@ -0,0 +1,19 @@
+const wasm = require('wasm-bindgen-test.js');
+const assert = require('assert');
+
+exports.js_traits_works = () => {
+    const up = wasm.traits_up_counter(2);
+    assert.ok(up instanceof wasm.TraitsCounter);
+    assert.strictEqual(up.currentValue(), 0);
+    assert.strictEqual(up.next(), 1);
+    assert.strictEqual(up.next(), 2);
+    assert.strictEqual(up.next(), undefined);
+    up.free();
+
+    const down = wasm.traits_down_counter(1);
+    assert.ok(down instanceof wasm.TraitsCounter);
+    assert.strictEqual(down.next(), 0);
+    assert.strictEqual(down.next(), undefined);
+    assert.strictEqual(down.currentValue(), 0);
+    down.free();
+};
@@ -0,0 +1,65 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen(module = "tests/wasm/traits.js")]
+extern "C" {
+    fn js_traits_works();
+}
+
+#[wasm_bindgen]
+pub trait TraitsCounter {
+    fn next(&mut self) -> Option<u32>;
+    #[wasm_bindgen(js_name = currentValue)]
+    fn current_value(&self) -> u32;
+}
+
+#[wasm_bindgen(implements = TraitsCounter)]
+pub struct TraitsUpCounter {
+    cur: u32,
+    max: u32,
+}
+
+impl TraitsCounter for TraitsUpCounter {
+    fn next(&mut self) -> Option<u32> {
+        if self.cur == self.max {
+            return None;
+        }
+        self.cur += 1;
+        Some(self.cur)
+    }
+
+    fn current_value(&self) -> u32 {
+        self.cur
+    }
+}
+
+struct TraitsDownCounter(u32);
+
+impl TraitsCounter for TraitsDownCounter {
+    fn next(&mut self) -> Option<u32> {
+        if self.0 == 0 {
+            return None;
+        }
+        self.0 -= 1;
+        Some(self.0)
+    }
+
+    fn current_value(&self) -> u32 {
+        self.0
+    }
+}
+
+#[wasm_bindgen]
+pub fn traits_up_counter(max: u32) -> TraitsUpCounter {
+    TraitsUpCounter { cur: 0, max }
+}
+
+#[wasm_bindgen]
+pub fn traits_down_counter(from: u32) -> Box<dyn TraitsCounter> {
+    Box::new(TraitsDownCounter(from))
+}
+
+#[wasm_bindgen_test]
+fn works() {
+    js_traits_works();
+}
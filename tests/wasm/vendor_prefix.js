@@ -9,3 +9,9 @@ global.MySpecialApi2 = class {
 global.bMySpecialApi3 = class {
   foo() { return 125; }
 };
+
+global.webkitMySpecialFunction = () => 126;
+global.mySpecialFunction2 = () => 127;
+global.MySpecialNamespace = {
+  bMySpecialMethod() { return this === global.MySpecialNamespace ? 128 : 0; }
+};
@@ -28,6 +28,20 @@ extern "C" {
     fn new() -> MySpecialApi3;
     #[wasm_bindgen(method)]
     fn foo(this: &MySpecialApi3) -> u32;
+
+    #[wasm_bindgen(vendor_prefix = webkit, js_name = mySpecialFunction)]
+    fn my_special_function() -> u32;
+
+    #[wasm_bindgen(vendor_prefix = webkit, js_name = mySpecialFunction2)]
+    fn my_special_function2() -> u32;
+
+    #[wasm_bindgen(
+        js_namespace = MySpecialNamespace,
+        vendor_prefix = a,
+        vendor_prefix = b,
+        js_name = mySpecialMethod
+    )]
+    fn my_special_method() -> u32;
 }
 
 #[wasm_bindgen_test]
@@ -37,4 +51,8 @@ pub fn polyfill_works() {
     assert_eq!(MySpecialApi::new().foo(), 123);
     assert_eq!(MySpecialApi2::new().foo(), 124);
     assert_eq!(MySpecialApi3::new().foo(), 125);
+
+    assert_eq!(my_special_function(), 126);
+    assert_eq!(my_special_function2(), 127);
+    assert_eq!(my_special_method(), 128);
 }